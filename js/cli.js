@@ -80,7 +80,7 @@ function parseFile(filePath) {
 
   const yaml = readFile(filePath);
   try {
-    const result = fastYaml.parse(yaml);
+    const result = fastYaml.parse(yaml, { filename: filePath });
     console.log(JSON.stringify(result, null, 2));
   } catch (error) {
     console.error(`Error parsing YAML: ${error.message}`);
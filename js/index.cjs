@@ -39,6 +39,10 @@ class YAMLException extends Error {
    * @param {number} [options.column] - Column number (1-based)
    * @param {string} [options.snippet] - Code snippet around the error
    * @param {string} [options.reason] - Reason for the error
+   * @param {string[]} [options.unknownKeys] - Option keys the function does not accept
+   * @param {Object} [options.suggestions] - Closest accepted option for each misspelled key
+   * @param {YAMLException[]} [options.errors] - Every syntax error, with the `allErrors` option
+   * @param {string} [options.code] - Stable code of the kind of error, such as `DUPLICATE_KEY`
    */
   constructor(message, options = {}) {
     super(message);
@@ -50,6 +54,10 @@ class YAMLException extends Error {
     this.line = options.line || null;
     this.column = options.column || null;
     this.snippet = options.snippet || null;
+    this.unknownKeys = options.unknownKeys || null;
+    this.suggestions = options.suggestions || null;
+    this.errors = options.errors || null;
+    this.code = options.code || null;
 
     // Capture stack trace
     if (Error.captureStackTrace) {
@@ -77,40 +85,221 @@ class YAMLException extends Error {
       }
     }
 
+    if (this.snippet) {
+      result += `\n\n${this.snippet}`;
+    }
+
     return result;
   }
 }
 
 /**
- * Parse a YAML string into a JavaScript object
- *
- * @param {string} input - YAML string to parse
- * @param {Object} [options] - Parsing options
- * @returns {Object} Parsed JavaScript object
+ * Schema constants for the `schema` load option (js-yaml compatible names)
  */
+const FAILSAFE_SCHEMA = 'failsafe';
+const JSON_SCHEMA = 'json';
+const CORE_SCHEMA = 'core';
+const DEFAULT_SCHEMA = 'default';
+
+// Milliseconds parseAsync loads for before yielding to the event loop
+const PARSE_SLICE_MS = 8;
+
+// Lines shown before and after the line of an error in its snippet
+const SNIPPET_LINES_BEFORE = 3;
+const SNIPPET_LINES_AFTER = 2;
+
 /**
  * Common error handling function
  *
  * @param {Error} error - Original error
+ * @param {string} [input] - YAML source the error is in, to render a snippet of
  * @throws {YAMLException} Converted YAMLException
  */
-function handleYamlError(error) {
-  const errorMsg = error.toString();
-  const lineMatch = errorMsg.match(/line (\d+)/i);
-  const columnMatch = errorMsg.match(/column (\d+)/i);
+function handleYamlError(error, input) {
+  throw toYamlException(error, input);
+}
+
+/**
+ * Convert an error from the WASM module to a YAMLException
+ *
+ * @param {Error|string|string[]} error - Original error, or every error with `allErrors`
+ * @param {string} [input] - YAML source the error is in, to render a snippet of
+ * @returns {YAMLException} The exception; with `allErrors`, the first one
+ */
+function toYamlException(error, input) {
+  if (Array.isArray(error)) {
+    // allErrors: every syntax error of the input, the first one carrying all of them
+    const errors = error.map((message) => createYamlException(message, input));
+    errors[0].errors = errors;
+    return errors[0];
+  }
+  return createYamlException(error.toString(), input);
+}
+
+/**
+ * Create a YAMLException from a message, extracting its line and column
+ *
+ * @param {string} message - Error or warning message
+ * @param {string} [input] - YAML source the message is about, to render a snippet of
+ * @returns {YAMLException} The exception
+ */
+function createYamlException(message, input) {
+  const lineMatch = message.match(/line (\d+)/i);
+  const columnMatch = message.match(/column (\d+)/i);
+  const line = lineMatch ? parseInt(lineMatch[1], 10) : null;
+  const column = columnMatch ? parseInt(columnMatch[1], 10) : null;
+  const snippet =
+    typeof input === 'string' && line !== null ? makeSnippet(input, line, column) : null;
+  const unknownMatch = message.match(/^Unknown options: (.+)$/);
+  let unknownKeys = null;
+  let suggestions = null;
+  if (unknownMatch) {
+    unknownKeys = [];
+    suggestions = {};
+    for (const entry of unknownMatch[1].split(', ')) {
+      const [, key, suggestion] = entry.match(/^(.*?)(?: \(did you mean '(.*)'\?\))?$/);
+      unknownKeys.push(key);
+      if (suggestion) {
+        suggestions[key] = suggestion;
+      }
+    }
+  }
+
+  return new YAMLException(snippet ? `${message}\n\n${snippet}` : message, {
+    reason: message,
+    line,
+    column,
+    snippet,
+    unknownKeys,
+    suggestions,
+    // Messages only come from the module once it is loaded
+    code: wasmModule ? wasmModule.error_code(message) : null,
+  });
+}
+
+/**
+ * Render the lines around a position with a caret under its column, like js-yaml's snippets
+ *
+ * @param {string} input - Source the position is in
+ * @param {number} line - Line number (1-based)
+ * @param {number|null} column - Column number (1-based)
+ * @returns {string|null} The snippet, or null for a position outside the source
+ */
+function makeSnippet(input, line, column) {
+  const lines = input.split(/\r\n|\r|\n/);
+  if (line < 1 || line > lines.length) {
+    return null;
+  }
+  const first = Math.max(1, line - SNIPPET_LINES_BEFORE);
+  const last = Math.min(lines.length, line + SNIPPET_LINES_AFTER);
+  const width = String(last).length;
+  let snippet = '';
+  for (let number = first; number <= last; number++) {
+    snippet += ` ${String(number).padStart(width, ' ')} | ${lines[number - 1]}\n`;
+    if (number === line) {
+      snippet += `${'-'.repeat(width + 4 + Math.max(0, (column || 1) - 1))}^\n`;
+    }
+  }
+  return snippet;
+}
+
+/**
+ * Wrap the `onWarning` option so it receives YAMLException instances like js-yaml
+ *
+ * A function in place of the options is a `JSON.parse`-style reviver (`parse(text, reviver)`).
+ *
+ * @param {Object|Function} options - Load options, or a reviver
+ * @returns {Object} Load options passed to the WASM module
+ */
+function loadOptions(options) {
+  if (typeof options === 'function') {
+    return { jsonReviver: options };
+  }
+  if (!options || typeof options.onWarning !== 'function') {
+    return options;
+  }
+  const onWarning = options.onWarning;
+  return { ...options, onWarning: (message) => onWarning(createYamlException(message)) };
+}
+
+/**
+ * Apply `map` to the result of a WASM call, or to its `value` when the call returned
+ * `{ value, timing }` for the `withTiming` option
+ *
+ * @param {*} result - The result of the call
+ * @param {Object} [options] - The options of the call
+ * @param {Function} map - Turns the untimed result into the returned one
+ * @returns {*} The mapped result
+ */
+function mapTimed(result, options, map) {
+  if (options && options.withTiming) {
+    return { ...result, value: map(result.value) };
+  }
+  return map(result);
+}
+
+/**
+ * Resolve the arguments of `loadAll` as js-yaml does: an object in place of the iterator holds
+ * the options unless options follow it
+ *
+ * @param {Function|Object|null} [iterator] - Callback receiving each document, or the options
+ * @param {Object} [options] - Load options
+ * @returns {Array} Iterator or null, and the load options passed to the WASM module
+ */
+function loadAllArguments(iterator, options) {
+  if (typeof iterator === 'function') {
+    return [iterator, loadOptions(options)];
+  }
+  if (iterator !== null && typeof iterator === 'object' && options === undefined) {
+    return [null, loadOptions(iterator)];
+  }
+  return [null, loadOptions(options)];
+}
 
-  throw new YAMLException(errorMsg, {
-    reason: errorMsg,
-    line: lineMatch ? parseInt(lineMatch[1], 10) : null,
-    column: columnMatch ? parseInt(columnMatch[1], 10) : null,
+/**
+ * Give `env` pipeline steps without an `env` object the process environment
+ *
+ * Steps written as YAML text are parsed first, so their `env` steps get it too.
+ *
+ * @param {Object} module - The WASM module
+ * @param {Array<string|Object>|string} steps - Pipeline steps, or their YAML text
+ * @returns {Array<string|Object>} Steps passed to the WASM module
+ */
+function pipelineSteps(module, steps) {
+  if (typeof steps === 'string') {
+    steps = module.parse(steps);
+  }
+  if (!Array.isArray(steps)) {
+    return steps;
+  }
+  const env = typeof process !== 'undefined' ? process.env : {};
+  return steps.map((step) => {
+    if (step === 'env') {
+      return { op: 'env', env };
+    }
+    if (step && step.op === 'env' && step.env === undefined) {
+      return { ...step, env };
+    }
+    return step;
   });
 }
 
+/**
+ * Parse a YAML string into a JavaScript object
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @param {string} [options.schema] - Schema used to resolve scalars (e.g. JSON_SCHEMA)
+ * @param {Function} [options.onWarning] - Callback receiving a YAMLException per warning
+ * @param {boolean} [options.withTiming] - Return `{ value, timing }` with the milliseconds
+ *   spent scanning, building node trees, converting and in total
+ * @returns {Object} Parsed JavaScript object
+ */
 function parseYAML(input, options = {}) {
   try {
-    return getWasmModule().parse(input);
+    return getWasmModule().parse(input, loadOptions(options));
   } catch (error) {
-    handleYamlError(error);
+    handleYamlError(error, input);
   }
 }
 
@@ -123,12 +312,290 @@ function parseYAML(input, options = {}) {
  */
 function parseAllYAML(input, options = {}) {
   try {
-    return getWasmModule().parse_all(input);
+    return getWasmModule().parse_all(input, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse the first document of each of many YAML strings in a single WASM call
+ *
+ * An input that fails does not affect the others: its entry holds the error instead.
+ *
+ * @param {string[]} inputs - YAML strings to parse, such as front matter blocks
+ * @param {Object} [options] - Parsing options, applied to every input
+ * @returns {Array<{value: any, error: YAMLException|null}>} One entry per input, in order
+ */
+function parseBatchYAML(inputs, options = {}) {
+  try {
+    const results = getWasmModule().parse_batch(inputs, loadOptions(options));
+    return mapTimed(results, options, (entries) =>
+      entries.map((result, i) =>
+        result.error === null
+          ? result
+          : { ...result, error: toYamlException(result.error, inputs[i]) }
+      )
+    );
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Create a parser reused across calls with the same options
+ *
+ * The options are read once, and the parser keeps its buffers between calls, so services
+ * parsing many documents do not allocate them every time.
+ *
+ * @param {Object} [options] - Parsing options for every call
+ * @returns {Object} Parser with `parse(input)` and `parseAll(input)`; call `free()` when done
+ */
+function createParserYAML(options = {}) {
+  try {
+    return wrapParser(getWasmModule().create_parser(loadOptions(options)));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Wrap a parser from the WASM module so its errors are thrown as YAMLExceptions
+ *
+ * @param {Object} parser - Parser returned by create_parser
+ * @returns {Object} Parser with `parse(input)`, `parseAll(input)` and `free()`
+ */
+function wrapParser(parser) {
+  return {
+    parse(input) {
+      try {
+        return parser.parse(input);
+      } catch (error) {
+        handleYamlError(error, input);
+      }
+    },
+    parseAll(input) {
+      try {
+        return parser.parseAll(input);
+      } catch (error) {
+        handleYamlError(error, input);
+      }
+    },
+    free() {
+      parser.free();
+    },
+  };
+}
+
+/**
+ * Parse a YAML stream, recovering from syntax errors instead of throwing on the first
+ *
+ * A broken document in a multi-document stream does not affect the others, and a badly
+ * indented line is skipped so the rest of its document still loads.
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @returns {{value: any, documents: Array, errors: YAMLException[]}} The first document,
+ *   every document that loaded and the errors recovered from
+ */
+function parseLenientYAML(input, options = {}) {
+  try {
+    const result = getWasmModule().parse_lenient(input, loadOptions(options));
+    return mapTimed(result, options, (lenient) => {
+      const errors = lenient.errors.map((message) => createYamlException(message, input));
+      return { ...lenient, errors };
+    });
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse the first document of a YAML string along with statistics about the parse
+ *
+ * Warnings are collected as YAMLExceptions, and still passed to `onWarning` when it is set.
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @returns {{value: any, documentCount: number, durationMs: number, warnings: YAMLException[],
+ *   yamlVersion: string|null}} The first document, the number of documents, the time spent,
+ *   the warnings and the version declared by `%YAML`
+ */
+function parseWithMetaYAML(input, options = {}) {
+  const { onWarning, ...rest } = typeof options === 'function' ? { jsonReviver: options } : options;
+  const warnings = [];
+  const collect = (message) => {
+    const warning = createYamlException(message);
+    warnings.push(warning);
+    if (onWarning) {
+      onWarning(warning);
+    }
+  };
+  try {
+    const result = getWasmModule().parse_with_meta(input, { ...rest, onWarning: collect });
+    return mapTimed(result, rest, (meta) => ({ ...meta, warnings }));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse YAML bytes into a JavaScript object, detecting their encoding
+ *
+ * A UTF-8, UTF-16LE or UTF-16BE byte order mark selects the encoding; without one, UTF-16 is
+ * recognized from the zero bytes of the first character and UTF-8 is assumed otherwise.
+ *
+ * @param {Uint8Array} bytes - YAML file contents, e.g. a Buffer from fs.readFileSync
+ * @param {Object} [options] - Parsing options
+ * @returns {Object} Parsed JavaScript object
+ */
+function parseBytesYAML(bytes, options = {}) {
+  try {
+    return getWasmModule().parse_bytes(bytes, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Allocate a buffer in WebAssembly memory to read a large input into, for parseInPlace
+ *
+ * Strings and byte arrays are copied into WebAssembly memory on every call; bytes written into
+ * the buffer's `view()` are parsed where they are. Take a new view after any other call, as
+ * growing memory detaches it.
+ *
+ * @param {number} length - The size of the input in bytes
+ * @returns {Object} Buffer with `length`, `view()` and `free()`
+ */
+function allocInputYAML(length) {
+  return getWasmModule().alloc_input(length);
+}
+
+/**
+ * Parse the bytes of a buffer from allocInput, detecting their encoding as parseBytes does
+ *
+ * @param {Object} buffer - Buffer returned by allocInput, holding the input
+ * @param {Object} [options] - Parsing options
+ * @returns {Object} Parsed JavaScript object
+ */
+function parseInPlaceYAML(buffer, options = {}) {
+  try {
+    return getWasmModule().parse_in_place(buffer, loadOptions(options));
   } catch (error) {
     handleYamlError(error);
   }
 }
 
+/**
+ * Parse the first document of a YAML string for a preview, cutting large values
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @param {number} [options.maxStringLength] - Longest string kept, in characters (default 1000)
+ * @param {number} [options.maxItems] - Most sequence items kept (default 100)
+ * @returns {{value: *, truncated: Array<{path: string, kind: string, length: number}>}} The
+ *   truncated value and the values that were cut, with their original length
+ */
+function parsePreviewYAML(input, options = {}) {
+  try {
+    return getWasmModule().parse_preview(input, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse the first document of a YAML string for conversion on demand
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @param {number} [options.maxDepth] - Levels converted per call (default 1)
+ * @returns {LazyDocument} Document whose `root()` and `expand(handle)` convert it level by level;
+ *   call `free()` when done
+ */
+function parseLazyYAML(input, options = {}) {
+  try {
+    return getWasmModule().parse_lazy(input, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse the first document of a YAML string into a handle kept in WebAssembly memory
+ *
+ * The handle converts, queries and validates the document without parsing the input again,
+ * and `update(edits)` parses again only the entries edits of the input touched.
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options, applied by `toJS()`
+ * @returns {DocumentHandle} Document with `toJS()`, `get(path)`, `query(path)`,
+ *   `validate(schema)` and `update(edits)`; call `free()` when done
+ */
+function parseToHandleYAML(input, options = {}) {
+  try {
+    return getWasmModule().parse_to_handle(input, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Parse the first document of a YAML string without blocking the event loop
+ *
+ * Loading runs in slices of a few milliseconds with a macrotask between them, so timers,
+ * I/O and an `AbortSignal` are serviced while a large document parses.
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @param {number} [options.timeoutMs] - Reject with a YAMLException once parsing takes longer
+ * @param {AbortSignal} [options.signal] - Reject with the signal's reason once it aborts
+ * @returns {Promise<any>} Parsed JavaScript object
+ */
+async function parseAsyncYAML(input, options = {}) {
+  const { signal, ...wasmOptions } = loadOptions(options) || {};
+  let task;
+  try {
+    if (signal && signal.aborted) {
+      throw signal.reason;
+    }
+    task = getWasmModule().start_parse(input, wasmOptions);
+    while (!task.advance(PARSE_SLICE_MS)) {
+      await new Promise((resolve) => setTimeout(resolve, 0));
+      if (signal && signal.aborted) {
+        throw signal.reason;
+      }
+    }
+    return task.finish();
+  } catch (error) {
+    if (signal && signal.aborted) {
+      throw signal.reason;
+    }
+    handleYamlError(error, input);
+  } finally {
+    if (task) {
+      task.free();
+    }
+  }
+}
+
+/**
+ * Parse a YAML string into a JSON string
+ *
+ * @param {string} input - YAML string to parse
+ * @param {Object} [options] - Parsing options
+ * @param {boolean} [options.sourceMap] - Also return a source map back to YAML positions
+ * @returns {string|{json: string, sourceMap: Uint32Array}} JSON string, or JSON string and
+ *   flat (jsonOffset, line, column) triples when `sourceMap` is set
+ */
+function parseToJsonStringYAML(input, options = {}) {
+  try {
+    return getWasmModule().parse_to_json_string(input, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
 /**
  * Parse a YAML string with schema validation into a JavaScript object
  *
@@ -138,22 +605,95 @@ function parseAllYAML(input, options = {}) {
  */
 function loadYAML(input, options = {}) {
   try {
-    return getWasmModule().load(input);
+    return getWasmModule().load(input, loadOptions(options));
   } catch (error) {
-    handleYamlError(error);
+    handleYamlError(error, input);
   }
 }
 
 /**
  * Parse all YAML documents in a string with schema validation into an array of JavaScript objects
  *
+ * As in js-yaml, an iterator function may come before the options: each document is then
+ * passed to it as it is parsed, and nothing is returned. Returning `false` from the iterator
+ * stops parsing there.
+ *
  * @param {string} input - YAML string containing multiple documents
+ * @param {Function|Object|null} [iterator] - Callback receiving each document, or the options
  * @param {Object} [options] - Parsing options
- * @returns {Array} Array of parsed JavaScript objects
+ * @returns {Array|undefined} Array of parsed JavaScript objects, unless an iterator is given
+ */
+function loadAllYAML(input, iterator, options) {
+  try {
+    return getWasmModule().load_all(input, ...loadAllArguments(iterator, options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Register a constructor for a tag, like a js-yaml Type
+ *
+ * @param {string} tag - Tag such as `!Ref` or `!!python/tuple`
+ * @param {Object} definition - Type definition
+ * @param {string} definition.kind - Node kind: 'scalar', 'sequence' or 'mapping'
+ * @param {Function} [definition.constructFn] - Builds the value from the string, array or object
+ * @param {Function} [definition.resolveFn] - Rejects the node by returning a falsy value
+ */
+function registerTypeYAML(tag, definition) {
+  try {
+    getWasmModule().register_type(tag, definition);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Register the extensions of a plugin package
+ *
+ * @param {Object} plugin - Extensions to register
+ * @param {string} [plugin.name] - Name of the plugin
+ * @param {Object} [plugin.types] - Tags mapped to `registerType` definitions
+ * @param {Object} [plugin.formats] - Names for the `format` schema keyword mapped to functions
+ *   returning whether a string is valid
+ * @param {Object} [plugin.functions] - YAMLPath filter function names mapped to predicates
+ * @param {Object} [plugin.lintRules] - Rule names mapped to functions taking
+ *   `{ path, kind, tag, source }` and returning a message, reported to `onWarning`
+ */
+function registerPluginYAML(plugin) {
+  try {
+    getWasmModule().register_plugin(plugin);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Serialize a JavaScript value as YAML
+ *
+ * @param {*} value - Value to serialize
+ * @param {Object} [options] - Dump options
+ * @param {Object} [options.styles] - Scalar styles by tag (e.g. `{ '!!null': 'empty' }`)
+ * @returns {string} YAML text
+ */
+function dumpYAML(value, options = {}) {
+  try {
+    return getWasmModule().dump(value, options);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Compute the size in UTF-8 bytes of the YAML text `dump` returns, without building it
+ *
+ * @param {*} value - Value to measure
+ * @param {Object} [options] - Dump options
+ * @returns {number} Size of the YAML text in bytes
  */
-function loadAllYAML(input, options = {}) {
+function estimateDumpSizeYAML(value, options = {}) {
   try {
-    return getWasmModule().load_all(input);
+    return getWasmModule().estimate_dump_size(value, options);
   } catch (error) {
     handleYamlError(error);
   }
@@ -163,48 +703,808 @@ function loadAllYAML(input, options = {}) {
  * Validate a YAML document against a JSON Schema
  *
  * @param {string} yaml - YAML document to validate
- * @param {Object} schema - JSON Schema to validate against
+ * @param {Object} schema - JSON Schema to validate against, or a schema returned by compileSchema
+ * @param {Object} [options] - Validation options
+ * @param {boolean} [options.decimalCommaHints] - Explain number type errors on strings like '1,5'
  * @returns {Object} Validation result with success flag and any errors
  */
-function validateYAML(yaml, schema) {
+function validateYAML(yaml, schema, options = {}) {
   try {
-    return getWasmModule().validate(yaml, schema);
+    const module = getWasmModule();
+    if (schema instanceof module.CompiledSchema) {
+      return schema.validate(yaml, options);
+    }
+    return module.validate(yaml, schema, options);
   } catch (error) {
     handleYamlError(error);
   }
 }
 
 /**
- * Query a YAML document using a YAMLPath expression
+ * Compile a JSON Schema once for use with validate and validateBatch
  *
- * @param {string} yaml - YAML document to query
- * @param {string} path - YAMLPath expression
- * @returns {Array} Array of matching values
+ * Passing a plain schema converts it from JavaScript on every call; a compiled schema is
+ * converted once. Its `validateHandle(handle, options)` validates a document from parseToHandle.
+ *
+ * @param {Object} schema - JSON Schema to compile
+ * @returns {Object} Compiled schema
  */
-function queryYAML(yaml, path) {
+function compileSchemaYAML(schema) {
   try {
-    return getWasmModule().query(yaml, path);
+    return getWasmModule().compile_schema(schema);
   } catch (error) {
     handleYamlError(error);
   }
 }
 
 /**
- * Parse a YAML document in a streaming fashion
+ * Validate many YAML documents against one compiled schema in a single call
  *
- * @param {string} yaml - YAML document to parse
- * @param {Function} callback - Callback function to receive parsed chunks
- * @param {Object} [options] - Parsing options
- * @returns {Promise} Promise that resolves when parsing is complete
+ * @param {string[]} docs - YAML documents to validate
+ * @param {Object} schema - Schema returned by compileSchema
+ * @param {Object} [options] - Validation options, as for validate
+ * @returns {Object[]} Validation results in input order
  */
-function parseStreamYAML(yaml, callback, options = {}) {
+function validateBatchYAML(docs, schema, options = {}) {
   try {
-    return getWasmModule().parse_stream(yaml, callback, options);
+    return getWasmModule().validate_batch(docs, schema, options);
   } catch (error) {
     handleYamlError(error);
   }
 }
 
+/**
+ * Generate a starter YAML document from a JSON Schema
+ *
+ * Required properties get their default, example or a placeholder; optional ones are
+ * commented out under their description.
+ *
+ * @param {Object} schema - JSON Schema describing the document
+ * @param {Object} [options] - `optional`, `descriptions` and `maxDepth`
+ * @returns {string} The YAML text
+ */
+function skeletonYAML(schema, options = {}) {
+  try {
+    return getWasmModule().skeleton(schema, options);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Check every default and example embedded in a JSON Schema against its own subschema
+ *
+ * @param {Object} schema - JSON Schema to check
+ * @param {Object} [options] - `snippets` adds each example written as YAML
+ * @returns {Object} `{ valid, examples }` with one result per example
+ */
+function verifyExamplesYAML(schema, options = {}) {
+  try {
+    return getWasmModule().verify_examples(schema, options);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Query a YAML document using a YAMLPath expression
+ *
+ * @param {string} yaml - YAML document to query
+ * @param {string|Object} path - YAMLPath expression or path returned by compilePath
+ * @param {Object} [options] - Query options
+ * @param {string[]} [options.descendOnlyInto] - Keys recursive descent is restricted to
+ * @returns {Array} Array of matching values
+ */
+function queryYAML(yaml, path, options = {}) {
+  try {
+    if (typeof path !== 'string') {
+      return path.query(yaml, loadOptions(options));
+    }
+    return getWasmModule().query(yaml, path, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, yaml);
+  }
+}
+
+/**
+ * Compile a YAMLPath expression once for repeated queries
+ *
+ * The compiled path has `query(yaml, options)`, `queryHandle(handle, options)` for a document
+ * from parseToHandle and `profile(yaml, options)` methods; `profile` also reports the time
+ * spent and nodes visited per path segment. It can be passed to query and queryMany as well.
+ *
+ * @param {string} path - YAMLPath expression
+ * @returns {Object} Compiled path
+ */
+function compilePathYAML(path) {
+  try {
+    return getWasmModule().compile_path(path);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Query many YAML documents with one path in a single call
+ *
+ * @param {string[]} docs - YAML documents to query
+ * @param {string|Object} path - YAMLPath expression or path returned by compilePath
+ * @param {Object} [options] - Query options
+ * @returns {Array[]} Matching values per document, in input order
+ */
+function queryManyYAML(docs, path, options = {}) {
+  try {
+    const module = getWasmModule();
+    const compiled = typeof path === 'string' ? module.compile_path(path) : path;
+    try {
+      return module.query_many(docs, compiled, loadOptions(options));
+    } finally {
+      if (compiled !== path) {
+        compiled.free();
+      }
+    }
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Parse a YAML document once for an interactive query session
+ *
+ * The session has `query(path)`, `cd(path)` and `up()` methods; paths starting with `.` or `[`
+ * are relative to the current location (`cwd`). Call `free()` when done.
+ *
+ * @param {string} yaml - YAML document to query
+ * @param {Object} [options] - Query options
+ * @returns {Object} The session, located at the root
+ */
+function createQuerySessionYAML(yaml, options = {}) {
+  try {
+    return getWasmModule().create_query_session(yaml, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, yaml);
+  }
+}
+
+/**
+ * Estimate how many values a path can select from documents valid against a JSON Schema
+ *
+ * @param {string} path - YAMLPath expression
+ * @param {Object} schema - JSON Schema the documents are valid against
+ * @returns {Object} `{ canMatch, maxMatches, selectivity, blockedAt }`
+ */
+function analyzePathYAML(path, schema) {
+  try {
+    return getWasmModule().analyze_path(path, schema);
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Report how much each anchor contributes to the expanded size of a YAML stream
+ *
+ * @param {string} input - YAML stream to analyze
+ * @returns {Object} `{ nodes, anchors }` with per-anchor alias counts and expansion sizes
+ */
+function analyzeAliasesYAML(input) {
+  try {
+    return getWasmModule().analyze_aliases(input);
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Export the node structure of a YAML stream as a Graphviz DOT graph or node and edge lists
+ *
+ * @param {string} input - YAML stream to export
+ * @param {Object} [options] - Graph options
+ * @param {string} [options.format] - 'dot' (default) or 'json' for `{ nodes, edges }`
+ * @param {boolean} [options.aliases] - Add a dashed edge from each alias to its anchored node
+ * @param {number} [options.maxLabelLength] - Longest scalar text kept in a label (default 40)
+ * @returns {string|Object} DOT text or node and edge lists
+ */
+function toDotYAML(input, options = {}) {
+  try {
+    return getWasmModule().to_dot(input, options);
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Compare the parse result of a YAML document with a reference JSON dump
+ *
+ * @param {string} yaml - YAML document to parse
+ * @param {string} reference - Expected result as a JSON string (e.g. from js-yaml)
+ * @param {Object} [options] - Load options used to parse the document
+ * @returns {Object} `{ equal, differences }`
+ */
+function compareWithReferenceYAML(yaml, reference, options = {}) {
+  try {
+    return getWasmModule().compare_with_reference(yaml, reference, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Parse the documents of a YAML stream one at a time
+ *
+ * The stream is a string, or an iterable or async iterable of string or Uint8Array chunks,
+ * such as a file read stream. Chunks are parsed as they arrive and only the text of the
+ * document being received is kept, so memory is bounded by the largest document, not by the
+ * stream; `maxInputSize` then applies to each document. Returning `false` from `callback`
+ * stops parsing there, and no more chunks are read. For async iterables, aborting the
+ * `signal` option rejects the promise with its reason, an AbortError by default, without
+ * waiting for the next chunk. A string or synchronous iterable is parsed before the call
+ * returns, so the count is returned directly rather than as a promise.
+ *
+ * @param {string|Iterable|AsyncIterable} yaml - YAML stream to parse
+ * @param {Function} callback - Callback receiving `{ index, value, start, end, hash? }` per document
+ * @param {Object} [options] - Load options
+ * @param {boolean} [options.checksum] - Add a hash of each document's source to its chunk
+ * @param {number} [options.sequenceChunk] - Pass the items of a root sequence on this many at
+ *   a time, as `{ index, item, value }` chunks
+ * @param {AbortSignal} [options.signal] - Signal aborting the parse of an async iterable
+ * @param {Function} [options.onProgress] - Callback receiving `{ bytesProcessed,
+ *   documentsEmitted }` every `progressInterval` bytes, 1 MiB by default, and at the end
+ * @returns {number|Promise<number>} Number of documents parsed, as a promise for async iterables
+ */
+function parseStreamYAML(yaml, callback, options = {}) {
+  if (typeof yaml !== 'string') {
+    return parseStreamChunks(yaml, callback, options);
+  }
+  try {
+    return getWasmModule().parse_stream(yaml, callback, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, yaml);
+  }
+}
+
+/**
+ * Parse a YAML stream received in chunks, decoding Uint8Array chunks as UTF-8
+ *
+ * @param {Iterable|AsyncIterable} chunks - String or Uint8Array chunks of the stream
+ * @param {Function} callback - Callback receiving a chunk object per document
+ * @param {Object} options - Load options
+ * @returns {number|Promise<number>} Number of documents parsed, as a promise for async iterables
+ */
+function parseStreamChunks(chunks, callback, options) {
+  const parser = createStreamParserYAML(callback, options);
+  if (chunks[Symbol.asyncIterator]) {
+    return (async () => {
+      try {
+        for await (const chunk of inputChunks(chunks, options.signal)) {
+          parser.write(chunk);
+          if (parser.stopped) {
+            break;
+          }
+        }
+        return parser.end();
+      } finally {
+        parser.free();
+      }
+    })();
+  }
+  try {
+    for (const chunk of chunks) {
+      parser.write(chunk);
+      if (parser.stopped) {
+        break;
+      }
+    }
+    return parser.end();
+  } finally {
+    parser.free();
+  }
+}
+
+/**
+ * Create a parser for a YAML stream pushed to it in chunks
+ *
+ * `write(chunk)` passes every document the chunks written so far complete to `callback`, as
+ * parseStream does, so data arriving from a socket or a chunked HTTP response is parsed as it
+ * arrives without buffering the whole body. Chunks are strings or Uint8Arrays decoded as UTF-8,
+ * also when a character is split between two of them. `end()` passes the last document,
+ * releases the parser and returns the number of documents; `free()` releases it without
+ * parsing the rest. Once `callback` returns `false`, later chunks are ignored and `stopped` is
+ * true, so the source can be closed early. `checkpoint()` returns the state of the parser, to
+ * resume parsing with restoreStreamParser.
+ *
+ * @param {Function} callback - Callback receiving a chunk object per document
+ * @param {Object} [options] - Load options, `checksum`, `directives`, `sequenceChunk`,
+ *   `onProgress` and `progressInterval`
+ * @returns {Object} Parser with `write(chunk)`, `end()`, `free()`, `checkpoint()` and `stopped`
+ */
+function createStreamParserYAML(callback, options = {}) {
+  try {
+    return wrapStreamParser(getWasmModule().create_stream_parser(callback, loadOptions(options)));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Create a stream parser resuming from a checkpoint of another one
+ *
+ * `checkpoint()` returns the state of a parser as a plain object JSON can hold, so a long
+ * stream can be parsed across processes: write the chunks following the `bytesReceived` first
+ * bytes of the stream to the parser this returns, and documents keep their index, offset and
+ * lines in the whole stream. The options need not be those of the first parser.
+ *
+ * @param {Object} checkpoint - Object returned by a parser's `checkpoint()`
+ * @param {Function} callback - Callback receiving a chunk object per document
+ * @param {Object} [options] - The options of createStreamParser
+ * @returns {Object} Parser with `write(chunk)`, `end()`, `free()`, `checkpoint()` and `stopped`
+ */
+function restoreStreamParserYAML(checkpoint, callback, options = {}) {
+  try {
+    return wrapStreamParser(
+      getWasmModule().restore_stream_parser(checkpoint, callback, loadOptions(options)),
+      checkpoint
+    );
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Count the bytes ending a Uint8Array that start a UTF-8 character it does not complete
+ *
+ * @param {Uint8Array} bytes - The bytes
+ * @returns {number} Number of bytes, at most 3
+ */
+function incompleteUtf8(bytes) {
+  for (let back = 1; back <= Math.min(3, bytes.length); back += 1) {
+    const byte = bytes[bytes.length - back];
+    if ((byte & 0xc0) !== 0x80) {
+      const size = byte >= 0xf0 ? 4 : byte >= 0xe0 ? 3 : byte >= 0xc0 ? 2 : 1;
+      return size > back ? back : 0;
+    }
+  }
+  return 0;
+}
+
+/**
+ * Wrap a stream parser from the WASM module so it accepts byte chunks and throws YAMLExceptions
+ *
+ * The bytes of a character split between two chunks wait in the decoder, so they are also
+ * kept here for `checkpoint()`, and given back to the decoder of a restored parser.
+ *
+ * @param {Object} parser - Parser returned by create_stream_parser or restore_stream_parser
+ * @param {Object} [checkpoint] - Checkpoint the parser was restored from
+ * @returns {Object} Parser with `write(chunk)`, `end()`, `free()`, `checkpoint()` and `stopped`
+ */
+function wrapStreamParser(parser, checkpoint) {
+  const received = checkpoint ? checkpoint.bytesReceived : 0;
+  const decoder = new TextDecoder('utf-8', { ignoreBOM: received > 0 });
+  let partial = Uint8Array.from((checkpoint && checkpoint.partial) || []);
+  decoder.decode(partial, { stream: true });
+  let freed = false;
+  let stopped = parser.stopped;
+  const free = () => {
+    if (!freed) {
+      freed = true;
+      parser.free();
+    }
+  };
+  return {
+    write(chunk) {
+      try {
+        if (typeof chunk === 'string') {
+          parser.write(chunk);
+        } else {
+          const tail = chunk.subarray(Math.max(0, chunk.length - 3));
+          const recent = new Uint8Array(partial.length + tail.length);
+          recent.set(partial);
+          recent.set(tail, partial.length);
+          partial = recent.slice(recent.length - incompleteUtf8(recent));
+          parser.write(decoder.decode(chunk, { stream: true }));
+        }
+        stopped = parser.stopped;
+      } catch (error) {
+        handleYamlError(error);
+      }
+    },
+    end() {
+      try {
+        parser.write(decoder.decode());
+        return parser.end();
+      } catch (error) {
+        handleYamlError(error);
+      } finally {
+        free();
+      }
+    },
+    free,
+    checkpoint() {
+      const state = parser.checkpoint();
+      state.partial = Array.from(partial);
+      state.bytesReceived += partial.length;
+      return state;
+    },
+    get stopped() {
+      return stopped;
+    },
+  };
+}
+
+/**
+ * Parse the documents of a WHATWG ReadableStream as its chunks arrive
+ *
+ * Chunks are pulled from the stream's reader and written to a stream parser, so a response
+ * body such as `fetch(url).body` is parsed while it downloads, in browsers, Deno and Bun as in
+ * Node.js. Chunks are strings or Uint8Arrays decoded as UTF-8. When parsing fails, or
+ * `onDocument` throws or returns `false` to stop parsing, the stream is cancelled. So it is
+ * when the `signal` option aborts, and the promise rejects with its reason.
+ *
+ * @param {ReadableStream} readable - Stream of the YAML text
+ * @param {Function} onDocument - Callback receiving a chunk object per document, as parseStream
+ *   passes them
+ * @param {Object} [options] - Load options, `checksum`, `directives`, `sequenceChunk`,
+ *   `signal` and `onProgress`
+ * @returns {Promise<number>} Number of documents parsed
+ */
+async function parseFromStreamYAML(readable, onDocument, options = {}) {
+  const parser = createStreamParserYAML(onDocument, options);
+  const reader = readable.getReader();
+  try {
+    for (;;) {
+      const { done, value } = await untilAborted(reader.read(), options.signal);
+      if (done) {
+        return parser.end();
+      }
+      parser.write(value);
+      if (parser.stopped) {
+        reader.cancel().catch(() => {});
+        return parser.end();
+      }
+    }
+  } catch (error) {
+    reader.cancel(error).catch(() => {});
+    throw error;
+  } finally {
+    reader.releaseLock();
+    parser.free();
+  }
+}
+
+/**
+ * Create a TransformStream parsing YAML text into its documents
+ *
+ * Strings or Uint8Arrays written to the stream come out as the parsed value of each document,
+ * as soon as the text written completes it, so `fetch(url).body.pipeThrough(documentStream())`
+ * reads the documents of a response while it downloads. With `sequenceChunk`, the items of a
+ * sequence at the root of a document come out one at a time instead, as they are loaded.
+ * Aborting the `signal` option errors both sides of the stream with its reason.
+ *
+ * @param {Object} [options] - Load options, `sequenceChunk`, `signal` and `onProgress`
+ * @returns {TransformStream} Stream of the documents' values
+ */
+function documentStreamYAML(options = {}) {
+  const { signal } = options;
+  let parser;
+  let abort;
+  return new TransformStream({
+    start(controller) {
+      signal?.throwIfAborted();
+      parser = createStreamParserYAML(
+        (chunk) => passDocument(chunk, (value) => controller.enqueue(value)),
+        options
+      );
+      abort = () => {
+        parser.free();
+        controller.error(signal.reason);
+      };
+      signal?.addEventListener('abort', abort, { once: true });
+    },
+    transform(chunk) {
+      try {
+        parser.write(chunk);
+      } catch (error) {
+        parser.free();
+        throw error;
+      }
+    },
+    flush() {
+      signal?.removeEventListener('abort', abort);
+      parser.end();
+    },
+    cancel() {
+      signal?.removeEventListener('abort', abort);
+      parser.free();
+    },
+  });
+}
+
+/**
+ * Iterate over the documents of a YAML stream
+ *
+ * The stream is a string, a Uint8Array, an iterable or async iterable of string or Uint8Array
+ * chunks such as a file read stream, or a WHATWG ReadableStream. Input is read only as the
+ * documents are consumed, so `for await (const doc of parseDocuments(src))` holds no more
+ * than the document being read, however large the stream, and stopping early cancels a
+ * ReadableStream. With `sequenceChunk`, the items of a sequence at the root of a document are
+ * iterated over one at a time instead, as they are loaded. Aborting the `signal` option makes
+ * the iteration throw its reason, without waiting for the next chunk.
+ *
+ * @param {string|Uint8Array|Iterable|AsyncIterable|ReadableStream} input - YAML stream
+ * @param {Object} [options] - Load options, `sequenceChunk`, `signal` and `onProgress`
+ * @returns {AsyncGenerator} The value of each document
+ */
+async function* parseDocumentsYAML(input, options = {}) {
+  let ready = [];
+  const parser = createStreamParserYAML(
+    (chunk) => passDocument(chunk, (value) => ready.push(value)),
+    options
+  );
+  try {
+    for await (const chunk of inputChunks(input, options.signal)) {
+      parser.write(chunk);
+      const values = ready;
+      ready = [];
+      for (const value of values) {
+        options.signal?.throwIfAborted();
+        yield value;
+      }
+    }
+    parser.end();
+    for (const value of ready) {
+      options.signal?.throwIfAborted();
+      yield value;
+    }
+  } finally {
+    parser.free();
+  }
+}
+
+// Size of the pieces a string or byte array input is written to a stream parser in
+const INPUT_PIECE_SIZE = 1 << 16;
+
+/**
+ * Read the chunks of a YAML stream, cutting a string or byte array into pieces so that its
+ * documents are parsed one at a time
+ *
+ * @param {string|Uint8Array|Iterable|AsyncIterable|ReadableStream} input - YAML stream
+ * @param {AbortSignal} [signal] - Signal aborting the read of the next chunk
+ * @returns {AsyncGenerator} String or Uint8Array chunks
+ */
+async function* inputChunks(input, signal) {
+  if (typeof input === 'string') {
+    // Strings are cut after a line break, never inside a surrogate pair
+    let start = 0;
+    while (start < input.length) {
+      const newline = input.indexOf('\n', start + INPUT_PIECE_SIZE);
+      const end = newline === -1 ? input.length : newline + 1;
+      yield input.slice(start, end);
+      start = end;
+    }
+  } else if (input instanceof Uint8Array) {
+    for (let start = 0; start < input.length; start += INPUT_PIECE_SIZE) {
+      yield input.subarray(start, start + INPUT_PIECE_SIZE);
+    }
+  } else if (typeof input.getReader === 'function') {
+    const reader = input.getReader();
+    try {
+      yield* readChunks({ next: () => reader.read(), return: () => reader.cancel() }, signal);
+    } finally {
+      reader.releaseLock();
+    }
+  } else {
+    const iterator = input[Symbol.asyncIterator]
+      ? input[Symbol.asyncIterator]()
+      : input[Symbol.iterator]();
+    yield* readChunks(iterator, signal);
+  }
+}
+
+/**
+ * Read the chunks of an iterator until it is done, closing it when reading stops early
+ *
+ * @param {Iterator|AsyncIterator} iterator - Iterator over the chunks
+ * @param {AbortSignal} [signal] - Signal aborting the read of the next chunk
+ * @returns {AsyncGenerator} The chunks
+ */
+async function* readChunks(iterator, signal) {
+  let done = false;
+  try {
+    while (!done) {
+      const result = await untilAborted(iterator.next(), signal);
+      done = result.done;
+      if (!done) {
+        yield result.value;
+      }
+    }
+  } finally {
+    if (!done && iterator.return) {
+      Promise.resolve(iterator.return()).catch(() => {});
+    }
+  }
+}
+
+/**
+ * Wait for a value or promise, rejecting with the reason of `signal` as soon as it aborts
+ *
+ * @param {*} promise - Value or promise to wait for
+ * @param {AbortSignal} [signal] - Signal to watch
+ * @returns {Promise} The value
+ */
+function untilAborted(promise, signal) {
+  if (!signal) {
+    return Promise.resolve(promise);
+  }
+  if (signal.aborted) {
+    return Promise.reject(signal.reason);
+  }
+  return new Promise((resolve, reject) => {
+    const abort = () => reject(signal.reason);
+    signal.addEventListener('abort', abort, { once: true });
+    Promise.resolve(promise)
+      .then(resolve, reject)
+      .finally(() => signal.removeEventListener('abort', abort));
+  });
+}
+
+/**
+ * Pass the values in a chunk of a stream parser on: the document's value, or the items of a
+ * root sequence one at a time
+ *
+ * @param {Object} chunk - Chunk passed by the stream parser
+ * @param {Function} push - Callback receiving each value
+ */
+function passDocument(chunk, push) {
+  if ('item' in chunk) {
+    for (const item of chunk.value) {
+      push(item);
+    }
+  } else {
+    push(chunk.value);
+  }
+}
+
+/**
+ * Find the documents of a YAML stream without parsing them
+ *
+ * A light scanner follows quoted and block scalars, so a `---` or `...` line inside one is not
+ * taken for a document marker, and even a very large concatenated dump is indexed at about the
+ * speed of reading it. Each range can then be parsed on its own, e.g.
+ * `parseBytes(buffer.subarray(start, end))`.
+ *
+ * @param {string|Uint8Array} input - YAML stream, e.g. a Buffer from fs.readFileSync
+ * @returns {Array<{start: number, end: number}>} UTF-8 byte range of each document's source
+ */
+function indexDocumentsYAML(input) {
+  try {
+    return getWasmModule().index_documents(utf8Bytes(input));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Count the documents of a YAML stream without parsing them, as indexDocuments finds them
+ *
+ * @param {string|Uint8Array} input - YAML stream
+ * @returns {number} Number of documents
+ */
+function countDocumentsYAML(input) {
+  try {
+    return getWasmModule().count_documents(utf8Bytes(input));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Split a YAML stream into the source of each document without parsing them, as
+ * indexDocuments finds them
+ *
+ * @param {string|Uint8Array} input - YAML stream
+ * @returns {Array<string|Uint8Array>} Source of each document: strings for a string input, and
+ *   views of the input's bytes for a Uint8Array
+ */
+function splitDocumentsYAML(input) {
+  try {
+    if (typeof input === 'string') {
+      return getWasmModule().split_documents(input);
+    }
+    const index = getWasmModule().index_documents(input);
+    return index.map(({ start, end }) => input.subarray(start, end));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Parse one document of a YAML stream without parsing the others
+ *
+ * The stream is scanned as indexDocuments does up to the document at `index`, counted from 0,
+ * and only that document is parsed. Errors give lines counted from the start of the stream.
+ *
+ * @param {string} input - YAML stream
+ * @param {number} index - Index of the document
+ * @param {Object} [options] - Parsing options
+ * @returns {any} The document, or what parse returns for an empty input when the stream
+ *   holds no document at `index`
+ */
+function extractDocumentYAML(input, index, options = {}) {
+  try {
+    return getWasmModule().extract_document(input, index, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, input);
+  }
+}
+
+/**
+ * Encode a string as UTF-8, passing byte arrays through
+ *
+ * @param {string|Uint8Array} input - Text or bytes
+ * @returns {Uint8Array} UTF-8 bytes
+ */
+function utf8Bytes(input) {
+  return typeof input === 'string' ? new TextEncoder().encode(input) : input;
+}
+
+/**
+ * Rewrite the documents of a YAML stream on the fly
+ *
+ * Each document is parsed, passed to `transform`, and the result is emitted as YAML to
+ * `output` before the next document is parsed. Returning `undefined` drops the document.
+ *
+ * @param {string} yaml - YAML stream to rewrite
+ * @param {Function} transform - Callback receiving each document and its index
+ * @param {Function} output - Callback receiving the YAML text of each rewritten document
+ * @param {Object} [options] - Load options used to parse the documents
+ * @returns {number} Number of documents emitted
+ */
+function transformStreamYAML(yaml, transform, output, options = {}) {
+  try {
+    return getWasmModule().transform_stream(yaml, transform, output, loadOptions(options));
+  } catch (error) {
+    handleYamlError(error, yaml);
+  }
+}
+
+/**
+ * Run built-in steps over each document of a YAML stream in one call
+ *
+ * Steps run in order without leaving WebAssembly: `resolveAliases`, `mergeKeys`, `env`,
+ * `coerce`, `validate`, `redact`, `set`, `delete`, `rename`, `sortKeys` and, last, `emit`.
+ * An `env` step without an `env` object substitutes the variables of `process.env`. The
+ * steps may also be the text of a YAML sequence, so transform rules can live in a file.
+ *
+ * @param {string} input - YAML stream to process
+ * @param {Array<string|Object>|string} steps - Step names, or objects with an `op` and its
+ *   parameters; or the YAML text of such a list
+ * @param {Object} [options] - Load options used to parse the documents
+ * @returns {string} The processed documents, as YAML or JSON
+ */
+function pipelineYAML(input, steps, options = {}) {
+  try {
+    const module = getWasmModule();
+    return module.pipeline(input, pipelineSteps(module, steps), loadOptions(options));
+  } catch (error) {
+    handleYamlError(error);
+  }
+}
+
+/**
+ * Report the counters of the last parse or dump call
+ *
+ * Only builds with the `instrumentation` feature (`npm run build:instrumented`) count; other
+ * builds return null.
+ *
+ * @returns {{bytesAllocated: number, peakBytes: number, peakTreeBytes: number,
+ *   phases: {load: number, convert: number, jsonParse: number, emit: number}}|null} Bytes
+ *   allocated, most bytes held at once, approximate node tree size and milliseconds per phase
+ */
+function instrumentationYAML() {
+  const module = getWasmModule();
+  return typeof module.instrumentation === 'function' ? module.instrumentation() : null;
+}
+
 /**
  * Get the version of the fast-yaml library
  *
@@ -224,11 +1524,54 @@ function getVersion() {
 module.exports = {
   parse: parseYAML,
   parseAll: parseAllYAML,
+  parseBatch: parseBatchYAML,
+  createParser: createParserYAML,
+  parseLenient: parseLenientYAML,
+  parseWithMeta: parseWithMetaYAML,
+  parseBytes: parseBytesYAML,
+  allocInput: allocInputYAML,
+  parseInPlace: parseInPlaceYAML,
+  parseToJsonString: parseToJsonStringYAML,
+  parsePreview: parsePreviewYAML,
+  parseLazy: parseLazyYAML,
+  parseToHandle: parseToHandleYAML,
+  parseAsync: parseAsyncYAML,
   load: loadYAML,
   loadAll: loadAllYAML,
+  registerType: registerTypeYAML,
+  registerPlugin: registerPluginYAML,
+  dump: dumpYAML,
+  estimateDumpSize: estimateDumpSizeYAML,
   validate: validateYAML,
+  compileSchema: compileSchemaYAML,
+  validateBatch: validateBatchYAML,
+  skeleton: skeletonYAML,
+  verifyExamples: verifyExamplesYAML,
   query: queryYAML,
+  compilePath: compilePathYAML,
+  queryMany: queryManyYAML,
+  createQuerySession: createQuerySessionYAML,
+  analyzePath: analyzePathYAML,
+  compareWithReference: compareWithReferenceYAML,
+  analyzeAliases: analyzeAliasesYAML,
+  toDot: toDotYAML,
   parseStream: parseStreamYAML,
+  createStreamParser: createStreamParserYAML,
+  restoreStreamParser: restoreStreamParserYAML,
+  parseFromStream: parseFromStreamYAML,
+  documentStream: documentStreamYAML,
+  parseDocuments: parseDocumentsYAML,
+  indexDocuments: indexDocumentsYAML,
+  countDocuments: countDocumentsYAML,
+  splitDocuments: splitDocumentsYAML,
+  extractDocument: extractDocumentYAML,
+  transformStream: transformStreamYAML,
+  pipeline: pipelineYAML,
+  instrumentation: instrumentationYAML,
   version: getVersion,
+  FAILSAFE_SCHEMA,
+  JSON_SCHEMA,
+  CORE_SCHEMA,
+  DEFAULT_SCHEMA,
   YAMLException, // Export YAMLException for users who need to catch or check error types
 };
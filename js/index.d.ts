@@ -4,6 +4,307 @@
  * Type definitions for TypeScript
  */
 
+/**
+ * Schema used to resolve scalars
+ */
+export type Schema = 'failsafe' | 'json' | 'core' | 'default';
+
+export const FAILSAFE_SCHEMA: 'failsafe';
+export const JSON_SCHEMA: 'json';
+export const CORE_SCHEMA: 'core';
+export const DEFAULT_SCHEMA: 'default';
+
+/**
+ * Options accepted by parse, parseAll, load and loadAll
+ */
+/**
+ * `JSON.parse`-style reviver, called with the holding object, array or `Map` as `this`
+ */
+export type JsonReviver = (this: any, key: any, value: any) => any;
+
+/**
+ * Scalar passed to the `reviver` load option
+ */
+export interface ReviverContext {
+  /** Location of the value (e.g. `$.servers[0].timeout`) */
+  path: string;
+  /** Explicit tag (`!duration`) or the core tag the value resolved to (`tag:yaml.org,2002:str`) */
+  tag: string;
+  /** Scalar text as written in the document */
+  source: string;
+}
+
+export interface LoadOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /** Schema used to resolve scalars (default: DEFAULT_SCHEMA) */
+  schema?: Schema;
+  /**
+   * YAML version of the plain scalar rules (default: '1.2'). With '1.1', `yes`/`no`, `on`/`off`
+   * and `y`/`n` are booleans as well, `0b1010` is a binary integer and `017` an octal one
+   * (core and default schemas only). `0o17` and `0x1F` are integers either way
+   */
+  version?: '1.1' | '1.2';
+  /**
+   * Resolve YAML 1.1 base 60 numbers such as `1:30:00` (5400) and `1:30.5` (90.5); off by
+   * default so values like `22:22` stay strings (core and default schemas only)
+   */
+  sexagesimal?: boolean;
+  /**
+   * Read every untagged plain scalar as a string, whatever the schema: `NO`, `1.0`, `~` and
+   * empty values stay text. Explicit tags such as `!!int` still apply
+   */
+  stringifyScalars?: boolean;
+  /**
+   * Read plain scalars whose meaning differs between YAML 1.1 and 1.2 as strings: `no`/`on`
+   * booleans, `022` octals, `0b101` binaries and `1:30` base 60 numbers. Each one is reported
+   * to `onWarning` with its position, so they can be found and quoted. Takes precedence over
+   * `version` and `sexagesimal` (core and default schemas only)
+   */
+  ambiguousAsString?: boolean;
+  /** Return a source map along with the JSON string (parseToJsonString only) */
+  sourceMap?: boolean;
+  /**
+   * Keep scanning after a syntax error and report every one: the thrown exception lists
+   * them all in `errors` (parse, parseAll, load and loadAll without an iterator)
+   */
+  allErrors?: boolean;
+  /**
+   * Return `{ value, timing }`, where `value` is what the call returns otherwise and `timing`
+   * holds the milliseconds the call spent in each of its parts (parse, parseAll, parseBatch,
+   * parseLenient, parseWithMeta, parseToJsonString, parsePreview, parseBytes, parseInPlace,
+   * extractDocument, load, loadAll and createParser); parseLazy, parseToHandle, parseAsync,
+   * the stream parsers, transformStream, pipeline and compareWithReference throw with it
+   */
+  withTiming?: boolean;
+  /** Round floats to this many significant digits (1-17) */
+  floatPrecision?: number;
+  /**
+   * Float representation: plain numbers (default), numbers keeping a decimal point in JSON
+   * output (`1.0`), or `{ $float: value }` wrappers distinguishable from integers
+   */
+  floatMode?: 'number' | 'decimal' | 'wrap';
+  /**
+   * Return integers outside the safe `Number` range (beyond ±2^53 - 1) as `BigInt` (parse,
+   * parseAll, load and loadAll)
+   */
+  useBigInt64?: boolean;
+  /**
+   * Return integers outside the safe `Number` range as decimal strings, reporting each to
+   * `onWarning`. Takes precedence over `useBigInt64`
+   */
+  largeIntAsString?: boolean;
+  /**
+   * Return mappings as `Map` so integer, boolean and null keys keep their type
+   * (parse, parseAll, load and loadAll)
+   */
+  mapAsMap?: boolean;
+  /**
+   * Return `!!set` mappings as `Set` instead of objects with null values (parse, parseAll, load
+   * and loadAll)
+   */
+  setAsSet?: boolean;
+  /** Decode `!!binary` scalars to `Uint8Array` (parse, parseAll, load and loadAll) */
+  decodeBinary?: boolean;
+  /**
+   * Resolve timestamps (`2001-12-14`, `!!timestamp`) to `Date` like js-yaml (parse, parseAll,
+   * load and loadAll; `parseToJsonString` writes ISO 8601 strings)
+   */
+  timestamps?: boolean;
+  /**
+   * Resolve `!duration` scalars (`250ms`, `1h30m`) to milliseconds and `!bytes` scalars
+   * (`512Mi`, `2GB`) to bytes; values that do not parse are errors
+   */
+  units?: boolean;
+  /**
+   * Return nodes with a local tag that no registered type handles (`!Ref x`) as
+   * `{ tag: '!Ref', value: 'x' }` instead of dropping the tag
+   */
+  wrapUnknownTags?: boolean;
+  /**
+   * Empty values (`key:`, `- `): resolved by the schema (default), empty strings, or, for
+   * mapping values, omitted
+   */
+  emptyValue?: 'null' | 'string' | 'omit';
+  /**
+   * Value of input holding no document (empty, or only comments): `null` (default), or
+   * `undefined` like js-yaml, telling it apart from an explicit null document (`---`, `null`)
+   */
+  noDocument?: 'null' | 'undefined';
+  /**
+   * How values are built: as a JSON string read with `JSON.parse` (default, the faster), or
+   * directly. Both give the same result
+   */
+  conversion?: 'direct' | 'json';
+  /**
+   * Build only plain objects, arrays and primitives, so the result can be passed to
+   * `postMessage` as it is: combining it with `useBigInt64`, `mapAsMap`, `setAsSet`,
+   * `complexKeys: 'map'`, `timestamps` or `decodeBinary` throws, and a `constructFn`,
+   * `reviver` or `jsonReviver` returning anything else (functions, symbols, `BigInt`s, class
+   * instances) throws
+   */
+  cloneable?: boolean;
+  /**
+   * Collection keys (`? [a, b] : value`): properties named by the key's JSON encoding
+   * (default), `Map` output for mappings with such keys, or an error
+   */
+  complexKeys?: 'json' | 'map' | 'error';
+  /** Duplicate mapping keys silently override earlier values instead of throwing, as in JSON.parse */
+  json?: boolean;
+  /**
+   * Duplicate mapping keys: throw with both positions (default), keep the first or last
+   * value, or keep the last value and report it to `onWarning`. Takes precedence over `json`
+   */
+  duplicateKey?: 'error' | 'first' | 'last' | 'warn';
+  /**
+   * Rename string mapping keys: `camel` (`max_retries` to `maxRetries`), `snake` (`maxRetries`
+   * to `max_retries`) or a function returning the new name. Renamed keys are checked for duplicates
+   */
+  keyStyle?: KeyStyle;
+  /**
+   * Only keep string mapping keys matching one of these patterns, at every level (`*` matches
+   * any text)
+   */
+  includeKeys?: string[];
+  /** Drop string mapping keys matching one of these patterns, at every level (e.g. `['x-*']`) */
+  excludeKeys?: string[];
+  /** File name prefixed to error messages */
+  filename?: string;
+  /**
+   * Called for non-fatal issues instead of ignoring them: duplicate keys with
+   * `duplicateKey: 'warn'`, unknown tags, and YAML 1.1 syntax read differently by YAML 1.2
+   * (`yes`/`no` booleans, `0755` octals, `1:30:00` base 60 numbers; code `YAML11_SCALAR`)
+   */
+  onWarning?: (warning: YAMLException) => void;
+  /**
+   * Called for each scalar value (not keys); the return value replaces it unless `undefined`
+   * (parse, parseAll, load and loadAll)
+   */
+  reviver?: (value: unknown, context: ReviverContext) => unknown;
+  /**
+   * Called like `JSON.parse`'s reviver with every mapping and sequence member, bottom-up, and
+   * finally with the root under the key `''`; returning `undefined` removes the member
+   * (parse, parseAll, load and loadAll)
+   */
+  jsonReviver?: JsonReviver;
+  /** Maximum aliases expanded per document, counting nested expansions (default: 10000) */
+  maxAliasCount?: number;
+  /** Maximum nodes produced by alias expansion per document (default: 1000000) */
+  maxAliasExpansion?: number;
+  /**
+   * Approximate bytes of memory the loaded node trees, and separately the converted values, may
+   * take per call; past it the call throws instead of growing WebAssembly memory (default:
+   * Infinity)
+   */
+  maxMemoryBytes?: number;
+  /**
+   * Maximum levels of nested sequences and mappings, counting nesting through aliases
+   * (default: 512)
+   */
+  maxNestingDepth?: number;
+  /**
+   * Maximum number of documents in a stream; `parseStream` and `transformStream` handle the
+   * documents before the limit, then throw (default: Infinity)
+   */
+  maxDocuments?: number;
+  /** Maximum nodes loaded per document, an alias counting as one node (default: Infinity) */
+  maxNodes?: number;
+  /** Maximum length of a scalar, in characters (default: Infinity) */
+  maxScalarLength?: number;
+  /**
+   * Maximum length of the input, in UTF-8 bytes (default: Infinity); `parseStream` given
+   * chunks applies it to each document
+   */
+  maxInputSize?: number;
+  /**
+   * All resource limits in one object, also accepted by validate, validateBatch and query;
+   * limits given as options of their own take precedence
+   */
+  limits?: Limits;
+}
+
+/**
+ * Resource limits for loading untrusted YAML, as accepted by the `limits` option
+ */
+export interface Limits {
+  maxAliasCount?: number;
+  maxAliasExpansion?: number;
+  maxMemoryBytes?: number;
+  maxNestingDepth?: number;
+  maxDocuments?: number;
+  maxNodes?: number;
+  maxScalarLength?: number;
+  maxInputSize?: number;
+}
+
+/**
+ * Options accepted by dump (js-yaml compatible subset)
+ */
+/**
+ * Renaming of mapping keys, accepted by load functions and `dump`
+ */
+export type KeyStyle = 'camel' | 'snake' | ((key: string) => string);
+
+export interface DumpOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /**
+   * Scalar styles by tag. `!!null`: `canonical` (`~`), `lowercase` (`null`, default),
+   * `uppercase` (`NULL`), `camelcase` (`Null`) or `empty` (`key:`)
+   */
+  styles?: {
+    '!!null'?: 'canonical' | 'lowercase' | 'uppercase' | 'camelcase' | 'empty';
+  };
+  /** Rename string mapping keys before they are written, like the `keyStyle` load option */
+  keyStyle?: KeyStyle;
+  /** Write `%YAML` and `%TAG` directives and a `---` marker before the document */
+  directives?: Partial<Directives>;
+}
+
+/**
+ * The `%YAML` and `%TAG` directives of a document
+ */
+export interface Directives {
+  /** Version declared by `%YAML` (`'1.1'` or `'1.2'`), or null */
+  version: string | null;
+  /** Tag prefixes by handle declared by `%TAG` (`{ '!e!': 'tag:example.com,2000:' }`) */
+  tags: Record<string, string>;
+}
+
+/**
+ * Error thrown for invalid YAML, with js-yaml compatible properties
+ */
+export class YAMLException extends Error {
+  constructor(
+    message: string,
+    options?: {
+      mark?: any;
+      line?: number;
+      column?: number;
+      snippet?: string;
+      reason?: string;
+      unknownKeys?: string[];
+      suggestions?: Record<string, string>;
+      errors?: YAMLException[];
+      code?: string;
+    }
+  );
+  reason: string;
+  mark: any;
+  line: number | null;
+  column: number | null;
+  /** Lines around the error with a `^` under its column, also appended to the message */
+  snippet: string | null;
+  /** Option keys the function does not accept, for `Unknown options` errors */
+  unknownKeys: string[] | null;
+  /** Closest accepted option for each unknown key that looks like a typo of one */
+  suggestions: Record<string, string> | null;
+  /** Every syntax error of the input, the thrown one first, with the `allErrors` option */
+  errors: YAMLException[] | null;
+  /** Stable code of the kind of error (`DUPLICATE_KEY`, `TAB_INDENT`, `UNEXPECTED_EOF`, ...) */
+  code: string | null;
+}
+
 /**
  * Parse a YAML string into a JavaScript object
  * 
@@ -11,7 +312,7 @@
  * @param options - Parsing options
  * @returns Parsed JavaScript object
  */
-export function parse(input: string, options?: object): any;
+export function parse(input: string, options?: LoadOptions | JsonReviver): any;
 
 /**
  * Parse all YAML documents in a string into an array of JavaScript objects
@@ -20,7 +321,297 @@ export function parse(input: string, options?: object): any;
  * @param options - Parsing options
  * @returns Array of parsed JavaScript objects
  */
-export function parseAll(input: string, options?: object): any[];
+export function parseAll(input: string, options?: LoadOptions | JsonReviver): any[];
+
+/**
+ * Result of one input of `parseBatch`
+ */
+export interface BatchResult {
+  /** The first document; `undefined` when the input failed */
+  value: any;
+  /** The error the input failed with, or `null` */
+  error: YAMLException | null;
+}
+
+/**
+ * Parse the first document of each of many YAML strings in a single WASM call
+ *
+ * An input that fails does not affect the others: its entry holds the error instead.
+ *
+ * @param inputs - YAML strings to parse, such as front matter blocks
+ * @param options - Parsing options, applied to every input
+ * @returns One result per input, in input order
+ */
+export function parseBatch(inputs: string[], options?: LoadOptions): BatchResult[];
+
+/**
+ * Parser reused across calls, returned by `createParser`
+ */
+export interface Parser {
+  /** Parse the first document of a YAML string */
+  parse(input: string): any;
+  /** Parse all documents of a YAML string */
+  parseAll(input: string): any[];
+  /** Release the parser and its buffers */
+  free(): void;
+}
+
+/**
+ * Create a parser reused across calls with the same options
+ *
+ * The options are read once, and the parser keeps its buffers between calls. Tag types and
+ * plugins registered after the parser is created do not apply to it.
+ *
+ * @param options - Parsing options for every call
+ * @returns The parser
+ */
+export function createParser(options?: LoadOptions): Parser;
+
+/**
+ * Result of `parseLenient`
+ */
+export interface LenientResult {
+  /** The first document; null for an empty input */
+  value: any;
+  /** Every document that loaded */
+  documents: any[];
+  /** The errors recovered from, with their line and column */
+  errors: YAMLException[];
+}
+
+/**
+ * Parse a YAML stream, recovering from syntax errors instead of throwing on the first
+ *
+ * A broken document in a multi-document stream does not affect the others, and a badly
+ * indented line is skipped so the rest of its document still loads.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options
+ * @returns The documents that loaded and the errors recovered from
+ */
+export function parseLenient(input: string, options?: LoadOptions | JsonReviver): LenientResult;
+
+/**
+ * Result of `parseWithMeta`
+ */
+export interface ParseMeta {
+  /** The first document, as `parse` returns it */
+  value: any;
+  /** Number of documents in the stream */
+  documentCount: number;
+  /** Milliseconds spent loading and converting */
+  durationMs: number;
+  /** Non-fatal issues, as `onWarning` receives them */
+  warnings: YAMLException[];
+  /** Version the first document declares with `%YAML`, or null */
+  yamlVersion: string | null;
+}
+
+/**
+ * Parse the first document of a YAML string along with statistics about the parse
+ *
+ * Warnings are collected, and still passed to `onWarning` when it is set.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options
+ * @returns The first document and metadata about the parse
+ */
+export function parseWithMeta(input: string, options?: LoadOptions | JsonReviver): ParseMeta;
+
+/**
+ * Parse YAML bytes into a JavaScript object, detecting their encoding
+ * 
+ * A UTF-8, UTF-16LE or UTF-16BE byte order mark selects the encoding; without one, UTF-16 is
+ * recognized from the zero bytes of the first character and UTF-8 is assumed otherwise.
+ * 
+ * @param bytes - YAML file contents, e.g. a Buffer from fs.readFileSync
+ * @param options - Parsing options
+ * @returns Parsed JavaScript object
+ */
+export function parseBytes(bytes: Uint8Array, options?: LoadOptions | JsonReviver): any;
+
+/**
+ * Input buffer in WebAssembly memory, returned by `allocInput`
+ */
+export interface InputBuffer {
+  /** The size of the buffer in bytes */
+  readonly length: number;
+  /** A view over the buffer to write the input into; take a new one after any other call */
+  view(): Uint8Array;
+  /** Release the buffer */
+  free(): void;
+}
+
+/**
+ * Allocate a buffer in WebAssembly memory to read a large input into, for `parseInPlace`
+ *
+ * Bytes written into the buffer's `view()` are parsed where they are instead of being copied
+ * into WebAssembly memory.
+ *
+ * @param length - The size of the input in bytes
+ * @returns The zero-filled buffer
+ */
+export function allocInput(length: number): InputBuffer;
+
+/**
+ * Parse the bytes of a buffer from `allocInput`, detecting their encoding as `parseBytes` does
+ *
+ * @param buffer - The buffer holding the input
+ * @param options - Parsing options
+ * @returns Parsed JavaScript object
+ */
+export function parseInPlace(buffer: InputBuffer, options?: LoadOptions | JsonReviver): any;
+
+/**
+ * Parse a YAML string into a JSON string
+ *
+ * With `sourceMap: true`, also returns flat `(jsonOffset, line, column)` triples sorted by
+ * offset, one per emitted key or value. Offsets index the JSON string; positions are 1-based.
+ * `.inf`, `-.inf` and `.nan`, which JSON cannot represent, are written as `null` like
+ * `JSON.stringify` does; `parse` returns them as `Infinity`, `-Infinity` and `NaN`.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options
+ * @returns JSON string, or JSON string and source map
+ */
+export function parseToJsonString(input: string, options?: LoadOptions & { sourceMap?: false }): string;
+export function parseToJsonString(
+  input: string,
+  options: LoadOptions & { sourceMap: true }
+): { json: string; sourceMap: Uint32Array };
+
+/**
+ * Options of `parsePreview`
+ */
+export interface PreviewOptions extends LoadOptions {
+  /** Longest string kept, in characters (default: 1000) */
+  maxStringLength?: number;
+  /** Most sequence items kept; later items are never converted (default: 100) */
+  maxItems?: number;
+}
+
+/**
+ * A value cut by `parsePreview`
+ */
+export interface Truncation {
+  /** Location of the value (e.g. `$.items`) */
+  path: string;
+  kind: 'string' | 'array';
+  /** Length before truncation, in characters or items */
+  length: number;
+}
+
+/**
+ * Parse the first document of a YAML string for a preview, cutting long strings and sequences
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options and size limits
+ * @returns The truncated value and the values that were cut
+ */
+export function parsePreview(
+  input: string,
+  options?: PreviewOptions
+): { value: any; truncated: Truncation[] };
+
+/**
+ * Collection left unconverted by a `LazyDocument`
+ */
+export interface LazyHandle {
+  $handle: number;
+  kind: 'mapping' | 'sequence';
+}
+
+/**
+ * Document converted level by level, returned by `parseLazy`
+ */
+export interface LazyDocument {
+  /** Convert the top levels of the document; `null` for an empty input */
+  root(): any;
+  /** Convert the collection behind a handle (or its id) the same way */
+  expand(handle: LazyHandle | number): any;
+  /** Number of handles created so far */
+  readonly handleCount: number;
+  /** Release the parsed document */
+  free(): void;
+}
+
+/**
+ * Parse the first document of a YAML string for conversion on demand
+ *
+ * Collections more than `maxDepth` levels below the converted node are returned as handles.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options and the levels converted per call
+ * @returns The parsed document
+ */
+export function parseLazy(
+  input: string,
+  options?: LoadOptions & { maxDepth?: number }
+): LazyDocument;
+
+/**
+ * Parsed document kept in WebAssembly memory, returned by `parseToHandle`
+ */
+export interface DocumentEdit {
+  /** Position of the edit in UTF-16 code units, like a JavaScript string index */
+  offset: number;
+  /** Number of UTF-16 code units removed at the offset (default: 0) */
+  removed?: number;
+  /** Text inserted at the offset (default: '') */
+  inserted?: string;
+}
+
+export interface DocumentHandle {
+  /** Convert the document with the load options it was parsed with */
+  toJS(): any;
+  /**
+   * Convert only the value at a path of properties and indexes, such as
+   * `$.spec.containers[0]`; `undefined` when there is none
+   */
+  get(path: string): any;
+  /** Query the document with a YAMLPath expression */
+  query(path: string, options?: QueryOptions): any[];
+  /** Validate the document against a JSON Schema */
+  validate(schema: object, options?: ValidateOptions): ValidationResult;
+  /**
+   * Apply edits to the source and parse it again; when the changes fall within entries of a
+   * block mapping or sequence at the root, only those entries are parsed and spliced in
+   *
+   * @param edits - `removed` UTF-16 code units at `offset` replaced by `inserted`, applied in
+   *   order to the text left by the previous edits
+   * @returns Whether only the edited entries were parsed again
+   */
+  update(edits: DocumentEdit[]): boolean;
+  /** Release the parsed document */
+  free(): void;
+}
+
+/**
+ * Parse the first document of a YAML string into a handle kept in WebAssembly memory
+ *
+ * The handle converts, queries and validates the document without parsing the input again,
+ * and `update(edits)` parses again only the entries edits of the input touched.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options, applied by `toJS()`
+ * @returns The parsed document
+ */
+export function parseToHandle(input: string, options?: LoadOptions): DocumentHandle;
+
+/**
+ * Parse the first document of a YAML string without blocking the event loop
+ *
+ * Loading runs in slices of a few milliseconds with a macrotask between them.
+ *
+ * @param input - YAML string to parse
+ * @param options - Parsing options, a time limit in milliseconds and an abort signal
+ * @returns Parsed JavaScript object; rejects with a YAMLException once `timeoutMs` passes, or
+ *   with the signal's reason once it aborts
+ */
+export function parseAsync(
+  input: string,
+  options?: (LoadOptions & { timeoutMs?: number; signal?: AbortSignal }) | JsonReviver
+): Promise<any>;
 
 /**
  * Parse a YAML string with schema validation into a JavaScript object
@@ -29,7 +620,7 @@ export function parseAll(input: string, options?: object): any[];
  * @param options - Parsing options
  * @returns Parsed JavaScript object
  */
-export function load(input: string, options?: object): any;
+export function load(input: string, options?: LoadOptions | JsonReviver): any;
 
 /**
  * Parse all YAML documents in a string with schema validation into an array of JavaScript objects
@@ -38,48 +629,920 @@ export function load(input: string, options?: object): any;
  * @param options - Parsing options
  * @returns Array of parsed JavaScript objects
  */
-export function loadAll(input: string, options?: object): any[];
+export function loadAll(input: string, options?: LoadOptions): any[];
+
+/**
+ * Parse all YAML documents in a string into an array, as `loadAll(input, options)` does
+ *
+ * @param input - YAML string containing multiple documents
+ * @param iterator - No iterator
+ * @param options - Parsing options
+ * @returns Array of parsed JavaScript objects
+ */
+export function loadAll(input: string, iterator: null | undefined, options?: LoadOptions): any[];
+
+/**
+ * Parse all YAML documents in a string, passing each to an iterator as it is parsed
+ * 
+ * @param input - YAML string containing multiple documents
+ * @param iterator - Callback receiving each document; returning `false` stops parsing
+ * @param options - Parsing options
+ */
+export function loadAll(
+  input: string,
+  iterator: (document: any) => void | boolean,
+  options?: LoadOptions
+): void;
+
+/**
+ * Definition of a custom tag type, like a js-yaml Type
+ */
+export interface TypeDefinition {
+  /** Kind of node the tag applies to */
+  kind: 'scalar' | 'sequence' | 'mapping';
+  /** Build the value from the scalar text, or the converted array or object */
+  constructFn?: (data: any) => any;
+  /** Accept or reject the node's data; a falsy result makes the node an error */
+  resolveFn?: (data: any) => boolean;
+}
+
+/**
+ * Register a constructor for a tag such as `!Ref` or `!!python/tuple`
+ *
+ * Applies to every later parse, parseAll, load and loadAll call. Registering a tag again for
+ * the same kind replaces the previous definition.
+ *
+ * @param tag - Tag, starting with `!`
+ * @param definition - Node kind and callbacks
+ */
+export function registerType(tag: string, definition: TypeDefinition): void;
+
+/**
+ * A node as lint rules see it
+ */
+export interface LintNode {
+  /** Location of the node, e.g. `$.services[0].image` */
+  path: string;
+  kind: 'scalar' | 'sequence' | 'mapping';
+  /** Explicit tag the way it is written (`!Ref`), or null */
+  tag: string | null;
+  /** Source text of a scalar */
+  source?: string;
+}
+
+/**
+ * Extensions a plugin package registers
+ */
+export interface Plugin {
+  /** Name of the plugin */
+  name?: string;
+  /** Tags mapped to their type definitions, as with `registerType` */
+  types?: Record<string, TypeDefinition>;
+  /** Names for the `format` schema keyword mapped to checks of a string */
+  formats?: Record<string, (value: string) => boolean>;
+  /** Functions callable in YAMLPath filters as `name(@.path)`, true when they hold */
+  functions?: Record<string, (value: any) => boolean>;
+  /** Rules run over every loaded node; a returned message is reported to `onWarning` */
+  lintRules?: Record<string, (node: LintNode) => string | undefined>;
+}
+
+/**
+ * Register the extensions of a plugin package
+ *
+ * Registering a name again replaces the previous entry. Formats apply to `validate`,
+ * functions to YAMLPath queries, and lint rules to load calls given an `onWarning` callback.
+ *
+ * @param plugin - Extensions to register
+ */
+export function registerPlugin(plugin: Plugin): void;
+
+/**
+ * Serialize a JavaScript value as YAML
+ *
+ * Map keys keep their type, so values parsed with `mapAsMap` round-trip.
+ *
+ * @param value - Value to serialize
+ * @param options - Dump options
+ * @returns YAML text ending with a newline
+ */
+export function dump(value: any, options?: DumpOptions): string;
+
+/**
+ * Compute the size in UTF-8 bytes of the YAML text `dump` returns, without building it
+ *
+ * The value is laid out exactly as `dump` would, so the result can size buffers, choose
+ * between `dump` and streaming emission, or enforce size quotas.
+ *
+ * @param value - Value to measure
+ * @param options - Dump options
+ * @returns Size of the YAML text in bytes
+ */
+export function estimateDumpSize(value: any, options?: DumpOptions): number;
 
 /**
  * Validate a YAML document against a JSON Schema
  * 
  * @param yaml - YAML document to validate
- * @param schema - JSON Schema to validate against
+ * @param schema - JSON Schema to validate against, or a schema returned by compileSchema
+ * @param options - Validation options
  * @returns Validation result with success flag and any errors
  */
-export function validate(yaml: string, schema: object): {
+export function validate(
+  yaml: string,
+  schema: object | CompiledSchema,
+  options?: ValidateOptions
+): ValidationResult;
+
+/**
+ * Options for validate and validateBatch
+ */
+export interface ValidateOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /**
+   * Explain type errors on strings that look like locale-formatted numbers, such as
+   * '1,5' or '1.000,5', where the schema expects a number or integer
+   */
+  decimalCommaHints?: boolean;
+  /** Resource limits applied while loading each document */
+  limits?: Limits;
+}
+
+/**
+ * Result of validating a YAML document
+ */
+export interface ValidationResult {
   valid: boolean;
   errors: Array<{
+    /** Stable code of the failure, such as `REQUIRED` or `TYPE_MISMATCH` */
+    code: string;
     message: string;
     path: string;
   }>;
-};
+}
+
+/**
+ * A JSON Schema parsed once and reusable across validations
+ */
+export interface CompiledSchema {
+  /** Validate a YAML document, as `validate` does */
+  validate(yaml: string, options?: ValidateOptions): ValidationResult;
+  /** Validate a document from `parseToHandle`, as its `validate` does */
+  validateHandle(handle: DocumentHandle, options?: ValidateOptions): ValidationResult;
+  free(): void;
+}
+
+/**
+ * Compile a JSON Schema once for use with validate and validateBatch
+ *
+ * Passing a plain schema converts it from JavaScript on every call; a compiled schema is
+ * converted once.
+ *
+ * @param schema - JSON Schema to compile
+ * @returns Compiled schema
+ */
+export function compileSchema(schema: object): CompiledSchema;
+
+/**
+ * Validate many YAML documents against one compiled schema in a single call
+ *
+ * Documents are validated concurrently when threads are available. A document that fails to
+ * parse yields an invalid result carrying the parse error instead of aborting the batch.
+ *
+ * @param docs - YAML documents to validate
+ * @param schema - Schema returned by compileSchema
+ * @param options - Validation options, as for validate
+ * @returns Validation results in input order
+ */
+export function validateBatch(
+  docs: string[],
+  schema: CompiledSchema,
+  options?: ValidateOptions
+): ValidationResult[];
+
+/**
+ * Options for skeleton
+ */
+export interface SkeletonOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /** Comment out optional properties (default), write them as required ones, or omit them */
+  optional?: 'comment' | 'include' | 'omit';
+  /** Write each property's description (or title) as a comment above it (default true) */
+  descriptions?: boolean;
+  /** Nesting level past which objects are written as `{}` (default 16) */
+  maxDepth?: number;
+}
+
+/**
+ * Generate a starter YAML document from a JSON Schema
+ *
+ * Required properties get their `default`, `const`, `example`, first `examples` or `enum`
+ * value, or a placeholder for their type; optional properties are commented out. Local
+ * `$ref`s are followed, `allOf` members merged and arrays get `minItems` items.
+ *
+ * @param schema - JSON Schema describing the document
+ * @param options - Skeleton options
+ * @returns The YAML text, ending with a newline
+ */
+export function skeleton(schema: object, options?: SkeletonOptions): string;
+
+/**
+ * Result of checking one example embedded in a schema
+ */
+export interface ExampleResult extends ValidationResult {
+  /** JSON pointer to the subschema holding the example (`#/properties/port`) */
+  pointer: string;
+  /** Keyword the example comes from */
+  keyword: 'default' | 'example' | 'examples';
+  /** Position in `examples` */
+  index?: number;
+  /** The example written as YAML, with the `snippets` option */
+  yaml?: string;
+}
+
+/**
+ * Check every `default`, `example` and `examples` item of a schema against its subschema
+ *
+ * @param schema - JSON Schema to check
+ * @param options - `snippets` adds each example written as YAML
+ * @returns Whether every example is valid, and the result per example in schema order
+ */
+export function verifyExamples(
+  schema: object,
+  options?: { snippets?: boolean; strictOptions?: boolean }
+): { valid: boolean; examples: ExampleResult[] };
 
 /**
  * Query a YAML document using a YAMLPath expression
  * 
  * @param yaml - YAML document to query
- * @param path - YAMLPath expression
+ * @param path - YAMLPath expression or path returned by compilePath
  * @returns Array of matching values
  */
-export function query(yaml: string, path: string): any[];
+export function query(yaml: string, path: string | CompiledPath, options?: QueryOptions): any[];
 
 /**
- * Parse a YAML document in a streaming fashion
- * 
+ * Options accepted by query
+ */
+export interface QueryOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /**
+   * Restrict recursive descent (`..`) to the values of these mapping keys; sequences are
+   * always descended into
+   */
+  descendOnlyInto?: string[];
+  /**
+   * Called for each value that cannot be resolved (e.g. `!!int abc`) and is read as null.
+   * Receives a YAMLException from query and queryMany, and the message from compiled paths
+   */
+  onWarning?: (warning: YAMLException | string) => void;
+  /** Resource limits applied while loading the document */
+  limits?: Limits;
+}
+
+/**
+ * Cost of evaluating one segment of a compiled path
+ */
+export interface SegmentProfile {
+  /** The segment as written in the path (e.g. `..image`) */
+  segment: string;
+  /** Time spent evaluating the segment, in milliseconds */
+  timeMs: number;
+  /** Nodes inspected, including nodes inspected by filters */
+  nodesVisited: number;
+  /** Values the segment produced */
+  matches: number;
+}
+
+/**
+ * A YAMLPath expression parsed once and reusable across queries
+ */
+export interface CompiledPath {
+  /** The path in normalized form (e.g. `$.services[*].name`) */
+  readonly path: string;
+  /** Query a YAML document */
+  query(yaml: string, options?: QueryOptions): any[];
+  /** Query a document from `parseToHandle`, parsing neither the path nor the document again */
+  queryHandle(handle: DocumentHandle, options?: QueryOptions): any[];
+  /** Query a YAML document, measuring the cost of every segment */
+  profile(
+    yaml: string,
+    options?: QueryOptions
+  ): { results: any[]; loadMs: number; segments: SegmentProfile[] };
+  free(): void;
+}
+
+/**
+ * Compile a YAMLPath expression once for repeated queries
+ *
+ * @param path - YAMLPath expression
+ * @returns Compiled path
+ */
+export function compilePath(path: string): CompiledPath;
+
+/**
+ * Query many YAML documents with one path in a single call
+ *
+ * A document that fails to parse throws an error naming its index.
+ *
+ * @param docs - YAML documents to query
+ * @param path - YAMLPath expression or path returned by compilePath
+ * @param options - Query options
+ * @returns Matching values per document, in input order
+ */
+export function queryMany(
+  docs: string[],
+  path: string | CompiledPath,
+  options?: QueryOptions
+): any[][];
+
+/**
+ * A document retained for relative queries, returned by createQuerySession
+ */
+export interface QuerySession {
+  /** Query from the current location, or from the root if the path starts with `$` */
+  query(path: string): any[];
+  /** Move the current location; throws if the path matches nothing */
+  cd(path: string): string;
+  /** Move the current location to its parent */
+  up(): string;
+  /** Current location in normalized form, such as `$.spec.containers` */
+  readonly cwd: string;
+  /** Absolute form of every path queried or moved to, oldest first */
+  readonly history: string[];
+  /** Number of distinct paths compiled so far */
+  readonly cacheSize: number;
+  /** Release the document held by the session */
+  free(): void;
+}
+
+/**
+ * Parse a YAML document once for an interactive query session
+ *
+ * Paths starting with `.` or `[` are relative to the session's current location.
+ *
+ * @param yaml - YAML document to query
+ * @param options - Query options
+ * @returns The session, located at the root
+ */
+export function createQuerySession(yaml: string, options?: QueryOptions): QuerySession;
+
+/**
+ * Static estimate of what a path can select
+ */
+export interface PathAnalysis {
+  /** Whether any valid document can produce a match */
+  canMatch: boolean;
+  /** Upper bound on the number of matches, or null when unbounded */
+  maxMatches: number | null;
+  /** Summary of the bound */
+  selectivity: 'none' | 'atMostOne' | 'bounded' | 'unbounded';
+  /** First segment that rules out every match, if any */
+  blockedAt: string | null;
+}
+
+/**
+ * Estimate how many values a path can select from documents valid against a JSON Schema
+ *
+ * The bound is conservative: filters are assumed to keep every item, and `allOf` branches
+ * are only partially intersected.
+ *
+ * @param path - YAMLPath expression
+ * @param schema - JSON Schema the documents are valid against
+ * @returns The analysis result
+ */
+export function analyzePath(path: string, schema: object): PathAnalysis;
+
+/**
+ * A semantic difference between the parse result and a reference
+ */
+export interface ReferenceDifference {
+  /** Location of the difference (e.g. `$.services[0].port`) */
+  path: string;
+  /**
+   * `type` and `value` for mismatches, `missing` for values only in the reference, `extra`
+   * for values only in the parse result
+   */
+  kind: 'type' | 'value' | 'missing' | 'extra';
+  /** Value in the reference, undefined when missing */
+  expected?: any;
+  /** Value produced by fast-yaml, undefined when missing */
+  actual?: any;
+}
+
+/**
+ * Compare the parse result of a YAML document with a reference JSON dump
+ *
+ * Numbers compare by value and mapping key order is ignored.
+ *
  * @param yaml - YAML document to parse
- * @param callback - Callback function to receive parsed chunks
- * @param options - Parsing options
- * @returns Promise that resolves when parsing is complete
+ * @param reference - Expected result as a JSON string (e.g. `JSON.stringify(jsyaml.load(yaml))`)
+ * @param options - Load options used to parse the document
+ * @returns Whether the results are equal, and every difference found
+ */
+export function compareWithReference(
+  yaml: string,
+  reference: string,
+  options?: LoadOptions
+): { equal: boolean; differences: ReferenceDifference[] };
+
+/**
+ * Usage of an anchor reported by `analyzeAliases`
+ */
+export interface AnchorUsage {
+  /** Anchor name, without `&` */
+  anchor: string;
+  /** Index of the document holding the anchor */
+  document: number;
+  /** Line of the anchor (1-based) */
+  line: number;
+  /** Column of the anchor (1-based) */
+  column: number;
+  /** Number of aliases written to the anchor */
+  aliases: number;
+  /** Number of nodes in the anchored node once expanded */
+  nodes: number;
+  /** Nodes added by expanding the anchor's aliases, including repeated expansions */
+  expandedNodes: number;
+}
+
+/**
+ * Report how much each anchor contributes to the expanded size of a YAML stream
+ *
+ * Sizes are computed without expanding any alias, so documents too large to load can be
+ * inspected before raising `maxAliasCount` or `maxAliasExpansion`.
+ *
+ * @param input - YAML stream to analyze
+ * @returns Expanded node count of every document, and each anchor in source order
+ */
+export function analyzeAliases(input: string): { nodes: number; anchors: AnchorUsage[] };
+
+/**
+ * Options accepted by `toDot`
+ */
+export interface GraphOptions {
+  /** Throw a YAMLException on keys this function does not read instead of ignoring them */
+  strictOptions?: boolean;
+  /** Output format: DOT text (default) or node and edge lists */
+  format?: 'dot' | 'json';
+  /** Add a dashed edge from each alias to its anchored node */
+  aliases?: boolean;
+  /** Longest scalar text kept in a label before it is cut with `...` (default: 40) */
+  maxLabelLength?: number;
+}
+
+/**
+ * A node of the graph returned by `toDot` with `format: 'json'`
+ */
+export interface GraphNode {
+  id: number;
+  kind: 'document' | 'mapping' | 'sequence' | 'scalar' | 'alias';
+  /** Anchor, tag and contents (`&base {}`, `!Ref name`, `*base`) */
+  label: string;
+  line: number;
+  column: number;
+}
+
+/**
+ * An edge of the graph returned by `toDot` with `format: 'json'`
+ */
+export interface GraphEdge {
+  from: number;
+  to: number;
+  /** `child` for containment, `alias` from an alias to its anchored node */
+  kind: 'child' | 'alias';
+  /** Mapping key or sequence index leading to the child; `key` and `value` for collection keys */
+  label?: string;
+}
+
+/**
+ * Export the node structure of a YAML stream as a Graphviz DOT graph
+ *
+ * Each document is an ellipse linked to its root; edges are labeled with the mapping key or
+ * sequence index leading to each node.
+ *
+ * @param input - YAML stream to export
+ * @param options - Graph options
+ * @returns DOT text, or node and edge lists with `format: 'json'`
+ */
+export function toDot(input: string, options?: GraphOptions & { format?: 'dot' }): string;
+export function toDot(
+  input: string,
+  options: GraphOptions & { format: 'json' }
+): { nodes: GraphNode[]; edges: GraphEdge[] };
+
+/**
+ * A document parsed by `parseStream`
+ */
+export interface StreamChunk {
+  /** Position of the document in the stream */
+  index: number;
+  /** The parsed document */
+  value: any;
+  /** UTF-8 byte offset where the document's source starts */
+  start: number;
+  /** UTF-8 byte offset where the document's source ends */
+  end: number;
+  /** Hex FNV-1a 64-bit hash of the document's source, with the `checksum` option */
+  hash?: string;
+  /** The document's `%YAML` and `%TAG` directives, with the `directives` option */
+  directives?: Directives;
+}
+
+/**
+ * Items of the sequence at the root of a document, passed on by `parseStream` with the
+ * `sequenceChunk` option
+ */
+export interface SequenceChunk {
+  /** Position of the document in the stream */
+  index: number;
+  /** Position of the first item in the sequence */
+  item: number;
+  /** The parsed items */
+  value: any[];
+}
+
+/**
+ * Options for `parseStream`
+ */
+export interface StreamOptions extends LoadOptions {
+  /** Add a hash of each document's source to its chunk */
+  checksum?: boolean;
+  /** Add the `%YAML` and `%TAG` directives of each document to its chunk */
+  directives?: boolean;
+  /**
+   * Pass the items of an untagged sequence at the root of a document on this many at a time
+   * as they are loaded, rather than the whole document at once
+   */
+  sequenceChunk?: number;
+  /**
+   * Signal stopping the async entry points with its reason, an AbortError by default, as soon
+   * as it aborts
+   */
+  signal?: AbortSignal;
+  /**
+   * Callback receiving the progress through the stream each time the documents passed on
+   * reach `progressInterval` more bytes of it, and once more at its end
+   */
+  onProgress?: (progress: StreamProgress) => void;
+  /** Bytes of the stream between two `onProgress` calls, 1 MiB by default */
+  progressInterval?: number;
+}
+
+/**
+ * Progress through a YAML stream, passed to the `onProgress` option
  */
+export interface StreamProgress {
+  /** UTF-8 bytes of the stream read through the end of the last document passed on */
+  bytesProcessed: number;
+  /** Number of documents passed on so far */
+  documentsEmitted: number;
+}
+
+/**
+ * Parse the documents of a YAML stream one at a time
+ *
+ * The stream is a string, or an iterable or async iterable of string or Uint8Array chunks,
+ * such as a file read stream. Chunks are parsed as they arrive and only the text of the
+ * document being received is kept, so memory is bounded by the largest document; `maxInputSize`
+ * then applies to each document. With `sequenceChunk`, a document holding a sequence at its
+ * root is passed on a few items at a time as it is loaded, so even one long sequence is not
+ * held in memory at once. Returning `false` from `callback` stops parsing there, and no more
+ * chunks are read. For async iterables, aborting `signal` rejects the promise with its reason
+ * without waiting for the next chunk.
+ *
+ * A string or synchronous iterable is parsed before the call returns, and the count is returned
+ * directly; async iterables, whose chunks arrive over time, give a promise, as does every call
+ * through the ES module entry point.
+ *
+ * @param yaml - YAML stream to parse
+ * @param callback - Callback receiving each document as soon as it is parsed
+ * @param options - Load options, `checksum`, `directives`, `sequenceChunk`, `signal` and
+ *   `onProgress`
+ * @returns Number of documents parsed, as a promise for async iterables
+ */
+export function parseStream(
+  yaml: string | Iterable<string | Uint8Array>,
+  callback: (chunk: StreamChunk | SequenceChunk) => void | boolean,
+  options: StreamOptions & { sequenceChunk: number }
+): number;
+export function parseStream(
+  yaml: AsyncIterable<string | Uint8Array>,
+  callback: (chunk: StreamChunk | SequenceChunk) => void | boolean,
+  options: StreamOptions & { sequenceChunk: number }
+): Promise<number>;
 export function parseStream(
+  yaml: string | Iterable<string | Uint8Array>,
+  callback: (chunk: StreamChunk) => void | boolean,
+  options?: StreamOptions
+): number;
+export function parseStream(
+  yaml: AsyncIterable<string | Uint8Array>,
+  callback: (chunk: StreamChunk) => void | boolean,
+  options?: StreamOptions
+): Promise<number>;
+
+/**
+ * Parser of a YAML stream pushed to it in chunks, returned by `createStreamParser`
+ */
+export interface StreamParser {
+  /**
+   * Receive the next chunk of the stream, passing every document it completes to the callback;
+   * Uint8Array chunks are decoded as UTF-8, also when a character is split between two of them
+   */
+  write(chunk: string | Uint8Array): void;
+  /** Pass the last document to the callback, release the parser and return the document count */
+  end(): number;
+  /** Release the parser without parsing the rest of the stream */
+  free(): void;
+  /** Export the state of the parser, to resume parsing with `restoreStreamParser` */
+  checkpoint(): StreamCheckpoint;
+  /** Whether the callback stopped parsing by returning `false`; later chunks are ignored */
+  readonly stopped: boolean;
+}
+
+/**
+ * State of a stream parser, returned by `StreamParser.checkpoint()`; a plain object
+ * `JSON.stringify` can save, to be given back to `restoreStreamParser` as it is
+ */
+export interface StreamCheckpoint {
+  /** Format of the checkpoint */
+  version: 1;
+  /** UTF-8 bytes of the stream written to the parser; the restored parser takes what follows */
+  bytesReceived: number;
+  /** Documents passed to the callback */
+  count: number;
+  /** Whether the callback stopped parsing by returning `false` */
+  stopped: boolean;
+  [state: string]: unknown;
+}
+
+/**
+ * Create a parser for a YAML stream pushed to it in chunks
+ *
+ * Documents are passed to `callback` as `parseStream` passes them, as soon as the chunks
+ * written complete them, so data arriving from a socket or a chunked HTTP response is parsed
+ * as it arrives without buffering the whole body.
+ *
+ * @param callback - Callback receiving each document as soon as it is parsed
+ * @param options - Load options, `checksum`, `directives`, `sequenceChunk` and `onProgress`
+ * @returns The parser
+ */
+export function createStreamParser(
+  callback: (chunk: StreamChunk | SequenceChunk) => void | boolean,
+  options?: StreamOptions
+): StreamParser;
+
+/**
+ * Create a parser for a YAML stream resuming from a checkpoint of another one
+ *
+ * Write the stream following its `bytesReceived` first bytes to the parser; documents keep
+ * their index, byte offsets and error lines in the whole stream.
+ *
+ * @param checkpoint - Checkpoint returned by `StreamParser.checkpoint()`
+ * @param callback - Callback receiving each document as soon as it is parsed
+ * @param options - The options of `createStreamParser`
+ * @returns The parser
+ */
+export function restoreStreamParser(
+  checkpoint: StreamCheckpoint,
+  callback: (chunk: StreamChunk | SequenceChunk) => void | boolean,
+  options?: StreamOptions
+): StreamParser;
+
+/**
+ * The part of a WHATWG `ReadableStream` that `parseFromStream` and `parseDocuments` read, so
+ * that no DOM typings are needed
+ */
+export interface ReadableStreamLike {
+  getReader(): {
+    read(): Promise<{ done: boolean; value?: string | Uint8Array }>;
+    cancel(reason?: any): Promise<void>;
+    releaseLock(): void;
+  };
+}
+
+/**
+ * Parse the documents of a WHATWG ReadableStream as its chunks arrive
+ *
+ * A response body such as `fetch(url).body` is parsed while it downloads, in browsers, Deno and
+ * Bun as in Node.js. Chunks are strings or Uint8Arrays decoded as UTF-8. When parsing fails, or
+ * `onDocument` throws or returns `false` to stop parsing, the stream is cancelled. So it is
+ * when `signal` aborts, and the promise rejects with its reason.
+ *
+ * @param readable - Stream of the YAML text
+ * @param onDocument - Callback receiving each document as soon as it is parsed
+ * @param options - Load options, `checksum`, `directives`, `sequenceChunk`, `signal` and
+ *   `onProgress`
+ * @returns Number of documents parsed
+ */
+export function parseFromStream(
+  readable: ReadableStreamLike,
+  onDocument: (chunk: StreamChunk | SequenceChunk) => void | boolean,
+  options?: StreamOptions
+): Promise<number>;
+
+/**
+ * Create a TransformStream parsing YAML text into its documents
+ *
+ * Strings or Uint8Arrays written to the stream come out as the parsed value of each document,
+ * as soon as the text written completes it, so `fetch(url).body.pipeThrough(documentStream())`
+ * reads the documents of a response while it downloads. With `sequenceChunk`, the items of a
+ * sequence at the root of a document come out one at a time instead, as they are loaded.
+ * Aborting `signal` errors both sides of the stream with its reason.
+ *
+ * @param options - Load options, `sequenceChunk`, `signal` and `onProgress`
+ * @returns Stream of the documents' values
+ */
+export function documentStream(
+  options?: Omit<StreamOptions, 'checksum' | 'directives'>
+): TransformStream<string | Uint8Array, any>;
+
+/**
+ * Iterate over the documents of a YAML stream
+ *
+ * The stream is a string, a Uint8Array, an iterable or async iterable of string or Uint8Array
+ * chunks such as a file read stream, or a WHATWG ReadableStream. Input is read only as the
+ * documents are consumed, so `for await (const doc of parseDocuments(src))` holds no more
+ * than the document being read, however large the stream, and stopping early cancels a
+ * ReadableStream. With `sequenceChunk`, the items of a sequence at the root of a document are
+ * iterated over one at a time instead, as they are loaded. Aborting `signal` makes the
+ * iteration throw its reason, without waiting for the next chunk.
+ *
+ * @param input - YAML stream
+ * @param options - Load options, `sequenceChunk`, `signal` and `onProgress`
+ * @returns The value of each document
+ */
+export function parseDocuments(
+  input:
+    | string
+    | Uint8Array
+    | Iterable<string | Uint8Array>
+    | AsyncIterable<string | Uint8Array>
+    | ReadableStreamLike,
+  options?: Omit<StreamOptions, 'checksum' | 'directives'>
+): AsyncGenerator<any, void, undefined>;
+
+/**
+ * The UTF-8 byte range of a document's source, found by `indexDocuments`
+ */
+export interface DocumentRange {
+  /** UTF-8 byte offset where the document's directives or `---` marker start */
+  start: number;
+  /** UTF-8 byte offset of the next document's `---` marker, or past this one's `...` marker */
+  end: number;
+}
+
+/**
+ * Find the documents of a YAML stream without parsing them
+ *
+ * A light scanner follows quoted and block scalars, so a `---` or `...` line inside one is not
+ * taken for a document marker, and even a very large concatenated dump is indexed at about the
+ * speed of reading it. Each range can then be parsed on its own, e.g.
+ * `parseBytes(buffer.subarray(start, end))`; errors inside a document are only
+ * found then.
+ *
+ * @param input - YAML stream, e.g. a Buffer from fs.readFileSync
+ * @returns The byte range of each document's source
+ */
+export function indexDocuments(input: string | Uint8Array): DocumentRange[];
+
+/**
+ * Count the documents of a YAML stream without parsing them, as `indexDocuments` finds them
+ *
+ * @param input - YAML stream
+ * @returns Number of documents
+ */
+export function countDocuments(input: string | Uint8Array): number;
+
+/**
+ * Split a YAML stream into the source of each document without parsing them, as
+ * `indexDocuments` finds them
+ *
+ * @param input - YAML stream
+ * @returns Source of each document: strings for a string input, and views of the input's bytes
+ *   for a Uint8Array
+ */
+export function splitDocuments(input: string): string[];
+export function splitDocuments(input: Uint8Array): Uint8Array[];
+
+/**
+ * Parse one document of a YAML stream without parsing the others
+ *
+ * The stream is scanned as `indexDocuments` does up to the document at `index`, counted from 0,
+ * and only that document is parsed. Errors give lines counted from the start of the stream.
+ *
+ * @param input - YAML stream
+ * @param index - Index of the document
+ * @param options - Parsing options
+ * @returns The document, or what `parse` returns for an empty input when the stream holds no
+ *   document at `index`
+ */
+export function extractDocument(input: string, index: number, options?: LoadOptions): any;
+
+/**
+ * Rewrite the documents of a YAML stream on the fly
+ *
+ * Each document is parsed, passed to `transform`, and the result is emitted as YAML to
+ * `output` before the next document is parsed. Returning `undefined` drops the document.
+ *
+ * @param yaml - YAML stream to rewrite
+ * @param transform - Callback receiving each document and its index
+ * @param output - Callback receiving the YAML text of each rewritten document
+ * @param options - Load options used to parse the documents
+ * @returns Number of documents emitted
+ */
+export function transformStream(
   yaml: string,
-  callback: (chunk: any) => void,
-  options?: {
-    chunkSize?: number;
-    [key: string]: any;
-  }
-): Promise<void>;
+  transform: (document: any, index: number) => any,
+  output: (yaml: string) => void,
+  options?: LoadOptions
+): number;
+
+/**
+ * A step of `pipeline`, given by name or as an object with an `op` and its parameters
+ */
+export type PipelineStep =
+  | 'resolveAliases'
+  | 'mergeKeys'
+  | 'env'
+  | 'sortKeys'
+  | 'emit'
+  | { op: 'resolveAliases' | 'mergeKeys' | 'sortKeys' }
+  /** Replace `${NAME}` and `${NAME:-default}` in string values (default: `process.env`) */
+  | { op: 'env'; env?: Record<string, string | undefined> }
+  /** Convert scalars to the types a JSON Schema expects (`'8080'` to `8080`) */
+  | { op: 'coerce'; schema: object }
+  /** Throw unless the document matches a JSON Schema */
+  | { op: 'validate'; schema: object }
+  /** Replace the values YAMLPath expressions match (default replacement: `'[REDACTED]'`) */
+  | { op: 'redact'; paths: string[]; replacement?: any }
+  /** Replace the values YAMLPath expressions match with `value` */
+  | { op: 'set'; match: string | string[]; value: any }
+  /** Remove the values YAMLPath expressions match from their mappings and sequences */
+  | { op: 'delete'; match: string | string[] }
+  /** Rename the keys of the mapping values YAMLPath expressions match */
+  | { op: 'rename'; match: string | string[]; to: string }
+  /** Write the documents as YAML, or as one JSON text per line (must come last) */
+  | { op: 'emit'; format?: 'yaml' | 'json' };
+
+/**
+ * Run built-in steps over each document of a YAML stream in one call
+ *
+ * Steps run in order without leaving WebAssembly. Aliases are expanded only when the steps
+ * include `resolveAliases`, and `<<` keys are merged only by `mergeKeys`.
+ *
+ * @param input - YAML stream to process
+ * @param steps - Steps run over each document, or the YAML text of a sequence of them
+ * @param options - Load options used to parse the documents
+ * @returns The processed documents, as YAML or JSON
+ */
+export function pipeline(
+  input: string,
+  steps: PipelineStep[] | string,
+  options?: LoadOptions
+): string;
+
+/**
+ * Counters of the last parse or dump call
+ */
+export interface Instrumentation {
+  /** Bytes allocated during the call */
+  bytesAllocated: number;
+  /** Most bytes the call held allocated at once */
+  peakBytes: number;
+  /** Approximate size in bytes of the largest node trees loaded */
+  peakTreeBytes: number;
+  /** Milliseconds spent building node trees, converting, in `JSON.parse` and writing YAML */
+  phases: { load: number; convert: number; jsonParse: number; emit: number };
+}
+
+/**
+ * Milliseconds a call made with the `withTiming` option spent in each of its parts
+ */
+export interface Timing {
+  /** Scanning the YAML text into events */
+  scan: number;
+  /** Building node trees from the events */
+  build: number;
+  /** Converting the node trees to JavaScript values, `JSON.parse` included */
+  convert: number;
+  /** The whole call */
+  total: number;
+}
+
+/**
+ * Report the counters of the last parse, parseAll, parseBytes, parseToJsonString,
+ * parsePreview, load, loadAll, dump or pipeline call
+ * 
+ * Only builds with the `instrumentation` feature (`npm run build:instrumented`) count; other
+ * builds return null.
+ * 
+ * @returns Counters of the last call, or null
+ */
+export function instrumentation(): Instrumentation | null;
 
 /**
  * Get the version of the fast-yaml library
@@ -91,21 +1554,103 @@ export function version(): string;
 // Legacy aliases for compatibility
 export const parseYAML: typeof parse;
 export const parseAllYAML: typeof parseAll;
+export const parseBatchYAML: typeof parseBatch;
+export const createParserYAML: typeof createParser;
+export const parseLenientYAML: typeof parseLenient;
+export const parseWithMetaYAML: typeof parseWithMeta;
+export const parseBytesYAML: typeof parseBytes;
+export const allocInputYAML: typeof allocInput;
+export const parseInPlaceYAML: typeof parseInPlace;
+export const parseToJsonStringYAML: typeof parseToJsonString;
+export const parsePreviewYAML: typeof parsePreview;
+export const parseLazyYAML: typeof parseLazy;
+export const parseToHandleYAML: typeof parseToHandle;
+export const parseAsyncYAML: typeof parseAsync;
 export const loadYAML: typeof load;
 export const loadAllYAML: typeof loadAll;
+export const registerTypeYAML: typeof registerType;
+export const registerPluginYAML: typeof registerPlugin;
+export const dumpYAML: typeof dump;
+export const estimateDumpSizeYAML: typeof estimateDumpSize;
 export const validateYAML: typeof validate;
+export const compileSchemaYAML: typeof compileSchema;
+export const validateBatchYAML: typeof validateBatch;
+export const skeletonYAML: typeof skeleton;
+export const verifyExamplesYAML: typeof verifyExamples;
 export const queryYAML: typeof query;
+export const compilePathYAML: typeof compilePath;
+export const queryManyYAML: typeof queryMany;
+export const createQuerySessionYAML: typeof createQuerySession;
+export const analyzePathYAML: typeof analyzePath;
+export const compareWithReferenceYAML: typeof compareWithReference;
+export const analyzeAliasesYAML: typeof analyzeAliases;
+export const toDotYAML: typeof toDot;
 export const parseStreamYAML: typeof parseStream;
+export const createStreamParserYAML: typeof createStreamParser;
+export const restoreStreamParserYAML: typeof restoreStreamParser;
+export const parseFromStreamYAML: typeof parseFromStream;
+export const documentStreamYAML: typeof documentStream;
+export const parseDocumentsYAML: typeof parseDocuments;
+export const indexDocumentsYAML: typeof indexDocuments;
+export const countDocumentsYAML: typeof countDocuments;
+export const splitDocumentsYAML: typeof splitDocuments;
+export const extractDocumentYAML: typeof extractDocument;
+export const transformStreamYAML: typeof transformStream;
+export const pipelineYAML: typeof pipeline;
+export const instrumentationYAML: typeof instrumentation;
 export const getVersion: typeof version;
 
 // Default export
 export default {
   parse,
   parseAll,
+  parseBatch,
+  createParser,
+  parseLenient,
+  parseWithMeta,
+  parseBytes,
+  allocInput,
+  parseInPlace,
+  parseToJsonString,
+  parsePreview,
+  parseLazy,
+  parseToHandle,
+  parseAsync,
   load,
   loadAll,
+  registerType,
+  registerPlugin,
+  dump,
+  estimateDumpSize,
   validate,
+  compileSchema,
+  validateBatch,
+  skeleton,
+  verifyExamples,
   query,
+  compilePath,
+  queryMany,
+  createQuerySession,
+  analyzePath,
+  compareWithReference,
+  analyzeAliases,
+  toDot,
   parseStream,
+  createStreamParser,
+  restoreStreamParser,
+  parseFromStream,
+  documentStream,
+  parseDocuments,
+  indexDocuments,
+  countDocuments,
+  splitDocuments,
+  extractDocument,
+  transformStream,
+  pipeline,
+  instrumentation,
   version,
+  FAILSAFE_SCHEMA,
+  JSON_SCHEMA,
+  CORE_SCHEMA,
+  DEFAULT_SCHEMA,
 };
\ No newline at end of file
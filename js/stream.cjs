@@ -0,0 +1,70 @@
+/**
+ * fast-yaml/stream - Node.js stream adapters
+ *
+ * This module plugs the push-based stream parser into Node.js stream pipelines, so files and
+ * sockets are parsed with `pipe` or `stream.pipeline` without glue around `createStreamParser`.
+ *
+ * @module fast-yaml/stream
+ */
+
+const { Transform } = require('node:stream');
+const { createStreamParser } = require('./index.cjs');
+
+/**
+ * Create a Transform stream parsing YAML text into document chunks
+ *
+ * Buffers or strings written to the stream come out, in object mode, as the chunk parseStream
+ * passes each document to its callback, `{ index, value, start, end }`, as soon as the text
+ * written completes the document. Chunk objects are pushed rather than bare values, as a
+ * `null` document would end an object stream. The stream is async iterable, like any readable
+ * stream, and invalid documents destroy it with a YAMLException. Aborting the `signal` option
+ * destroys it with an AbortError.
+ *
+ * @param {Object} [options] - Load options, `checksum`, `directives`, `sequenceChunk`,
+ *   `signal` and `onProgress`
+ * @returns {Transform} The stream
+ */
+function createParseStream(options = {}) {
+  let parser;
+  return new Transform({
+    readableObjectMode: true,
+    signal: options.signal,
+    construct(callback) {
+      try {
+        // `push` returns false when the buffer is full, which must not stop the parser
+        parser = createStreamParser((chunk) => {
+          this.push(chunk);
+        }, options);
+        callback();
+      } catch (error) {
+        callback(error);
+      }
+    },
+    transform(chunk, _encoding, callback) {
+      try {
+        parser.write(chunk);
+        callback();
+      } catch (error) {
+        callback(error);
+      }
+    },
+    flush(callback) {
+      try {
+        parser.end();
+        callback();
+      } catch (error) {
+        callback(error);
+      }
+    },
+    destroy(error, callback) {
+      if (parser) {
+        parser.free();
+      }
+      callback(error);
+    },
+  });
+}
+
+module.exports = {
+  createParseStream,
+};
@@ -0,0 +1,27 @@
+/**
+ * fast-yaml/stream - Node.js stream adapters
+ *
+ * Type definitions for TypeScript
+ */
+
+import { Transform } from 'node:stream';
+import { StreamOptions } from './index';
+
+/**
+ * Create a Transform stream parsing YAML text into document chunks
+ *
+ * Buffers or strings written to the stream come out, in object mode, as the `StreamChunk`
+ * (or, with `sequenceChunk`, `SequenceChunk`) objects `parseStream` passes to its callback, as
+ * soon as the text written completes each document. Invalid documents destroy the stream with
+ * a YAMLException, and aborting `signal` destroys it with an AbortError.
+ *
+ * @param options - Load options, `checksum`, `directives`, `sequenceChunk`, `signal` and
+ *   `onProgress`
+ * @returns The stream
+ */
+export function createParseStream(options?: StreamOptions): Transform;
+
+// Default export
+export default {
+  createParseStream,
+};
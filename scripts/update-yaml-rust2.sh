@@ -2,7 +2,8 @@
 
 # This script updates the vendored yaml-rust2 library
 # It clones the latest version of yaml-rust2, removes unnecessary files,
-# and copies the source code to the third_party directory
+# copies the source code to the third_party directory and applies the
+# local patches in third_party/patches on top of it
 
 set -e
 
@@ -12,6 +13,7 @@ YAML_RUST2_REPO="https://github.com/Ethiraric/yaml-rust2.git"
 YAML_RUST2_BRANCH="master"
 TEMP_DIR="./tmp-yaml-rust2"
 TARGET_DIR="./third_party/yaml-rust2"
+PATCH_DIR="./third_party/patches"
 
 # Clean up any existing temporary directory
 if [ -d "$TEMP_DIR" ]; then
@@ -44,4 +46,11 @@ fi
 echo "Cleaning up..."
 rm -rf $TEMP_DIR
 
+# Re-apply the local changes, in order; a patch that no longer applies stops the update
+echo "Applying local patches..."
+for PATCH_FILE in "$PATCH_DIR"/*.patch; do
+  echo "  $(basename "$PATCH_FILE")"
+  git apply --directory="${TARGET_DIR#./}" "$PATCH_FILE"
+done
+
 echo "Done! yaml-rust2 has been updated in $TARGET_DIR"
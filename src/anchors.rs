@@ -0,0 +1,192 @@
+//! Alias usage analysis
+//!
+//! This module reports how much each anchor contributes to the expanded size of a YAML stream,
+//! computed from parser events so that no alias is actually expanded.
+
+use std::collections::HashMap;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::{Marker, Scanner, TokenType};
+
+use crate::loader::error_at;
+
+/// Usage of an anchor
+struct AnchorUsage {
+    /// Index of the document holding the anchor
+    document: usize,
+    /// Id of the innermost anchor enclosing this one, 0 for none
+    region: usize,
+    /// Ids of the innermost anchors enclosing each alias to this anchor, 0 for none
+    aliases: Vec<usize>,
+    /// Number of nodes in the anchored node once expanded
+    nodes: u64,
+}
+
+/// Event receiver measuring anchors and aliases
+#[derive(Default)]
+struct Collector {
+    // Anchors indexed by id - 1; the parser numbers anchors from 1 in stream order
+    anchors: Vec<AnchorUsage>,
+    // (anchor id, expanded nodes so far) of each open collection
+    stack: Vec<(usize, u64)>,
+    // Ids of the open anchored collections
+    regions: Vec<usize>,
+    documents: usize,
+    // Expanded nodes of every document
+    nodes: u64,
+    error: Option<(String, Marker)>,
+}
+
+impl MarkedEventReceiver for Collector {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        match ev {
+            Event::DocumentEnd => self.documents += 1,
+            Event::SequenceStart(aid, _) | Event::MappingStart(aid, _) => {
+                if aid > 0 {
+                    self.register(0);
+                    self.regions.push(aid);
+                }
+                self.stack.push((aid, 1));
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                let (aid, nodes) = self.stack.pop().unwrap();
+                if aid > 0 {
+                    self.anchors[aid - 1].nodes = nodes;
+                    self.regions.pop();
+                }
+                self.add(nodes);
+            }
+            Event::Scalar(_, _, aid, _) => {
+                if aid > 0 {
+                    self.register(1);
+                }
+                self.add(1);
+            }
+            Event::Alias(id) => {
+                let region = self.region();
+                match self.anchors.get_mut(id.wrapping_sub(1)) {
+                    Some(anchor) => {
+                        anchor.aliases.push(region);
+                        let nodes = anchor.nodes;
+                        self.add(nodes);
+                    }
+                    None => self.error = Some(("unknown anchor".to_string(), mark)),
+                }
+            }
+            Event::DocumentStart | Event::Nothing | Event::StreamStart | Event::StreamEnd => {}
+        }
+    }
+}
+
+impl Collector {
+    fn region(&self) -> usize {
+        self.regions.last().copied().unwrap_or(0)
+    }
+
+    fn register(&mut self, nodes: u64) {
+        self.anchors.push(AnchorUsage {
+            document: self.documents,
+            region: self.region(),
+            aliases: Vec::new(),
+            nodes,
+        });
+    }
+
+    fn add(&mut self, nodes: u64) {
+        match self.stack.last_mut() {
+            Some((_, total)) => *total = total.saturating_add(nodes),
+            None => self.nodes = self.nodes.saturating_add(nodes),
+        }
+    }
+
+    /// Number of times the anchored node appears once every alias is expanded
+    fn occurrences(&self, id: usize, cache: &mut HashMap<usize, u64>) -> u64 {
+        if id == 0 {
+            return 1;
+        }
+        if let Some(&count) = cache.get(&id) {
+            return count;
+        }
+        let anchor = &self.anchors[id - 1];
+        let count = anchor
+            .aliases
+            .iter()
+            .fold(self.occurrences(anchor.region, cache), |count, &region| {
+                count.saturating_add(self.occurrences(region, cache))
+            });
+        cache.insert(id, count);
+        count
+    }
+}
+
+/// Names and positions of the anchors of a valid YAML stream, in anchor id order
+///
+/// Names are not part of parser events; anchor tokens come in the same order as their ids.
+pub(crate) fn anchor_names(input: &str) -> impl Iterator<Item = (String, Marker)> + '_ {
+    Scanner::new(input.chars()).filter_map(|token| match token.1 {
+        TokenType::Anchor(name) => Some((name, token.0)),
+        _ => None,
+    })
+}
+
+/// Report how much each anchor contributes to the expanded size of a YAML stream
+///
+/// Returns `{ nodes, anchors }` where `nodes` is the number of nodes of every document once
+/// aliases are expanded, and `anchors` lists each anchor in source order as
+/// `{ anchor, document, line, column, aliases, nodes, expandedNodes }`: `aliases` counts the
+/// aliases written to it, `nodes` is the size of the anchored node and `expandedNodes` the
+/// nodes its aliases add, including aliases repeated by the expansion of other anchors.
+/// Counts beyond 2^64 - 1 are capped.
+///
+/// @param {string} input - The YAML stream to analyze
+/// @returns {Object} - The alias report
+#[wasm_bindgen]
+pub fn analyze_aliases(input: &str) -> Result<JsValue, JsValue> {
+    let mut collector = Collector::default();
+    Parser::new_from_str(input)
+        .load(&mut collector, true)
+        .map_err(|e| JsValue::from_str(&error_at(e.info(), e.marker())))?;
+    if let Some((info, mark)) = &collector.error {
+        return Err(JsValue::from_str(&error_at(info, mark)));
+    }
+
+    let names = anchor_names(input);
+
+    let mut cache = HashMap::new();
+    let anchors = Array::new();
+    for (id, (anchor, (name, mark))) in collector.anchors.iter().zip(names).enumerate() {
+        let added = collector
+            .occurrences(id + 1, &mut cache)
+            .saturating_sub(collector.occurrences(anchor.region, &mut cache));
+        let entry = Object::new();
+        for (key, value) in [
+            ("anchor", JsValue::from_str(&name)),
+            ("document", JsValue::from(anchor.document as u32)),
+            ("line", JsValue::from(mark.line() as u32)),
+            ("column", JsValue::from(mark.col() as u32 + 1)),
+            ("aliases", JsValue::from(anchor.aliases.len() as u32)),
+            ("nodes", JsValue::from_f64(anchor.nodes as f64)),
+            (
+                "expandedNodes",
+                JsValue::from_f64(added.saturating_mul(anchor.nodes) as f64),
+            ),
+        ] {
+            Reflect::set(&entry, &JsValue::from_str(key), &value)?;
+        }
+        anchors.push(&entry);
+    }
+
+    let result = Object::new();
+    Reflect::set(
+        &result,
+        &JsValue::from_str("nodes"),
+        &JsValue::from_f64(collector.nodes as f64),
+    )?;
+    Reflect::set(&result, &JsValue::from_str("anchors"), &anchors)?;
+    Ok(result.into())
+}
@@ -0,0 +1,83 @@
+//! Error codes
+//!
+//! Errors cross into JavaScript as messages; this module names the kind of error a message
+//! reports with a stable code (`DUPLICATE_KEY`, `TAB_INDENT`, `UNEXPECTED_EOF`, ...), so
+//! callers can branch on the code instead of matching message text that may be reworded.
+
+use wasm_bindgen::prelude::*;
+
+/// Text identifying a kind of error, with its code, most specific first
+const CODES: &[(&str, &str)] = &[
+    // Resource limits name the option in parentheses
+    ("(maxInputSize)", "INPUT_TOO_LARGE"),
+    ("(maxDocuments)", "TOO_MANY_DOCUMENTS"),
+    ("(maxNodes)", "TOO_MANY_NODES"),
+    ("(maxScalarLength)", "SCALAR_TOO_LONG"),
+    ("(maxNestingDepth)", "NESTING_TOO_DEEP"),
+    ("recursion limit exceeded", "NESTING_TOO_DEEP"),
+    ("(maxMemoryBytes)", "MEMORY_LIMIT"),
+    ("(maxAliasCount)", "TOO_MANY_ALIASES"),
+    ("(maxAliasExpansion)", "ALIAS_EXPANSION_LIMIT"),
+    ("(timeoutMs)", "TIMEOUT"),
+    // Pipelines list the failures of their validate steps
+    ("Validation failed: ", "VALIDATION_FAILED"),
+    // Arguments
+    ("Unknown options: ", "UNKNOWN_OPTION"),
+    ("Unknown YAMLPath function", "UNKNOWN_PATH_FUNCTION"),
+    ("YAMLPath function name", "INVALID_PLUGIN"),
+    ("YAMLPath parsing error", "INVALID_PATH"),
+    ("Schema parsing error", "INVALID_SCHEMA"),
+    ("Pipeline step", "INVALID_PIPELINE"),
+    ("Invalid pipeline steps", "INVALID_PIPELINE"),
+    ("Unknown pipeline step", "INVALID_PIPELINE"),
+    ("Plugin ", "INVALID_PLUGIN"),
+    ("Type '", "INVALID_TYPE"),
+    ("Type definition", "INVALID_TYPE"),
+    ("Unknown kind", "INVALID_TYPE"),
+    ("Option '", "INVALID_OPTION"),
+    ("Unknown ", "INVALID_OPTION"),
+    ("Invalid input encoding", "INVALID_ENCODING"),
+    // Documents
+    ("is a YAML 1.1", "YAML11_SCALAR"),
+    ("unknown tag", "UNKNOWN_TAG"),
+    ("duplicated key", "DUPLICATE_KEY"),
+    ("duplicated anchor", "DUPLICATE_ANCHOR"),
+    ("unknown anchor", "UNKNOWN_ANCHOR"),
+    ("cannot resolve", "INVALID_TAGGED_VALUE"),
+    ("tab cannot be used", "TAB_INDENT"),
+    ("tabs disallowed", "TAB_INDENT"),
+    ("found a tab", "TAB_INDENT"),
+    ("start with a tab", "TAB_INDENT"),
+    ("unexpected eof", "UNEXPECTED_EOF"),
+    ("unexpected end of stream", "UNEXPECTED_EOF"),
+    ("while scanning a block scalar", "INVALID_BLOCK_SCALAR"),
+    ("indentation", "BAD_INDENT"),
+    ("wrongly indented", "BAD_INDENT"),
+    ("handle wasn't declared", "UNDECLARED_TAG_HANDLE"),
+    ("while parsing a tag", "INVALID_TAG"),
+    ("while scanning a tag", "INVALID_TAG"),
+    ("verbatim tag", "INVALID_TAG"),
+    ("directive", "INVALID_DIRECTIVE"),
+    ("escape", "INVALID_ESCAPE"),
+    ("hexadecimal number", "INVALID_ESCAPE"),
+    ("while parsing a flow", "UNCLOSED_FLOW_COLLECTION"),
+    ("mapping values are not allowed", "UNEXPECTED_MAPPING_VALUE"),
+    ("YAML parsing error", "SYNTAX_ERROR"),
+];
+
+/// The code of an error message, `YAML_ERROR` for errors of no more specific kind
+pub(crate) fn code_of(message: &str) -> &'static str {
+    CODES
+        .iter()
+        .find(|(text, _)| message.contains(text))
+        .map_or("YAML_ERROR", |(_, code)| code)
+}
+
+/// Get the stable code of an error or warning message
+///
+/// @param {string} message - The message of a thrown error or a warning
+/// @returns {string} - The code, such as `DUPLICATE_KEY`
+#[wasm_bindgen]
+pub fn error_code(message: &str) -> String {
+    code_of(message).to_string()
+}
@@ -0,0 +1,195 @@
+//! Reference comparison
+//!
+//! This module compares fast-yaml's output with a reference JSON dump (e.g.
+//! `JSON.stringify(jsyaml.load(text))`) to find incompatibilities when migrating from js-yaml.
+
+use js_sys::{Array, Object, Reflect, JSON};
+use serde_json::Value as JsonValue;
+use wasm_bindgen::prelude::*;
+
+use crate::json::JsonWriter;
+use crate::loader::{error_at, load_documents};
+use crate::options::LoadOptions;
+
+/// A semantic difference between the parsed document and the reference
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Difference {
+    /// Location of the difference (e.g. `$.services[0].port`)
+    pub path: String,
+    /// `type`, `value`, `missing` (only in the reference) or `extra` (only in the output)
+    pub kind: &'static str,
+    /// Value in the reference, if present
+    pub expected: Option<JsonValue>,
+    /// Value produced by fast-yaml, if present
+    pub actual: Option<JsonValue>,
+}
+
+/// Compare the parse result of a YAML document with a reference JSON string
+///
+/// Numbers compare by value and mapping key order is ignored. Returns
+/// `{ equal, differences }` where each difference is `{ path, kind, expected, actual }`.
+///
+/// @param {string} yaml - The YAML document to parse
+/// @param {string} reference - The expected result as a JSON string
+/// @param {Object} options - Load options used to parse the document
+/// @returns {Object} - The comparison result
+#[wasm_bindgen]
+pub fn compare_with_reference(
+    yaml: &str,
+    reference: &str,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = LoadOptions::from_js(&options)?;
+    options.reject_timing("compareWithReference")?;
+
+    let reference: JsonValue = serde_json::from_str(reference)
+        .map_err(|e| JsValue::from_str(&format!("Reference parsing error: {}", e)))?;
+
+    let docs = load_documents(yaml, &options.limits)
+        .map_err(|e| options.error(&error_at(e.info(), e.marker())))?;
+    let mut writer = JsonWriter::new(&options);
+    let json_string = match docs.first() {
+        Some(doc) => writer.write_document(doc),
+        None => Ok("null".to_string()),
+    };
+    options.warn(writer.take_warnings())?;
+    let json_string = json_string.map_err(|e| options.error(&e))?;
+    let actual: JsonValue = serde_json::from_str(&json_string)
+        .map_err(|e| JsValue::from_str(&format!("Failed to read parse result: {}", e)))?;
+
+    let mut differences = Vec::new();
+    diff_values(&reference, &actual, "$", &mut differences);
+
+    let js_differences = Array::new();
+    for difference in &differences {
+        let entry = Object::new();
+        Reflect::set(
+            &entry,
+            &JsValue::from_str("path"),
+            &JsValue::from_str(&difference.path),
+        )?;
+        Reflect::set(
+            &entry,
+            &JsValue::from_str("kind"),
+            &JsValue::from_str(difference.kind),
+        )?;
+        Reflect::set(
+            &entry,
+            &JsValue::from_str("expected"),
+            &json_to_js_value(difference.expected.as_ref())?,
+        )?;
+        Reflect::set(
+            &entry,
+            &JsValue::from_str("actual"),
+            &json_to_js_value(difference.actual.as_ref())?,
+        )?;
+        js_differences.push(&entry);
+    }
+
+    let result = Object::new();
+    Reflect::set(
+        &result,
+        &JsValue::from_str("equal"),
+        &JsValue::from_bool(differences.is_empty()),
+    )?;
+    Reflect::set(&result, &JsValue::from_str("differences"), &js_differences)?;
+    Ok(result.into())
+}
+
+/// Collect the differences between a reference value and an actual value
+pub(crate) fn diff_values(
+    expected: &JsonValue,
+    actual: &JsonValue,
+    path: &str,
+    differences: &mut Vec<Difference>,
+) {
+    let difference = |kind| Difference {
+        path: path.to_string(),
+        kind,
+        expected: Some(expected.clone()),
+        actual: Some(actual.clone()),
+    };
+
+    match (expected, actual) {
+        (JsonValue::Object(expected), JsonValue::Object(actual)) => {
+            for (key, expected_value) in expected {
+                let item_path = format!("{}.{}", path, key);
+                match actual.get(key) {
+                    Some(actual_value) => {
+                        diff_values(expected_value, actual_value, &item_path, differences)
+                    }
+                    None => differences.push(Difference {
+                        path: item_path,
+                        kind: "missing",
+                        expected: Some(expected_value.clone()),
+                        actual: None,
+                    }),
+                }
+            }
+            for (key, actual_value) in actual {
+                if !expected.contains_key(key) {
+                    differences.push(Difference {
+                        path: format!("{}.{}", path, key),
+                        kind: "extra",
+                        expected: None,
+                        actual: Some(actual_value.clone()),
+                    });
+                }
+            }
+        }
+        (JsonValue::Array(expected), JsonValue::Array(actual)) => {
+            for (i, expected_item) in expected.iter().enumerate() {
+                let item_path = format!("{}[{}]", path, i);
+                match actual.get(i) {
+                    Some(actual_item) => {
+                        diff_values(expected_item, actual_item, &item_path, differences)
+                    }
+                    None => differences.push(Difference {
+                        path: item_path,
+                        kind: "missing",
+                        expected: Some(expected_item.clone()),
+                        actual: None,
+                    }),
+                }
+            }
+            for (i, actual_item) in actual.iter().enumerate().skip(expected.len()) {
+                differences.push(Difference {
+                    path: format!("{}[{}]", path, i),
+                    kind: "extra",
+                    expected: None,
+                    actual: Some(actual_item.clone()),
+                });
+            }
+        }
+        (JsonValue::Number(x), JsonValue::Number(y)) => {
+            if x.as_f64() != y.as_f64() {
+                differences.push(difference("value"));
+            }
+        }
+        _ if type_name(expected) != type_name(actual) => differences.push(difference("type")),
+        _ => {
+            if expected != actual {
+                differences.push(difference("value"));
+            }
+        }
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+/// Convert a JSON value to a JavaScript value; absent values become `undefined`
+fn json_to_js_value(value: Option<&JsonValue>) -> Result<JsValue, JsValue> {
+    match value {
+        Some(value) => JSON::parse(&value.to_string()),
+        None => Ok(JsValue::UNDEFINED),
+    }
+}
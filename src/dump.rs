@@ -0,0 +1,178 @@
+//! YAML emission
+//!
+//! This module converts JavaScript values back into YAML text using yaml-rust2's emitter.
+
+use std::fmt::{self, Write};
+
+use js_sys::{Array, Map, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::{Yaml, YamlEmitter};
+
+use crate::instrument::{start_call, timed, Phase};
+use crate::loader::Directives;
+use crate::options::{DumpOptions, KeyStyle, NullStyle};
+
+/// Largest integer a JavaScript number represents exactly (2^53)
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_992.0;
+
+/// Convert a JavaScript value to a YAML value
+///
+/// `undefined` becomes null and `Map` keys keep their type; functions, symbols and other
+/// non-data values are rejected. String keys are renamed by `key_style`.
+pub(crate) fn js_value_to_yaml(value: &JsValue, key_style: &KeyStyle) -> Result<Yaml, JsValue> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(Yaml::Null);
+    }
+    if let Some(b) = value.as_bool() {
+        return Ok(Yaml::Boolean(b));
+    }
+    if let Some(n) = value.as_f64() {
+        return Ok(number_to_yaml(n));
+    }
+    if let Some(s) = value.as_string() {
+        return Ok(Yaml::String(s));
+    }
+    if Array::is_array(value) {
+        let array: &Array = value.unchecked_ref();
+        let mut items = Vec::with_capacity(array.length() as usize);
+        for item in array.iter() {
+            items.push(js_value_to_yaml(&item, key_style)?);
+        }
+        return Ok(Yaml::Array(items));
+    }
+    if let Some(map) = value.dyn_ref::<Map>() {
+        let mut hash = Hash::new();
+        for entry in map.entries() {
+            let entry: Array = entry?.unchecked_into();
+            let key = match js_value_to_yaml(&entry.get(0), key_style)? {
+                Yaml::String(key) => Yaml::String(rename_key(&key, key_style)?),
+                key => key,
+            };
+            hash.insert(key, js_value_to_yaml(&entry.get(1), key_style)?);
+        }
+        return Ok(Yaml::Hash(hash));
+    }
+    if value.is_object() && !value.is_function() {
+        let object: &Object = value.unchecked_ref();
+        let mut hash = Hash::new();
+        for key in Object::keys(object).iter() {
+            let item = Reflect::get(object, &key)?;
+            let key = rename_key(&key.as_string().unwrap_or_default(), key_style)?;
+            hash.insert(Yaml::String(key), js_value_to_yaml(&item, key_style)?);
+        }
+        return Ok(Yaml::Hash(hash));
+    }
+    Err(JsValue::from_str("Unacceptable kind of value to dump"))
+}
+
+fn rename_key(key: &str, key_style: &KeyStyle) -> Result<String, JsValue> {
+    key_style.apply(key).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Convert a JavaScript number, keeping integral values as integers
+fn number_to_yaml(n: f64) -> Yaml {
+    if n.is_nan() {
+        Yaml::Real(".nan".to_string())
+    } else if n.is_infinite() {
+        Yaml::Real(if n > 0.0 { ".inf" } else { "-.inf" }.to_string())
+    } else if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER {
+        Yaml::Integer(n as i64)
+    } else {
+        Yaml::Real(format!("{:?}", n))
+    }
+}
+
+/// Serialize a JavaScript value as YAML
+///
+/// This function is API-compatible with js-yaml's dump function for the supported options.
+///
+/// @param {*} value - The value to serialize
+/// With the `directives` option (`{ version, tags }`), the document is written after its
+/// `%YAML` and `%TAG` directives and a `---` marker.
+///
+/// @param {Object} options - Dump options (`styles`, `keyStyle`, `directives`)
+/// @returns {string} - The YAML text, ending with a newline
+#[wasm_bindgen]
+pub fn dump(value: JsValue, options: JsValue) -> Result<String, JsValue> {
+    start_call();
+    let options = DumpOptions::from_js(&options)?;
+    let yaml = timed(Phase::Convert, || {
+        js_value_to_yaml(&value, &options.key_style)
+    })?;
+    let output = timed(Phase::Emit, || emit(&yaml, options.null_style.repr()))
+        .map_err(|e| JsValue::from_str(&e))?;
+    Ok(match &options.directives {
+        Some(directives) => format!("{}{}", directive_lines(directives), output),
+        None => output.strip_prefix("---\n").unwrap_or(&output).to_string(),
+    })
+}
+
+/// Emit a value as YAML text the way `dump` does with the default options
+pub(crate) fn dump_yaml(yaml: &Yaml) -> Result<String, String> {
+    let output = emit(yaml, NullStyle::default().repr())?;
+    Ok(output.strip_prefix("---\n").unwrap_or(&output).to_string())
+}
+
+/// Compute the length in UTF-8 bytes of the YAML text `dump` returns for a value
+///
+/// The value is laid out exactly as `dump` would, but the text is only counted, never built,
+/// so buffers can be sized or size quotas enforced before serializing.
+///
+/// @param {*} value - The value to measure
+/// @param {Object} options - Dump options (`styles`, `keyStyle`)
+/// @returns {number} - The size of the YAML text in bytes
+#[wasm_bindgen]
+pub fn estimate_dump_size(value: JsValue, options: JsValue) -> Result<f64, JsValue> {
+    let options = DumpOptions::from_js(&options)?;
+    let yaml = js_value_to_yaml(&value, &options.key_style)?;
+    let mut counter = ByteCounter(0);
+    emit_to(&yaml, options.null_style.repr(), &mut counter).map_err(|e| JsValue::from_str(&e))?;
+    // `dump` drops the leading `---\n` unless it writes directives, and adds a trailing newline
+    let size = match &options.directives {
+        Some(directives) => counter.0 + directive_lines(directives).len(),
+        None => counter.0 - "---\n".len(),
+    };
+    Ok((size + 1) as f64)
+}
+
+/// Write `%YAML` and `%TAG` directive lines
+pub(crate) fn directive_lines(directives: &Directives) -> String {
+    let mut lines = String::new();
+    if let Some(version) = &directives.version {
+        let _ = writeln!(lines, "%YAML {}", version);
+    }
+    for (handle, prefix) in &directives.tags {
+        let _ = writeln!(lines, "%TAG {} {}", handle, prefix);
+    }
+    lines
+}
+
+/// Emit a YAML value as a document, starting with `---` and ending with a newline
+pub(crate) fn emit_document(yaml: &Yaml) -> Result<String, String> {
+    emit(yaml, "~")
+}
+
+pub(crate) fn emit(yaml: &Yaml, null_repr: &str) -> Result<String, String> {
+    let mut output = String::new();
+    emit_to(yaml, null_repr, &mut output)?;
+    output.push('\n');
+    Ok(output)
+}
+
+fn emit_to(yaml: &Yaml, null_repr: &str, writer: &mut dyn fmt::Write) -> Result<(), String> {
+    let mut emitter = YamlEmitter::new(writer);
+    emitter.null_repr(null_repr);
+    emitter.dump(yaml).map_err(|e| e.to_string())
+}
+
+/// Writer counting the bytes written to it
+struct ByteCounter(usize);
+
+impl fmt::Write for ByteCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
@@ -0,0 +1,333 @@
+//! Document structure graphs
+//!
+//! This module exports the node structure of a YAML stream as a Graphviz DOT graph or as plain
+//! node and edge lists, to visualize large configurations.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml_rust2::scanner::Marker;
+
+use crate::anchors::anchor_names;
+use crate::loader::error_at;
+use crate::options::{check_option_keys, get_bool, get_limit, get_string};
+use crate::schema::tag_name;
+
+/// Default `maxLabelLength`: longest scalar text kept in a label, in characters
+const MAX_LABEL_LENGTH: usize = 40;
+
+/// A node of the graph
+struct GraphNode {
+    /// `document`, `mapping`, `sequence`, `scalar` or `alias`
+    kind: &'static str,
+    label: String,
+    mark: Marker,
+}
+
+/// An edge of the graph
+struct GraphEdge {
+    from: usize,
+    to: usize,
+    /// Mapping key or sequence index leading to the child
+    label: Option<String>,
+    /// Whether the edge links an alias to its anchored node
+    alias: bool,
+}
+
+/// A collection or document whose children are being added
+struct Frame {
+    id: usize,
+    kind: &'static str,
+    children: usize,
+    // Text of the scalar key waiting for its value
+    key: Option<String>,
+    // Node of the collection or anchored key waiting for its value
+    key_node: Option<usize>,
+}
+
+/// Event receiver building the graph
+struct GraphBuilder {
+    names: Vec<String>,
+    alias_edges: bool,
+    max_label_length: usize,
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+    stack: Vec<Frame>,
+    // Graph node of each anchor id
+    anchors: HashMap<usize, usize>,
+    documents: usize,
+}
+
+impl MarkedEventReceiver for GraphBuilder {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        match ev {
+            Event::DocumentStart => {
+                let id = self.add_node("document", format!("document {}", self.documents), mark);
+                self.documents += 1;
+                self.push(id, "document");
+            }
+            Event::DocumentEnd | Event::SequenceEnd | Event::MappingEnd => {
+                self.stack.pop();
+            }
+            Event::SequenceStart(aid, tag) => {
+                let label = self.label(aid, tag.as_ref(), "[]");
+                let id = self.add_node("sequence", label, mark);
+                self.attach(id, aid);
+                self.push(id, "sequence");
+            }
+            Event::MappingStart(aid, tag) => {
+                let label = self.label(aid, tag.as_ref(), "{}");
+                let id = self.add_node("mapping", label, mark);
+                self.attach(id, aid);
+                self.push(id, "mapping");
+            }
+            Event::Scalar(value, _, aid, tag) => {
+                let text = self.truncate(&value);
+                if let Some(frame) = self.stack.last_mut() {
+                    // Plain keys label the edge to their value instead of getting a node
+                    if frame.kind == "mapping" && frame.children % 2 == 0 && aid == 0 {
+                        frame.children += 1;
+                        frame.key = Some(match &tag {
+                            Some(tag) => format!("{} {}", tag_name(tag), text),
+                            None => text,
+                        });
+                        return;
+                    }
+                }
+                let label = self.label(aid, tag.as_ref(), &text);
+                let id = self.add_node("scalar", label, mark);
+                self.attach(id, aid);
+            }
+            Event::Alias(aid) => {
+                let name = self.names.get(aid.wrapping_sub(1)).cloned();
+                let id = self.add_node("alias", format!("*{}", name.unwrap_or_default()), mark);
+                self.attach(id, 0);
+                if let (true, Some(&target)) = (self.alias_edges, self.anchors.get(&aid)) {
+                    self.edges.push(GraphEdge {
+                        from: id,
+                        to: target,
+                        label: None,
+                        alias: true,
+                    });
+                }
+            }
+            Event::Nothing | Event::StreamStart | Event::StreamEnd => {}
+        }
+    }
+}
+
+impl GraphBuilder {
+    fn add_node(&mut self, kind: &'static str, label: String, mark: Marker) -> usize {
+        self.nodes.push(GraphNode { kind, label, mark });
+        self.nodes.len() - 1
+    }
+
+    fn push(&mut self, id: usize, kind: &'static str) {
+        self.stack.push(Frame {
+            id,
+            kind,
+            children: 0,
+            key: None,
+            key_node: None,
+        });
+    }
+
+    /// Label a collection or scalar with its anchor and tag (`&base !Ref {}`)
+    fn label(&self, aid: usize, tag: Option<&Tag>, contents: &str) -> String {
+        let mut label = String::new();
+        if let Some(name) = aid.checked_sub(1).and_then(|i| self.names.get(i)) {
+            let _ = write!(label, "&{} ", name);
+        }
+        if let Some(tag) = tag {
+            let _ = write!(label, "{} ", tag_name(tag));
+        }
+        label.push_str(contents);
+        label
+    }
+
+    fn truncate(&self, text: &str) -> String {
+        match text.char_indices().nth(self.max_label_length) {
+            Some((end, _)) => format!("{}...", &text[..end]),
+            None => text.to_string(),
+        }
+    }
+
+    /// Link a new node to the collection or document holding it
+    fn attach(&mut self, id: usize, aid: usize) {
+        if aid > 0 {
+            self.anchors.insert(aid, id);
+        }
+        let Some(frame) = self.stack.last_mut() else {
+            return;
+        };
+        let index = frame.children;
+        frame.children += 1;
+        let (from, label) = match frame.kind {
+            "sequence" => (frame.id, Some(index.to_string())),
+            "mapping" if index % 2 == 0 => {
+                frame.key_node = Some(id);
+                (frame.id, Some("key".to_string()))
+            }
+            // Collection keys point to their value; plain keys label the edge from the mapping
+            "mapping" => match (frame.key_node.take(), frame.key.take()) {
+                (Some(key), _) => (key, Some("value".to_string())),
+                (None, key) => (frame.id, key),
+            },
+            _ => (frame.id, None),
+        };
+        self.edges.push(GraphEdge {
+            from,
+            to: id,
+            label,
+            alias: false,
+        });
+    }
+
+    /// Write the graph in Graphviz DOT
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph yaml {\n  node [shape=box];\n");
+        for (id, node) in self.nodes.iter().enumerate() {
+            let shape = match node.kind {
+                "document" => ", shape=ellipse",
+                "alias" => ", shape=diamond",
+                _ => "",
+            };
+            let _ = writeln!(
+                dot,
+                "  n{} [label={}{}];",
+                id,
+                dot_string(&node.label),
+                shape
+            );
+        }
+        for edge in &self.edges {
+            let _ = write!(dot, "  n{} -> n{}", edge.from, edge.to);
+            match (&edge.label, edge.alias) {
+                (_, true) => dot.push_str(" [style=dashed, constraint=false]"),
+                (Some(label), false) => {
+                    let _ = write!(dot, " [label={}]", dot_string(label));
+                }
+                (None, false) => {}
+            }
+            dot.push_str(";\n");
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Build the `{ nodes, edges }` object
+    fn to_js(&self) -> Result<JsValue, JsValue> {
+        let nodes = Array::new();
+        for (id, node) in self.nodes.iter().enumerate() {
+            let entry = Object::new();
+            for (key, value) in [
+                ("id", JsValue::from(id as u32)),
+                ("kind", JsValue::from_str(node.kind)),
+                ("label", JsValue::from_str(&node.label)),
+                ("line", JsValue::from(node.mark.line() as u32)),
+                ("column", JsValue::from(node.mark.col() as u32 + 1)),
+            ] {
+                Reflect::set(&entry, &JsValue::from_str(key), &value)?;
+            }
+            nodes.push(&entry);
+        }
+        let edges = Array::new();
+        for edge in &self.edges {
+            let entry = Object::new();
+            for (key, value) in [
+                ("from", JsValue::from(edge.from as u32)),
+                ("to", JsValue::from(edge.to as u32)),
+                (
+                    "kind",
+                    JsValue::from_str(if edge.alias { "alias" } else { "child" }),
+                ),
+            ] {
+                Reflect::set(&entry, &JsValue::from_str(key), &value)?;
+            }
+            if let Some(label) = &edge.label {
+                Reflect::set(
+                    &entry,
+                    &JsValue::from_str("label"),
+                    &JsValue::from_str(label),
+                )?;
+            }
+            edges.push(&entry);
+        }
+        let result = Object::new();
+        Reflect::set(&result, &JsValue::from_str("nodes"), &nodes)?;
+        Reflect::set(&result, &JsValue::from_str("edges"), &edges)?;
+        Ok(result.into())
+    }
+}
+
+/// Quote a DOT string
+fn dot_string(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => {}
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Export the node structure of a YAML stream as a graph
+///
+/// Every document gets a `document` node linked to its root. Collections, scalars and aliases
+/// are nodes labeled with their anchor, tag and text (scalars longer than `maxLabelLength`
+/// characters, default 40, are cut); edges carry the mapping key or sequence index leading to
+/// the child. Plain scalar keys label the edge to their value, while collection and anchored
+/// keys are nodes linked to their value by a `value` edge. With `aliases: true`, each alias also
+/// has a dashed edge to its anchored node. With `format: 'json'`, returns
+/// `{ nodes: [{ id, kind, label, line, column }], edges: [{ from, to, kind, label? }] }`, where
+/// `kind` is `child` or `alias`, instead of DOT text.
+///
+/// @param {string} input - The YAML stream
+/// @param {Object} options - `format` (`'dot'` or `'json'`), `aliases` and `maxLabelLength`
+/// @returns {string|Object} - The DOT text or the node and edge lists
+#[wasm_bindgen]
+pub fn to_dot(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let (mut format, mut alias_edges, mut max_label_length) = (None, false, MAX_LABEL_LENGTH);
+    if !options.is_undefined() && !options.is_null() {
+        check_option_keys(&options, &[&["format", "aliases", "maxLabelLength"]])?;
+        format = get_string(&options, "format")?;
+        alias_edges = get_bool(&options, "aliases")?.unwrap_or(false);
+        max_label_length = get_limit(&options, "maxLabelLength")?.unwrap_or(MAX_LABEL_LENGTH);
+    }
+    let json = match format.as_deref() {
+        None | Some("dot") => false,
+        Some("json") => true,
+        Some(format) => {
+            return Err(JsValue::from_str(&format!("Unknown format: {}", format)));
+        }
+    };
+
+    let mut builder = GraphBuilder {
+        names: anchor_names(input).map(|(name, _)| name).collect(),
+        alias_edges,
+        max_label_length,
+        nodes: Vec::new(),
+        edges: Vec::new(),
+        stack: Vec::new(),
+        anchors: HashMap::new(),
+        documents: 0,
+    };
+    Parser::new_from_str(input)
+        .load(&mut builder, true)
+        .map_err(|e| JsValue::from_str(&error_at(e.info(), e.marker())))?;
+
+    if json {
+        builder.to_js()
+    } else {
+        Ok(JsValue::from_str(&builder.to_dot()))
+    }
+}
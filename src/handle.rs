@@ -0,0 +1,526 @@
+//! Document handles
+//!
+//! This module keeps a parsed document inside WebAssembly memory, so it can be converted,
+//! queried and validated any number of times without parsing the input again.
+
+use std::cell::OnceCell;
+use std::mem;
+
+use js_sys::Array;
+use serde_json::Value as JsonValue;
+use wasm_bindgen::prelude::*;
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+use yaml_rust2::Yaml;
+
+use crate::json::key_to_string;
+use crate::loader::{error_at, load_documents, Node, NodeKind};
+use crate::options::{get_number, get_string, LoadOptions};
+use crate::parse::node_to_js_value;
+use crate::resolve::Resolver;
+use crate::schema::Diagnostic;
+use crate::validate::{schema_from_js, validate_resolved};
+use crate::value::ValueBuilder;
+use crate::yamlpath::{path_segments, query_resolved, PathExpr};
+
+/// A parsed document retained for repeated use
+#[wasm_bindgen]
+pub struct DocumentHandle {
+    source: String,
+    root: Option<Node>,
+    // Whether `root` was loaded from `source`, false after an edit that did not parse
+    current: bool,
+    // Whether `root` holds nodes tied to text outside their entry, which edits load in whole
+    tied: bool,
+    options: LoadOptions,
+    // The document resolved for queries and validation, on first use
+    resolved: OnceCell<(Yaml, Vec<Diagnostic>)>,
+}
+
+/// An edit of the source: `removed` UTF-16 code units at `offset` replaced by `inserted`
+struct Edit {
+    offset: usize,
+    removed: usize,
+    inserted: String,
+}
+
+/// Parse the first document of a YAML string into a handle kept in WebAssembly memory
+///
+/// `toJS()` converts the document with the load options given here and `get(path)` converts
+/// only the value at a path; `query(path)` and `validate(schema)` work on the document without
+/// parsing the input again, and `update(edits)` parses again only what edits of the input
+/// touched. Values that cannot be resolved for queries and validation are read as null and
+/// reported to `onWarning` once. Call `free()` when done.
+///
+/// @param {string} input - The YAML document
+/// @param {Object} options - Load options
+/// @returns {DocumentHandle} - The parsed document
+#[wasm_bindgen]
+pub fn parse_to_handle(input: &str, options: JsValue) -> Result<DocumentHandle, JsValue> {
+    let options = LoadOptions::from_js(&options)?;
+    options.reject_timing("parseToHandle")?;
+    let mut handle = DocumentHandle {
+        source: input.to_string(),
+        root: None,
+        current: false,
+        tied: false,
+        options,
+        resolved: OnceCell::new(),
+    };
+    handle.load()?;
+    Ok(handle)
+}
+
+#[wasm_bindgen]
+impl DocumentHandle {
+    /// Convert the document; `null` (or `undefined`, see `noDocument`) for an empty input
+    #[wasm_bindgen(js_name = toJS)]
+    pub fn to_js(&self) -> Result<JsValue, JsValue> {
+        match &self.root {
+            Some(root) => node_to_js_value(root, &self.options),
+            None => Ok(self.options.no_document()),
+        }
+    }
+
+    /// Convert only the value at a path (`$.spec.containers[0]`), as `toJS()` would convert it
+    ///
+    /// The rest of the document is left unconverted. The path names a single value by
+    /// properties and indexes; `undefined` when there is none.
+    ///
+    /// @param {string} path - The YAMLPath expression
+    /// @returns {any} - The value at the path
+    pub fn get(&self, path: &str) -> Result<JsValue, JsValue> {
+        let segments = path_segments(path)?;
+        if let Some(segment) = segments.iter().find(|segment| {
+            !matches!(
+                segment,
+                PathExpr::Root | PathExpr::Property(_) | PathExpr::Index(_)
+            )
+        }) {
+            return Err(JsValue::from_str(&format!(
+                "get() selects a single value by properties and indexes; use query() for '{}'",
+                segment
+            )));
+        }
+        let Some(root) = &self.root else {
+            return Ok(JsValue::UNDEFINED);
+        };
+        let options = &self.options;
+        let Some(node) = select(root, &segments, options).map_err(|e| options.error(&e))? else {
+            return Ok(JsValue::UNDEFINED);
+        };
+
+        let location: String = segments.iter().map(ToString::to_string).collect();
+        let mut builder = ValueBuilder::new(options);
+        let value = builder.build_at(node, Vec::new(), &location);
+        options.warn(builder.take_warnings())?;
+        value
+    }
+
+    /// Apply edits to the source and parse the changed part again
+    ///
+    /// Each edit `{ offset, removed, inserted }` replaces `removed` characters at `offset`,
+    /// counted in UTF-16 code units like JavaScript string indexes, with `inserted`; edits
+    /// apply in order, each to the text left by the previous ones. When the changes fall
+    /// within entries of a block mapping or sequence at the root, only those entries are
+    /// parsed again and spliced into the document; otherwise the whole source is. Returns
+    /// whether the parse was confined to the edited entries. When the edited source does not
+    /// parse, the error is thrown, the handle keeps the last document that parsed and the next
+    /// update parses the whole source.
+    ///
+    /// @param {Object[]} edits - The edits, in order
+    /// @returns {boolean} - Whether only the edited entries were parsed again
+    pub fn update(&mut self, edits: &Array) -> Result<bool, JsValue> {
+        let mut source = self.source.clone();
+        for edit in edits.iter() {
+            Edit::from_js(&edit)?.apply(&mut source)?;
+        }
+        let old = mem::replace(&mut self.source, source);
+        self.resolved = OnceCell::new();
+        if self.current && self.splice(&old) {
+            return Ok(true);
+        }
+        self.load()?;
+        Ok(false)
+    }
+
+    /// Query the document; an empty input yields no matches
+    ///
+    /// @param {string} path - The YAMLPath expression
+    /// @param {Object} options - Query options
+    /// @returns {Array} - Array of matching values
+    pub fn query(&self, path: &str, options: JsValue) -> Result<JsValue, JsValue> {
+        let doc = self.resolved()?.map(|(doc, _)| doc);
+        query_resolved(doc, path, &options)
+    }
+
+    /// Validate the document against a JSON Schema, as `validate` does
+    ///
+    /// @param {Object} schema - The JSON Schema to validate against
+    /// @param {Object} options - Validation options (`decimalCommaHints`)
+    /// @returns {Object} - Validation result with success flag and any errors
+    pub fn validate(&self, schema: &JsValue, options: &JsValue) -> Result<JsValue, JsValue> {
+        self.validate_with(&schema_from_js(schema)?, options)
+    }
+}
+
+impl DocumentHandle {
+    /// Load the document from the whole source
+    fn load(&mut self) -> Result<(), JsValue> {
+        self.current = false;
+        let docs = load_documents(&self.source, &self.options.limits)
+            .map_err(|e| self.options.error(&error_at(e.info(), e.marker())))?;
+        self.root = docs.into_iter().next();
+        self.current = true;
+        self.tied = self.root.as_ref().is_some_and(tied);
+        Ok(())
+    }
+
+    /// Load again only the root entries an edit from `old` touched, splicing them into the
+    /// document; false when the edit cannot be confined to whole entries
+    fn splice(&mut self, old: &str) -> bool {
+        let new = self.source.as_str();
+        let limits = &self.options.limits;
+        let Some(root) = &mut self.root else {
+            return false;
+        };
+        // Size limits apply to the whole document
+        if self.tied
+            || root.tag.is_some()
+            || [
+                limits.max_nodes,
+                limits.max_memory_bytes,
+                limits.max_input_size,
+            ]
+            .iter()
+            .any(|&limit| limit != usize::MAX)
+            || new.lines().any(is_boundary)
+        {
+            return false;
+        }
+        let sequence = matches!(root.kind, NodeKind::Sequence(_));
+        let lines = line_starts(old);
+        let starts: Vec<&Node> = match &root.kind {
+            NodeKind::Mapping(entries) => entries.iter().map(|(key, _)| key).collect(),
+            NodeKind::Sequence(items) => items.iter().collect(),
+            _ => return false,
+        };
+        if !starts
+            .iter()
+            .all(|node| starts_line(old, &lines, node, sequence))
+        {
+            return false;
+        }
+        let starts: Vec<Marker> = starts.iter().map(|node| node.mark).collect();
+
+        // The changed bytes: old[prefix..old.len() - suffix] became new[prefix..new.len() - suffix]
+        let mut prefix = old
+            .bytes()
+            .zip(new.bytes())
+            .take_while(|(a, b)| a == b)
+            .count();
+        while !old.is_char_boundary(prefix) {
+            prefix -= 1;
+        }
+        let mut suffix = old
+            .bytes()
+            .rev()
+            .zip(new.bytes().rev())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(old.len().min(new.len()) - prefix);
+        while !old.is_char_boundary(old.len() - suffix) {
+            suffix -= 1;
+        }
+
+        // The entries holding the change, from the line of the first one to the next entry
+        let line_of = |offset: usize| lines.partition_point(|&start| start <= offset);
+        let first_line = line_of(prefix);
+        let last_line = line_of(old.len() - suffix);
+        let Some(first) = starts.iter().rposition(|mark| mark.line() <= first_line) else {
+            return false;
+        };
+        let last = starts
+            .iter()
+            .rposition(|mark| mark.line() <= last_line)
+            .unwrap_or(first);
+        let start = lines[starts[first].line() - 1];
+        let old_end = starts
+            .get(last + 1)
+            .map_or(old.len(), |mark| lines[mark.line() - 1]);
+        let new_end = old_end + new.len() - old.len();
+        let (replaced, text) = (&old[start..old_end], &new[start..new_end]);
+
+        let Ok(docs) = load_documents(text, limits) else {
+            return false;
+        };
+        let mut doc = docs.into_iter().next();
+        let text_lines = line_starts(text);
+        let entries: Vec<&Node> = match doc.as_ref().map(|doc| &doc.kind) {
+            None => Vec::new(),
+            Some(NodeKind::Mapping(entries)) if !sequence => {
+                entries.iter().map(|(key, _)| key).collect()
+            }
+            Some(NodeKind::Sequence(items)) if sequence => items.iter().collect(),
+            Some(_) => return false,
+        };
+        // Lines before the first entry could belong to the value of the entry before it
+        if doc.as_ref().is_some_and(tied)
+            || entries
+                .first()
+                .map_or(!text.is_empty(), |node| node.mark.line() != 1)
+            || starts.len() - (last + 1 - first) + entries.len() == 0
+            || !entries
+                .iter()
+                .all(|node| starts_line(text, &text_lines, node, sequence))
+        {
+            return false;
+        }
+
+        // Positions in the loaded text start at the first replaced entry's line, and those
+        // of later entries move by the lines and characters the edit added
+        if let Some(doc) = &mut doc {
+            let mark = starts[first];
+            shift(doc, mark.line() - 1, mark.index() - mark.col());
+        }
+        let lines_added =
+            text.matches('\n').count() as isize - replaced.matches('\n').count() as isize;
+        let chars_added = text.chars().count() as isize - replaced.chars().count() as isize;
+        let move_after = |node: &mut Node| {
+            node.mark = Marker::new(
+                node.mark.index().wrapping_add_signed(chars_added),
+                node.mark.line().wrapping_add_signed(lines_added),
+                node.mark.col(),
+            );
+        };
+        let doc_mark = doc.as_ref().map(|doc| doc.mark);
+        match (&mut root.kind, doc.map(|doc| doc.kind)) {
+            (NodeKind::Mapping(entries), spliced) => {
+                let spliced = match spliced {
+                    Some(NodeKind::Mapping(spliced)) => spliced,
+                    _ => Vec::new(),
+                };
+                for (key, value) in &mut entries[last + 1..] {
+                    walk(key, &move_after);
+                    walk(value, &move_after);
+                }
+                entries.splice(first..=last, spliced);
+            }
+            (NodeKind::Sequence(items), spliced) => {
+                let spliced = match spliced {
+                    Some(NodeKind::Sequence(spliced)) => spliced,
+                    _ => Vec::new(),
+                };
+                for item in &mut items[last + 1..] {
+                    walk(item, &move_after);
+                }
+                items.splice(first..=last, spliced);
+            }
+            _ => return false,
+        }
+        if first == 0 {
+            root.mark = doc_mark.unwrap_or_else(|| root_mark(root));
+        }
+        true
+    }
+
+    /// Validate the document against a schema already converted from JavaScript
+    pub(crate) fn validate_with(
+        &self,
+        schema: &JsonValue,
+        options: &JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let (doc, diagnostics) = self
+            .resolved()?
+            .ok_or_else(|| self.options.error("Empty YAML document"))?;
+        validate_resolved(doc, diagnostics, schema, options)
+    }
+
+    /// The resolved document, reporting values that could not be resolved the first time
+    pub(crate) fn resolved(&self) -> Result<Option<&(Yaml, Vec<Diagnostic>)>, JsValue> {
+        let Some(root) = &self.root else {
+            return Ok(None);
+        };
+        if let Some(resolved) = self.resolved.get() {
+            return Ok(Some(resolved));
+        }
+        let mut diagnostics = Vec::new();
+        let options = &self.options;
+        let mut resolver = Resolver::new(options);
+        let doc = resolver
+            .resolve_document(root, &mut diagnostics)
+            .map_err(|e| options.error(&e))?;
+        let mut warnings = resolver.take_warnings();
+        warnings.extend(diagnostics.iter().map(|d| d.message.clone()));
+        options.warn(warnings)?;
+        Ok(Some(self.resolved.get_or_init(|| (doc, diagnostics))))
+    }
+}
+
+/// Follow properties and indexes from the root, reading mappings the way conversion does
+fn select<'n>(
+    root: &'n Node,
+    segments: &[PathExpr],
+    options: &LoadOptions,
+) -> Result<Option<&'n Node>, String> {
+    // Issues are reported when the selected value is converted
+    let mut resolver = Resolver::new(options);
+    let mut node = root;
+    for segment in segments {
+        while let NodeKind::Alias(target) = &node.kind {
+            node = target;
+        }
+        let next = match (segment, &node.kind) {
+            (PathExpr::Property(name), NodeKind::Mapping(entries)) => resolver
+                .mapping(node, entries)?
+                .properties()
+                .find(|(_, key, _)| key_to_string(key).is_ok_and(|key| key == *name))
+                .map(|(_, _, value)| value),
+            (PathExpr::Index(index), NodeKind::Sequence(items)) => items.get(*index),
+            (PathExpr::Root, _) => Some(node),
+            _ => None,
+        };
+        match next {
+            Some(next) => node = next,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(node))
+}
+
+impl Edit {
+    /// Read an edit from `{ offset, removed, inserted }`
+    fn from_js(edit: &JsValue) -> Result<Self, JsValue> {
+        let count = |key: &str| match get_number(edit, key)? {
+            Some(count) if count >= 0.0 && count.fract() == 0.0 => Ok(count as usize),
+            None if key == "removed" => Ok(0),
+            _ => Err(JsValue::from_str(&format!(
+                "Edit '{}' must be a non-negative integer",
+                key
+            ))),
+        };
+        Ok(Edit {
+            offset: count("offset")?,
+            removed: count("removed")?,
+            inserted: get_string(edit, "inserted")?.unwrap_or_default(),
+        })
+    }
+
+    /// Apply the edit to a text
+    fn apply(&self, text: &mut String) -> Result<(), JsValue> {
+        let start = byte_index(text, self.offset);
+        let end = start.and_then(|start| Some(start + byte_index(&text[start..], self.removed)?));
+        let (Some(start), Some(end)) = (start, end) else {
+            return Err(JsValue::from_str(&format!(
+                "Edit at offset {} removing {} is outside the text or splits a character",
+                self.offset, self.removed
+            )));
+        };
+        text.replace_range(start..end, &self.inserted);
+        Ok(())
+    }
+}
+
+/// The byte index of a UTF-16 code unit index, if it is in the text and between characters
+fn byte_index(text: &str, units: usize) -> Option<usize> {
+    let mut count = 0;
+    for (index, c) in text.char_indices() {
+        if count >= units {
+            return (count == units).then_some(index);
+        }
+        count += c.len_utf16();
+    }
+    (count == units).then_some(text.len())
+}
+
+/// Byte offsets of the starts of the lines of a text
+fn line_starts(text: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+        .collect()
+}
+
+/// Whether a line is a document marker or a directive, which end or start documents
+fn is_boundary(line: &str) -> bool {
+    line.starts_with('%')
+        || ["---", "..."].iter().any(|marker| {
+            line.strip_prefix(marker)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t', '\r']))
+        })
+}
+
+/// Whether a root entry starts its line: a mapping key at column 0, or a sequence item on
+/// the line of its `-` indicator at column 0; an empty entry is placed at the token after it
+fn starts_line(text: &str, lines: &[usize], node: &Node, sequence: bool) -> bool {
+    let mark = &node.mark;
+    if matches!(&node.kind, NodeKind::Scalar(text, TScalarStyle::Plain) if text.is_empty()) {
+        false
+    } else if sequence {
+        lines
+            .get(mark.line() - 1)
+            .is_some_and(|&start| text[start..].starts_with('-'))
+    } else {
+        mark.col() == 0
+    }
+}
+
+/// Whether a node holds nodes tied to text outside their entry: aliases, which share the
+/// node of their anchor, and block scalars of only line breaks, placed at the token after them
+fn tied(node: &Node) -> bool {
+    holds(node, &|node| match &node.kind {
+        NodeKind::Alias(_) => true,
+        NodeKind::Scalar(text, TScalarStyle::Literal | TScalarStyle::Folded) => {
+            text.chars().all(|c| c == '\n')
+        }
+        _ => false,
+    })
+}
+
+/// Whether a node or any node inside it satisfies a predicate
+fn holds(node: &Node, f: &impl Fn(&Node) -> bool) -> bool {
+    f(node)
+        || match &node.kind {
+            NodeKind::Scalar(..) | NodeKind::Alias(_) => false,
+            NodeKind::Sequence(items) => items.iter().any(|item| holds(item, f)),
+            NodeKind::Mapping(entries) => entries
+                .iter()
+                .any(|(key, value)| holds(key, f) || holds(value, f)),
+        }
+}
+
+/// Call a function on a node and every node inside it
+fn walk(node: &mut Node, f: &impl Fn(&mut Node)) {
+    f(node);
+    match &mut node.kind {
+        NodeKind::Sequence(items) => items.iter_mut().for_each(|item| walk(item, f)),
+        NodeKind::Mapping(entries) => entries.iter_mut().for_each(|(key, value)| {
+            walk(key, f);
+            walk(value, f);
+        }),
+        NodeKind::Scalar(..) | NodeKind::Alias(_) => {}
+    }
+}
+
+/// Move the positions of the nodes of a text loaded on its own to where the text starts
+fn shift(node: &mut Node, lines: usize, index: usize) {
+    walk(node, &|node: &mut Node| {
+        let mark = node.mark;
+        node.mark = Marker::new(mark.index() + index, mark.line() + lines, mark.col());
+    });
+}
+
+/// The position the loader gives a block collection at the root, from its first entry
+fn root_mark(root: &Node) -> Marker {
+    match &root.kind {
+        // A block mapping is placed one column past its first key
+        NodeKind::Mapping(entries) => {
+            let key = entries[0].0.mark;
+            Marker::new(key.index() + 1, key.line(), key.col() + 1)
+        }
+        // A block sequence is placed at the `-` of its first item
+        NodeKind::Sequence(items) => {
+            let item = items[0].mark;
+            Marker::new(item.index() - item.col(), item.line(), 0)
+        }
+        _ => root.mark,
+    }
+}
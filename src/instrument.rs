@@ -0,0 +1,250 @@
+//! Instrumentation counters
+//!
+//! With the `instrumentation` feature, this module counts heap allocations, the size of the
+//! loaded node trees and the time spent in each phase of a parse or dump call, for
+//! `instrumentation()` to report once the call returns. Without the feature, the hooks do
+//! nothing and no counting allocator is installed, except for timing the phases of calls made
+//! with the `withTiming` option.
+
+use std::cell::Cell;
+
+use crate::timing::now_ms;
+
+/// A part of a call timed on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Phase {
+    /// Building node trees from the YAML text
+    Load,
+    /// Converting node trees to JSON or JavaScript values, or JavaScript values to YAML
+    Convert,
+    /// `JSON.parse` of the converted JSON text
+    JsonParse,
+    /// Writing YAML text
+    Emit,
+}
+
+#[cfg(feature = "instrumentation")]
+impl Phase {
+    const ALL: [Phase; 4] = [Phase::Load, Phase::Convert, Phase::JsonParse, Phase::Emit];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Load => "load",
+            Phase::Convert => "convert",
+            Phase::JsonParse => "jsonParse",
+            Phase::Emit => "emit",
+        }
+    }
+}
+
+/// Milliseconds a call made with the `withTiming` option spent in each part of it
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Timing {
+    /// Loading node trees, scanning the YAML text included
+    pub load: f64,
+    /// Building node trees from the scanned events
+    pub build: f64,
+    /// Converting node trees to JavaScript values, `JSON.parse` included
+    pub convert: f64,
+}
+
+thread_local! {
+    static TIMING: Cell<Option<Timing>> = const { Cell::new(None) };
+}
+
+/// Start timing the parts of the current call
+pub(crate) fn start_timing() {
+    TIMING.set(Some(Timing::default()));
+}
+
+/// Stop timing the current call, returning the time spent in each part
+pub(crate) fn take_timing() -> Option<Timing> {
+    TIMING.take()
+}
+
+/// Whether the current call is timed
+pub(crate) fn is_timing() -> bool {
+    TIMING.get().is_some()
+}
+
+/// Add to the time the current call spent building node trees
+pub(crate) fn record_build(ms: f64) {
+    record_timing(|timing| timing.build += ms);
+}
+
+/// Add the time spent in a phase to the timing of the current call
+fn record_phase(phase: Phase, ms: f64) {
+    record_timing(|timing| match phase {
+        Phase::Load => timing.load += ms,
+        Phase::Convert | Phase::JsonParse => timing.convert += ms,
+        Phase::Emit => {}
+    });
+}
+
+fn record_timing(f: impl FnOnce(&mut Timing)) {
+    if let Some(mut timing) = TIMING.get() {
+        f(&mut timing);
+        TIMING.set(Some(timing));
+    }
+}
+
+#[cfg(feature = "instrumentation")]
+mod counters {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::cell::RefCell;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use js_sys::{Object, Reflect};
+    use wasm_bindgen::prelude::*;
+
+    use super::{now_ms, record_phase, Phase};
+
+    // Bytes allocated since the call started, currently allocated, and most allocated at once
+    static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+    static LIVE: AtomicUsize = AtomicUsize::new(0);
+    static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+    /// The system allocator, counting the bytes it hands out
+    struct CountingAllocator;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    fn record_alloc(size: usize) {
+        ALLOCATED.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for CountingAllocator {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                record_alloc(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            if !new_ptr.is_null() {
+                LIVE.fetch_sub(layout.size(), Ordering::Relaxed);
+                record_alloc(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    /// Counters of the current call other than allocations
+    #[derive(Default)]
+    struct Counters {
+        // Bytes allocated when the call started
+        start_live: usize,
+        peak_tree_bytes: usize,
+        // Milliseconds per phase, indexed like `Phase::ALL`
+        phase_ms: [f64; 4],
+    }
+
+    thread_local! {
+        static COUNTERS: RefCell<Counters> = RefCell::default();
+    }
+
+    /// Reset the counters at the start of a call
+    pub(crate) fn start_call() {
+        let live = LIVE.load(Ordering::Relaxed);
+        ALLOCATED.store(0, Ordering::Relaxed);
+        PEAK.store(live, Ordering::Relaxed);
+        COUNTERS.with(|counters| {
+            *counters.borrow_mut() = Counters {
+                start_live: live,
+                ..Counters::default()
+            }
+        });
+    }
+
+    /// Run `f`, adding the time it takes to `phase`
+    pub(crate) fn timed<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = now_ms();
+        let result = f();
+        let elapsed = now_ms() - start;
+        let index = Phase::ALL.iter().position(|&p| p == phase).unwrap_or(0);
+        COUNTERS.with(|counters| counters.borrow_mut().phase_ms[index] += elapsed);
+        record_phase(phase, elapsed);
+        result
+    }
+
+    /// Record the approximate size of the node trees held at once
+    pub(crate) fn record_tree(bytes: usize) {
+        COUNTERS.with(|counters| {
+            let mut counters = counters.borrow_mut();
+            counters.peak_tree_bytes = counters.peak_tree_bytes.max(bytes);
+        });
+    }
+
+    /// Report the counters of the last parse or dump call
+    ///
+    /// Returns `{ bytesAllocated, peakBytes, peakTreeBytes, phases }`: the bytes allocated
+    /// during the call, the most bytes it held allocated at once, the approximate size of the
+    /// largest node trees loaded, and the milliseconds spent in each phase (`load`, `convert`,
+    /// `jsonParse`, `emit`).
+    #[wasm_bindgen]
+    pub fn instrumentation() -> Result<JsValue, JsValue> {
+        let allocated = ALLOCATED.load(Ordering::Relaxed);
+        let peak = PEAK.load(Ordering::Relaxed);
+        COUNTERS.with(|counters| {
+            let counters = counters.borrow();
+            let phases = Object::new();
+            for (phase, ms) in Phase::ALL.iter().zip(counters.phase_ms) {
+                Reflect::set(&phases, &JsValue::from_str(phase.name()), &ms.into())?;
+            }
+            let result = Object::new();
+            for (key, value) in [
+                ("bytesAllocated", allocated as f64),
+                ("peakBytes", peak.saturating_sub(counters.start_live) as f64),
+                ("peakTreeBytes", counters.peak_tree_bytes as f64),
+            ] {
+                Reflect::set(&result, &JsValue::from_str(key), &value.into())?;
+            }
+            Reflect::set(&result, &JsValue::from_str("phases"), &phases)?;
+            Ok(result.into())
+        })
+    }
+}
+
+#[cfg(feature = "instrumentation")]
+pub use counters::instrumentation;
+#[cfg(feature = "instrumentation")]
+pub(crate) use counters::{record_tree, start_call, timed};
+
+/// Reset the counters at the start of a call
+#[cfg(not(feature = "instrumentation"))]
+pub(crate) fn start_call() {}
+
+/// Run `f`, adding the time it takes to `phase`
+#[cfg(not(feature = "instrumentation"))]
+pub(crate) fn timed<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !is_timing() {
+        return f();
+    }
+    let start = now_ms();
+    let result = f();
+    record_phase(phase, now_ms() - start);
+    result
+}
+
+/// Record the approximate size of the node trees held at once
+#[cfg(not(feature = "instrumentation"))]
+pub(crate) fn record_tree(_bytes: usize) {}
@@ -0,0 +1,529 @@
+//! JSON emission
+//!
+//! This module converts loaded YAML nodes into a JSON string that is handed to `JSON.parse`,
+//! and recognizes input that is plain JSON already, which `JSON.parse` then reads directly.
+
+use std::collections::HashSet;
+use std::fmt::Write as FmtWrite;
+use std::mem;
+
+use yaml_rust2::scanner::Marker;
+use yaml_rust2::Yaml;
+
+use crate::loader::{Node, NodeKind};
+use crate::options::{FloatMode, LoadOptions};
+use crate::resolve::{Pairs, Resolver};
+use crate::timestamp::format_timestamp;
+
+/// Writes YAML node trees as JSON
+pub(crate) struct JsonWriter<'a> {
+    resolver: Resolver<'a>,
+    output: String,
+    // (output byte offset, source position) of every emitted key and value
+    source_map: Option<Vec<(usize, Marker)>>,
+    // Whether a non-finite float was written as `null`
+    non_finite: bool,
+}
+
+impl<'a> JsonWriter<'a> {
+    pub fn new(options: &'a LoadOptions) -> Self {
+        JsonWriter {
+            resolver: Resolver::new(options),
+            output: String::with_capacity(1024),
+            source_map: None,
+            non_finite: false,
+        }
+    }
+
+    /// Write into a buffer left by an earlier writer instead of a new one
+    pub fn with_buffer(mut self, mut buffer: String) -> Self {
+        buffer.clear();
+        self.output = buffer;
+        self
+    }
+
+    /// Record the source position of every emitted key and value
+    pub fn with_source_map(mut self) -> Self {
+        self.source_map = Some(Vec::new());
+        self
+    }
+
+    /// Convert a document to a JSON string
+    pub fn write_document(&mut self, node: &Node) -> Result<String, String> {
+        self.output.clear();
+        if let Some(source_map) = self.source_map.as_mut() {
+            source_map.clear();
+        }
+        self.non_finite = false;
+        self.resolver.start_document();
+        self.write_node(node)?;
+        Ok(mem::take(&mut self.output))
+    }
+
+    /// Whether the last document held `.inf` or `.nan`, which JSON has no form for
+    ///
+    /// Such floats are written as `null`, like `JSON.stringify` does.
+    pub fn has_non_finite(&self) -> bool {
+        self.non_finite
+    }
+
+    /// Flatten the recorded positions into `(jsonOffset, line, column)` triples
+    ///
+    /// `json` must be the string returned by the last `write_document` call; byte offsets are
+    /// translated to UTF-16 offsets so they index JavaScript strings directly.
+    pub fn source_map(&self, json: &str) -> Vec<u32> {
+        let Some(entries) = self.source_map.as_ref() else {
+            return Vec::new();
+        };
+        let mut result = Vec::with_capacity(entries.len() * 3);
+        let mut chars = json.char_indices().peekable();
+        let mut utf16_offset = 0;
+        for (byte_offset, mark) in entries {
+            while let Some((i, c)) = chars.peek() {
+                if i >= byte_offset {
+                    break;
+                }
+                utf16_offset += c.len_utf16();
+                chars.next();
+            }
+            result.push(utf16_offset as u32);
+            result.push(mark.line() as u32);
+            result.push(mark.col() as u32 + 1);
+        }
+        result
+    }
+
+    /// Take the warnings collected so far
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        self.resolver.take_warnings()
+    }
+
+    fn record(&mut self, mark: Marker) {
+        if let Some(source_map) = self.source_map.as_mut() {
+            source_map.push((self.output.len(), mark));
+        }
+    }
+
+    /// Write a node, recording its position
+    fn write_node(&mut self, node: &Node) -> Result<(), String> {
+        self.record(node.mark);
+        self.write_contents(node)
+    }
+
+    /// Write the contents of a node; aliases are recorded at the alias, not the anchor
+    fn write_contents(&mut self, node: &Node) -> Result<(), String> {
+        self.resolver.count_expanded()?;
+        self.resolver.charge(node)?;
+        self.resolver.descend(node)?;
+        let written = self.write_tagged(node);
+        self.resolver.ascend(node);
+        written
+    }
+
+    /// Write the contents of a node, in a `{ tag, value }` wrapper for a kept local tag
+    fn write_tagged(&mut self, node: &Node) -> Result<(), String> {
+        if let Some(tag) = self.resolver.wrapped_tag(node) {
+            self.output.push_str("{\"tag\":");
+            write_json_string(&tag, &mut self.output)?;
+            self.output.push_str(",\"value\":");
+            self.write_untagged(node)?;
+            self.output.push('}');
+            return Ok(());
+        }
+        self.write_untagged(node)
+    }
+
+    /// Write the contents of a node as if an unknown tag were absent
+    fn write_untagged(&mut self, node: &Node) -> Result<(), String> {
+        match &node.kind {
+            NodeKind::Scalar(value, style) => {
+                // Timestamps are written the way `JSON.stringify` writes a `Date`
+                if let Some(ms) = self.resolver.timestamp(node, value, *style)? {
+                    return write_json_string(&format_timestamp(ms), &mut self.output);
+                }
+                let scalar = self.resolver.scalar(node, value, *style)?;
+                self.write_scalar(&scalar)?;
+            }
+            NodeKind::Sequence(items) => {
+                if let Some(pairs) = self.resolver.pairs(node, items)? {
+                    return self.write_pairs(&pairs);
+                }
+                self.resolver
+                    .warn(self.resolver.collection_warning(node, "seq"), &node.mark);
+                self.output.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.output.push(',');
+                    }
+                    self.write_node(item)?;
+                }
+                self.output.push(']');
+            }
+            NodeKind::Mapping(entries) => {
+                let mapping = self.resolver.mapping(node, entries)?;
+                self.output.push('{');
+                for (n, (key_node, key, value)) in mapping.properties().enumerate() {
+                    if n > 0 {
+                        self.output.push(',');
+                    }
+
+                    // Write key as string
+                    self.record(key_node.mark);
+                    match key {
+                        Yaml::String(s) => write_json_string(s, &mut self.output)?,
+                        key => write_json_string(&key_to_string(key)?, &mut self.output)?,
+                    }
+
+                    self.output.push(':');
+                    self.write_node(value)?;
+                }
+                self.output.push('}');
+            }
+            NodeKind::Alias(target) => {
+                let outer = self.resolver.enter_alias(node)?;
+                let written = self.write_contents(target);
+                self.resolver.leave_alias(outer);
+                written?
+            }
+        }
+        Ok(())
+    }
+
+    /// Write `!!omap` and `!!pairs` entries as an array of `[key, value]` arrays
+    fn write_pairs(&mut self, pairs: &Pairs) -> Result<(), String> {
+        self.output.push('[');
+        for (i, (key, value)) in pairs.entries.iter().enumerate() {
+            if i > 0 {
+                self.output.push(',');
+            }
+            self.output.push('[');
+            self.write_node(key)?;
+            self.output.push(',');
+            self.write_node(value)?;
+            self.output.push(']');
+        }
+        self.output.push(']');
+        Ok(())
+    }
+
+    /// Write a resolved scalar
+    fn write_scalar(&mut self, yaml: &Yaml) -> Result<(), String> {
+        match yaml {
+            Yaml::Null => self.output.push_str("null"),
+            Yaml::Boolean(b) => {
+                self.output.push_str(if *b { "true" } else { "false" });
+            }
+            Yaml::Integer(i) => {
+                write!(self.output, "{}", i).map_err(|e| e.to_string())?;
+            }
+            Yaml::Real(s) => match yaml.as_f64() {
+                Some(f) => self.write_float(f)?,
+                None => return Err(format!("Invalid float: {}", s)),
+            },
+            Yaml::String(s) => write_json_string(s, &mut self.output)?,
+            _ => return Err("Invalid YAML value".to_string()),
+        }
+        Ok(())
+    }
+
+    /// Write a float honoring `floatPrecision` and `floatMode`
+    fn write_float(&mut self, f: f64) -> Result<(), String> {
+        let f = self.resolver.float(f)?;
+        let float_mode = self.resolver.options.float_mode;
+        if float_mode == FloatMode::Wrap {
+            self.output.push_str("{\"$float\":");
+        }
+        let start = self.output.len();
+        if !f.is_finite() {
+            self.non_finite = true;
+            self.output.push_str("null");
+        } else {
+            write!(self.output, "{}", f).map_err(|e| e.to_string())?;
+            if float_mode != FloatMode::Number && !self.output[start..].contains('.') {
+                self.output.push_str(".0");
+            }
+        }
+        if float_mode == FloatMode::Wrap {
+            self.output.push('}');
+        }
+        Ok(())
+    }
+}
+
+/// Name the property of a non-string mapping key
+///
+/// Scalars become their JSON text (`1`, `true`, `null`) and collections their JSON encoding
+/// (`["a","b"]`), with nested keys named the same way.
+pub(crate) fn key_to_string(key: &Yaml) -> Result<String, String> {
+    match key {
+        Yaml::String(s) => Ok(s.clone()),
+        key => {
+            let mut output = String::new();
+            write_key_json(key, &mut output)?;
+            Ok(output)
+        }
+    }
+}
+
+/// Write a resolved key as JSON; non-finite floats are written like `String(number)`
+fn write_key_json(key: &Yaml, output: &mut String) -> Result<(), String> {
+    match key {
+        Yaml::Null => output.push_str("null"),
+        Yaml::Boolean(b) => output.push_str(if *b { "true" } else { "false" }),
+        Yaml::Integer(i) => write!(output, "{}", i).map_err(|e| e.to_string())?,
+        Yaml::Real(s) => match key.as_f64() {
+            Some(f) if f.is_nan() => output.push_str("NaN"),
+            Some(f) if f.is_infinite() => {
+                output.push_str(if f > 0.0 { "Infinity" } else { "-Infinity" })
+            }
+            Some(f) => write!(output, "{}", f).map_err(|e| e.to_string())?,
+            None => return Err(format!("Invalid float: {}", s)),
+        },
+        Yaml::String(s) => write_json_string(s, output)?,
+        Yaml::Array(items) => {
+            output.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_key_json(item, output)?;
+            }
+            output.push(']');
+        }
+        Yaml::Hash(hash) => {
+            output.push('{');
+            for (i, (key, value)) in hash.iter().enumerate() {
+                if i > 0 {
+                    output.push(',');
+                }
+                write_json_string(&key_to_string(key)?, output)?;
+                output.push(':');
+                write_key_json(value, output)?;
+            }
+            output.push('}');
+        }
+        _ => return Err("Invalid YAML value".to_string()),
+    }
+    Ok(())
+}
+
+/// Write a string as a quoted JSON string literal
+fn write_json_string(s: &str, output: &mut String) -> Result<(), String> {
+    output.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\x08' => output.push_str("\\b"),
+            '\x0C' => output.push_str("\\f"),
+            c if c.is_control() => {
+                write!(output, "\\u{:04x}", c as u32).map_err(|e| e.to_string())?;
+            }
+            c => output.push(c),
+        }
+    }
+    output.push('"');
+    Ok(())
+}
+
+/// Whether input is a single JSON object or array that `JSON.parse` reads the way the YAML
+/// loader would
+///
+/// Besides the JSON grammar, this turns down what the two read differently or what the loader
+/// has to report: duplicate keys unless `last_key_wins` (the `duplicateKey: 'last'` policy
+/// `JSON.parse` follows), `<<` merge keys, keys with escapes or on a line of their own, `-0`
+/// (an integer zero in YAML), escaped surrogates, tabs, and `max_depth` levels of nesting.
+pub(crate) fn is_plain_json(input: &str, max_depth: usize, last_key_wins: bool) -> bool {
+    let mut scanner = JsonScanner {
+        bytes: input.as_bytes(),
+        pos: 0,
+        max_depth,
+        last_key_wins,
+    };
+    scanner.skip_whitespace();
+    if !matches!(scanner.peek(), Some(b'{' | b'[')) || !scanner.value(0) {
+        return false;
+    }
+    scanner.skip_whitespace();
+    scanner.pos == scanner.bytes.len()
+}
+
+/// Checks JSON text a byte at a time without building values
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    max_depth: usize,
+    // Whether a repeated key is read as `JSON.parse` reads it
+    last_key_wins: bool,
+}
+
+impl<'a> JsonScanner<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let found = self.peek() == Some(byte);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip spaces but no line break, as between an implicit key and its colon
+    fn skip_spaces(&mut self) {
+        while self.peek() == Some(b' ') {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> bool {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b'{') => depth + 1 < self.max_depth && self.object(depth + 1),
+            Some(b'[') => depth + 1 < self.max_depth && self.array(depth + 1),
+            Some(b'"') => self.string().is_some(),
+            Some(b't') => self.literal(b"true"),
+            Some(b'f') => self.literal(b"false"),
+            Some(b'n') => self.literal(b"null"),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => false,
+        }
+    }
+
+    fn object(&mut self, depth: usize) -> bool {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return true;
+        }
+        let mut keys = HashSet::new();
+        loop {
+            self.skip_whitespace();
+            let Some(key) = self.string() else {
+                return false;
+            };
+            if key.contains(&b'\\') || key == b"<<" || !(keys.insert(key) || self.last_key_wins) {
+                return false;
+            }
+            self.skip_spaces();
+            if !self.eat(b':') || !self.value(depth) {
+                return false;
+            }
+            self.skip_whitespace();
+            if self.eat(b'}') {
+                return true;
+            }
+            if !self.eat(b',') {
+                return false;
+            }
+        }
+    }
+
+    fn array(&mut self, depth: usize) -> bool {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return true;
+        }
+        loop {
+            if !self.value(depth) {
+                return false;
+            }
+            self.skip_whitespace();
+            if self.eat(b']') {
+                return true;
+            }
+            if !self.eat(b',') {
+                return false;
+            }
+        }
+    }
+
+    /// Scan a string, returning its text between the quotes
+    fn string(&mut self) -> Option<&'a [u8]> {
+        if !self.eat(b'"') {
+            return None;
+        }
+        let start = self.pos;
+        loop {
+            match self.peek()? {
+                b'"' => break,
+                b'\\' => {
+                    self.pos += 1;
+                    match self.peek()? {
+                        b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't' => {}
+                        b'u' => {
+                            let hex = self.bytes.get(self.pos + 1..self.pos + 5)?;
+                            let hex = std::str::from_utf8(hex).ok()?;
+                            let code = u32::from_str_radix(hex, 16).ok()?;
+                            if !hex.bytes().all(|b| b.is_ascii_hexdigit())
+                                || (0xD800..0xE000).contains(&code)
+                            {
+                                return None;
+                            }
+                            self.pos += 4;
+                        }
+                        _ => return None,
+                    }
+                }
+                byte if byte < 0x20 => return None,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        let text = &self.bytes[start..self.pos];
+        self.pos += 1;
+        Some(text)
+    }
+
+    fn literal(&mut self, text: &[u8]) -> bool {
+        let found = self.bytes[self.pos..].starts_with(text);
+        if found {
+            self.pos += text.len();
+        }
+        found
+    }
+
+    /// Scan `-?(0|[1-9][0-9]*)(\.[0-9]+)?([eE][-+]?[0-9]+)?`, turning down `-0`
+    fn number(&mut self) -> bool {
+        let start = self.pos;
+        self.eat(b'-');
+        if !self.eat(b'0') && self.digits() == 0 {
+            return false;
+        }
+        if &self.bytes[start..self.pos] == b"-0" && !matches!(self.peek(), Some(b'.' | b'e' | b'E'))
+        {
+            return false;
+        }
+        if self.eat(b'.') && self.digits() == 0 {
+            return false;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            if !self.eat(b'-') {
+                self.eat(b'+');
+            }
+            if self.digits() == 0 {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.peek().is_some_and(|b| b.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos - start
+    }
+}
@@ -0,0 +1,154 @@
+//! Depth-limited conversion
+//!
+//! This module converts a document only down to a given depth, leaving deeper collections as
+//! handles that are converted on demand, for tree views over documents too large to convert at
+//! once.
+
+use std::fmt::Write;
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+use crate::json::key_to_string;
+use crate::loader::{error_at, load_documents, Node, NodeKind};
+use crate::options::{get_limit, LoadOptions};
+use crate::resolve::Resolver;
+use crate::value::ValueBuilder;
+
+/// A parsed document converted on demand
+#[wasm_bindgen]
+pub struct LazyDocument {
+    root: Option<Node>,
+    options: LoadOptions,
+    max_depth: usize,
+    // Child indexes from the root of the collection behind each handle
+    handles: Vec<Vec<usize>>,
+}
+
+/// Parse the first document of a YAML string for conversion on demand
+///
+/// Nothing is converted until `root()` is called. Collections more than `maxDepth` levels
+/// (default 1) below the converted node become `{ $handle, kind }` objects, with `kind`
+/// `'mapping'` or `'sequence'`, that `expand(handle)` converts the same way.
+///
+/// @param {string} input - The YAML document
+/// @param {Object} options - Load options and `maxDepth`
+/// @returns {LazyDocument} - The parsed document
+#[wasm_bindgen]
+pub fn parse_lazy(input: &str, options: JsValue) -> Result<LazyDocument, JsValue> {
+    let max_depth = if options.is_undefined() || options.is_null() {
+        None
+    } else {
+        get_limit(&options, "maxDepth")?
+    };
+    if max_depth == Some(0) {
+        return Err(JsValue::from_str("Option 'maxDepth' must be at least 1"));
+    }
+    let options = LoadOptions::from_js_with(&options, &["maxDepth"])?;
+    options.reject_timing("parseLazy")?;
+
+    let docs = load_documents(input, &options.limits)
+        .map_err(|e| options.error(&error_at(e.info(), e.marker())))?;
+
+    Ok(LazyDocument {
+        root: docs.into_iter().next(),
+        options,
+        max_depth: max_depth.unwrap_or(1),
+        handles: Vec::new(),
+    })
+}
+
+#[wasm_bindgen]
+impl LazyDocument {
+    /// Convert the top levels of the document; `null` for an empty input
+    pub fn root(&mut self) -> Result<JsValue, JsValue> {
+        self.convert(Vec::new())
+    }
+
+    /// Convert the collection behind a handle, given as the handle object or its id
+    pub fn expand(&mut self, handle: &JsValue) -> Result<JsValue, JsValue> {
+        let id = match handle.as_f64() {
+            Some(id) => Some(id),
+            None if handle.is_object() => {
+                Reflect::get(handle, &JsValue::from_str("$handle"))?.as_f64()
+            }
+            None => None,
+        };
+        let steps = id
+            .filter(|id| id.fract() == 0.0 && *id >= 0.0)
+            .and_then(|id| self.handles.get(id as usize))
+            .cloned()
+            .ok_or_else(|| JsValue::from_str("Unknown handle"))?;
+        self.convert(steps)
+    }
+
+    /// Number of handles created so far
+    #[wasm_bindgen(getter, js_name = handleCount)]
+    pub fn handle_count(&self) -> usize {
+        self.handles.len()
+    }
+}
+
+impl LazyDocument {
+    fn convert(&mut self, steps: Vec<usize>) -> Result<JsValue, JsValue> {
+        let Some(root) = &self.root else {
+            return Ok(JsValue::NULL);
+        };
+        let options = &self.options;
+        let (node, path) = locate(root, &steps, options).map_err(|e| options.error(&e))?;
+
+        let mut builder =
+            ValueBuilder::new(options).with_depth_limit(self.max_depth, self.handles.len());
+        let value = builder.build_at(node, steps, &path);
+        options.warn(builder.take_warnings())?;
+        let value = value?;
+        self.handles.extend(builder.take_handles());
+        Ok(value)
+    }
+}
+
+/// Find the node at `steps` from the root, and its path, the way `ValueBuilder` numbers children
+fn locate<'n>(
+    root: &'n Node,
+    steps: &[usize],
+    options: &LoadOptions,
+) -> Result<(&'n Node, String), String> {
+    // Issues were reported when the handle was created
+    let mut resolver = Resolver::new(options);
+    let mut node = root;
+    let mut path = String::from("$");
+    for &step in steps {
+        while let NodeKind::Alias(target) = &node.kind {
+            node = target;
+        }
+        node = match &node.kind {
+            NodeKind::Sequence(items) => match resolver.pairs(node, items)? {
+                Some(pairs) => {
+                    let _ = write!(path, "[{}][{}]", step / 2, step % 2);
+                    pairs
+                        .entries
+                        .get(step / 2)
+                        .map(|&(key, value)| if step % 2 == 0 { key } else { value })
+                }
+                None => {
+                    let _ = write!(path, "[{}]", step);
+                    items.get(step)
+                }
+            },
+            NodeKind::Mapping(entries) => {
+                let mapping = resolver.mapping(node, entries)?;
+                let property = mapping.properties().nth(step);
+                match property {
+                    Some((_, key, value)) => {
+                        let _ = write!(path, ".{}", key_to_string(key)?);
+                        Some(value)
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        }
+        .ok_or_else(|| "Handle does not match the document".to_string())?;
+    }
+    Ok((node, path))
+}
@@ -5,16 +5,61 @@
 
 use wasm_bindgen::prelude::*;
 
+mod anchors;
+mod codes;
+mod compare;
+mod dump;
+mod graph;
+mod handle;
+mod instrument;
+mod json;
+mod lazy;
+mod loader;
+mod options;
 mod parse;
+mod pipeline;
+mod plugins;
+mod resolve;
+mod schema;
+mod skeleton;
+mod split;
 mod stream;
+mod timestamp;
+mod timing;
+mod types;
+mod units;
 mod validate;
+mod value;
 mod yamlpath;
 
 // Re-export the main functions
-pub use parse::{load, loadAll, load_all, parse, parse_all};
-pub use stream::parse_stream;
-pub use validate::validate;
-pub use yamlpath::query;
+pub use anchors::analyze_aliases;
+pub use codes::error_code;
+pub use compare::compare_with_reference;
+pub use dump::{dump, estimate_dump_size};
+pub use graph::to_dot;
+pub use handle::{parse_to_handle, DocumentHandle};
+#[cfg(feature = "instrumentation")]
+pub use instrument::instrumentation;
+pub use lazy::{parse_lazy, LazyDocument};
+pub use parse::{
+    alloc_input, create_parser, load, loadAll, load_all, parse, parse_all, parse_batch,
+    parse_bytes, parse_in_place, parse_lenient, parse_preview, parse_to_json_string,
+    parse_with_meta, start_parse, InputBuffer, ParseTask, Parser,
+};
+pub use pipeline::pipeline;
+pub use plugins::{
+    register_native_format, register_native_lint_rule, register_native_path_function,
+    register_plugin, LintNode,
+};
+pub use skeleton::skeleton;
+pub use split::{count_documents, extract_document, index_documents, split_documents};
+pub use stream::{create_stream_parser, parse_stream, transform_stream, StreamParser};
+pub use types::register_type;
+pub use validate::{compile_schema, validate, validate_batch, verify_examples, CompiledSchema};
+pub use yamlpath::{
+    analyze_path, compile_path, create_query_session, query, query_many, CompiledPath, QuerySession,
+};
 
 /// Version information
 #[wasm_bindgen]
@@ -0,0 +1,774 @@
+//! YAML document loader
+//!
+//! This module builds a node tree from yaml-rust2 parser events. Unlike `YamlLoader`, it keeps
+//! scalar styles, tags and source positions, leaving scalar resolution to the selected schema.
+
+use std::collections::HashMap;
+use std::mem;
+use std::ops::Range;
+use std::rc::Rc;
+use std::str::Chars;
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser, Tag};
+use yaml_rust2::scanner::{Marker, ScanError, TScalarStyle};
+use yaml_rust2::Yaml;
+
+use crate::instrument::{is_timing, record_build, record_tree, timed, Phase};
+use crate::options::{Limits, LoadOptions};
+use crate::resolve::Resolver;
+use crate::schema::Diagnostic;
+use crate::split::Ranges;
+use crate::timing::now_ms;
+
+/// A node of a loaded YAML document
+#[derive(Debug, Clone)]
+pub(crate) struct Node {
+    /// Node contents
+    pub kind: NodeKind,
+    /// Explicit tag, if any
+    pub tag: Option<Tag>,
+    /// Position of the node in the source
+    pub mark: Marker,
+}
+
+/// Node contents
+#[derive(Debug, Clone)]
+pub(crate) enum NodeKind {
+    /// Unresolved scalar text and the style it was written in
+    Scalar(String, TScalarStyle),
+    /// Sequence items
+    Sequence(Vec<Node>),
+    /// Mapping entries in document order, duplicates included
+    Mapping(Vec<(Node, Node)>),
+    /// Alias to an anchored node
+    Alias(Rc<Node>),
+}
+
+/// The `%YAML` and `%TAG` directives written before a document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Directives {
+    /// Version declared by `%YAML` (`1.1`)
+    pub version: Option<String>,
+    /// `(handle, prefix)` pairs declared by `%TAG`, in order
+    pub tags: Vec<(String, String)>,
+}
+
+impl Directives {
+    /// Read the directives in the text between two documents
+    ///
+    /// Only directives, comments and `...` markers can appear there, so every line starting
+    /// with `%` is a directive; reserved directives are ignored.
+    fn read(text: &str) -> Self {
+        let mut directives = Directives::default();
+        for line in text.lines() {
+            let Some(line) = line.strip_prefix('%') else {
+                continue;
+            };
+            let mut words = line.split_whitespace();
+            match (words.next(), words.next(), words.next()) {
+                (Some("YAML"), Some(version), _) => directives.version = Some(version.to_string()),
+                (Some("TAG"), Some(handle), Some(prefix)) => directives
+                    .tags
+                    .push((handle.to_string(), prefix.to_string())),
+                _ => {}
+            }
+        }
+        directives
+    }
+}
+
+/// Approximate bytes of memory a node takes, not counting its children
+pub(crate) fn node_bytes(node: &Node) -> usize {
+    match &node.kind {
+        NodeKind::Scalar(text, _) => mem::size_of::<Node>() + text.len(),
+        _ => mem::size_of::<Node>(),
+    }
+}
+
+/// Approximate bytes of memory a node tree takes, not counting alias targets
+fn tree_bytes(node: &Node) -> usize {
+    node_bytes(node)
+        + match &node.kind {
+            NodeKind::Sequence(items) => items.iter().map(tree_bytes).sum(),
+            NodeKind::Mapping(entries) => entries
+                .iter()
+                .map(|(key, value)| tree_bytes(key) + tree_bytes(value))
+                .sum(),
+            NodeKind::Scalar(..) | NodeKind::Alias(_) => 0,
+        }
+}
+
+/// Event receiver building `Node` trees
+struct Loader {
+    docs: Vec<Node>,
+    // (node under construction, anchor_id) tuples
+    doc_stack: Vec<(Node, usize)>,
+    // pending key of each open mapping
+    key_stack: Vec<Option<Node>>,
+    anchors: HashMap<usize, Rc<Node>>,
+    error: Option<ScanError>,
+    // Character indexes of the start and end of the last document
+    doc_span: Range<usize>,
+    limits: Limits,
+    // Approximate bytes taken by the trees loaded, nodes loaded and documents started
+    memory_used: usize,
+    nodes: usize,
+    documents: usize,
+}
+
+impl MarkedEventReceiver for Loader {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        if self.error.is_some() {
+            return;
+        }
+        match ev {
+            Event::DocumentStart => {
+                self.documents += 1;
+                if self.documents > self.limits.max_documents {
+                    let info = format!(
+                        "stream holds more than {} documents (maxDocuments)",
+                        self.limits.max_documents
+                    );
+                    self.error = Some(ScanError::new_string(mark, info));
+                    return;
+                }
+                self.doc_span.start = mark.index();
+            }
+            Event::DocumentEnd => {
+                self.doc_span.end = mark.index();
+                match self.doc_stack.pop() {
+                    Some((node, _)) => self.docs.push(node),
+                    None => self.docs.push(Node {
+                        kind: NodeKind::Scalar(String::new(), TScalarStyle::Plain),
+                        tag: None,
+                        mark,
+                    }),
+                }
+            }
+            Event::SequenceStart(aid, tag) => {
+                self.check_nesting(mark);
+                let node = Node {
+                    kind: NodeKind::Sequence(Vec::new()),
+                    tag,
+                    mark,
+                };
+                self.add_node(&node);
+                self.doc_stack.push((node, aid));
+            }
+            Event::MappingStart(aid, tag) => {
+                self.check_nesting(mark);
+                let node = Node {
+                    kind: NodeKind::Mapping(Vec::new()),
+                    tag,
+                    mark,
+                };
+                self.add_node(&node);
+                self.doc_stack.push((node, aid));
+                self.key_stack.push(None);
+            }
+            Event::SequenceEnd => {
+                let node = self.doc_stack.pop().unwrap();
+                self.insert_new_node(node);
+            }
+            Event::MappingEnd => {
+                self.key_stack.pop().unwrap();
+                let node = self.doc_stack.pop().unwrap();
+                self.insert_new_node(node);
+            }
+            Event::Scalar(value, style, aid, tag) => {
+                let node = Node {
+                    kind: NodeKind::Scalar(value, style),
+                    tag,
+                    mark,
+                };
+                self.add_node(&node);
+                self.insert_new_node((node, aid));
+            }
+            Event::Alias(id) => match self.anchors.get(&id) {
+                Some(target) => {
+                    let node = Node {
+                        kind: NodeKind::Alias(Rc::clone(target)),
+                        tag: None,
+                        mark,
+                    };
+                    self.add_node(&node);
+                    self.insert_new_node((node, 0));
+                }
+                None => self.error = Some(ScanError::new(mark, "unknown anchor")),
+            },
+            Event::Nothing | Event::StreamStart | Event::StreamEnd => {}
+        }
+    }
+}
+
+impl Loader {
+    fn new(limits: &Limits) -> Self {
+        Loader {
+            docs: Vec::new(),
+            doc_stack: Vec::new(),
+            key_stack: Vec::new(),
+            anchors: HashMap::new(),
+            error: None,
+            doc_span: 0..0,
+            limits: *limits,
+            memory_used: 0,
+            nodes: 0,
+            documents: 0,
+        }
+    }
+
+    /// Forget the anchors and counts of the previous document
+    fn start_document(&mut self) {
+        self.anchors.clear();
+        self.memory_used = 0;
+        self.nodes = 0;
+    }
+
+    /// Take the items of an untagged sequence at the root of the document being loaded, once
+    /// `size` of them are loaded; the memory they take is no longer counted
+    fn take_root_items(&mut self, size: usize) -> Option<Vec<Node>> {
+        let (root, _) = self.doc_stack.first_mut()?;
+        match &mut root.kind {
+            NodeKind::Sequence(items) if root.tag.is_none() && items.len() >= size => {
+                let items = mem::take(items);
+                let bytes = items.iter().map(tree_bytes).sum::<usize>();
+                self.memory_used = self.memory_used.saturating_sub(bytes);
+                Some(items)
+            }
+            _ => None,
+        }
+    }
+
+    /// Fail on input longer than `maxInputSize` before any of it is parsed
+    fn check_input(&mut self, input: &str) {
+        if input.len() > self.limits.max_input_size {
+            let info = format!(
+                "input is longer than {} bytes (maxInputSize)",
+                self.limits.max_input_size
+            );
+            self.error = Some(ScanError::new_string(Marker::new(0, 1, 0), info));
+        }
+    }
+
+    /// Count a new node, failing past `maxNodes`, `maxScalarLength` or `maxMemoryBytes`
+    fn add_node(&mut self, node: &Node) {
+        self.nodes += 1;
+        if self.nodes > self.limits.max_nodes && self.error.is_none() {
+            let info = format!(
+                "input holds more than {} nodes (maxNodes)",
+                self.limits.max_nodes
+            );
+            self.error = Some(ScanError::new_string(node.mark, info));
+        }
+        if let NodeKind::Scalar(text, _) = &node.kind {
+            let max = self.limits.max_scalar_length;
+            if text.len() > max && text.chars().count() > max && self.error.is_none() {
+                let info = format!("scalar is longer than {} characters (maxScalarLength)", max);
+                self.error = Some(ScanError::new_string(node.mark, info));
+            }
+        }
+        self.charge(node_bytes(node), node.mark);
+    }
+
+    /// Fail when a collection starting at `mark` would nest past `maxNestingDepth`
+    fn check_nesting(&mut self, mark: Marker) {
+        if self.doc_stack.len() >= self.limits.max_nesting_depth && self.error.is_none() {
+            let info = format!(
+                "nesting exceeds {} levels (maxNestingDepth)",
+                self.limits.max_nesting_depth
+            );
+            self.error = Some(ScanError::new_string(mark, info));
+        }
+    }
+
+    /// Count memory taken by the trees, failing past `maxMemoryBytes`
+    fn charge(&mut self, bytes: usize, mark: Marker) {
+        self.memory_used = self.memory_used.saturating_add(bytes);
+        if self.memory_used > self.limits.max_memory_bytes && self.error.is_none() {
+            let info = format!(
+                "loading needs more than {} bytes of memory (maxMemoryBytes)",
+                self.limits.max_memory_bytes
+            );
+            self.error = Some(ScanError::new_string(mark, info));
+        }
+    }
+
+    fn insert_new_node(&mut self, (node, aid): (Node, usize)) {
+        // valid anchor id starts from 1
+        if aid > 0 {
+            // Anchored nodes are copied, so the copy is counted too
+            if self.limits.max_memory_bytes != usize::MAX {
+                self.charge(tree_bytes(&node), node.mark);
+            }
+            self.anchors.insert(aid, Rc::new(node.clone()));
+        }
+        match self.doc_stack.last_mut() {
+            None => self.doc_stack.push((node, aid)),
+            Some((parent, _)) => match parent.kind {
+                NodeKind::Sequence(ref mut items) => items.push(node),
+                NodeKind::Mapping(ref mut entries) => {
+                    let pending_key = self.key_stack.last_mut().unwrap();
+                    match pending_key.take() {
+                        Some(key) => entries.push((key, node)),
+                        None => *pending_key = Some(node),
+                    }
+                }
+                _ => unreachable!(),
+            },
+        }
+    }
+}
+
+/// Iterator loading the documents of a YAML stream one at a time
+///
+/// Only the document being loaded is kept in memory, so large multi-document streams can be
+/// processed without building every tree up front.
+pub(crate) struct Documents<'a> {
+    input: &'a str,
+    parser: Parser<Chars<'a>>,
+    loader: Loader,
+    done: bool,
+    // (character index, byte offset) of the end of the last document
+    end: (usize, usize),
+    span: Range<usize>,
+    // Byte range between the end of the previous document and the start of the last one
+    prefix: Range<usize>,
+}
+
+impl Documents<'_> {
+    /// Byte range of the source of the last document returned
+    ///
+    /// A document starts at its `---` marker, or where the previous document ended if it has
+    /// none, and ends before its `...` marker or the next document's `---`.
+    pub(crate) fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Directives of the last document returned
+    pub(crate) fn directives(&self) -> Directives {
+        Directives::read(&self.input[self.prefix.clone()])
+    }
+
+    /// Byte offset of a character index at or after the end of the last document
+    fn byte_offset(&self, index: usize) -> usize {
+        let (chars, bytes) = self.end;
+        bytes
+            + self.input[bytes..]
+                .chars()
+                .take(index.saturating_sub(chars))
+                .map(char::len_utf8)
+                .sum::<usize>()
+    }
+
+    /// Load the next part of the stream: `size` items of the untagged sequence at the root of
+    /// the document being loaded as soon as they are loaded, or the rest of the document
+    ///
+    /// Items returned are dropped from the document, so a document holding a long sequence
+    /// is loaded without holding all of its items at once.
+    pub(crate) fn next_part(&mut self, size: usize) -> Option<Result<Part, ScanError>> {
+        if self.done {
+            return None;
+        }
+        if let Some(e) = self.loader.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        let result = timed(Phase::Load, || loop {
+            let (ev, mark) = self.parser.next_token()?;
+            let end = matches!(ev, Event::DocumentEnd | Event::StreamEnd);
+            if ev == Event::DocumentStart {
+                self.loader.start_document();
+            }
+            self.loader.on_event(ev, mark);
+            if end || self.loader.error.is_some() {
+                return Ok(None);
+            }
+            if let Some(items) = self.loader.take_root_items(size) {
+                return Ok(Some(items));
+            }
+        });
+        match result {
+            Ok(Some(items)) => Some(Ok(Part::Items(items))),
+            Ok(None) => self.finish(Ok(())).map(|doc| doc.map(Part::Document)),
+            Err(e) => self.finish(Err(e)).map(|doc| doc.map(Part::Document)),
+        }
+    }
+
+    /// Take the document loaded by the parser, noting its span
+    fn finish(&mut self, result: Result<(), ScanError>) -> Option<Result<Node, ScanError>> {
+        record_tree(self.loader.memory_used);
+        let error = result.err().map(|e| explain_tabs(self.input, e));
+        if let Some(e) = error.or_else(|| self.loader.error.take()) {
+            self.done = true;
+            return Some(Err(e));
+        }
+        match self.loader.docs.pop() {
+            Some(doc) => {
+                // The start mark of an implicit document is not where its text begins, so
+                // only an explicit `---` is taken; otherwise the previous document's end is
+                let start = self.byte_offset(self.loader.doc_span.start);
+                let start = if self.input[start..].starts_with("---") {
+                    start
+                } else {
+                    self.end.1
+                };
+                let end = self.byte_offset(self.loader.doc_span.end);
+                self.prefix = self.end.1..start;
+                self.span = start..end;
+                self.end = (self.loader.doc_span.end, end);
+                Some(Ok(doc))
+            }
+            None => {
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl Iterator for Documents<'_> {
+    type Item = Result<Node, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if let Some(e) = self.loader.error.take() {
+            self.done = true;
+            return Some(Err(e));
+        }
+        self.loader.start_document();
+        let result = timed(Phase::Load, || self.parser.load(&mut self.loader, false));
+        self.finish(result)
+    }
+}
+
+/// A part of a YAML stream loaded by `Documents::next_part`
+pub(crate) enum Part {
+    /// Items of the untagged sequence at the root of the document being loaded
+    Items(Vec<Node>),
+    /// The rest of a document, without the items returned before
+    Document(Node),
+}
+
+/// Point a syntax error on a line indented with tabs at the first tab, suggesting spaces
+///
+/// YAML does not allow tabs in indentation, but the scanner reports them in terms of the
+/// tokens it expected, such as "mapping values are not allowed in this context", and often
+/// at a column past the tab. Errors that mention a tab may be reported at the end of the
+/// line before the one indented with it.
+fn explain_tabs(input: &str, error: ScanError) -> ScanError {
+    let line = error.marker().line();
+    let lines = if error.info().contains("tab") { 2 } else { 1 };
+    let skipped = line.saturating_sub(1);
+    for (number, text) in input
+        .split_inclusive('\n')
+        .enumerate()
+        .skip(skipped)
+        .take(lines)
+    {
+        let content = text.trim_start_matches([' ', '\t']);
+        let indent = &text[..text.len() - content.len()];
+        // Whitespace-only lines may hold tabs
+        if let Some(col) = indent.find('\t').filter(|_| !content.trim_end().is_empty()) {
+            let index = input
+                .split_inclusive('\n')
+                .take(number)
+                .map(|text| text.chars().count())
+                .sum::<usize>();
+            return ScanError::new(
+                Marker::new(index + col, number + 1, col),
+                "found a tab character used for indentation; indent with spaces instead",
+            );
+        }
+    }
+    error
+}
+
+/// Load the documents of a YAML stream lazily
+///
+/// `maxMemoryBytes` and `maxNodes` apply to each document on its own.
+pub(crate) fn documents<'a>(input: &'a str, limits: &Limits) -> Documents<'a> {
+    let mut loader = Loader::new(limits);
+    loader.check_input(input);
+    Documents {
+        input,
+        parser: Parser::new_from_str(input),
+        loader,
+        done: false,
+        end: (0, 0),
+        span: 0..0,
+        prefix: 0..0,
+    }
+}
+
+/// Load every document of a YAML stream as a node tree
+pub(crate) fn load_documents(input: &str, limits: &Limits) -> Result<Vec<Node>, ScanError> {
+    let mut loader = Loader::new(limits);
+    loader.check_input(input);
+    if let Some(e) = loader.error {
+        return Err(e);
+    }
+    let mut parser = Parser::new_from_str(input);
+    timed(Phase::Load, || {
+        if is_timing() {
+            load_timed(&mut parser, &mut loader)
+        } else {
+            parser.load(&mut loader, true)
+        }
+    })
+    .map_err(|e| explain_tabs(input, e))?;
+    record_tree(loader.memory_used);
+    match loader.error {
+        Some(e) => Err(e),
+        None => Ok(loader.docs),
+    }
+}
+
+/// Events scanned at a time by `load_timed`
+const TIMED_BATCH: usize = 1024;
+
+/// Load every event of a stream, timing the building of node trees apart from the scanning
+///
+/// Events are scanned a batch at a time before the loader receives them, so the clock is read
+/// twice a batch rather than around every event.
+fn load_timed(parser: &mut Parser<Chars<'_>>, loader: &mut Loader) -> Result<(), ScanError> {
+    let mut batch = Vec::with_capacity(TIMED_BATCH);
+    loop {
+        let mut end = false;
+        while batch.len() < TIMED_BATCH && !end {
+            let (ev, mark) = parser.next_token()?;
+            end = ev == Event::StreamEnd;
+            batch.push((ev, mark));
+        }
+        let start = now_ms();
+        for (ev, mark) in batch.drain(..) {
+            loader.on_event(ev, mark);
+        }
+        record_build(now_ms() - start);
+        if end {
+            return Ok(());
+        }
+    }
+}
+
+/// Characters of an owned string, for a parser that outlives the call that started it
+pub(crate) struct OwnedChars {
+    text: Rc<str>,
+    pos: usize,
+}
+
+impl Iterator for OwnedChars {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.text[self.pos..].chars().next()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+}
+
+/// Loader of a YAML stream that runs a time slice at a time, so the caller can yield between
+/// slices
+pub(crate) struct IncrementalLoad {
+    parser: Parser<OwnedChars>,
+    // The input, shared with the parser, to locate tabs in errors
+    text: Rc<str>,
+    loader: Loader,
+    done: bool,
+    // Position of the last event loaded
+    mark: Marker,
+}
+
+impl IncrementalLoad {
+    pub fn new(input: String, limits: &Limits) -> Self {
+        let mut loader = Loader::new(limits);
+        loader.check_input(&input);
+        let text: Rc<str> = input.into();
+        IncrementalLoad {
+            parser: Parser::new(OwnedChars {
+                text: Rc::clone(&text),
+                pos: 0,
+            }),
+            text,
+            loader,
+            done: false,
+            mark: Marker::new(0, 1, 0),
+        }
+    }
+
+    /// Load events until the stream ends or `slice_ms` milliseconds have passed
+    ///
+    /// Returns whether the whole stream is loaded.
+    pub fn advance(&mut self, slice_ms: f64) -> Result<bool, ScanError> {
+        if let Some(e) = self.loader.error.take() {
+            return Err(e);
+        }
+        if self.done {
+            return Ok(true);
+        }
+        let deadline = now_ms() + slice_ms;
+        timed(Phase::Load, || {
+            // The clock is read every few hundred events, which is cheap next to parsing them
+            for events in 1usize.. {
+                if events % 256 == 0 && now_ms() >= deadline {
+                    return Ok(false);
+                }
+                let (ev, mark) = self
+                    .parser
+                    .next_token()
+                    .map_err(|e| explain_tabs(&self.text, e))?;
+                self.mark = mark;
+                let end = ev == Event::StreamEnd;
+                if ev == Event::DocumentStart {
+                    // Anchors do not carry over from one document to the next
+                    self.loader.anchors.clear();
+                }
+                self.loader.on_event(ev, mark);
+                if let Some(e) = self.loader.error.take() {
+                    return Err(e);
+                }
+                if end {
+                    self.done = true;
+                    record_tree(self.loader.memory_used);
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        })
+    }
+
+    /// Position of the last event loaded
+    pub fn mark(&self) -> Marker {
+        self.mark
+    }
+
+    /// The documents loaded so far
+    pub fn documents(&self) -> &[Node] {
+        &self.loader.docs
+    }
+}
+
+/// Most lines dropped from one document before it is given up on
+const MAX_RECOVERIES: usize = 16;
+
+/// Load every document of a YAML stream, recovering from syntax errors
+///
+/// The stream is split at its document markers, as `split_documents` splits it, and each document
+/// is loaded on its own, so a broken document does not take the others with it. A document that
+/// fails to load is retried with the line of the error blanked out, which gets past a badly
+/// indented line or a stray key; one that still fails after `MAX_RECOVERIES` tries loads as
+/// nothing. Returns the documents and the errors recovered from.
+/// `maxInputSize` and `maxDocuments` apply to the whole stream and are not recovered from;
+/// the other limits apply to each document on its own.
+pub(crate) fn load_lenient(
+    input: &str,
+    limits: &Limits,
+) -> Result<(Vec<Node>, Vec<ScanError>), ScanError> {
+    let mut loader = Loader::new(limits);
+    loader.check_input(input);
+    if let Some(e) = loader.error {
+        return Err(e);
+    }
+    let chunk_limits = Limits {
+        max_documents: usize::MAX,
+        ..*limits
+    };
+
+    let mut docs = Vec::new();
+    let mut errors = Vec::new();
+    let (mut first_line, mut counted) = (0, 0);
+    for range in Ranges::new(input.as_bytes()) {
+        first_line += input[counted..range.start].matches('\n').count();
+        counted = range.start;
+        // Blank lines in place of the documents before keep line numbers those of `input`
+        let mut text = "\n".repeat(first_line) + &input[range];
+        for _ in 0..=MAX_RECOVERIES {
+            match load_documents(&text, &chunk_limits) {
+                Ok(loaded) => {
+                    docs.extend(loaded);
+                    break;
+                }
+                Err(e) => {
+                    let line = e.marker().line();
+                    errors.push(e);
+                    if !blank_line(&mut text, line) {
+                        break;
+                    }
+                }
+            }
+        }
+        if docs.len() > limits.max_documents {
+            let info = format!(
+                "stream holds more than {} documents (maxDocuments)",
+                limits.max_documents
+            );
+            return Err(ScanError::new_string(
+                Marker::new(0, first_line + 1, 0),
+                info,
+            ));
+        }
+    }
+    Ok((docs, errors))
+}
+
+/// Blank out a 1-based line of a document, or the last line with text before it when it has
+/// none; returns false when no line with text is left
+fn blank_line(text: &mut String, line: usize) -> bool {
+    let mut lines: Vec<&str> = text.split_inclusive('\n').collect();
+    let Some(index) = (0..line.min(lines.len()))
+        .rev()
+        .find(|&i| !lines[i].trim().is_empty())
+    else {
+        return false;
+    };
+    lines[index] = if lines[index].ends_with('\n') {
+        "\n"
+    } else {
+        ""
+    };
+    *text = lines.concat();
+    true
+}
+
+/// Load and resolve the first document of a YAML stream, continuing past unresolvable values
+///
+/// Used by queries and validation, which work on resolved values. Returns `None` for an empty
+/// stream; values that cannot be resolved become null and are listed as diagnostics. Aliases
+/// are expanded within the alias limits, as `load` expands them.
+pub(crate) fn load_first_resolved(
+    input: &str,
+    limits: &Limits,
+) -> Result<Option<(Yaml, Vec<Diagnostic>)>, String> {
+    let docs = load_documents(input, limits).map_err(|e| error_at(e.info(), e.marker()))?;
+    let Some(doc) = docs.first() else {
+        return Ok(None);
+    };
+    let options = LoadOptions {
+        limits: *limits,
+        ..LoadOptions::default()
+    };
+    let mut diagnostics = Vec::new();
+    let yaml = Resolver::new(&options).resolve_document(doc, &mut diagnostics)?;
+    Ok(Some((yaml, diagnostics)))
+}
+
+/// Format an error message with the 1-based position of `mark`
+pub(crate) fn error_at(info: &str, mark: &Marker) -> String {
+    format!(
+        "YAML parsing error: {} at line {}, column {}",
+        info,
+        mark.line(),
+        mark.col() + 1
+    )
+}
+
+/// Format a warning message with the 1-based position of `mark`
+pub(crate) fn warning_at(info: &str, mark: &Marker) -> String {
+    format!(
+        "{} at line {}, column {}",
+        info,
+        mark.line(),
+        mark.col() + 1
+    )
+}
@@ -0,0 +1,1103 @@
+//! Load options
+//!
+//! This module reads the js-yaml compatible options objects accepted by the load and dump
+//! functions.
+
+use js_sys::{Array, Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::loader::Directives;
+use crate::plugins::{registered_lint_rules, LintRule};
+use crate::schema::{ScalarRules, Schema, YamlVersion};
+use crate::types::{registered_types, CustomType};
+
+/// Options accepted by `parse`, `parse_all`, `load` and `load_all`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LoadOptions {
+    /// Schema used to resolve plain scalars
+    pub schema: Schema,
+    /// Refinements of the schema rules for plain scalars
+    pub rules: ScalarRules,
+    /// Return a source map along with the JSON string (`parse_to_json_string` only)
+    pub source_map: bool,
+    /// Report every syntax error of the input instead of only the first
+    pub all_errors: bool,
+    /// Return the time spent in each part of the call along with the value
+    pub with_timing: bool,
+    /// Number of significant digits floats are rounded to
+    pub float_precision: Option<usize>,
+    /// How floats are represented in the output
+    pub float_mode: FloatMode,
+    /// Return integers outside the safe `Number` range as `BigInt`
+    pub use_big_int: bool,
+    /// Return integers outside the safe `Number` range as decimal strings
+    pub large_int_as_string: bool,
+    /// Return mappings as `Map` with typed keys instead of plain objects
+    pub map_as_map: bool,
+    /// Return `!!set` mappings as `Set` instead of objects with null values
+    pub set_as_set: bool,
+    /// Decode `!!binary` scalars to `Uint8Array` instead of returning the base64 text
+    pub decode_binary: bool,
+    /// Resolve timestamps to `Date` instead of strings
+    pub timestamps: bool,
+    /// Resolve `!duration` scalars to milliseconds and `!bytes` scalars to bytes
+    pub units: bool,
+    /// Return nodes with an unknown local tag as `{ tag, value }` objects
+    pub wrap_unknown_tags: bool,
+    /// How empty values (`key:`) are resolved
+    pub empty_values: EmptyValues,
+    /// Return `undefined` rather than `null` for input holding no document
+    pub undefined_without_document: bool,
+    /// Build values directly rather than through a JSON string and `JSON.parse`
+    pub direct_conversion: bool,
+    /// Build only plain objects, arrays and primitives, rejecting other values from callbacks
+    pub cloneable: bool,
+    /// How collection (sequence and mapping) keys are represented
+    pub complex_keys: ComplexKeys,
+    /// How duplicate mapping keys are handled
+    pub duplicate_keys: DuplicateKeys,
+    /// How string mapping keys are renamed
+    pub key_style: KeyStyle,
+    /// Which string mapping keys are kept
+    pub key_filter: KeyFilter,
+    /// Name of the parsed file, prefixed to error messages
+    pub filename: Option<String>,
+    /// Callback receiving non-fatal issues found while loading
+    pub on_warning: Option<Function>,
+    /// Callback converting scalar values, given their path, tag and source text
+    pub reviver: Option<Function>,
+    /// Callback called like `JSON.parse`'s reviver for every collection member, bottom-up
+    pub json_reviver: Option<Function>,
+    /// Tag types registered with `registerType` when the options were read
+    pub types: Vec<CustomType>,
+    /// Lint rules registered by plugins when the options were read
+    pub(crate) lint_rules: Vec<LintRule>,
+    /// Limits guarding against malicious documents
+    pub limits: Limits,
+    /// Keep `<<` keys as ordinary keys instead of merging them (set by `pipeline`)
+    pub keep_merge_keys: bool,
+}
+
+/// Handling of duplicate mapping keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum DuplicateKeys {
+    /// Fail with the positions of the duplicate and the first definition
+    #[default]
+    Error,
+    /// Keep the first value
+    First,
+    /// Keep the last value at the position of the first, like JSON.parse
+    Last,
+    /// Keep the last value and report the duplicate to `onWarning`
+    Warn,
+}
+
+/// Resolution of empty values: untagged plain scalars with no text (`key:`, `- `)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum EmptyValues {
+    /// Null, in every schema
+    #[default]
+    Null,
+    /// Empty strings
+    String,
+    /// Mapping entries with empty values are dropped; sequence items are null
+    Omit,
+}
+
+/// Representation of collection mapping keys (`? [a, b] : value`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum ComplexKeys {
+    /// Object properties named by the JSON encoding of the key (`'["a","b"]'`)
+    #[default]
+    Json,
+    /// Mappings with collection keys are returned as `Map`, keyed by arrays and maps
+    Map,
+    /// Fail at the position of the key
+    Error,
+}
+
+/// Renaming of string mapping keys (`keyStyle`), applied by both load and dump
+#[derive(Debug, Clone, Default)]
+pub(crate) enum KeyStyle {
+    /// Keys are kept as written
+    #[default]
+    Keep,
+    /// `snake_case` and `kebab-case` keys become `camelCase`
+    Camel,
+    /// `camelCase` and `kebab-case` keys become `snake_case`
+    Snake,
+    /// Keys are renamed by a callback returning the new name
+    Custom(Function),
+}
+
+impl KeyStyle {
+    /// Read the `keyStyle` option: `'camel'`, `'snake'` or a function
+    fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let Some(style) = get_property(options, "keyStyle")? else {
+            return Ok(KeyStyle::Keep);
+        };
+        if let Some(callback) = style.dyn_ref::<Function>() {
+            return Ok(KeyStyle::Custom(callback.clone()));
+        }
+        match style.as_string().as_deref() {
+            Some("camel") => Ok(KeyStyle::Camel),
+            Some("snake") => Ok(KeyStyle::Snake),
+            Some(style) => Err(JsValue::from_str(&format!("Unknown keyStyle: {}", style))),
+            None => Err(JsValue::from_str(
+                "Option 'keyStyle' must be 'camel', 'snake' or a function",
+            )),
+        }
+    }
+
+    /// Whether keys are renamed at all
+    pub fn is_keep(&self) -> bool {
+        matches!(self, KeyStyle::Keep)
+    }
+
+    /// Rename a key
+    pub fn apply(&self, key: &str) -> Result<String, String> {
+        match self {
+            KeyStyle::Keep => Ok(key.to_string()),
+            KeyStyle::Camel => Ok(to_camel_case(key)),
+            KeyStyle::Snake => Ok(to_snake_case(key)),
+            KeyStyle::Custom(callback) => {
+                let renamed = callback
+                    .call1(&JsValue::NULL, &JsValue::from_str(key))
+                    .map_err(|e| {
+                        let message = match e.dyn_ref::<js_sys::Error>() {
+                            Some(error) => String::from(error.message()),
+                            None => format!("{:?}", e),
+                        };
+                        format!("keyStyle callback failed for '{}': {}", key, message)
+                    })?;
+                renamed
+                    .as_string()
+                    .ok_or_else(|| format!("keyStyle callback must return a string for '{}'", key))
+            }
+        }
+    }
+}
+
+/// Filtering of string mapping keys by patterns where `*` matches any text (`x-*`)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct KeyFilter {
+    /// Only keys matching one of these patterns are kept (`includeKeys`)
+    pub include: Option<Vec<String>>,
+    /// Keys matching one of these patterns are dropped (`excludeKeys`)
+    pub exclude: Vec<String>,
+}
+
+impl KeyFilter {
+    /// Whether every key is kept
+    pub fn is_empty(&self) -> bool {
+        self.include.is_none() && self.exclude.is_empty()
+    }
+
+    /// Whether a key is kept
+    pub fn keeps(&self, key: &str) -> bool {
+        let included = self
+            .include
+            .as_ref()
+            .is_none_or(|patterns| patterns.iter().any(|pattern| matches_pattern(pattern, key)));
+        included
+            && !self
+                .exclude
+                .iter()
+                .any(|pattern| matches_pattern(pattern, key))
+    }
+}
+
+/// Match a key against a pattern where `*` matches any text, including none
+fn matches_pattern(pattern: &str, key: &str) -> bool {
+    let pattern = pattern.as_bytes();
+    let key = key.as_bytes();
+    let (mut p, mut k) = (0, 0);
+    // Position after the last `*` and the key position it was tried at
+    let mut backtrack = None;
+    while k < key.len() {
+        if p < pattern.len() && pattern[p] == b'*' {
+            p += 1;
+            backtrack = Some((p, k));
+        } else if p < pattern.len() && pattern[p] == key[k] {
+            p += 1;
+            k += 1;
+        } else if let Some((star, tried)) = backtrack {
+            p = star;
+            k = tried + 1;
+            backtrack = Some((star, k));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+/// Join `_`/`-` separated words in camelCase (`max_retry-count` becomes `maxRetryCount`)
+///
+/// Leading and trailing separators are kept (`_private`).
+fn to_camel_case(key: &str) -> String {
+    let mut result = String::with_capacity(key.len());
+    let mut upper = false;
+    for c in key.chars() {
+        if (c == '_' || c == '-') && !result.trim_start_matches(['_', '-']).is_empty() {
+            upper = true;
+        } else if upper {
+            result.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            result.push(c);
+        }
+    }
+    if upper {
+        result.push('_');
+    }
+    result
+}
+
+/// Split camelCase and kebab-case words with underscores (`maxRetryCount`, `HTTPServer` and
+/// `max-retry` become `max_retry_count`, `http_server` and `max_retry`)
+fn to_snake_case(key: &str) -> String {
+    let chars: Vec<char> = key.chars().collect();
+    let mut result = String::with_capacity(key.len() + 4);
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_uppercase() {
+            let prev = i.checked_sub(1).map(|i| chars[i]);
+            let next = chars.get(i + 1);
+            let starts_word = prev.is_some_and(|p| p.is_lowercase() || p.is_ascii_digit())
+                || (prev.is_some_and(char::is_uppercase) && next.is_some_and(|n| n.is_lowercase()));
+            if starts_word {
+                result.push('_');
+            }
+            result.extend(c.to_lowercase());
+        } else if c == '-' {
+            result.push('_');
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// Resource limits applied while loading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Limits {
+    /// Maximum number of aliases expanded per document, counting aliases inside expansions
+    pub max_alias_count: usize,
+    /// Maximum number of nodes produced by alias expansion per document
+    pub max_alias_expansion: usize,
+    /// Approximate bytes of memory the loaded trees, and separately the converted values,
+    /// may take
+    pub max_memory_bytes: usize,
+    /// Maximum levels of nested sequences and mappings, counting nesting through aliases
+    pub max_nesting_depth: usize,
+    /// Maximum number of documents in a stream
+    pub max_documents: usize,
+    /// Maximum number of nodes in the input, aliases counted once
+    pub max_nodes: usize,
+    /// Maximum length of a scalar, in characters
+    pub max_scalar_length: usize,
+    /// Maximum length of the input, in UTF-8 bytes
+    pub max_input_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_alias_count: 10_000,
+            max_alias_expansion: 1_000_000,
+            max_memory_bytes: usize::MAX,
+            max_nesting_depth: 512,
+            max_documents: usize::MAX,
+            max_nodes: usize::MAX,
+            max_scalar_length: usize::MAX,
+            max_input_size: usize::MAX,
+        }
+    }
+}
+
+/// Keys of the `limits` option, which can also be given as options of their own
+const LIMIT_KEYS: &[&str] = &[
+    "maxAliasCount",
+    "maxAliasExpansion",
+    "maxMemoryBytes",
+    "maxNestingDepth",
+    "maxDocuments",
+    "maxNodes",
+    "maxScalarLength",
+    "maxInputSize",
+];
+
+impl Limits {
+    /// Read the `limits` option, an object holding any of the resource limits
+    ///
+    /// Gives servers parsing untrusted YAML one setting to pass to every function that loads
+    /// documents. Limits left out keep their defaults.
+    pub fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut limits = Limits::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(limits);
+        }
+        if let Some(value) = get_property(options, "limits")? {
+            if !value.is_object() {
+                return Err(JsValue::from_str("Option 'limits' must be an object"));
+            }
+            if strict_options(options)? {
+                check_keys(&value, &[LIMIT_KEYS])?;
+            }
+            limits.read(&value)?;
+        }
+        Ok(limits)
+    }
+
+    /// Apply the limits an object sets
+    fn read(&mut self, value: &JsValue) -> Result<(), JsValue> {
+        let fields = [
+            &mut self.max_alias_count,
+            &mut self.max_alias_expansion,
+            &mut self.max_memory_bytes,
+            &mut self.max_nesting_depth,
+            &mut self.max_documents,
+            &mut self.max_nodes,
+            &mut self.max_scalar_length,
+            &mut self.max_input_size,
+        ];
+        for (key, field) in LIMIT_KEYS.iter().zip(fields) {
+            if let Some(limit) = get_limit(value, key)? {
+                *field = limit;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Representation of YAML floats in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum FloatMode {
+    /// Plain numbers; integral floats are indistinguishable from integers
+    #[default]
+    Number,
+    /// Integral floats keep a decimal point in the JSON output (`1.0`)
+    Decimal,
+    /// Floats are wrapped as `{ "$float": value }`
+    Wrap,
+}
+
+/// Keys of the load options object, including js-yaml's `listener`, which is not supported
+pub(crate) const LOAD_OPTION_KEYS: &[&str] = &[
+    "schema",
+    "version",
+    "sexagesimal",
+    "stringifyScalars",
+    "ambiguousAsString",
+    "sourceMap",
+    "allErrors",
+    "withTiming",
+    "floatPrecision",
+    "floatMode",
+    "useBigInt64",
+    "largeIntAsString",
+    "mapAsMap",
+    "decodeBinary",
+    "setAsSet",
+    "timestamps",
+    "units",
+    "wrapUnknownTags",
+    "emptyValue",
+    "noDocument",
+    "conversion",
+    "cloneable",
+    "complexKeys",
+    "json",
+    "duplicateKey",
+    "keyStyle",
+    "includeKeys",
+    "excludeKeys",
+    "filename",
+    "limits",
+    "maxAliasCount",
+    "maxAliasExpansion",
+    "maxMemoryBytes",
+    "maxNestingDepth",
+    "maxDocuments",
+    "maxNodes",
+    "maxScalarLength",
+    "maxInputSize",
+    "onWarning",
+    "reviver",
+    "jsonReviver",
+    "listener",
+];
+
+/// Keys of the dump options object, including the js-yaml options that are not supported
+const DUMP_OPTION_KEYS: &[&str] = &[
+    "styles",
+    "keyStyle",
+    "directives",
+    "indent",
+    "noArrayIndent",
+    "skipInvalid",
+    "flowLevel",
+    "schema",
+    "sortKeys",
+    "lineWidth",
+    "noRefs",
+    "noCompatMode",
+    "condenseFlow",
+    "quotingType",
+    "forceQuotes",
+    "replacer",
+];
+
+impl LoadOptions {
+    /// Read load options from a JavaScript value
+    ///
+    /// `undefined` and `null` yield the default options.
+    pub fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        Self::from_js_with(options, &[])
+    }
+
+    /// Read load options from a JavaScript value that also holds the function's own options,
+    /// named by `extra_keys`
+    pub fn from_js_with(options: &JsValue, extra_keys: &[&str]) -> Result<Self, JsValue> {
+        check_option_keys(options, &[LOAD_OPTION_KEYS, extra_keys])?;
+        let mut result = LoadOptions {
+            types: registered_types(),
+            lint_rules: registered_lint_rules(),
+            ..LoadOptions::default()
+        };
+        if options.is_undefined() || options.is_null() {
+            return Ok(result);
+        }
+
+        if let Some(name) = get_string(options, "schema")? {
+            result.schema = Schema::from_name(&name)
+                .ok_or_else(|| JsValue::from_str(&format!("Unknown schema: {}", name)))?;
+        }
+
+        if let Some(version) = get_string(options, "version")? {
+            result.rules.version = YamlVersion::from_name(&version)
+                .ok_or_else(|| JsValue::from_str(&format!("Unknown version: {}", version)))?;
+        }
+
+        if let Some(sexagesimal) = get_bool(options, "sexagesimal")? {
+            result.rules.sexagesimal = sexagesimal;
+        }
+
+        if let Some(stringify) = get_bool(options, "stringifyScalars")? {
+            result.rules.stringify = stringify;
+        }
+
+        if let Some(ambiguous) = get_bool(options, "ambiguousAsString")? {
+            result.rules.ambiguous_as_string = ambiguous;
+        }
+
+        if let Some(source_map) = get_bool(options, "sourceMap")? {
+            result.source_map = source_map;
+        }
+
+        if let Some(all_errors) = get_bool(options, "allErrors")? {
+            result.all_errors = all_errors;
+        }
+
+        if let Some(with_timing) = get_bool(options, "withTiming")? {
+            result.with_timing = with_timing;
+        }
+
+        if let Some(precision) = get_number(options, "floatPrecision")? {
+            if !(1.0..=17.0).contains(&precision) || precision.fract() != 0.0 {
+                return Err(JsValue::from_str(
+                    "Option 'floatPrecision' must be an integer between 1 and 17",
+                ));
+            }
+            result.float_precision = Some(precision as usize);
+        }
+
+        if let Some(mode) = get_string(options, "floatMode")? {
+            result.float_mode = match mode.as_str() {
+                "number" => FloatMode::Number,
+                "decimal" => FloatMode::Decimal,
+                "wrap" => FloatMode::Wrap,
+                _ => return Err(JsValue::from_str(&format!("Unknown floatMode: {}", mode))),
+            };
+        }
+
+        if let Some(use_big_int) = get_bool(options, "useBigInt64")? {
+            result.use_big_int = use_big_int;
+        }
+
+        if let Some(as_string) = get_bool(options, "largeIntAsString")? {
+            result.large_int_as_string = as_string;
+        }
+
+        if let Some(map_as_map) = get_bool(options, "mapAsMap")? {
+            result.map_as_map = map_as_map;
+        }
+
+        if let Some(decode_binary) = get_bool(options, "decodeBinary")? {
+            result.decode_binary = decode_binary;
+        }
+
+        if let Some(set_as_set) = get_bool(options, "setAsSet")? {
+            result.set_as_set = set_as_set;
+        }
+
+        if let Some(timestamps) = get_bool(options, "timestamps")? {
+            result.timestamps = timestamps;
+        }
+
+        if let Some(units) = get_bool(options, "units")? {
+            result.units = units;
+        }
+
+        if let Some(wrap) = get_bool(options, "wrapUnknownTags")? {
+            result.wrap_unknown_tags = wrap;
+        }
+
+        if let Some(policy) = get_string(options, "emptyValue")? {
+            result.empty_values = match policy.as_str() {
+                "null" => EmptyValues::Null,
+                "string" => EmptyValues::String,
+                "omit" => EmptyValues::Omit,
+                _ => {
+                    return Err(JsValue::from_str(&format!(
+                        "Unknown emptyValue: {}",
+                        policy
+                    )))
+                }
+            };
+        }
+
+        if let Some(value) = get_string(options, "noDocument")? {
+            result.undefined_without_document = match value.as_str() {
+                "null" => false,
+                "undefined" => true,
+                _ => return Err(JsValue::from_str(&format!("Unknown noDocument: {}", value))),
+            };
+        }
+
+        if let Some(value) = get_string(options, "conversion")? {
+            result.direct_conversion = match value.as_str() {
+                "direct" => true,
+                "json" => false,
+                _ => return Err(JsValue::from_str(&format!("Unknown conversion: {}", value))),
+            };
+        }
+
+        if let Some(cloneable) = get_bool(options, "cloneable")? {
+            result.cloneable = cloneable;
+        }
+
+        if let Some(mode) = get_string(options, "complexKeys")? {
+            result.complex_keys = match mode.as_str() {
+                "json" => ComplexKeys::Json,
+                "map" => ComplexKeys::Map,
+                "error" => ComplexKeys::Error,
+                _ => return Err(JsValue::from_str(&format!("Unknown complexKeys: {}", mode))),
+            };
+        }
+
+        // js-yaml's `json` lets later keys override earlier ones silently, as `JSON.parse` does;
+        // `duplicateKey` takes precedence
+        if get_bool(options, "json")? == Some(true) {
+            result.duplicate_keys = DuplicateKeys::Last;
+        }
+
+        if let Some(policy) = get_string(options, "duplicateKey")? {
+            result.duplicate_keys = match policy.as_str() {
+                "error" => DuplicateKeys::Error,
+                "first" => DuplicateKeys::First,
+                "last" => DuplicateKeys::Last,
+                "warn" => DuplicateKeys::Warn,
+                _ => {
+                    return Err(JsValue::from_str(&format!(
+                        "Unknown duplicateKey: {}",
+                        policy
+                    )))
+                }
+            };
+        }
+
+        result.key_style = KeyStyle::from_js(options)?;
+
+        result.key_filter.include = get_string_array(options, "includeKeys")?;
+        if let Some(patterns) = get_string_array(options, "excludeKeys")? {
+            result.key_filter.exclude = patterns;
+        }
+
+        result.filename = get_string(options, "filename")?;
+
+        // Limits given as options of their own take precedence over the `limits` object
+        result.limits = Limits::from_js(options)?;
+        result.limits.read(options)?;
+
+        if let Some(callback) = get_property(options, "onWarning")? {
+            if !callback.is_null() {
+                result.on_warning = Some(
+                    callback
+                        .dyn_into()
+                        .map_err(|_| JsValue::from_str("Option 'onWarning' must be a function"))?,
+                );
+            }
+        }
+
+        if let Some(callback) = get_property(options, "reviver")? {
+            if !callback.is_null() {
+                result.reviver = Some(
+                    callback
+                        .dyn_into()
+                        .map_err(|_| JsValue::from_str("Option 'reviver' must be a function"))?,
+                );
+            }
+        }
+
+        if let Some(callback) = get_property(options, "jsonReviver")? {
+            if !callback.is_null() {
+                result.json_reviver =
+                    Some(callback.dyn_into().map_err(|_| {
+                        JsValue::from_str("Option 'jsonReviver' must be a function")
+                    })?);
+            }
+        }
+
+        // Cloneable output holds plain objects, arrays and primitives only
+        if result.cloneable {
+            let conflicts = [
+                ("useBigInt64", result.use_big_int),
+                ("mapAsMap", result.map_as_map),
+                ("setAsSet", result.set_as_set),
+                ("decodeBinary", result.decode_binary),
+                ("timestamps", result.timestamps),
+                (
+                    "complexKeys: 'map'",
+                    result.complex_keys == ComplexKeys::Map,
+                ),
+            ];
+            if let Some((name, _)) = conflicts.iter().find(|(_, set)| *set) {
+                return Err(JsValue::from_str(&format!(
+                    "Option 'cloneable' cannot be combined with '{}'",
+                    name
+                )));
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Whether the output needs JavaScript values JSON cannot represent (`Map`, `Set`,
+    /// `Uint8Array`, `Date`, `BigInt`), a `reviver` or `jsonReviver` is called or tag types are
+    /// registered
+    pub fn needs_js_values(&self) -> bool {
+        self.use_big_int
+            || self.map_as_map
+            || self.complex_keys == ComplexKeys::Map
+            || self.set_as_set
+            || self.decode_binary
+            || self.timestamps
+            || self.reviver.is_some()
+            || self.json_reviver.is_some()
+            || !self.types.is_empty()
+    }
+
+    /// Whether `JSON.parse` reads plain JSON input the way these options would
+    ///
+    /// Options that convert values, rename or drop keys, report lint findings or bound the size
+    /// of what is loaded need the YAML loader; nesting depth is checked while scanning the input.
+    pub fn reads_json_as_is(&self) -> bool {
+        let limits = &self.limits;
+        let unbounded = [
+            limits.max_memory_bytes,
+            limits.max_nodes,
+            limits.max_scalar_length,
+            limits.max_input_size,
+        ]
+        .iter()
+        .all(|&limit| limit == usize::MAX);
+        self.schema != Schema::Failsafe
+            && !self.rules.stringify
+            && self.float_precision.is_none()
+            && self.float_mode == FloatMode::Number
+            && !self.large_int_as_string
+            && !self.needs_js_values()
+            && self.key_style.is_keep()
+            && self.key_filter.is_empty()
+            && (self.on_warning.is_none() || self.lint_rules.is_empty())
+            && unbounded
+            && limits.max_documents > 0
+    }
+
+    /// The value of input holding no document (empty, or only comments and directives)
+    pub fn no_document(&self) -> JsValue {
+        if self.undefined_without_document {
+            JsValue::UNDEFINED
+        } else {
+            JsValue::NULL
+        }
+    }
+
+    /// Fail with the `withTiming` option, which `function` cannot return timings from
+    pub fn reject_timing(&self, function: &str) -> Result<(), JsValue> {
+        if !self.with_timing {
+            return Ok(());
+        }
+        Err(JsValue::from_str(&format!(
+            "Option 'withTiming' is not supported by {}",
+            function
+        )))
+    }
+
+    /// Build an error value, prefixing the message with `filename` when set
+    pub fn error(&self, message: &str) -> JsValue {
+        JsValue::from_str(&self.with_filename(message))
+    }
+
+    /// Pass warnings to the `onWarning` callback, prefixed with `filename` when set
+    pub fn warn(&self, warnings: Vec<String>) -> Result<(), JsValue> {
+        let Some(callback) = &self.on_warning else {
+            return Ok(());
+        };
+        for warning in warnings {
+            callback.call1(
+                &JsValue::NULL,
+                &JsValue::from_str(&self.with_filename(&warning)),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn with_filename(&self, message: &str) -> String {
+        match &self.filename {
+            Some(filename) => format!("{}: {}", filename, message),
+            None => message.to_string(),
+        }
+    }
+}
+
+/// Keys of the preview limits, read from the load options object
+pub(crate) const PREVIEW_OPTION_KEYS: &[&str] = &["maxStringLength", "maxItems"];
+
+/// Size limits of `parse_preview`, read from the load options object
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PreviewLimits {
+    /// Strings longer than this many characters are cut
+    pub max_string_length: usize,
+    /// Sequences with more items than this are cut
+    pub max_items: usize,
+}
+
+impl Default for PreviewLimits {
+    fn default() -> Self {
+        PreviewLimits {
+            max_string_length: 1000,
+            max_items: 100,
+        }
+    }
+}
+
+impl PreviewLimits {
+    /// Read `maxStringLength` and `maxItems`
+    pub fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut result = PreviewLimits::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(result);
+        }
+        if let Some(limit) = get_limit(options, "maxStringLength")? {
+            result.max_string_length = limit;
+        }
+        if let Some(limit) = get_limit(options, "maxItems")? {
+            result.max_items = limit;
+        }
+        Ok(result)
+    }
+}
+
+/// Options accepted by `dump`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DumpOptions {
+    /// How null values are written
+    pub null_style: NullStyle,
+    /// How string mapping keys are renamed
+    pub key_style: KeyStyle,
+    /// `%YAML` and `%TAG` directives written before the document
+    pub directives: Option<Directives>,
+}
+
+/// js-yaml styles of `!!null`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum NullStyle {
+    /// `~`
+    Canonical,
+    /// `null`
+    #[default]
+    Lowercase,
+    /// `NULL`
+    Uppercase,
+    /// `Null`
+    Camelcase,
+    /// Nothing (`key:`)
+    Empty,
+}
+
+impl NullStyle {
+    /// Text null values are written as
+    pub fn repr(self) -> &'static str {
+        match self {
+            NullStyle::Canonical => "~",
+            NullStyle::Lowercase => "null",
+            NullStyle::Uppercase => "NULL",
+            NullStyle::Camelcase => "Null",
+            NullStyle::Empty => "",
+        }
+    }
+}
+
+impl DumpOptions {
+    /// Read dump options from a JavaScript value
+    ///
+    /// `undefined` and `null` yield the default options.
+    pub fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut result = DumpOptions::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(result);
+        }
+        check_option_keys(options, &[DUMP_OPTION_KEYS])?;
+
+        if let Some(styles) = get_property(options, "styles")? {
+            // js-yaml accepts both the `!!` shorthand and the full tag
+            let style = match get_string(&styles, "!!null")? {
+                Some(style) => Some(style),
+                None => get_string(&styles, "tag:yaml.org,2002:null")?,
+            };
+            if let Some(style) = style {
+                result.null_style = match style.as_str() {
+                    "canonical" => NullStyle::Canonical,
+                    "lowercase" => NullStyle::Lowercase,
+                    "uppercase" => NullStyle::Uppercase,
+                    "camelcase" => NullStyle::Camelcase,
+                    "empty" => NullStyle::Empty,
+                    _ => {
+                        return Err(JsValue::from_str(&format!(
+                            "Unknown !!null style: {}",
+                            style
+                        )))
+                    }
+                };
+            }
+        }
+
+        result.key_style = KeyStyle::from_js(options)?;
+
+        if let Some(directives) = get_property(options, "directives")? {
+            result.directives = Some(directives_from_js(&directives)?);
+        }
+
+        Ok(result)
+    }
+}
+
+/// Read `{ version, tags }` directives to write before a dumped document
+fn directives_from_js(value: &JsValue) -> Result<Directives, JsValue> {
+    if !value.is_object() {
+        return Err(JsValue::from_str("Option 'directives' must be an object"));
+    }
+    let version = get_string(value, "version")?;
+    if let Some(version) = &version {
+        if YamlVersion::from_name(version).is_none() {
+            return Err(JsValue::from_str(&format!(
+                "Unknown YAML version: {}",
+                version
+            )));
+        }
+    }
+    let mut tags = Vec::new();
+    if let Some(map) = get_property(value, "tags")? {
+        if !map.is_object() {
+            return Err(JsValue::from_str("Directive 'tags' must be an object"));
+        }
+        for entry in Object::entries(&Object::from(map)).iter() {
+            let entry = Array::from(&entry);
+            let handle = entry.get(0).as_string().unwrap_or_default();
+            if !is_tag_handle(&handle) {
+                return Err(JsValue::from_str(&format!(
+                    "Invalid tag handle: {}",
+                    handle
+                )));
+            }
+            match entry.get(1).as_string() {
+                Some(prefix) if !prefix.is_empty() && !prefix.contains(char::is_whitespace) => {
+                    tags.push((handle, prefix));
+                }
+                _ => {
+                    return Err(JsValue::from_str(&format!(
+                        "Invalid tag prefix for {}",
+                        handle
+                    )))
+                }
+            }
+        }
+    }
+    Ok(Directives { version, tags })
+}
+
+/// Check a `%TAG` handle: `!`, `!!` or `!name!` with word characters
+fn is_tag_handle(handle: &str) -> bool {
+    match handle
+        .strip_prefix('!')
+        .and_then(|rest| rest.strip_suffix('!'))
+    {
+        Some(name) => name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'),
+        None => handle == "!",
+    }
+}
+
+/// Fail when an options object with `strictOptions: true` holds keys the function does not read
+///
+/// Unknown keys are ignored otherwise, as js-yaml ignores them, so that options meant for
+/// another library or version pass through.
+pub(crate) fn check_option_keys(options: &JsValue, known: &[&[&str]]) -> Result<(), JsValue> {
+    if !strict_options(options)? {
+        return Ok(());
+    }
+    check_keys(options, &[known, &[&["strictOptions"]]].concat())
+}
+
+/// Whether an options object asks for unknown keys to be rejected
+pub(crate) fn strict_options(options: &JsValue) -> Result<bool, JsValue> {
+    if !options.is_object() {
+        return Ok(false);
+    }
+    Ok(get_bool(options, "strictOptions")?.unwrap_or(false))
+}
+
+/// Fail when an object holds keys the function does not read
+///
+/// Every unknown key is listed, in property order, with the closest known option when one is
+/// near enough: `Unknown options: scheme (did you mean 'schema'?), colour`.
+pub(crate) fn check_keys(object: &JsValue, known: &[&[&str]]) -> Result<(), JsValue> {
+    if !object.is_object() {
+        return Ok(());
+    }
+    let unknown: Vec<String> = Object::keys(object.unchecked_ref::<Object>())
+        .iter()
+        .filter_map(|key| key.as_string())
+        .filter(|key| !known.iter().any(|keys| keys.contains(&key.as_str())))
+        .map(|key| match closest_option(&key, known) {
+            Some(option) => format!("{} (did you mean '{}'?)", key, option),
+            None => key,
+        })
+        .collect();
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(JsValue::from_str(&format!(
+        "Unknown options: {}",
+        unknown.join(", ")
+    )))
+}
+
+/// Find the known option closest to a misspelled key, if it is near enough to be a typo
+///
+/// Keys are compared ignoring case, allowing about one edit for every three characters; the
+/// first of equally close options wins.
+fn closest_option<'a>(key: &str, known: &[&[&'a str]]) -> Option<&'a str> {
+    let max_distance = key.chars().count().div_ceil(3);
+    known
+        .iter()
+        .flat_map(|keys| keys.iter())
+        .map(|&option| (edit_distance(key, option), option))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min_by_key(|&(distance, _)| distance)
+        .map(|(_, option)| option)
+}
+
+/// Levenshtein distance between two strings, ignoring case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().flat_map(char::to_lowercase).collect();
+    let b: Vec<char> = b.chars().flat_map(char::to_lowercase).collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(diagonal + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Read a property, treating `undefined` as absent
+pub(crate) fn get_property(options: &JsValue, key: &str) -> Result<Option<JsValue>, JsValue> {
+    let value = Reflect::get(options, &JsValue::from_str(key))
+        .map_err(|_| JsValue::from_str(&format!("Failed to read option '{}'", key)))?;
+    Ok(if value.is_undefined() {
+        None
+    } else {
+        Some(value)
+    })
+}
+
+/// Read a string property
+pub(crate) fn get_string(options: &JsValue, key: &str) -> Result<Option<String>, JsValue> {
+    match get_property(options, key)? {
+        None => Ok(None),
+        Some(value) => value
+            .as_string()
+            .map(Some)
+            .ok_or_else(|| JsValue::from_str(&format!("Option '{}' must be a string", key))),
+    }
+}
+
+/// Read an array of strings property into any collection of strings
+pub(crate) fn get_string_array<T: FromIterator<String>>(
+    options: &JsValue,
+    key: &str,
+) -> Result<Option<T>, JsValue> {
+    let Some(value) = get_property(options, key)? else {
+        return Ok(None);
+    };
+    let invalid = || JsValue::from_str(&format!("Option '{}' must be an array of strings", key));
+    if !Array::is_array(&value) {
+        return Err(invalid());
+    }
+    Array::from(&value)
+        .iter()
+        .map(|item| item.as_string().ok_or_else(invalid))
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+/// Read a boolean property
+pub(crate) fn get_bool(options: &JsValue, key: &str) -> Result<Option<bool>, JsValue> {
+    match get_property(options, key)? {
+        None => Ok(None),
+        Some(value) => value
+            .as_bool()
+            .map(Some)
+            .ok_or_else(|| JsValue::from_str(&format!("Option '{}' must be a boolean", key))),
+    }
+}
+
+/// Read a number property
+pub(crate) fn get_number(options: &JsValue, key: &str) -> Result<Option<f64>, JsValue> {
+    match get_property(options, key)? {
+        None => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or_else(|| JsValue::from_str(&format!("Option '{}' must be a number", key))),
+    }
+}
+
+/// Read a limit property: a non-negative integer, or `Infinity` for no limit
+pub(crate) fn get_limit(options: &JsValue, key: &str) -> Result<Option<usize>, JsValue> {
+    match get_number(options, key)? {
+        None => Ok(None),
+        Some(limit) if limit == f64::INFINITY => Ok(Some(usize::MAX)),
+        Some(limit) if limit >= 0.0 && limit.fract() == 0.0 => Ok(Some(limit as usize)),
+        Some(_) => Err(JsValue::from_str(&format!(
+            "Option '{}' must be a non-negative integer or Infinity",
+            key
+        ))),
+    }
+}
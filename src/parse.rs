@@ -2,194 +2,734 @@
 //!
 //! This module provides the core YAML parsing functions that are API-compatible with js-yaml.
 
-use js_sys::{Array, Boolean, JsString, Number, Object};
-use std::fmt::Write as FmtWrite;
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::mem;
+
+use js_sys::{
+    Array, Boolean, Function, JsString, Number, Object, Reflect, Uint32Array, Uint8Array,
+};
 use wasm_bindgen::prelude::*;
-use yaml_rust2::{Yaml, YamlLoader};
+use yaml_rust2::scanner::ScanError;
+use yaml_rust2::Yaml;
+
+use crate::instrument::{start_call, start_timing, take_timing, timed, Phase};
+use crate::json::{is_plain_json, JsonWriter};
+use crate::loader::{documents, error_at, load_documents, load_lenient, IncrementalLoad, Node};
+use crate::options::{
+    get_number, DuplicateKeys, Limits, LoadOptions, PreviewLimits, PREVIEW_OPTION_KEYS,
+};
+use crate::plugins::lint_document;
+use crate::timing::now_ms;
+use crate::value::ValueBuilder;
 
 /// Parse a YAML string into a JavaScript object
 ///
 /// This function is API-compatible with js-yaml's parse function.
-/// Uses direct JSON string conversion for optimal performance.
+/// Values are built directly, or through a JSON string with `conversion: 'json'`.
+#[wasm_bindgen]
+pub fn parse(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        parse_with(input, &options, &mut Scratch::default())
+    })
+}
+
+/// Parse YAML bytes into a JavaScript object
+///
+/// The encoding is taken from a byte order mark (UTF-8, UTF-16LE or UTF-16BE) or, without one,
+/// from the zero bytes of a leading ASCII character as the YAML specification describes; plain
+/// UTF-8 is assumed otherwise. The bytes are decoded inside WebAssembly, so a file buffer needs
+/// no `TextDecoder` pass first.
+#[wasm_bindgen]
+pub fn parse_bytes(bytes: &[u8], options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let input = decode_bytes(bytes)
+            .map_err(|e| options.error(&format!("Invalid input encoding: {}", e)))?;
+        parse_with(&input, &options, &mut Scratch::default())
+    })
+}
+
+/// Input bytes allocated in WebAssembly memory, written from JavaScript and parsed in place
+#[wasm_bindgen]
+pub struct InputBuffer {
+    bytes: Vec<u8>,
+}
+
+/// Allocate a buffer of `length` bytes in WebAssembly memory for `parseInPlace`
+///
+/// Passing a string or a `Uint8Array` copies it into WebAssembly memory on every call; a file
+/// read straight into the buffer's `view()` is not copied at all, which matters for inputs of
+/// hundreds of megabytes. Call `free()` when done.
+///
+/// @param {number} length - The size of the input in bytes
+/// @returns {InputBuffer} - The zero-filled buffer
+#[wasm_bindgen]
+pub fn alloc_input(length: usize) -> InputBuffer {
+    InputBuffer {
+        bytes: vec![0; length],
+    }
+}
+
+#[wasm_bindgen]
+impl InputBuffer {
+    /// The size of the buffer in bytes
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// A `Uint8Array` over the buffer, to write the input into
+    ///
+    /// The view is detached when WebAssembly memory grows, so take a new one after any other
+    /// call instead of keeping it.
+    pub fn view(&self) -> Uint8Array {
+        // SAFETY: the view is returned to JavaScript right away, before Rust allocates again
+        unsafe { Uint8Array::view(&self.bytes) }
+    }
+}
+
+/// Parse the bytes of an `InputBuffer` into a JavaScript object, as `parse_bytes` does
+///
+/// The bytes are decoded where they are; UTF-8 input is parsed without being copied.
 #[wasm_bindgen]
-pub fn parse(input: &str) -> Result<JsValue, JsValue> {
+pub fn parse_in_place(input: &InputBuffer, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let text = decode_bytes(&input.bytes)
+            .map_err(|e| options.error(&format!("Invalid input encoding: {}", e)))?;
+        parse_with(&text, &options, &mut Scratch::default())
+    })
+}
+
+/// Run a parse, returning `{ value, timing }` with the `withTiming` option
+///
+/// `timing` holds the milliseconds the call spent scanning the YAML text, building node trees
+/// from the scanned events, converting them to JavaScript values, and in total.
+pub(crate) fn timed_call(
+    options: &LoadOptions,
+    parse: impl FnOnce() -> Result<JsValue, JsValue>,
+) -> Result<JsValue, JsValue> {
+    if !options.with_timing {
+        return parse();
+    }
+    start_timing();
+    let start = now_ms();
+    let value = parse();
+    let total = now_ms() - start;
+    let timing = take_timing().unwrap_or_default();
+    let value = value?;
+
+    let phases = Object::new();
+    for (key, ms) in [
+        ("scan", (timing.load - timing.build).max(0.0)),
+        ("build", timing.build),
+        ("convert", timing.convert),
+        ("total", total),
+    ] {
+        Reflect::set(&phases, &JsValue::from_str(key), &ms.into())?;
+    }
+    let result = Object::new();
+    Reflect::set(&result, &JsValue::from_str("value"), &value)?;
+    Reflect::set(&result, &JsValue::from_str("timing"), &phases)?;
+    Ok(result.into())
+}
+
+/// Decode YAML bytes according to their byte order mark or leading zero bytes
+fn decode_bytes(bytes: &[u8]) -> Result<Cow<'_, str>, String> {
+    let (big_endian, body) = match bytes {
+        [0, 0, 0xFE, 0xFF, ..] | [0xFF, 0xFE, 0, 0, ..] => {
+            return Err("UTF-32 input is not supported".to_string())
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => return decode_utf8(rest, 3),
+        [0xFF, 0xFE, rest @ ..] => (false, rest),
+        [0xFE, 0xFF, rest @ ..] => (true, rest),
+        [0, c, ..] if *c != 0 => (true, bytes),
+        [c, 0, ..] if *c != 0 => (false, bytes),
+        _ => return decode_utf8(bytes, 0),
+    };
+
+    if body.len() % 2 != 0 {
+        return Err("UTF-16 input has an odd number of bytes".to_string());
+    }
+    let units = body.chunks_exact(2).map(|pair| {
+        let pair = [pair[0], pair[1]];
+        if big_endian {
+            u16::from_be_bytes(pair)
+        } else {
+            u16::from_le_bytes(pair)
+        }
+    });
+    char::decode_utf16(units)
+        .collect::<Result<String, _>>()
+        .map(Cow::Owned)
+        .map_err(|e| format!("unpaired UTF-16 surrogate {:#06x}", e.unpaired_surrogate()))
+}
+
+/// Borrow UTF-8 bytes as text, reporting the byte offset of the first invalid sequence
+fn decode_utf8(bytes: &[u8], offset: usize) -> Result<Cow<'_, str>, String> {
+    std::str::from_utf8(bytes)
+        .map(Cow::Borrowed)
+        .map_err(|e| format!("invalid UTF-8 at byte {}", offset + e.valid_up_to()))
+}
+
+/// Parse the first document of already decoded input
+fn parse_with(
+    input: &str,
+    options: &LoadOptions,
+    scratch: &mut Scratch,
+) -> Result<JsValue, JsValue> {
+    if let Some(value) = parse_plain_json(input, options) {
+        return Ok(value);
+    }
+
     // Parse the YAML string using yaml-rust2
-    let docs = match YamlLoader::load_from_str(input) {
+    let docs = match load_documents(input, &options.limits) {
         Ok(docs) => docs,
-        Err(e) => {
-            let error_msg = format!(
-                "YAML parsing error: {} at line {}, column {}",
-                e.info(),
-                e.marker().line(),
-                e.marker().col() + 1
-            );
-            return Err(JsValue::from_str(&error_msg));
-        }
+        Err(e) => return Err(syntax_error(input, &e, options)),
     };
 
     if docs.is_empty() {
-        return Ok(JsValue::NULL);
+        return Ok(options.no_document());
+    }
+
+    convert_node(&docs[0], options, scratch)
+}
+
+/// Read input that is plain JSON with `JSON.parse`, when the options leave its values as they are
+///
+/// JSON is YAML, and `JSON.parse` reads it much faster than loading and converting it does.
+/// `None` sends the input through the YAML loader.
+fn parse_plain_json(input: &str, options: &LoadOptions) -> Option<JsValue> {
+    // Repeated keys need the loader unless they resolve as `JSON.parse` resolves them
+    let last_key_wins = options.duplicate_keys == DuplicateKeys::Last;
+    if !options.reads_json_as_is()
+        || !is_plain_json(input, options.limits.max_nesting_depth, last_key_wins)
+    {
+        return None;
     }
+    timed(Phase::JsonParse, || js_sys::JSON::parse(input)).ok()
+}
 
-    // Convert to JSON string (single allocation)
-    let json_string = yaml_to_json_string(&docs[0]).map_err(|e| JsValue::from_str(&e))?;
+/// The error for input that failed to load
+///
+/// With `allErrors`, the input is scanned again recovering from each error, and the messages
+/// of all of them are returned as an array, the first one first.
+fn syntax_error(input: &str, error: &ScanError, options: &LoadOptions) -> JsValue {
+    let first = options.error(&error_at(error.info(), error.marker()));
+    if !options.all_errors {
+        return first;
+    }
+    let errors = Array::new();
+    if let Ok((_, scan_errors)) = load_lenient(input, &options.limits) {
+        for e in scan_errors {
+            errors.push(&options.error(&error_at(e.info(), e.marker())));
+        }
+    }
+    if errors.length() == 0 {
+        errors.push(&first);
+    }
+    errors.into()
+}
 
-    // Parse JSON string to JsValue (single WASM boundary crossing)
-    js_sys::JSON::parse(&json_string).map_err(|_| JsValue::from_str("Failed to parse JSON"))
+/// A parse that loads its input a time slice at a time
+#[wasm_bindgen]
+pub struct ParseTask {
+    load: IncrementalLoad,
+    options: LoadOptions,
+    timeout_ms: Option<f64>,
+    deadline: f64,
+}
+
+/// Start parsing the first document of a YAML string in time slices
+///
+/// `advance(sliceMs)` loads for at most `sliceMs` milliseconds and returns whether the input is
+/// loaded, so the caller can yield between slices; `finish()` then converts the document. With
+/// `timeoutMs`, a slice that ends past the deadline fails with a timeout error.
+///
+/// @param {string} input - The YAML document
+/// @param {Object} options - Load options and `timeoutMs`
+/// @returns {ParseTask} - The started parse
+#[wasm_bindgen]
+pub fn start_parse(input: String, options: JsValue) -> Result<ParseTask, JsValue> {
+    start_call();
+    let timeout_ms = if options.is_undefined() || options.is_null() {
+        None
+    } else {
+        get_number(&options, "timeoutMs")?
+    };
+    if timeout_ms.is_some_and(|ms| ms.is_nan() || ms < 0.0) {
+        return Err(JsValue::from_str(
+            "Option 'timeoutMs' must be a non-negative number",
+        ));
+    }
+    let options = LoadOptions::from_js_with(&options, &["timeoutMs"])?;
+    options.reject_timing("parseAsync")?;
+    let load = IncrementalLoad::new(input, &options.limits);
+    Ok(ParseTask {
+        load,
+        options,
+        timeout_ms,
+        deadline: now_ms() + timeout_ms.unwrap_or(f64::INFINITY),
+    })
+}
+
+#[wasm_bindgen]
+impl ParseTask {
+    /// Load for at most `slice_ms` milliseconds; returns whether the whole input is loaded
+    pub fn advance(&mut self, slice_ms: f64) -> Result<bool, JsValue> {
+        let slice_ms = slice_ms.min(self.deadline - now_ms()).max(0.0);
+        let done = self
+            .load
+            .advance(slice_ms)
+            .map_err(|e| self.options.error(&error_at(e.info(), e.marker())))?;
+        match self.timeout_ms {
+            Some(timeout_ms) if !done && now_ms() >= self.deadline => {
+                let info = format!("parsing timed out after {} ms (timeoutMs)", timeout_ms);
+                Err(self.options.error(&error_at(&info, &self.load.mark())))
+            }
+            _ => Ok(done),
+        }
+    }
+
+    /// Convert the loaded document; `null` (or `undefined`, see `noDocument`) for an empty input
+    pub fn finish(&self) -> Result<JsValue, JsValue> {
+        match self.load.documents().first() {
+            Some(doc) => node_to_js_value(doc, &self.options),
+            None => Ok(self.options.no_document()),
+        }
+    }
 }
 
 /// Parse all YAML documents in a string into an array of JavaScript objects
 #[wasm_bindgen]
-pub fn parse_all(input: &str) -> Result<Array, JsValue> {
-    let docs = match YamlLoader::load_from_str(input) {
+pub fn parse_all(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        parse_all_with(input, &options, &mut Scratch::default()).map(JsValue::from)
+    })
+}
+
+/// Parse all documents of already decoded input
+fn parse_all_with(
+    input: &str,
+    options: &LoadOptions,
+    scratch: &mut Scratch,
+) -> Result<Array, JsValue> {
+    if let Some(value) = parse_plain_json(input, options) {
+        return Ok(Array::of1(&value));
+    }
+
+    let docs = match load_documents(input, &options.limits) {
         Ok(docs) => docs,
-        Err(e) => {
-            let error_msg = format!(
-                "YAML parsing error: {} at line {}, column {}",
-                e.info(),
-                e.marker().line(),
-                e.marker().col() + 1
-            );
-            return Err(JsValue::from_str(&error_msg));
-        }
+        Err(e) => return Err(syntax_error(input, &e, options)),
     };
 
     let result = Array::new();
     for doc in docs {
-        let json_string = yaml_to_json_string(&doc).map_err(|e| JsValue::from_str(&e))?;
-        let js_value = js_sys::JSON::parse(&json_string)
-            .map_err(|_| JsValue::from_str("Failed to parse JSON"))?;
-        result.push(&js_value);
+        result.push(&convert_node(&doc, options, scratch)?);
     }
 
     Ok(result)
 }
 
+/// A parser reused across calls, keeping its options and buffers between them
 #[wasm_bindgen]
-pub fn load(input: &str) -> Result<JsValue, JsValue> {
-    parse(input)
+pub struct Parser {
+    options: LoadOptions,
+    scratch: Scratch,
 }
 
+/// Create a parser for many documents parsed with the same options
+///
+/// The options are read once, and the buffers conversion fills (the JSON text with
+/// `conversion: 'json'`, the JavaScript strings of mapping keys) are kept for the next call,
+/// so services parsing document after document do not allocate them every time. Tag types and
+/// plugins registered after the parser is created do not apply to it.
+///
+/// @param {Object} options - Load options for every call
+/// @returns {Parser} - The parser, with `parse(input)` and `parseAll(input)`
 #[wasm_bindgen]
-pub fn load_all(input: &str) -> Result<Array, JsValue> {
-    parse_all(input)
+pub fn create_parser(options: JsValue) -> Result<Parser, JsValue> {
+    Ok(Parser {
+        options: LoadOptions::from_js(&options)?,
+        scratch: Scratch::default(),
+    })
 }
 
-/// Alias for load_all with camelCase naming for JavaScript compatibility
 #[wasm_bindgen]
-#[allow(non_snake_case)]
-pub fn loadAll(input: &str) -> Result<Array, JsValue> {
-    load_all(input)
-}
+impl Parser {
+    /// Parse the first document of a YAML string
+    pub fn parse(&mut self, input: &str) -> Result<JsValue, JsValue> {
+        start_call();
+        timed_call(&self.options, || {
+            parse_with(input, &self.options, &mut self.scratch)
+        })
+    }
 
-/// Convert YAML to JSON string efficiently
-fn yaml_to_json_string(yaml: &Yaml) -> Result<String, String> {
-    let mut output = String::with_capacity(1024);
-    write_yaml_as_json(yaml, &mut output)?;
-    Ok(output)
+    /// Parse all documents of a YAML string
+    #[wasm_bindgen(js_name = parseAll)]
+    pub fn parse_all(&mut self, input: &str) -> Result<JsValue, JsValue> {
+        start_call();
+        timed_call(&self.options, || {
+            parse_all_with(input, &self.options, &mut self.scratch).map(JsValue::from)
+        })
+    }
 }
 
-/// Write YAML value as JSON to a string buffer
-fn write_yaml_as_json(yaml: &Yaml, output: &mut String) -> Result<(), String> {
-    match yaml {
-        Yaml::Null => output.push_str("null"),
-        Yaml::Boolean(b) => {
-            output.push_str(if *b { "true" } else { "false" });
+/// Parse the first document of each of many YAML strings in one call
+///
+/// Returns one `{ value, error }` entry per input, in input order: the parsed value with a
+/// null error, or an undefined value with the error the input failed with, so one broken input
+/// does not fail the others. Crossing into WebAssembly once for the whole batch saves the
+/// per-call overhead that dominates when the inputs are small (front matter blocks).
+///
+/// @param {string[]} inputs - The YAML strings
+/// @param {Object} options - Load options, applied to every input
+/// @returns {Object[]} - `{ value, error }` per input
+#[wasm_bindgen]
+pub fn parse_batch(inputs: &Array, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let mut scratch = Scratch::default();
+        let results = Array::new();
+        for input in inputs.iter() {
+            let input = input
+                .as_string()
+                .ok_or_else(|| JsValue::from_str("parse_batch expects an array of strings"))?;
+            let (value, error) = match parse_with(&input, &options, &mut scratch) {
+                Ok(value) => (value, JsValue::NULL),
+                Err(error) => (JsValue::UNDEFINED, error),
+            };
+            let entry = Object::new();
+            Reflect::set(&entry, &JsValue::from_str("value"), &value)?;
+            Reflect::set(&entry, &JsValue::from_str("error"), &error)?;
+            results.push(&entry);
         }
-        Yaml::Integer(i) => {
-            write!(output, "{}", i).map_err(|e| e.to_string())?;
+        Ok(results.into())
+    })
+}
+
+/// Parse a YAML stream, recovering from syntax errors instead of failing on the first
+///
+/// Returns `{ value, documents, errors }`: the first document, every document that loaded,
+/// and the messages of the errors recovered from. A broken document in a multi-document
+/// stream does not affect the others, and a badly indented line is skipped so the rest of
+/// its document still loads. Documents that fail to convert are null and listed in `errors`.
+///
+/// @param {string} input - The YAML stream
+/// @param {Object} options - Load options
+/// @returns {Object} - `{ value, documents, errors }`
+#[wasm_bindgen]
+pub fn parse_lenient(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let (docs, scan_errors) = load_lenient(input, &options.limits)
+            .map_err(|e| options.error(&error_at(e.info(), e.marker())))?;
+
+        let errors = Array::new();
+        for e in scan_errors {
+            errors.push(&options.error(&error_at(e.info(), e.marker())));
         }
-        Yaml::Real(s) => match s.parse::<f64>() {
-            Ok(f) => {
-                write!(output, "{}", f).map_err(|e| e.to_string())?;
-            }
-            Err(_) => return Err(format!("Invalid float: {}", s)),
-        },
-        Yaml::String(s) => {
-            output.push('"');
-            for ch in s.chars() {
-                match ch {
-                    '"' => output.push_str("\\\""),
-                    '\\' => output.push_str("\\\\"),
-                    '\n' => output.push_str("\\n"),
-                    '\r' => output.push_str("\\r"),
-                    '\t' => output.push_str("\\t"),
-                    '\x08' => output.push_str("\\b"),
-                    '\x0C' => output.push_str("\\f"),
-                    c if c.is_control() => {
-                        write!(output, "\\u{:04x}", c as u32).map_err(|e| e.to_string())?;
-                    }
-                    c => output.push(c),
+        let documents = Array::new();
+        for doc in &docs {
+            match node_to_js_value(doc, &options) {
+                Ok(value) => documents.push(&value),
+                Err(error) => {
+                    errors.push(&error);
+                    documents.push(&JsValue::NULL)
                 }
-            }
-            output.push('"');
+            };
         }
-        Yaml::Array(arr) => {
-            output.push('[');
-            for (i, item) in arr.iter().enumerate() {
-                if i > 0 {
-                    output.push(',');
+
+        let result = Object::new();
+        let value = if documents.length() > 0 {
+            documents.get(0)
+        } else {
+            options.no_document()
+        };
+        Reflect::set(&result, &JsValue::from_str("value"), &value)?;
+        Reflect::set(&result, &JsValue::from_str("documents"), &documents)?;
+        Reflect::set(&result, &JsValue::from_str("errors"), &errors)?;
+        Ok(result.into())
+    })
+}
+
+/// Parse the first document of a YAML stream along with statistics about the parse
+///
+/// Returns `{ value, documentCount, durationMs, yamlVersion }`: the first document (as for
+/// `parse`), the number of documents in the stream, the time spent loading and converting,
+/// and the version the first document declares with `%YAML`, or null. Every document is
+/// loaded to count them, but only the first is converted.
+///
+/// @param {string} input - The YAML stream
+/// @param {Object} options - Load options
+/// @returns {Object} - `{ value, documentCount, durationMs, yamlVersion }`
+#[wasm_bindgen]
+pub fn parse_with_meta(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let started = now_ms();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let mut value = options.no_document();
+        let mut version = JsValue::NULL;
+        let mut count = 0u32;
+        let mut docs = documents(input, &options.limits);
+        while let Some(doc) = docs.next() {
+            let doc = doc.map_err(|e| syntax_error(input, &e, &options))?;
+            if count == 0 {
+                value = node_to_js_value(&doc, &options)?;
+                if let Some(declared) = docs.directives().version {
+                    version = JsValue::from_str(&declared);
                 }
-                write_yaml_as_json(item, output)?;
             }
-            output.push(']');
+            count += 1;
         }
-        Yaml::Hash(hash) => {
-            output.push('{');
-            let mut first = true;
-            for (key, value) in hash {
-                if !first {
-                    output.push(',');
-                }
-                first = false;
-
-                // Write key as string
-                match key {
-                    Yaml::String(s) => {
-                        output.push('"');
-                        for ch in s.chars() {
-                            match ch {
-                                '"' => output.push_str("\\\""),
-                                '\\' => output.push_str("\\\\"),
-                                '\n' => output.push_str("\\n"),
-                                '\r' => output.push_str("\\r"),
-                                '\t' => output.push_str("\\t"),
-                                c => output.push(c),
-                            }
-                        }
-                        output.push('"');
-                    }
-                    _ => {
-                        write!(output, "\"{key:?}\"").map_err(|e| e.to_string())?;
-                    }
-                }
 
-                output.push(':');
-                write_yaml_as_json(value, output)?;
+        let result = Object::new();
+        Reflect::set(&result, &JsValue::from_str("value"), &value)?;
+        Reflect::set(
+            &result,
+            &JsValue::from_str("documentCount"),
+            &JsValue::from(count),
+        )?;
+        Reflect::set(
+            &result,
+            &JsValue::from_str("durationMs"),
+            &JsValue::from(now_ms() - started),
+        )?;
+        Reflect::set(&result, &JsValue::from_str("yamlVersion"), &version)?;
+        Ok(result.into())
+    })
+}
+
+/// Parse a YAML string into a JSON string
+///
+/// With the `sourceMap` option, returns `{ json, sourceMap }` where `sourceMap` is a flat
+/// `Uint32Array` of `(jsonOffset, line, column)` triples sorted by offset: one per emitted
+/// key or value, with UTF-16 offsets into `json` and 1-based YAML positions. Non-finite
+/// floats are written as `null`, like `JSON.stringify` does.
+#[wasm_bindgen]
+pub fn parse_to_json_string(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let docs = match load_documents(input, &options.limits) {
+            Ok(docs) => docs,
+            Err(e) => return Err(options.error(&error_at(e.info(), e.marker()))),
+        };
+
+        let mut writer = JsonWriter::new(&options);
+        if options.source_map {
+            writer = writer.with_source_map();
+        }
+        let json_string = match docs.first() {
+            Some(doc) => timed(Phase::Convert, || writer.write_document(doc)),
+            None => Ok("null".to_string()),
+        };
+        options.warn(writer.take_warnings())?;
+        let json_string = json_string.map_err(|e| options.error(&e))?;
+
+        if !options.source_map {
+            return Ok(JsString::from(json_string).into());
+        }
+
+        let mappings = writer.source_map(&json_string);
+        let result = Object::new();
+        Reflect::set(
+            &result,
+            &JsValue::from_str("json"),
+            &JsValue::from_str(&json_string),
+        )?;
+        Reflect::set(
+            &result,
+            &JsValue::from_str("sourceMap"),
+            &Uint32Array::from(mappings.as_slice()),
+        )?;
+        Ok(result.into())
+    })
+}
+
+/// Parse the first document of a YAML string for a preview, cutting large values
+///
+/// Strings longer than `maxStringLength` characters (default 1000) and sequences longer than
+/// `maxItems` items (default 100) are cut; items past the limit are never converted. Returns
+/// `{ value, truncated }` where `truncated` lists `{ path, kind, length }` per cut value, with
+/// `kind` `'string'` or `'array'` and the original length.
+#[wasm_bindgen]
+pub fn parse_preview(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let limits = PreviewLimits::from_js(&options)?;
+    let options = LoadOptions::from_js_with(&options, PREVIEW_OPTION_KEYS)?;
+    timed_call(&options, || {
+        let docs = match load_documents(input, &options.limits) {
+            Ok(docs) => docs,
+            Err(e) => return Err(options.error(&error_at(e.info(), e.marker()))),
+        };
+
+        let mut builder = ValueBuilder::new(&options).with_preview(limits);
+        let value = match docs.first() {
+            Some(doc) => timed(Phase::Convert, || builder.build_document(doc)),
+            None => Ok(options.no_document()),
+        };
+        options.warn(builder.take_warnings())?;
+        let value = value?;
+
+        let truncated = Array::new();
+        for truncation in builder.take_truncated() {
+            let entry = Object::new();
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("path"),
+                &JsValue::from_str(&truncation.path),
+            )?;
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("kind"),
+                &JsValue::from_str(truncation.kind),
+            )?;
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("length"),
+                &JsValue::from_f64(truncation.length as f64),
+            )?;
+            truncated.push(&entry);
+        }
+
+        let result = Object::new();
+        Reflect::set(&result, &JsValue::from_str("value"), &value)?;
+        Reflect::set(&result, &JsValue::from_str("truncated"), &truncated)?;
+        Ok(result.into())
+    })
+}
+
+#[wasm_bindgen]
+pub fn load(input: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    parse(input, options)
+}
+
+/// Parse all YAML documents in a string, as js-yaml's loadAll does
+///
+/// With an iterator function, each document is passed to it as soon as it is parsed and `undefined`
+/// is returned, so documents before a syntax error still reach the iterator. Returning `false` from
+/// the iterator stops parsing there. Otherwise an array of the documents is returned. As in
+/// js-yaml, an object in place of the iterator holds the options unless options follow it, so
+/// `loadAll(input, null, options)` applies `options`.
+///
+/// @param {string} input - The YAML stream
+/// @param {Function|Object|null} iterator - Callback receiving each document, or the options
+/// @param {Object} options - Load options
+/// @returns {Array|undefined} - The documents, unless an iterator receives them
+#[wasm_bindgen]
+pub fn load_all(input: &str, iterator: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    let Some(iterator) = iterator.dyn_ref::<Function>() else {
+        if iterator.is_object() && options.is_undefined() {
+            return parse_all(input, iterator);
+        }
+        return parse_all(input, options);
+    };
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        for doc in documents(input, &options.limits) {
+            let doc = doc.map_err(|e| syntax_error(input, &e, &options))?;
+            let result = iterator.call1(&JsValue::NULL, &node_to_js_value(&doc, &options)?)?;
+            if result.as_bool() == Some(false) {
+                break;
             }
-            output.push('}');
         }
-        Yaml::Alias(_) => return Err("YAML aliases are not supported".to_string()),
-        Yaml::BadValue => return Err("Invalid YAML value".to_string()),
+        Ok(JsValue::UNDEFINED)
+    })
+}
+
+/// Alias for load_all with camelCase naming for JavaScript compatibility
+#[wasm_bindgen]
+#[allow(non_snake_case)]
+pub fn loadAll(input: &str, iterator: JsValue, options: JsValue) -> Result<JsValue, JsValue> {
+    load_all(input, iterator, options)
+}
+
+/// Buffers conversions fill, kept from one conversion for the next
+#[derive(Debug, Default)]
+pub(crate) struct Scratch {
+    // JSON text written with `conversion: 'json'`
+    json: String,
+    // JavaScript strings of mapping keys
+    keys: HashMap<String, JsValue>,
+}
+
+/// Convert a loaded document to a JavaScript value
+pub(crate) fn node_to_js_value(node: &Node, options: &LoadOptions) -> Result<JsValue, JsValue> {
+    convert_node(node, options, &mut Scratch::default())
+}
+
+/// Convert a loaded document, reusing the buffers of earlier conversions
+fn convert_node(
+    node: &Node,
+    options: &LoadOptions,
+    scratch: &mut Scratch,
+) -> Result<JsValue, JsValue> {
+    if options.on_warning.is_some() && !options.lint_rules.is_empty() {
+        options.warn(lint_document(&options.lint_rules, node)?)?;
+    }
+    if !options.direct_conversion && !options.needs_js_values() {
+        // Convert to JSON string (single allocation)
+        let mut writer = JsonWriter::new(options).with_buffer(mem::take(&mut scratch.json));
+        let json_string = timed(Phase::Convert, || writer.write_document(node));
+
+        // JSON has no Infinity or NaN, so documents holding them are built directly
+        if json_string.is_err() || !writer.has_non_finite() {
+            options.warn(writer.take_warnings())?;
+            let json_string = json_string.map_err(|e| options.error(&e))?;
+
+            // Parse JSON string to JsValue (single WASM boundary crossing)
+            let value = timed(Phase::JsonParse, || js_sys::JSON::parse(&json_string))
+                .map_err(|_| JsValue::from_str("Failed to parse JSON"));
+            scratch.json = json_string;
+            return value;
+        }
     }
-    Ok(())
+
+    let mut builder = ValueBuilder::new(options).with_keys(mem::take(&mut scratch.keys));
+    let value = timed(Phase::Convert, || builder.build_document(node));
+    scratch.keys = builder.take_keys();
+    options.warn(builder.take_warnings())?;
+    value
 }
 
 // Keep this for yamlpath compatibility
 pub(crate) fn yaml_to_js_value(yaml: &Yaml) -> Result<JsValue, JsValue> {
+    yaml_to_js_value_within(yaml, Limits::default().max_nesting_depth)
+}
+
+/// Convert a resolved value, failing when collections nest more than `levels` deep
+fn yaml_to_js_value_within(yaml: &Yaml, levels: usize) -> Result<JsValue, JsValue> {
+    if matches!(yaml, Yaml::Array(_) | Yaml::Hash(_)) && levels == 0 {
+        return Err(JsValue::from_str(&format!(
+            "Value nesting exceeds {} levels (maxNestingDepth)",
+            Limits::default().max_nesting_depth
+        )));
+    }
     match yaml {
         Yaml::Null => Ok(JsValue::NULL),
         Yaml::Boolean(b) => Ok(Boolean::from(*b).into()),
         Yaml::Integer(i) => Ok(Number::from(*i as f64).into()),
-        Yaml::Real(s) => match s.parse::<f64>() {
-            Ok(f) => Ok(Number::from(f).into()),
-            Err(_) => Err(JsValue::from_str(&format!("Invalid float: {}", s))),
+        Yaml::Real(s) => match yaml.as_f64() {
+            Some(f) => Ok(Number::from(f).into()),
+            None => Err(JsValue::from_str(&format!("Invalid float: {}", s))),
         },
         Yaml::String(s) => Ok(JsString::from(s.as_str()).into()),
         Yaml::Array(arr) => {
             let js_array = Array::new_with_length(arr.len() as u32);
             for (i, item) in arr.iter().enumerate() {
-                js_array.set(i as u32, yaml_to_js_value(item)?);
+                js_array.set(i as u32, yaml_to_js_value_within(item, levels - 1)?);
             }
             Ok(js_array.into())
         }
@@ -200,7 +740,7 @@ pub(crate) fn yaml_to_js_value(yaml: &Yaml) -> Result<JsValue, JsValue> {
                     Yaml::String(s) => s.as_str(),
                     _ => &format!("{:?}", key),
                 };
-                let js_value = yaml_to_js_value(value)?;
+                let js_value = yaml_to_js_value_within(value, levels - 1)?;
                 js_sys::Reflect::set(&js_obj, &JsString::from(key_str).into(), &js_value)
                     .map_err(|_| JsValue::from_str("Failed to set property"))?;
             }
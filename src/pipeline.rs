@@ -0,0 +1,828 @@
+//! Pipelines
+//!
+//! This module runs a list of built-in steps over every document of a YAML stream in one call:
+//! alias resolution, merge keys, environment variable substitution, coercion and validation
+//! against a JSON Schema, redaction, edits at YAMLPath matches, key sorting and emission. The
+//! documents stay in Rust between steps, so multi-step configuration processing crosses the
+//! WebAssembly boundary once. The steps can themselves be a YAML document, so rules kept under
+//! version control run the same in every host.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as FmtWrite;
+use std::mem;
+
+use js_sys::{Array, Object, Reflect};
+use serde_json::Value as JsonValue;
+use wasm_bindgen::prelude::*;
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::Yaml;
+
+use crate::dump::{dump_yaml, emit_document, js_value_to_yaml};
+use crate::instrument::{start_call, timed, Phase};
+use crate::json::key_to_string;
+use crate::loader::{documents, error_at, load_documents, Node, NodeKind};
+use crate::options::{check_keys, get_property, get_string, KeyStyle, LoadOptions};
+use crate::parse::node_to_js_value;
+use crate::resolve::Resolver;
+use crate::timestamp::format_timestamp;
+use crate::validate::{resolve_ref, schema_from_js, validate_value, yaml_to_json};
+use crate::yamlpath::{parse_path, select, PathExpr};
+
+/// Text `redact` replaces values with unless a `replacement` is given
+const REDACTED: &str = "[REDACTED]";
+
+/// A step run over each document
+enum Step {
+    /// Merge `<<` keys into their mappings
+    MergeKeys,
+    /// Replace `${NAME}` and `${NAME:-default}` in string values
+    Env(HashMap<String, String>),
+    /// Convert scalars to the types a JSON Schema expects
+    Coerce(JsonValue),
+    /// Fail unless the document matches a JSON Schema
+    Validate(JsonValue),
+    /// Replace the values YAMLPath expressions match
+    Redact(Vec<PathExpr>, Yaml),
+    /// Remove the values YAMLPath expressions match from their mappings and sequences
+    Delete(Vec<PathExpr>),
+    /// Rename the keys of the mapping values YAMLPath expressions match
+    Rename(Vec<PathExpr>, Yaml),
+    /// Sort mapping keys
+    SortKeys,
+}
+
+/// Output format of the `emit` step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Yaml,
+    Json,
+}
+
+/// The steps of a pipeline, read from a JavaScript array
+struct Pipeline {
+    steps: Vec<Step>,
+    /// Whether aliases are expanded (`resolveAliases`); otherwise they are an error
+    resolve_aliases: bool,
+    format: Format,
+}
+
+impl Pipeline {
+    /// Read the steps, each an operation name or an object with an `op` and its parameters
+    ///
+    /// A string holds the steps as a YAML sequence.
+    fn from_js(steps: &JsValue) -> Result<Self, JsValue> {
+        if let Some(text) = steps.as_string() {
+            return Self::from_js(&steps_from_yaml(&text)?);
+        }
+        if !Array::is_array(steps) {
+            return Err(JsValue::from_str(
+                "Pipeline steps must be an array or a YAML sequence",
+            ));
+        }
+        let mut pipeline = Pipeline {
+            steps: Vec::new(),
+            resolve_aliases: false,
+            format: Format::Yaml,
+        };
+        let steps = Array::from(steps);
+        for (index, step) in steps.iter().enumerate() {
+            let op = match step.as_string() {
+                Some(op) => op,
+                None if step.is_object() => get_string(&step, "op")?.ok_or_else(|| {
+                    JsValue::from_str(&format!("Pipeline step {} has no 'op'", index))
+                })?,
+                None => {
+                    return Err(JsValue::from_str(&format!(
+                        "Pipeline step {} must be a string or an object",
+                        index
+                    )))
+                }
+            };
+            if op == "emit" && index + 1 != steps.length() as usize {
+                return Err(JsValue::from_str("Step 'emit' must come last"));
+            }
+            match op.as_str() {
+                "resolveAliases" => {
+                    check_keys(&step, &[&["op"]])?;
+                    pipeline.resolve_aliases = true;
+                }
+                "mergeKeys" => {
+                    check_keys(&step, &[&["op"]])?;
+                    pipeline.steps.push(Step::MergeKeys);
+                }
+                "env" => {
+                    check_keys(&step, &[&["op", "env"]])?;
+                    pipeline.steps.push(Step::Env(read_env(&step)?));
+                }
+                "coerce" | "validate" => {
+                    check_keys(&step, &[&["op", "schema"]])?;
+                    let schema = match param(&step, "schema")? {
+                        Some(schema) => schema_from_js(&schema)?,
+                        None => {
+                            return Err(JsValue::from_str(&format!(
+                                "Step '{}' needs a 'schema'",
+                                op
+                            )))
+                        }
+                    };
+                    pipeline.steps.push(if op == "coerce" {
+                        Step::Coerce(schema)
+                    } else {
+                        Step::Validate(schema)
+                    });
+                }
+                "redact" => {
+                    check_keys(&step, &[&["op", "paths", "replacement"]])?;
+                    pipeline.steps.push(read_redact(&step)?);
+                }
+                "set" => {
+                    check_keys(&step, &[&["op", "match", "value"]])?;
+                    let value = match param(&step, "value")? {
+                        Some(value) => js_value_to_yaml(&value, &KeyStyle::Keep)?,
+                        None => return Err(JsValue::from_str("Step 'set' needs a 'value'")),
+                    };
+                    pipeline
+                        .steps
+                        .push(Step::Redact(read_match(&step, "set")?, value));
+                }
+                "delete" => {
+                    check_keys(&step, &[&["op", "match"]])?;
+                    pipeline
+                        .steps
+                        .push(Step::Delete(read_match(&step, "delete")?));
+                }
+                "rename" => {
+                    check_keys(&step, &[&["op", "match", "to"]])?;
+                    let to = match param(&step, "to")?.and_then(|to| to.as_string()) {
+                        Some(to) => Yaml::String(to),
+                        None => return Err(JsValue::from_str("Step 'rename' needs a 'to' string")),
+                    };
+                    pipeline
+                        .steps
+                        .push(Step::Rename(read_match(&step, "rename")?, to));
+                }
+                "sortKeys" => {
+                    check_keys(&step, &[&["op"]])?;
+                    pipeline.steps.push(Step::SortKeys);
+                }
+                "emit" => {
+                    check_keys(&step, &[&["op", "format"]])?;
+                    let format = match param(&step, "format")? {
+                        Some(format) => format.as_string(),
+                        None => Some("yaml".to_string()),
+                    };
+                    pipeline.format = match format.as_deref() {
+                        Some("yaml") => Format::Yaml,
+                        Some("json") => Format::Json,
+                        _ => {
+                            return Err(JsValue::from_str(
+                                "Option 'format' must be 'yaml' or 'json'",
+                            ))
+                        }
+                    };
+                }
+                _ => {
+                    return Err(JsValue::from_str(&format!(
+                        "Unknown pipeline step '{}'",
+                        op
+                    )))
+                }
+            }
+        }
+        Ok(pipeline)
+    }
+}
+
+/// Read steps written as a YAML sequence into the JavaScript values `from_js` reads
+fn steps_from_yaml(text: &str) -> Result<JsValue, JsValue> {
+    let options = LoadOptions::from_js(&JsValue::UNDEFINED)?;
+    let docs = load_documents(text, &options.limits).map_err(|e| {
+        JsValue::from_str(&format!(
+            "Invalid pipeline steps: {}",
+            error_at(e.info(), e.marker())
+        ))
+    })?;
+    match docs.as_slice() {
+        [doc] => node_to_js_value(doc, &options),
+        _ => Err(JsValue::from_str(
+            "Pipeline steps must be a single YAML document",
+        )),
+    }
+}
+
+/// Read a parameter of a step, which a step given by name alone has none of
+fn param(step: &JsValue, key: &str) -> Result<Option<JsValue>, JsValue> {
+    if !step.is_object() {
+        return Ok(None);
+    }
+    get_property(step, key)
+}
+
+/// Read the variables of an `env` step
+fn read_env(step: &JsValue) -> Result<HashMap<String, String>, JsValue> {
+    let env = match param(step, "env")? {
+        Some(env) if env.is_object() => env,
+        _ => return Err(JsValue::from_str("Step 'env' needs an 'env' object")),
+    };
+    let mut variables = HashMap::new();
+    for name in Object::keys(env.unchecked_ref::<Object>()).iter() {
+        // Values that are not strings, such as `undefined`, count as unset
+        if let (Some(key), Some(value)) = (name.as_string(), Reflect::get(&env, &name)?.as_string())
+        {
+            variables.insert(key, value);
+        }
+    }
+    Ok(variables)
+}
+
+/// Read the `match` of a step: a YAMLPath string or an array of them
+fn read_match(step: &JsValue, op: &str) -> Result<Vec<PathExpr>, JsValue> {
+    let invalid = || {
+        JsValue::from_str(&format!(
+            "Step '{}' needs 'match', a YAMLPath string or an array of them",
+            op
+        ))
+    };
+    let paths = match param(step, "match")? {
+        Some(path) if path.is_string() => vec![path],
+        Some(paths) if Array::is_array(&paths) => Array::from(&paths).to_vec(),
+        _ => return Err(invalid()),
+    };
+    paths
+        .iter()
+        .map(|path| {
+            let path = path.as_string().ok_or_else(invalid)?;
+            parse_path(&path).map_err(|e| JsValue::from_str(&e))
+        })
+        .collect()
+}
+
+/// Read the paths and replacement of a `redact` step
+fn read_redact(step: &JsValue) -> Result<Step, JsValue> {
+    let invalid = || JsValue::from_str("Step 'redact' needs 'paths', an array of YAMLPath strings");
+    let paths = match param(step, "paths")? {
+        Some(paths) if Array::is_array(&paths) => Array::from(&paths),
+        _ => return Err(invalid()),
+    };
+    let paths = paths
+        .iter()
+        .map(|path| {
+            let path = path.as_string().ok_or_else(invalid)?;
+            parse_path(&path).map_err(|e| JsValue::from_str(&e))
+        })
+        .collect::<Result<_, _>>()?;
+    let replacement = match param(step, "replacement")? {
+        Some(value) => js_value_to_yaml(&value, &KeyStyle::Keep)?,
+        None => Yaml::String(REDACTED.to_string()),
+    };
+    Ok(Step::Redact(paths, replacement))
+}
+
+/// Run built-in steps over every document of a YAML stream in one call
+///
+/// Steps run in order, each an operation name or an object with an `op` and its parameters:
+/// `resolveAliases`, `mergeKeys`, `{ op: 'env', env }`, `{ op: 'coerce', schema }`,
+/// `{ op: 'validate', schema }`, `{ op: 'redact', paths, replacement }`,
+/// `{ op: 'set', match, value }`, `{ op: 'delete', match }`, `{ op: 'rename', match, to }`,
+/// `sortKeys` and, last, `{ op: 'emit', format }`. The steps may also be given as the text
+/// of a YAML sequence of the same steps. Aliases are expanded while loading when the steps
+/// include `resolveAliases` and are an error otherwise; `<<` keys are only merged by
+/// `mergeKeys`. The result is YAML text, or with `format: 'json'` one JSON text per document.
+///
+/// @param {string} input - The YAML stream to process
+/// @param {Array|string} steps - The steps to run over each document, or their YAML text
+/// @param {Object} options - Load options used to read the documents
+/// @returns {string} - The processed documents
+#[wasm_bindgen]
+pub fn pipeline(input: &str, steps: &JsValue, options: JsValue) -> Result<String, JsValue> {
+    start_call();
+    let pipeline = Pipeline::from_js(steps)?;
+    let mut options = LoadOptions::from_js(&options)?;
+    options.reject_timing("pipeline")?;
+    options.keep_merge_keys = true;
+
+    let mut values = Vec::new();
+    let mut builder = YamlBuilder::new(&options, pipeline.resolve_aliases);
+    for doc in documents(input, &options.limits) {
+        let doc = doc.map_err(|e| options.error(&error_at(e.info(), e.marker())))?;
+        let value = timed(Phase::Convert, || builder.build_document(&doc));
+        options.warn(builder.take_warnings())?;
+        let mut value = value.map_err(|e| options.error(&e))?;
+        for step in &pipeline.steps {
+            run_step(step, &mut value).map_err(|e| options.error(&e))?;
+        }
+        values.push(value);
+    }
+
+    timed(Phase::Emit, || emit_values(&values, pipeline.format)).map_err(|e| options.error(&e))
+}
+
+/// Write the processed documents in the requested format
+fn emit_values(values: &[Yaml], format: Format) -> Result<String, String> {
+    match (format, values) {
+        (Format::Yaml, [value]) => dump_yaml(value),
+        (Format::Yaml, values) => values.iter().map(emit_document).collect(),
+        (Format::Json, values) => {
+            let mut output = String::new();
+            for (i, value) in values.iter().enumerate() {
+                if i > 0 {
+                    output.push('\n');
+                }
+                let json = serde_json::to_string(&yaml_to_json(value)?)
+                    .map_err(|e| format!("JSON serialization error: {}", e))?;
+                output.push_str(&json);
+            }
+            Ok(output)
+        }
+    }
+}
+
+/// Run a step over a document
+fn run_step(step: &Step, value: &mut Yaml) -> Result<(), String> {
+    match step {
+        Step::MergeKeys => merge_keys(value, &mut String::from("$")),
+        Step::Env(env) => substitute_env(value, env, &mut String::from("$")),
+        Step::Coerce(schema) => {
+            coerce(value, schema, schema);
+            Ok(())
+        }
+        Step::Validate(schema) => {
+            let json = yaml_to_json(value)?;
+            let mut errors = Vec::new();
+            validate_value(&json, schema, schema, "$", &mut errors);
+            if errors.is_empty() {
+                return Ok(());
+            }
+            let errors: Vec<String> = errors
+                .iter()
+                .map(|error| format!("{}: {}", error.path, error.message))
+                .collect();
+            Err(format!("Validation failed: {}", errors.join("; ")))
+        }
+        Step::Redact(paths, replacement) => {
+            redact(value, paths, replacement);
+            Ok(())
+        }
+        Step::Delete(paths) => {
+            let targets = match_targets(value, paths);
+            if !targets.is_empty() {
+                delete_targets(value, &targets);
+            }
+            Ok(())
+        }
+        Step::Rename(paths, to) => {
+            let targets = match_targets(value, paths);
+            if !targets.is_empty() {
+                rename_targets(value, &targets, to, &mut String::from("$"))?;
+            }
+            Ok(())
+        }
+        Step::SortKeys => {
+            sort_keys(value);
+            Ok(())
+        }
+    }
+}
+
+/// Append the path segment leading to a mapping value, named like validation errors
+fn push_key(path: &mut String, key: &Yaml) {
+    let _ = write!(path, ".{}", key_to_string(key).unwrap_or_default());
+}
+
+/// Merge `<<` keys, innermost mappings first
+///
+/// Explicit keys take precedence over merged ones and earlier sources over later ones, as
+/// when loading with the default schema.
+fn merge_keys(value: &mut Yaml, path: &mut String) -> Result<(), String> {
+    let len = path.len();
+    match value {
+        Yaml::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                let _ = write!(path, "[{}]", i);
+                merge_keys(item, path)?;
+                path.truncate(len);
+            }
+        }
+        Yaml::Hash(hash) => {
+            for (key, item) in hash.iter_mut() {
+                push_key(path, key);
+                merge_keys(item, path)?;
+                path.truncate(len);
+            }
+            let merge_key = Yaml::String("<<".to_string());
+            if !hash.contains_key(&merge_key) {
+                return Ok(());
+            }
+            let mut merged = Hash::new();
+            for (key, item) in mem::take(hash) {
+                if key != merge_key {
+                    // Explicit keys replace merged values, keeping their position
+                    merged.replace(key, item);
+                    continue;
+                }
+                let sources = match item {
+                    Yaml::Array(sources) => sources,
+                    source => vec![source],
+                };
+                for source in sources {
+                    let Yaml::Hash(source) = source else {
+                        return Err(format!(
+                            "cannot merge a value that is not a mapping at {}",
+                            path
+                        ));
+                    };
+                    for (key, item) in source {
+                        if !merged.contains_key(&key) {
+                            merged.insert(key, item);
+                        }
+                    }
+                }
+            }
+            *hash = merged;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Substitute environment variables in every string value
+fn substitute_env(
+    value: &mut Yaml,
+    env: &HashMap<String, String>,
+    path: &mut String,
+) -> Result<(), String> {
+    let len = path.len();
+    match value {
+        Yaml::String(text) if text.contains("${") => {
+            *text = substitute(text, env).map_err(|e| format!("{} at {}", e, path))?;
+        }
+        Yaml::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                let _ = write!(path, "[{}]", i);
+                substitute_env(item, env, path)?;
+                path.truncate(len);
+            }
+        }
+        Yaml::Hash(hash) => {
+            for (key, item) in hash.iter_mut() {
+                push_key(path, key);
+                substitute_env(item, env, path)?;
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Replace `${NAME}` with a variable and `${NAME:-default}` with a variable or, when it is
+/// unset or empty, the default; `$${` writes a literal `${`
+fn substitute(text: &str, env: &HashMap<String, String>) -> Result<String, String> {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        let (before, after) = rest.split_at(start);
+        if let Some(before) = before.strip_suffix('$') {
+            result.push_str(before);
+            result.push_str("${");
+            rest = &after[2..];
+            continue;
+        }
+        result.push_str(before);
+        let Some(end) = after.find('}') else {
+            break;
+        };
+        let expr = &after[2..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+        let value = match (env.get(name), default) {
+            (Some(value), Some(default)) if value.is_empty() => default,
+            (Some(value), _) => value.as_str(),
+            (None, Some(default)) => default,
+            (None, None) => return Err(format!("environment variable '{}' is not set", name)),
+        };
+        result.push_str(value);
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Convert scalars whose type a JSON Schema rejects to the first type it allows that they can
+/// be read as (`'8080'` to `8080` for an integer, `8080` to `'8080'` for a string)
+///
+/// Follows `$ref`, `properties`, `additionalProperties` and `items`; values that cannot be
+/// converted are left for `validate` to report.
+fn coerce(value: &mut Yaml, schema: &JsonValue, root: &JsonValue) {
+    let JsonValue::Object(keywords) = schema else {
+        return;
+    };
+    if let Some(target) = keywords
+        .get("$ref")
+        .and_then(JsonValue::as_str)
+        .and_then(|reference| resolve_ref(root, reference))
+    {
+        coerce(value, target, root);
+    }
+    let types: Vec<&str> = match keywords.get("type") {
+        Some(JsonValue::String(name)) => vec![name.as_str()],
+        Some(JsonValue::Array(names)) => names.iter().filter_map(JsonValue::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !types.is_empty() && !types.iter().any(|name| has_type(value, name)) {
+        if let Some(coerced) = types.iter().find_map(|name| coerce_scalar(value, name)) {
+            *value = coerced;
+        }
+    }
+    match value {
+        Yaml::Hash(hash) => {
+            let properties = keywords.get("properties").and_then(JsonValue::as_object);
+            let additional = keywords.get("additionalProperties");
+            for (key, item) in hash.iter_mut() {
+                let property = key
+                    .as_str()
+                    .and_then(|key| properties.and_then(|properties| properties.get(key)));
+                if let Some(schema) = property.or(additional) {
+                    coerce(item, schema, root);
+                }
+            }
+        }
+        Yaml::Array(items) => {
+            if let Some(schema) = keywords.get("items") {
+                for item in items {
+                    coerce(item, schema, root);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check a value against a JSON Schema type name
+fn has_type(value: &Yaml, name: &str) -> bool {
+    match name {
+        "null" => matches!(value, Yaml::Null),
+        "boolean" => matches!(value, Yaml::Boolean(_)),
+        "integer" => matches!(value, Yaml::Integer(_)),
+        "number" => matches!(value, Yaml::Integer(_) | Yaml::Real(_)),
+        "string" => matches!(value, Yaml::String(_)),
+        "array" => matches!(value, Yaml::Array(_)),
+        "object" => matches!(value, Yaml::Hash(_)),
+        _ => false,
+    }
+}
+
+/// Read a scalar as a JSON Schema type, if it can be
+fn coerce_scalar(value: &Yaml, name: &str) -> Option<Yaml> {
+    match (name, value) {
+        ("integer", Yaml::String(text)) => text.parse().ok().map(Yaml::Integer),
+        ("number", Yaml::String(text)) => match text.parse() {
+            Ok(i) => Some(Yaml::Integer(i)),
+            Err(_) => text
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .map(|f| Yaml::Real(format!("{:?}", f))),
+        },
+        ("boolean", Yaml::String(text)) => match text.as_str() {
+            "true" => Some(Yaml::Boolean(true)),
+            "false" => Some(Yaml::Boolean(false)),
+            _ => None,
+        },
+        ("null", Yaml::String(text)) if text.is_empty() || text == "null" => Some(Yaml::Null),
+        ("string", Yaml::Integer(i)) => Some(Yaml::String(i.to_string())),
+        ("string", Yaml::Real(text)) => Some(Yaml::String(text.clone())),
+        ("string", Yaml::Boolean(b)) => Some(Yaml::String(b.to_string())),
+        _ => None,
+    }
+}
+
+/// Find the addresses of the values the paths match
+///
+/// Matches are told apart by address, so each is edited where it sits in the document.
+fn match_targets(value: &Yaml, paths: &[PathExpr]) -> HashSet<*const Yaml> {
+    paths
+        .iter()
+        .flat_map(|path| select(value, path))
+        .map(|matched| matched as *const Yaml)
+        .collect()
+}
+
+/// Replace every value the paths match
+fn redact(value: &mut Yaml, paths: &[PathExpr], replacement: &Yaml) {
+    let targets = match_targets(value, paths);
+    if !targets.is_empty() {
+        replace_targets(value, &targets, replacement);
+    }
+}
+
+fn replace_targets(value: &mut Yaml, targets: &HashSet<*const Yaml>, replacement: &Yaml) {
+    if targets.contains(&(value as *const Yaml)) {
+        *value = replacement.clone();
+        return;
+    }
+    match value {
+        Yaml::Array(items) => {
+            for item in items {
+                replace_targets(item, targets, replacement);
+            }
+        }
+        Yaml::Hash(hash) => {
+            for item in hash.values_mut() {
+                replace_targets(item, targets, replacement);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove every target from the mapping or sequence holding it; the document itself stays
+fn delete_targets(value: &mut Yaml, targets: &HashSet<*const Yaml>) {
+    match value {
+        Yaml::Array(items) => {
+            items.retain(|item| !targets.contains(&(item as *const Yaml)));
+            for item in items {
+                delete_targets(item, targets);
+            }
+        }
+        Yaml::Hash(hash) => {
+            hash.retain(|_, item| !targets.contains(&(item as *const Yaml)));
+            for item in hash.values_mut() {
+                delete_targets(item, targets);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Give every target held by a mapping the key `to`, keeping its position
+fn rename_targets(
+    value: &mut Yaml,
+    targets: &HashSet<*const Yaml>,
+    to: &Yaml,
+    path: &mut String,
+) -> Result<(), String> {
+    let len = path.len();
+    match value {
+        Yaml::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                let _ = write!(path, "[{}]", i);
+                rename_targets(item, targets, to, path)?;
+                path.truncate(len);
+            }
+        }
+        Yaml::Hash(hash) => {
+            for (key, item) in hash.iter_mut() {
+                push_key(path, key);
+                rename_targets(item, targets, to, path)?;
+                path.truncate(len);
+            }
+            let keys: HashSet<Yaml> = hash
+                .iter()
+                .filter(|(_, item)| targets.contains(&(*item as *const Yaml)))
+                .map(|(key, _)| key.clone())
+                .collect();
+            if keys.is_empty() {
+                return Ok(());
+            }
+            let mut renamed = Hash::new();
+            for (key, item) in mem::take(hash) {
+                let key = if keys.contains(&key) { to.clone() } else { key };
+                if renamed.insert(key, item).is_some() {
+                    push_key(path, to);
+                    return Err(format!(
+                        "cannot rename to a key that is already set at {}",
+                        path
+                    ));
+                }
+            }
+            *hash = renamed;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Sort the keys of every mapping
+fn sort_keys(value: &mut Yaml) {
+    match value {
+        Yaml::Array(items) => items.iter_mut().for_each(sort_keys),
+        Yaml::Hash(hash) => {
+            let mut entries: Vec<(Yaml, Yaml)> = mem::take(hash).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            for (key, mut item) in entries {
+                sort_keys(&mut item);
+                hash.insert(key, item);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Builds YAML values from node trees, resolving scalars like the other load functions
+struct YamlBuilder<'a> {
+    resolver: Resolver<'a>,
+    resolve_aliases: bool,
+}
+
+impl<'a> YamlBuilder<'a> {
+    fn new(options: &'a LoadOptions, resolve_aliases: bool) -> Self {
+        YamlBuilder {
+            resolver: Resolver::new(options),
+            resolve_aliases,
+        }
+    }
+
+    /// Convert a document to a YAML value
+    fn build_document(&mut self, node: &Node) -> Result<Yaml, String> {
+        self.resolver.start_document();
+        self.build(node)
+    }
+
+    /// Take the warnings collected so far
+    fn take_warnings(&mut self) -> Vec<String> {
+        self.resolver.take_warnings()
+    }
+
+    fn build(&mut self, node: &Node) -> Result<Yaml, String> {
+        self.resolver.count_expanded()?;
+        self.resolver.charge(node)?;
+        self.resolver.descend(node)?;
+        let built = self.build_tagged(node);
+        self.resolver.ascend(node);
+        built
+    }
+
+    /// Convert a node, in a `{ tag, value }` mapping for a kept local tag
+    fn build_tagged(&mut self, node: &Node) -> Result<Yaml, String> {
+        let Some(tag) = self.resolver.wrapped_tag(node) else {
+            return self.build_untagged(node);
+        };
+        let mut wrapper = Hash::new();
+        wrapper.insert(Yaml::String("tag".to_string()), Yaml::String(tag));
+        wrapper.insert(
+            Yaml::String("value".to_string()),
+            self.build_untagged(node)?,
+        );
+        Ok(Yaml::Hash(wrapper))
+    }
+
+    /// Convert a node as if an unknown tag were absent
+    fn build_untagged(&mut self, node: &Node) -> Result<Yaml, String> {
+        match &node.kind {
+            NodeKind::Scalar(value, style) => {
+                if let Some(ms) = self.resolver.timestamp(node, value, *style)? {
+                    return Ok(Yaml::String(format_timestamp(ms)));
+                }
+                match self.resolver.scalar(node, value, *style)? {
+                    Yaml::Real(text) if self.resolver.options.float_precision.is_some() => {
+                        let f = text.parse::<f64>().map_err(|e| e.to_string())?;
+                        Ok(Yaml::Real(format!("{:?}", self.resolver.float(f)?)))
+                    }
+                    scalar => Ok(scalar),
+                }
+            }
+            NodeKind::Sequence(items) => {
+                if let Some(pairs) = self.resolver.pairs(node, items)? {
+                    return pairs
+                        .entries
+                        .iter()
+                        .map(|(key, value)| {
+                            Ok(Yaml::Array(vec![self.build(key)?, self.build(value)?]))
+                        })
+                        .collect::<Result<_, String>>()
+                        .map(Yaml::Array);
+                }
+                self.resolver
+                    .warn(self.resolver.collection_warning(node, "seq"), &node.mark);
+                items
+                    .iter()
+                    .map(|item| self.build(item))
+                    .collect::<Result<_, _>>()
+                    .map(Yaml::Array)
+            }
+            NodeKind::Mapping(entries) => {
+                let mapping = self.resolver.mapping(node, entries)?;
+                let mut hash = Hash::new();
+                for (_, key, value) in mapping.properties() {
+                    hash.insert(key.clone(), self.build(value)?);
+                }
+                Ok(Yaml::Hash(hash))
+            }
+            NodeKind::Alias(target) => {
+                if !self.resolve_aliases {
+                    return Err(error_at(
+                        "aliases are only expanded with the 'resolveAliases' step",
+                        &node.mark,
+                    ));
+                }
+                let outer = self.resolver.enter_alias(node)?;
+                let built = self.build(target);
+                self.resolver.leave_alias(outer);
+                built
+            }
+        }
+    }
+}
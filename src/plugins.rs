@@ -0,0 +1,315 @@
+//! Plugins
+//!
+//! This module holds what extensions register on top of the built-in engines: string formats
+//! checked by the `format` schema keyword, predicates callable in YAMLPath filters
+//! (`[?(isPort(@.port))]`) and lint rules whose findings are reported as warnings while
+//! loading. JavaScript packages register them, along with tag types, through
+//! `register_plugin`; Rust crates built on this one register native functions with
+//! `register_native_format`, `register_native_path_function` and `register_native_lint_rule`.
+
+use std::cell::RefCell;
+use std::fmt::Write as FmtWrite;
+
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use yaml_rust2::Yaml;
+
+use crate::loader::{warning_at, Node, NodeKind};
+use crate::options::{check_keys, get_property, get_string};
+use crate::parse::yaml_to_js_value;
+use crate::schema::tag_name;
+use crate::types::register_type;
+
+/// Check of a string for a `format`
+type FormatCheck = Callback<fn(&str) -> bool>;
+
+/// Predicate callable in YAMLPath filters
+type PathFunction = Callback<fn(&Yaml) -> bool>;
+
+thread_local! {
+    static FORMATS: RefCell<Vec<(String, FormatCheck)>> = const { RefCell::new(Vec::new()) };
+    static PATH_FUNCTIONS: RefCell<Vec<(String, PathFunction)>> =
+        const { RefCell::new(Vec::new()) };
+    static LINT_RULES: RefCell<Vec<LintRule>> = const { RefCell::new(Vec::new()) };
+}
+
+/// A function registered from JavaScript or, by a Rust crate, natively
+#[derive(Debug, Clone)]
+pub(crate) enum Callback<F> {
+    Js(Function),
+    Native(F),
+}
+
+/// A node as lint rules see it
+#[derive(Debug, Clone, Copy)]
+pub struct LintNode<'a> {
+    /// Location of the node (e.g. `$.services[0].image`)
+    pub path: &'a str,
+    /// `"scalar"`, `"sequence"` or `"mapping"`
+    pub kind: &'static str,
+    /// Explicit tag the way it is written (`!Ref`), if any
+    pub tag: Option<&'a str>,
+    /// Source text of a scalar
+    pub source: Option<&'a str>,
+}
+
+/// A lint rule: a finding for a node, or `None`
+#[derive(Debug, Clone)]
+pub(crate) struct LintRule {
+    pub name: String,
+    check: Callback<fn(&LintNode) -> Option<String>>,
+}
+
+/// Add a named entry, replacing one registered before under the same name
+fn register<T>(entries: &mut Vec<(String, T)>, name: &str, entry: T) {
+    entries.retain(|(existing, _)| existing != name);
+    entries.push((name.to_string(), entry));
+}
+
+/// Register a check for strings whose schema has `format: name`
+pub fn register_native_format(name: &str, check: fn(&str) -> bool) {
+    FORMATS.with(|formats| register(&mut formats.borrow_mut(), name, Callback::Native(check)));
+}
+
+/// Register a predicate callable in YAMLPath filters as `name(@.path)`
+pub fn register_native_path_function(name: &str, predicate: fn(&Yaml) -> bool) {
+    PATH_FUNCTIONS.with(|functions| {
+        register(
+            &mut functions.borrow_mut(),
+            name,
+            Callback::Native(predicate),
+        )
+    });
+}
+
+/// Register a lint rule run over every node loaded while warnings are collected
+pub fn register_native_lint_rule(name: &str, check: fn(&LintNode) -> Option<String>) {
+    add_lint_rule(LintRule {
+        name: name.to_string(),
+        check: Callback::Native(check),
+    });
+}
+
+fn add_lint_rule(rule: LintRule) {
+    LINT_RULES.with(|rules| {
+        let mut rules = rules.borrow_mut();
+        rules.retain(|existing| existing.name != rule.name);
+        rules.push(rule);
+    });
+}
+
+/// Register the extensions of a plugin
+///
+/// `plugin` holds any of `types` (tags mapped to `registerType` definitions), `formats`
+/// (format names mapped to functions taking a string and returning whether it is valid),
+/// `functions` (YAMLPath filter function names mapped to predicates taking the value of their
+/// argument) and `lintRules` (rule names mapped to functions taking
+/// `{ path, kind, tag, source }` and returning a message or `undefined`), and optionally a
+/// `name`. Registering a name again replaces the previous entry.
+///
+/// @param {Object} plugin - The extensions to register
+#[wasm_bindgen]
+pub fn register_plugin(plugin: &JsValue) -> Result<(), JsValue> {
+    if !plugin.is_object() {
+        return Err(JsValue::from_str("Plugin must be an object"));
+    }
+    check_keys(
+        plugin,
+        &[&["name", "types", "formats", "functions", "lintRules"]],
+    )?;
+    get_string(plugin, "name")?;
+
+    for (tag, definition) in entries(plugin, "types")? {
+        register_type(&tag, &definition)?;
+    }
+    for (name, check) in functions(plugin, "formats")? {
+        FORMATS.with(|formats| register(&mut formats.borrow_mut(), &name, Callback::Js(check)));
+    }
+    for (name, predicate) in functions(plugin, "functions")? {
+        let identifier = name.starts_with(char::is_alphabetic)
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !identifier {
+            return Err(JsValue::from_str(&format!(
+                "YAMLPath function name '{}' must be an identifier",
+                name
+            )));
+        }
+        PATH_FUNCTIONS.with(|functions| {
+            register(&mut functions.borrow_mut(), &name, Callback::Js(predicate))
+        });
+    }
+    for (name, check) in functions(plugin, "lintRules")? {
+        add_lint_rule(LintRule {
+            name,
+            check: Callback::Js(check),
+        });
+    }
+    Ok(())
+}
+
+/// Read the entries of an object property of a plugin
+fn entries(plugin: &JsValue, key: &str) -> Result<Vec<(String, JsValue)>, JsValue> {
+    match get_property(plugin, key)? {
+        None => Ok(Vec::new()),
+        Some(object) if object.is_object() => Object::keys(object.unchecked_ref::<Object>())
+            .iter()
+            .map(|name| {
+                let value = Reflect::get(&object, &name)?;
+                Ok((name.as_string().unwrap_or_default(), value))
+            })
+            .collect(),
+        Some(_) => Err(JsValue::from_str(&format!(
+            "Plugin '{}' must be an object",
+            key
+        ))),
+    }
+}
+
+/// Read an object property of a plugin whose values are functions
+fn functions(plugin: &JsValue, key: &str) -> Result<Vec<(String, Function)>, JsValue> {
+    entries(plugin, key)?
+        .into_iter()
+        .map(|(name, value)| {
+            let function = value.dyn_into().map_err(|_| {
+                JsValue::from_str(&format!("Plugin {} '{}' must be a function", key, name))
+            })?;
+            Ok((name, function))
+        })
+        .collect()
+}
+
+/// Check a string against a registered format; `None` for a format nobody registered
+pub(crate) fn check_format(name: &str, value: &str) -> Option<bool> {
+    // Cloned out, so a callback that registers more plugins does not find the registry borrowed
+    let check = FORMATS.with(|formats| {
+        let formats = formats.borrow();
+        formats
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, check)| check.clone())
+    })?;
+    Some(match check {
+        Callback::Native(check) => check(value),
+        Callback::Js(check) => check
+            .call1(&JsValue::NULL, &JsValue::from_str(value))
+            .is_ok_and(|result| result.is_truthy()),
+    })
+}
+
+/// Whether a YAMLPath function is registered
+pub(crate) fn has_path_function(name: &str) -> bool {
+    PATH_FUNCTIONS.with(|functions| {
+        functions
+            .borrow()
+            .iter()
+            .any(|(existing, _)| existing == name)
+    })
+}
+
+/// Call a YAMLPath function; unknown functions and JavaScript errors count as no match
+pub(crate) fn call_path_function(name: &str, value: &Yaml) -> bool {
+    let predicate = PATH_FUNCTIONS.with(|functions| {
+        let functions = functions.borrow();
+        functions
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, predicate)| predicate.clone())
+    });
+    match predicate {
+        Some(Callback::Native(predicate)) => predicate(value),
+        Some(Callback::Js(predicate)) => yaml_to_js_value(value)
+            .and_then(|value| predicate.call1(&JsValue::NULL, &value))
+            .is_ok_and(|result| result.is_truthy()),
+        None => false,
+    }
+}
+
+/// The lint rules registered so far
+pub(crate) fn registered_lint_rules() -> Vec<LintRule> {
+    LINT_RULES.with(|rules| rules.borrow().clone())
+}
+
+/// Run lint rules over a document, returning their findings as warnings
+pub(crate) fn lint_document(rules: &[LintRule], node: &Node) -> Result<Vec<String>, JsValue> {
+    let mut findings = Vec::new();
+    lint_node(rules, node, &mut String::from("$"), &mut findings)?;
+    Ok(findings)
+}
+
+fn lint_node(
+    rules: &[LintRule],
+    node: &Node,
+    path: &mut String,
+    findings: &mut Vec<String>,
+) -> Result<(), JsValue> {
+    let tag = node.tag.as_ref().map(tag_name);
+    let (kind, source) = match &node.kind {
+        NodeKind::Scalar(text, _) => ("scalar", Some(text.as_str())),
+        NodeKind::Sequence(_) => ("sequence", None),
+        NodeKind::Mapping(_) => ("mapping", None),
+        // The anchored node was linted where it is defined
+        NodeKind::Alias(_) => return Ok(()),
+    };
+    let info = LintNode {
+        path,
+        kind,
+        tag: tag.as_deref(),
+        source,
+    };
+    for rule in rules {
+        if let Some(message) = rule.check(&info)? {
+            let info = format!("{} ({})", message, rule.name);
+            findings.push(warning_at(&info, &node.mark));
+        }
+    }
+
+    let len = path.len();
+    match &node.kind {
+        NodeKind::Sequence(items) => {
+            for (i, item) in items.iter().enumerate() {
+                let _ = write!(path, "[{}]", i);
+                lint_node(rules, item, path, findings)?;
+                path.truncate(len);
+            }
+        }
+        NodeKind::Mapping(entries) => {
+            for (key, value) in entries {
+                // Complex keys have no name to write
+                let name = match &key.kind {
+                    NodeKind::Scalar(text, _) => text.as_str(),
+                    _ => "?",
+                };
+                let _ = write!(path, ".{}", name);
+                lint_node(rules, value, path, findings)?;
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+impl LintRule {
+    /// Check a node, passing JavaScript rules `{ path, kind, tag, source }`
+    fn check(&self, node: &LintNode) -> Result<Option<String>, JsValue> {
+        let check = match &self.check {
+            Callback::Native(check) => return Ok(check(node)),
+            Callback::Js(check) => check,
+        };
+        let info = Object::new();
+        Reflect::set(&info, &"path".into(), &node.path.into())?;
+        Reflect::set(&info, &"kind".into(), &node.kind.into())?;
+        Reflect::set(
+            &info,
+            &"tag".into(),
+            &node.tag.map_or(JsValue::NULL, JsValue::from),
+        )?;
+        Reflect::set(
+            &info,
+            &"source".into(),
+            &node.source.map_or(JsValue::UNDEFINED, JsValue::from),
+        )?;
+        let result = check.call1(&JsValue::NULL, &info)?;
+        Ok(result.as_string())
+    }
+}
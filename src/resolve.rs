@@ -0,0 +1,739 @@
+//! Node resolution
+//!
+//! This module holds the state shared by every output format while a document is converted:
+//! scalar resolution, warnings, alias limits, merge keys and duplicate key handling.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::mem;
+
+use yaml_rust2::scanner::{Marker, TScalarStyle};
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::Yaml;
+
+use crate::json::key_to_string;
+use crate::loader::{error_at, node_bytes, warning_at, Node, NodeKind};
+use crate::options::{ComplexKeys, DuplicateKeys, EmptyValues, LoadOptions};
+use crate::schema::{
+    collection_warning, decode_base64, is_binary_tag, is_set_tag, is_timestamp_tag, tag_name,
+    Diagnostic, ScalarRules, Schema, CORE_TAG_HANDLE,
+};
+use crate::timestamp::parse_timestamp;
+use crate::types::CustomType;
+use crate::units::{parse_byte_size, parse_duration};
+
+/// Largest integer a `Number` holds exactly (`Number.MAX_SAFE_INTEGER`)
+pub(crate) const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Resolves the nodes of a document according to the load options
+pub(crate) struct Resolver<'a> {
+    pub options: &'a LoadOptions,
+    // Non-fatal issues, collected only when an `onWarning` callback is set
+    warnings: Option<Vec<String>>,
+    // Aliases expanded and nodes produced by expansion in the current document
+    aliases_expanded: usize,
+    nodes_expanded: usize,
+    // Position of the innermost alias being expanded
+    expanding: Option<Marker>,
+    // Approximate bytes taken by the values converted so far
+    memory_used: usize,
+    // Collections enclosing the node being converted, including those reached through aliases
+    depth: usize,
+}
+
+/// The properties of a mapping after merge keys and duplicate keys are resolved
+pub(crate) struct Mapping<'n> {
+    /// Whether the mapping is a `!!set`, whose values are all null
+    pub is_set: bool,
+    entries: Vec<MapEntry<'n>>,
+    keys: Vec<Yaml>,
+    // (key entry, value entry) per property
+    slots: Vec<(usize, usize)>,
+}
+
+/// The entries of an `!!omap` or `!!pairs` sequence of single-pair mappings
+pub(crate) struct Pairs<'n> {
+    /// Whether the sequence is an `!!omap`, whose keys are unique
+    pub is_omap: bool,
+    /// `(key, value)` nodes in document order
+    pub entries: Vec<(&'n Node, &'n Node)>,
+}
+
+/// An entry of a mapping after merge keys are expanded
+struct MapEntry<'n> {
+    key: &'n Node,
+    value: &'n Node,
+    /// Whether the entry comes from a `<<` merge source
+    merged: bool,
+}
+
+impl<'n> Mapping<'n> {
+    /// Check for sequence or mapping keys
+    pub fn has_complex_keys(&self) -> bool {
+        self.keys
+            .iter()
+            .any(|key| matches!(key, Yaml::Array(_) | Yaml::Hash(_)))
+    }
+
+    /// Iterate over `(key node, resolved key, value node)` in output order
+    pub fn properties(&self) -> impl Iterator<Item = (&'n Node, &Yaml, &'n Node)> + '_ {
+        self.slots.iter().map(|&(key_index, value_index)| {
+            (
+                self.entries[key_index].key,
+                &self.keys[key_index],
+                self.entries[value_index].value,
+            )
+        })
+    }
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(options: &'a LoadOptions) -> Self {
+        Resolver {
+            options,
+            warnings: options.on_warning.as_ref().map(|_| Vec::new()),
+            aliases_expanded: 0,
+            nodes_expanded: 0,
+            expanding: None,
+            memory_used: 0,
+            depth: 0,
+        }
+    }
+
+    /// Reset the per-document alias counters and nesting depth
+    pub fn start_document(&mut self) {
+        self.aliases_expanded = 0;
+        self.nodes_expanded = 0;
+        self.depth = 0;
+    }
+
+    /// Take the warnings collected so far
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        self.warnings.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    pub fn warn(&mut self, warning: Option<String>, mark: &Marker) {
+        if let (Some(warnings), Some(warning)) = (self.warnings.as_mut(), warning) {
+            warnings.push(warning_at(&warning, mark));
+        }
+    }
+
+    /// Find the registered type a node's tag and kind select, if any
+    pub fn custom_type(&self, node: &Node) -> Option<&'a CustomType> {
+        let tag = node.tag.as_ref()?;
+        self.options
+            .types
+            .iter()
+            .find(|custom| custom.matches(tag, &node.kind))
+    }
+
+    /// Name the local tag (`!Foo`) to keep in a `{ tag, value }` wrapper with
+    /// `wrap_unknown_tags`, if the node has one no registered type or `units` handles
+    pub fn wrapped_tag(&self, node: &Node) -> Option<String> {
+        let tag = node.tag.as_ref()?;
+        if !self.options.wrap_unknown_tags || tag.handle != "!" || tag.suffix.is_empty() {
+            return None;
+        }
+        let quantity = matches!(tag.suffix.as_str(), "duration" | "bytes")
+            && matches!(node.kind, NodeKind::Scalar(..));
+        if (quantity && self.options.units) || self.custom_type(node).is_some() {
+            return None;
+        }
+        Some(tag_name(tag))
+    }
+
+    /// Describe an unknown tag on a sequence (`expected` is `seq`) or mapping (`map`), if any
+    pub fn collection_warning(&self, node: &Node, expected: &str) -> Option<String> {
+        if self.custom_type(node).is_some() || self.wrapped_tag(node).is_some() {
+            return None;
+        }
+        collection_warning(node.tag.as_ref(), expected)
+    }
+
+    /// Report issues with a scalar node
+    fn check_scalar(&mut self, node: &Node) {
+        if self.warnings.is_none()
+            || self.custom_type(node).is_some()
+            || self.wrapped_tag(node).is_some()
+        {
+            return;
+        }
+        if let NodeKind::Scalar(value, style) = &node.kind {
+            let warning = self.options.schema.scalar_warning(
+                value,
+                *style,
+                node.tag.as_ref(),
+                self.options.rules,
+            );
+            self.warn(warning, &node.mark);
+        }
+    }
+
+    /// Resolve a scalar node, reporting issues with it
+    pub fn scalar(
+        &mut self,
+        node: &Node,
+        value: &str,
+        style: TScalarStyle,
+    ) -> Result<Yaml, String> {
+        if let Some(quantity) = self.quantity(node, value)? {
+            return Ok(quantity);
+        }
+        self.check_scalar(node);
+        if self.options.empty_values == EmptyValues::String && is_empty_value(node) {
+            return Ok(Yaml::String(String::new()));
+        }
+        let resolved = self
+            .options
+            .schema
+            .resolve_scalar(value, style, node.tag.as_ref(), self.options.rules)
+            .map_err(|e| error_at(&e, &node.mark))?;
+        if self.options.large_int_as_string {
+            if let Some(digits) = large_int_digits(&resolved) {
+                self.warn(
+                    Some(format!(
+                        "integer {} is beyond the safe Number range, read as a string",
+                        digits
+                    )),
+                    &node.mark,
+                );
+                return Ok(Yaml::String(digits));
+            }
+        }
+        Ok(resolved)
+    }
+
+    /// Resolve a `!duration` scalar to milliseconds or a `!bytes` scalar to bytes when `units`
+    /// is set
+    fn quantity(&self, node: &Node, value: &str) -> Result<Option<Yaml>, String> {
+        let Some(tag) = node.tag.as_ref().filter(|tag| tag.handle == "!") else {
+            return Ok(None);
+        };
+        let parsed = match tag.suffix.as_str() {
+            "duration" if self.options.units => parse_duration(value),
+            "bytes" if self.options.units => parse_byte_size(value),
+            _ => return Ok(None),
+        };
+        let number = parsed.ok_or_else(|| {
+            error_at(
+                &format!("cannot resolve '{}' as !{}", value, tag.suffix),
+                &node.mark,
+            )
+        })?;
+        Ok(Some(if number.fract() == 0.0 && number.abs() < 1e18 {
+            Yaml::Integer(number as i64)
+        } else {
+            Yaml::Real(format!("{:?}", number))
+        }))
+    }
+
+    /// Decode a `!!binary` scalar when `decodeBinary` is set
+    pub fn binary(&mut self, node: &Node, value: &str) -> Result<Option<Vec<u8>>, String> {
+        if !self.options.decode_binary || !is_binary_tag(node.tag.as_ref()) {
+            return Ok(None);
+        }
+        decode_base64(value)
+            .map(Some)
+            .map_err(|e| error_at(&e, &node.mark))
+    }
+
+    /// Resolve a timestamp to milliseconds since the epoch when `timestamps` is set
+    ///
+    /// Plain scalars are timestamps in the default schema only; `!!timestamp` scalars must be.
+    pub fn timestamp(
+        &mut self,
+        node: &Node,
+        value: &str,
+        style: TScalarStyle,
+    ) -> Result<Option<f64>, String> {
+        if !self.options.timestamps {
+            return Ok(None);
+        }
+        if is_timestamp_tag(node.tag.as_ref()) {
+            return parse_timestamp(value).map(Some).ok_or_else(|| {
+                error_at(
+                    &format!("cannot resolve '{}' as !!timestamp", value),
+                    &node.mark,
+                )
+            });
+        }
+        let implicit = node.tag.is_none()
+            && style == TScalarStyle::Plain
+            && self.options.schema == Schema::Default
+            && !self.options.rules.stringify;
+        Ok(if implicit {
+            parse_timestamp(value)
+        } else {
+            None
+        })
+    }
+
+    /// Round a float to `floatPrecision` significant digits
+    pub fn float(&self, f: f64) -> Result<f64, String> {
+        match self.options.float_precision {
+            // Round through scientific notation to keep `precision` significant digits
+            Some(precision) => format!("{:.*e}", precision - 1, f)
+                .parse::<f64>()
+                .map_err(|e| e.to_string()),
+            None => Ok(f),
+        }
+    }
+
+    /// Count a node produced by alias expansion, failing past `maxAliasExpansion`
+    pub fn count_expanded(&mut self) -> Result<(), String> {
+        let Some(mark) = self.expanding else {
+            return Ok(());
+        };
+        self.nodes_expanded += 1;
+        let limit = self.options.limits.max_alias_expansion;
+        if self.nodes_expanded > limit {
+            return Err(error_at(
+                &format!(
+                    "alias expansion exceeds {} nodes (maxAliasExpansion)",
+                    limit
+                ),
+                &mark,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Count memory taken by converting a node, failing past `maxMemoryBytes`
+    ///
+    /// Unlike the alias counters, this one spans every document of the call.
+    pub fn charge(&mut self, node: &Node) -> Result<(), String> {
+        self.memory_used = self.memory_used.saturating_add(node_bytes(node));
+        let limit = self.options.limits.max_memory_bytes;
+        if self.memory_used > limit {
+            return Err(error_at(
+                &format!(
+                    "conversion needs more than {} bytes of memory (maxMemoryBytes)",
+                    limit
+                ),
+                &node.mark,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Enter a node, failing when a collection nests past `maxNestingDepth`
+    ///
+    /// Aliases can nest a document deeper than its text does, so this is checked again while
+    /// converting. Every `descend` is paired with an `ascend` unless it fails.
+    pub fn descend(&mut self, node: &Node) -> Result<(), String> {
+        if !matches!(node.kind, NodeKind::Sequence(_) | NodeKind::Mapping(_)) {
+            return Ok(());
+        }
+        let limit = self.options.limits.max_nesting_depth;
+        if self.depth >= limit {
+            return Err(error_at(
+                &format!("nesting exceeds {} levels (maxNestingDepth)", limit),
+                &node.mark,
+            ));
+        }
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Leave a node entered with `descend`
+    pub fn ascend(&mut self, node: &Node) {
+        if matches!(node.kind, NodeKind::Sequence(_) | NodeKind::Mapping(_)) {
+            self.depth -= 1;
+        }
+    }
+
+    /// Start expanding an alias, failing past `maxAliasCount`
+    ///
+    /// Returns the enclosing expansion, to be passed to `leave_alias` once done.
+    pub fn enter_alias(&mut self, alias: &Node) -> Result<Option<Marker>, String> {
+        self.aliases_expanded += 1;
+        let limit = self.options.limits.max_alias_count;
+        if self.aliases_expanded > limit {
+            return Err(error_at(
+                &format!(
+                    "document expands more than {} aliases (maxAliasCount)",
+                    limit
+                ),
+                &alias.mark,
+            ));
+        }
+        Ok(self.expanding.replace(alias.mark))
+    }
+
+    /// Finish expanding an alias
+    pub fn leave_alias(&mut self, outer: Option<Marker>) {
+        self.expanding = outer;
+    }
+
+    /// Apply the alias limits to a mapping key, which is resolved rather than written
+    fn check_key_expansion(&mut self, node: &Node) -> Result<(), String> {
+        self.count_expanded()?;
+        match &node.kind {
+            NodeKind::Scalar(..) => Ok(()),
+            NodeKind::Sequence(items) => items
+                .iter()
+                .try_for_each(|item| self.check_key_expansion(item)),
+            NodeKind::Mapping(entries) => entries.iter().try_for_each(|(key, value)| {
+                self.check_key_expansion(key)?;
+                self.check_key_expansion(value)
+            }),
+            NodeKind::Alias(target) => {
+                let outer = self.enter_alias(node)?;
+                let result = self.check_key_expansion(target);
+                self.leave_alias(outer);
+                result
+            }
+        }
+    }
+
+    /// Resolve a document to a YAML value, continuing past scalars that cannot be resolved
+    ///
+    /// Scalars that fail to resolve (`!!int abc`) become null and are recorded in
+    /// `diagnostics` with their position and path. Aliases, merge keys and duplicate keys are
+    /// handled as when converting, so the limits and `duplicateKey` apply.
+    pub fn resolve_document(
+        &mut self,
+        node: &Node,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<Yaml, String> {
+        self.start_document();
+        let mut path = String::from("$");
+        self.resolve_lossy(node, &mut path, diagnostics)
+    }
+
+    fn resolve_lossy(
+        &mut self,
+        node: &Node,
+        path: &mut String,
+        diagnostics: &mut Vec<Diagnostic>,
+    ) -> Result<Yaml, String> {
+        self.count_expanded()?;
+        self.charge(node)?;
+        self.descend(node)?;
+        let len = path.len();
+        let resolved = match &node.kind {
+            NodeKind::Scalar(value, style) => self
+                .options
+                .schema
+                .resolve_scalar(value, *style, node.tag.as_ref(), self.options.rules)
+                .unwrap_or_else(|e| {
+                    diagnostics.push(Diagnostic {
+                        message: warning_at(&format!("{}, read as null", e), &node.mark),
+                        path: path.clone(),
+                    });
+                    Yaml::Null
+                }),
+            NodeKind::Sequence(items) => {
+                let mut array = Vec::with_capacity(items.len());
+                for (i, item) in items.iter().enumerate() {
+                    let _ = write!(path, "[{}]", i);
+                    array.push(self.resolve_lossy(item, path, diagnostics)?);
+                    path.truncate(len);
+                }
+                Yaml::Array(array)
+            }
+            NodeKind::Mapping(entries) => {
+                let mapping = self.mapping(node, entries)?;
+                let mut hash = Hash::new();
+                for (_, key, value) in mapping.properties() {
+                    let _ = write!(path, ".{}", key_to_string(key).unwrap_or_default());
+                    hash.insert(key.clone(), self.resolve_lossy(value, path, diagnostics)?);
+                    path.truncate(len);
+                }
+                Yaml::Hash(hash)
+            }
+            NodeKind::Alias(target) => {
+                let outer = self.enter_alias(node)?;
+                let resolved = self.resolve_lossy(target, path, diagnostics);
+                self.leave_alias(outer);
+                resolved?
+            }
+        };
+        self.ascend(node);
+        Ok(resolved)
+    }
+
+    /// Resolve the properties of a mapping node
+    ///
+    /// Merged entries never replace an existing key and explicit entries replace merged ones;
+    /// other duplicates follow `duplicateKey`, keeping the position of the first definition.
+    pub fn mapping<'n>(
+        &mut self,
+        node: &Node,
+        entries: &'n [(Node, Node)],
+    ) -> Result<Mapping<'n>, String> {
+        self.warn(self.collection_warning(node, "map"), &node.mark);
+        let mut entries = self.mapping_entries(entries)?;
+        for entry in &entries {
+            if !entry.merged {
+                self.check_scalar(entry.key);
+            }
+            self.check_key_expansion(entry.key)?;
+        }
+        if self.options.complex_keys == ComplexKeys::Error {
+            if let Some(entry) = entries.iter().find(|entry| is_complex_key(entry.key)) {
+                return Err(error_at(
+                    "collection mapping keys are not allowed (complexKeys)",
+                    &entry.key.mark,
+                ));
+            }
+        }
+        let is_set = is_set_tag(node.tag.as_ref());
+        if is_set {
+            if let Some(entry) = entries.iter().find(|entry| !is_null_value(entry.value)) {
+                return Err(error_at(
+                    "cannot resolve a mapping with non-null values as !!set",
+                    &entry.value.mark,
+                ));
+            }
+        }
+        let mut keys = entries
+            .iter()
+            .map(|entry| {
+                self.options
+                    .schema
+                    .resolve_node(entry.key, self.options.rules)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        // Filtered out before renaming and duplicate checks, so dropped values are never built
+        let filter = &self.options.key_filter;
+        if !filter.is_empty() {
+            (entries, keys) = entries
+                .into_iter()
+                .zip(keys)
+                .filter(|(_, key)| !matches!(key, Yaml::String(name) if !filter.keeps(name)))
+                .unzip();
+        }
+        // Renamed keys are checked for duplicates, so `max_count` and `maxCount` collide
+        if !self.options.key_style.is_keep() {
+            for (entry, key) in entries.iter().zip(&mut keys) {
+                if let Yaml::String(name) = key {
+                    *name = self
+                        .options
+                        .key_style
+                        .apply(name)
+                        .map_err(|e| error_at(&e, &entry.key.mark))?;
+                }
+            }
+        }
+
+        let mut slots: Vec<(usize, usize)> = Vec::with_capacity(entries.len());
+        let mut slot_of_key: HashMap<&Yaml, usize> = HashMap::with_capacity(entries.len());
+        for (i, key) in keys.iter().enumerate() {
+            let Some(&slot) = slot_of_key.get(key) else {
+                slot_of_key.insert(key, slots.len());
+                slots.push((i, i));
+                continue;
+            };
+            if entries[i].merged {
+                continue;
+            }
+            if entries[slots[slot].1].merged {
+                slots[slot].1 = i;
+                continue;
+            }
+            let mark = &entries[i].key.mark;
+            match self.options.duplicate_keys {
+                DuplicateKeys::Error => {
+                    let first = &entries[slots[slot].0].key.mark;
+                    return Err(format!(
+                        "{} (first defined at line {}, column {})",
+                        error_at(
+                            &format!("duplicated key {} in mapping", key_label(key)),
+                            mark
+                        ),
+                        first.line(),
+                        first.col() + 1
+                    ));
+                }
+                DuplicateKeys::First => {}
+                DuplicateKeys::Last => slots[slot].1 = i,
+                DuplicateKeys::Warn => {
+                    self.warn(
+                        Some(format!(
+                            "duplicated key {} in mapping overrides an earlier value",
+                            key_label(key)
+                        )),
+                        mark,
+                    );
+                    slots[slot].1 = i;
+                }
+            }
+        }
+
+        // Set members have empty values but are not omitted
+        if self.options.empty_values == EmptyValues::Omit && !is_set {
+            slots.retain(|&(_, value)| !is_empty_value(entries[value].value));
+        }
+
+        Ok(Mapping {
+            is_set,
+            entries,
+            keys,
+            slots,
+        })
+    }
+
+    /// Read an `!!omap` or `!!pairs` sequence; `None` for other sequences
+    pub fn pairs<'n>(
+        &mut self,
+        node: &Node,
+        items: &'n [Node],
+    ) -> Result<Option<Pairs<'n>>, String> {
+        let Some(tag) = node
+            .tag
+            .as_ref()
+            .filter(|tag| tag.handle == CORE_TAG_HANDLE)
+            .filter(|tag| tag.suffix == "omap" || tag.suffix == "pairs")
+        else {
+            return Ok(None);
+        };
+        let is_omap = tag.suffix == "omap";
+        let mut entries = Vec::with_capacity(items.len());
+        let mut keys = HashSet::new();
+        for item in items {
+            let [(key, value)] = (match &item.kind {
+                NodeKind::Mapping(entries) => entries.as_slice(),
+                _ => &[],
+            }) else {
+                return Err(error_at(
+                    &format!(
+                        "cannot resolve a sequence item that is not a single-pair mapping as !!{}",
+                        tag.suffix
+                    ),
+                    &item.mark,
+                ));
+            };
+            if is_omap {
+                let resolved = self.options.schema.resolve_node(key, self.options.rules)?;
+                if let Some(duplicate) = keys.replace(resolved) {
+                    return Err(error_at(
+                        &format!("duplicated key {} in !!omap", key_label(&duplicate)),
+                        &key.mark,
+                    ));
+                }
+            }
+            entries.push((key, value));
+        }
+        Ok(Some(Pairs { is_omap, entries }))
+    }
+
+    /// List the entries of a mapping, expanding `<<` merge keys (default schema only)
+    fn mapping_entries<'n>(
+        &mut self,
+        entries: &'n [(Node, Node)],
+    ) -> Result<Vec<MapEntry<'n>>, String> {
+        let mut result = Vec::with_capacity(entries.len());
+        for (key, value) in entries {
+            if self.options.schema == Schema::Default
+                && !self.options.keep_merge_keys
+                && is_merge_key(key)
+            {
+                self.merge_source(value, true, &mut result)?;
+            } else {
+                result.push(MapEntry {
+                    key,
+                    value,
+                    merged: false,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Append the entries of a merge source: a mapping or a sequence of mappings
+    fn merge_source<'n>(
+        &mut self,
+        node: &'n Node,
+        allow_sequence: bool,
+        result: &mut Vec<MapEntry<'n>>,
+    ) -> Result<(), String> {
+        match &node.kind {
+            NodeKind::Mapping(entries) => {
+                for entry in self.mapping_entries(entries)? {
+                    result.push(MapEntry {
+                        merged: true,
+                        ..entry
+                    });
+                }
+                Ok(())
+            }
+            NodeKind::Sequence(items) if allow_sequence => items
+                .iter()
+                .try_for_each(|item| self.merge_source(item, false, result)),
+            NodeKind::Alias(target) => {
+                let outer = self.enter_alias(node)?;
+                let merged = self.merge_source(target, allow_sequence, result);
+                self.leave_alias(outer);
+                merged
+            }
+            _ => Err(error_at(
+                "cannot merge mappings; the provided source object is unacceptable",
+                &node.mark,
+            )),
+        }
+    }
+}
+
+/// Describe a mapping key in messages
+fn key_label(key: &Yaml) -> String {
+    match key {
+        Yaml::String(s) => format!("'{}'", s),
+        Yaml::Integer(i) => i.to_string(),
+        Yaml::Real(r) => r.clone(),
+        Yaml::Boolean(b) => b.to_string(),
+        Yaml::Null => "null".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Decimal digits of an integer a `Number` cannot hold exactly (beyond ±2^53 - 1)
+///
+/// Integers beyond the 64-bit range resolve as floats and are recognized by their text.
+fn large_int_digits(resolved: &Yaml) -> Option<String> {
+    match resolved {
+        Yaml::Integer(i) if i.unsigned_abs() > MAX_SAFE_INTEGER => Some(i.to_string()),
+        Yaml::Real(s) => {
+            let unsigned = s.strip_prefix(['-', '+']).unwrap_or(s);
+            let digits = !unsigned.is_empty() && unsigned.bytes().all(|b| b.is_ascii_digit());
+            digits.then(|| s.strip_prefix('+').unwrap_or(s).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Check for an empty value: an untagged plain scalar with no text
+fn is_empty_value(node: &Node) -> bool {
+    matches!(&node.kind, NodeKind::Scalar(value, TScalarStyle::Plain) if value.is_empty())
+        && node.tag.is_none()
+}
+
+/// Check for a scalar resolving to null (`? a`, `a: ~`), following aliases
+fn is_null_value(node: &Node) -> bool {
+    match &node.kind {
+        NodeKind::Scalar(value, style) => matches!(
+            Schema::Core.resolve_scalar(value, *style, node.tag.as_ref(), ScalarRules::default()),
+            Ok(Yaml::Null)
+        ),
+        NodeKind::Alias(target) => is_null_value(target),
+        _ => false,
+    }
+}
+
+/// Check for a sequence or mapping key, following aliases
+fn is_complex_key(key: &Node) -> bool {
+    match &key.kind {
+        NodeKind::Scalar(..) => false,
+        NodeKind::Sequence(_) | NodeKind::Mapping(_) => true,
+        NodeKind::Alias(target) => is_complex_key(target),
+    }
+}
+
+/// Check for a `<<` merge key (plain, or tagged `!!merge`)
+fn is_merge_key(key: &Node) -> bool {
+    match (&key.kind, &key.tag) {
+        (NodeKind::Scalar(value, TScalarStyle::Plain), None) => value == "<<",
+        (NodeKind::Scalar(..), Some(tag)) => tag.handle == CORE_TAG_HANDLE && tag.suffix == "merge",
+        _ => false,
+    }
+}
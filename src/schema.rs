@@ -0,0 +1,569 @@
+//! Scalar resolution schemas
+//!
+//! This module resolves raw scalars from the loader according to js-yaml's FAILSAFE, JSON,
+//! CORE and DEFAULT schemas.
+
+use yaml_rust2::parser::Tag;
+use yaml_rust2::scanner::TScalarStyle;
+use yaml_rust2::yaml::Hash;
+use yaml_rust2::Yaml;
+
+use crate::loader::{error_at, Node, NodeKind};
+
+/// A value that could not be resolved while loading a document leniently
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Diagnostic {
+    /// What went wrong, ending with the position (`... at line 2, column 6`)
+    pub message: String,
+    /// Location of the value in the document (e.g. `$.ports[0]`)
+    pub path: String,
+}
+
+/// Handle of the standard `!!` tags once resolved by the parser
+pub(crate) const CORE_TAG_HANDLE: &str = "tag:yaml.org,2002:";
+
+/// Schema used to resolve plain scalars
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum Schema {
+    /// Every scalar is a string
+    Failsafe,
+    /// Only JSON-compatible null, boolean and number literals are recognized
+    Json,
+    /// YAML 1.2 core schema
+    Core,
+    /// js-yaml's default schema (core schema plus js-yaml extensions)
+    #[default]
+    Default,
+}
+
+/// YAML version whose plain scalar rules apply with the core and default schemas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum YamlVersion {
+    /// YAML 1.1: `yes`/`no`/`on`/`off`/`y`/`n` are booleans as well, `0b1010` is a binary
+    /// integer and `017` an octal one
+    V1_1,
+    /// YAML 1.2: only `true` and `false` are booleans, and octals are written `0o17`
+    #[default]
+    V1_2,
+}
+
+impl YamlVersion {
+    /// Look up a version by its number (`"1.1"`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "1.1" => Some(YamlVersion::V1_1),
+            "1.2" => Some(YamlVersion::V1_2),
+            _ => None,
+        }
+    }
+}
+
+/// Refinements of the plain scalar rules of the schemas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct ScalarRules {
+    /// YAML version whose booleans and integer forms are recognized
+    pub version: YamlVersion,
+    /// Resolve YAML 1.1 base 60 numbers (`1:30:00`)
+    pub sexagesimal: bool,
+    /// Read every untagged plain scalar as a string, including empty ones, whatever the schema
+    pub stringify: bool,
+    /// Read plain scalars whose meaning depends on the YAML version (`no`, `022`) as strings
+    pub ambiguous_as_string: bool,
+}
+
+impl ScalarRules {
+    /// Resolve a plain scalar with the core schema and these rules
+    fn resolve(self, value: &str) -> Yaml {
+        let legacy = match self.version {
+            YamlVersion::V1_1 => resolve_yaml11_bool(value).or_else(|| resolve_yaml11_int(value)),
+            YamlVersion::V1_2 => None,
+        };
+        legacy
+            .or_else(|| {
+                self.sexagesimal
+                    .then(|| resolve_sexagesimal(value))
+                    .flatten()
+            })
+            .unwrap_or_else(|| resolve_core(value))
+    }
+}
+
+impl Schema {
+    /// Look up a schema by name
+    ///
+    /// Accepts both the short names (`"core"`) and js-yaml's constant names (`"CORE_SCHEMA"`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        match name.strip_suffix("_schema").unwrap_or(&name) {
+            "failsafe" => Some(Schema::Failsafe),
+            "json" => Some(Schema::Json),
+            "core" => Some(Schema::Core),
+            "default" => Some(Schema::Default),
+            _ => None,
+        }
+    }
+
+    /// Resolve a scalar to a YAML value
+    ///
+    /// Explicit `!!str`, `!!int`, `!!float`, `!!bool` and `!!null` tags take precedence over
+    /// the schema. Quoted and block scalars are always strings.
+    pub fn resolve_scalar(
+        self,
+        value: &str,
+        style: TScalarStyle,
+        tag: Option<&Tag>,
+        rules: ScalarRules,
+    ) -> Result<Yaml, String> {
+        if let Some(tag) = tag {
+            if tag.handle == CORE_TAG_HANDLE {
+                return resolve_tagged(value, &tag.suffix, rules);
+            }
+            return Ok(Yaml::String(value.to_string()));
+        }
+        if style != TScalarStyle::Plain || rules.stringify {
+            return Ok(Yaml::String(value.to_string()));
+        }
+        // Empty nodes are null regardless of the schema
+        if value.is_empty() {
+            return Ok(Yaml::Null);
+        }
+        Ok(match self {
+            Schema::Failsafe => Yaml::String(value.to_string()),
+            Schema::Json => resolve_json(value),
+            Schema::Core | Schema::Default
+                if rules.ambiguous_as_string && yaml11_kind(value).is_some() =>
+            {
+                Yaml::String(value.to_string())
+            }
+            Schema::Core | Schema::Default => rules.resolve(value),
+        })
+    }
+
+    /// Describe a non-fatal issue with a scalar, if any
+    ///
+    /// Reports tags the schema does not know (the scalar is then read as a string) and plain
+    /// scalars whose meaning changed since YAML 1.1: leading-zero octals, base 60 numbers
+    /// (`1:30:00`), and `yes`/`no`/`on`/`off` booleans and `0b` binary integers unless read
+    /// with YAML 1.1 rules. With `ambiguous_as_string`, each of these forms is reported as read
+    /// as a string.
+    pub fn scalar_warning(
+        self,
+        value: &str,
+        style: TScalarStyle,
+        tag: Option<&Tag>,
+        rules: ScalarRules,
+    ) -> Option<String> {
+        if let Some(tag) = tag {
+            let known = tag.handle == CORE_TAG_HANDLE
+                && matches!(
+                    tag.suffix.as_str(),
+                    "null" | "bool" | "int" | "float" | "str"
+                );
+            return if known {
+                None
+            } else {
+                Some(format!("unknown tag {}, read as a string", tag_name(tag)))
+            };
+        }
+        if style != TScalarStyle::Plain
+            || rules.stringify
+            || !matches!(self, Schema::Core | Schema::Default)
+        {
+            return None;
+        }
+        if rules.ambiguous_as_string {
+            return yaml11_kind(value).map(|kind| {
+                format!(
+                    "'{}' is a YAML 1.1 {}, read as a string (quote it to keep it a string)",
+                    value, kind
+                )
+            });
+        }
+        if rules.version == YamlVersion::V1_2 && is_yaml11_bool(value) {
+            return Some(format!(
+                "'{}' is a YAML 1.1 boolean, read as a string in YAML 1.2",
+                value
+            ));
+        }
+        let digits = value
+            .strip_prefix('-')
+            .or_else(|| value.strip_prefix('+'))
+            .unwrap_or(value);
+        if digits.len() > 1 && digits.starts_with('0') && is_radix_digits(digits, 8) {
+            let octal = digits.trim_start_matches('0');
+            return Some(match (rules.version, resolve_yaml11_int(value)) {
+                (YamlVersion::V1_1, Some(Yaml::Integer(i))) => format!(
+                    "'{}' is a legacy YAML 1.1 octal, read as {} (YAML 1.2 reads a decimal; use 0o{})",
+                    value, i, octal
+                ),
+                _ => format!(
+                    "'{}' is a YAML 1.1 octal, read as a decimal in YAML 1.2 (use 0o{})",
+                    value, octal
+                ),
+            });
+        }
+        let binary = digits.strip_prefix("0b");
+        if rules.version == YamlVersion::V1_2 && binary.is_some_and(|b| is_radix_digits(b, 2)) {
+            return Some(format!(
+                "'{}' is a YAML 1.1 binary integer, read as a string in YAML 1.2",
+                value
+            ));
+        }
+        let number = match resolve_sexagesimal(value)? {
+            Yaml::Integer(n) => n.to_string(),
+            Yaml::Real(n) => n,
+            _ => return None,
+        };
+        Some(if rules.sexagesimal {
+            format!(
+                "'{}' is a YAML 1.1 base 60 number, read as {} (YAML 1.2 reads a string)",
+                value, number
+            )
+        } else {
+            format!(
+                "'{}' is a YAML 1.1 base 60 number, read as a string in YAML 1.2",
+                value
+            )
+        })
+    }
+
+    /// Resolve a node tree to a YAML value, expanding aliases
+    pub fn resolve_node(self, node: &Node, rules: ScalarRules) -> Result<Yaml, String> {
+        match &node.kind {
+            NodeKind::Scalar(value, style) => self
+                .resolve_scalar(value, *style, node.tag.as_ref(), rules)
+                .map_err(|e| error_at(&e, &node.mark)),
+            NodeKind::Sequence(items) => items
+                .iter()
+                .map(|item| self.resolve_node(item, rules))
+                .collect::<Result<_, _>>()
+                .map(Yaml::Array),
+            NodeKind::Mapping(entries) => {
+                let mut hash = Hash::new();
+                for (key, value) in entries {
+                    if hash
+                        .insert(
+                            self.resolve_node(key, rules)?,
+                            self.resolve_node(value, rules)?,
+                        )
+                        .is_some()
+                    {
+                        return Err(error_at("duplicated key in mapping", &key.mark));
+                    }
+                }
+                Ok(Yaml::Hash(hash))
+            }
+            NodeKind::Alias(target) => self.resolve_node(target, rules),
+        }
+    }
+}
+
+/// Resolve a scalar carrying an explicit `!!` tag
+fn resolve_tagged(value: &str, suffix: &str, rules: ScalarRules) -> Result<Yaml, String> {
+    let resolved = match suffix {
+        "null" => match rules.resolve(value) {
+            Yaml::Null => Some(Yaml::Null),
+            _ => None,
+        },
+        "bool" => match rules.resolve(value) {
+            b @ Yaml::Boolean(_) => Some(b),
+            _ => None,
+        },
+        "int" => match rules.resolve(value) {
+            i @ Yaml::Integer(_) => Some(i),
+            _ => None,
+        },
+        "float" => match rules.resolve(value) {
+            Yaml::Integer(i) => Some(Yaml::Real(i.to_string())),
+            r @ Yaml::Real(_) => Some(r),
+            _ => None,
+        },
+        _ => Some(Yaml::String(value.to_string())),
+    };
+    resolved.ok_or_else(|| format!("cannot resolve '{}' as !!{}", value, suffix))
+}
+
+/// Check for a `!!set` tag
+pub(crate) fn is_set_tag(tag: Option<&Tag>) -> bool {
+    tag.is_some_and(|tag| tag.handle == CORE_TAG_HANDLE && tag.suffix == "set")
+}
+
+/// Check for a `!!timestamp` tag
+pub(crate) fn is_timestamp_tag(tag: Option<&Tag>) -> bool {
+    tag.is_some_and(|tag| tag.handle == CORE_TAG_HANDLE && tag.suffix == "timestamp")
+}
+
+/// Check for a `!!binary` tag
+pub(crate) fn is_binary_tag(tag: Option<&Tag>) -> bool {
+    tag.is_some_and(|tag| tag.handle == CORE_TAG_HANDLE && tag.suffix == "binary")
+}
+
+/// Decode the base64 text of a `!!binary` scalar, ignoring line breaks and spaces
+pub(crate) fn decode_base64(value: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("cannot resolve '{}' as !!binary", value);
+    let mut result = Vec::with_capacity(value.len() * 3 / 4);
+    let (mut bits, mut bit_count, mut padding) = (0u32, 0, 0);
+    for c in value.chars().filter(|c| !c.is_ascii_whitespace()) {
+        let digit = match c {
+            'A'..='Z' => c as u32 - 'A' as u32,
+            'a'..='z' => c as u32 - 'a' as u32 + 26,
+            '0'..='9' => c as u32 - '0' as u32 + 52,
+            '+' => 62,
+            '/' => 63,
+            '=' => {
+                padding += 1;
+                continue;
+            }
+            _ => return Err(invalid()),
+        };
+        if padding > 0 {
+            return Err(invalid());
+        }
+        bits = (bits << 6) | digit;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            result.push((bits >> bit_count) as u8);
+            bits &= (1 << bit_count) - 1;
+        }
+    }
+    // Leftover bits must be the zero fill of a partial group, padded to 4 characters
+    if bits != 0 || bit_count == 6 || (padding > 0 && bit_count / 2 != padding) {
+        return Err(invalid());
+    }
+    Ok(result)
+}
+
+/// Describe a tag on a sequence (`expected` is `seq`) or mapping (`map`) that is not known
+pub(crate) fn collection_warning(tag: Option<&Tag>, expected: &str) -> Option<String> {
+    let tag = tag?;
+    if tag.handle == CORE_TAG_HANDLE && tag.suffix == expected {
+        return None;
+    }
+    if expected == "map" && is_set_tag(Some(tag)) {
+        return None;
+    }
+    Some(format!("unknown tag {}, ignored", tag_name(tag)))
+}
+
+/// Format a tag the way it is written in YAML
+pub(crate) fn tag_name(tag: &Tag) -> String {
+    match tag.handle.as_str() {
+        CORE_TAG_HANDLE => format!("!!{}", tag.suffix),
+        "!" => format!("!{}", tag.suffix),
+        handle => format!("!<{}{}>", handle, tag.suffix),
+    }
+}
+
+/// Check the YAML 1.1 boolean spellings that YAML 1.2 reads as strings
+fn is_yaml11_bool(value: &str) -> bool {
+    matches!(
+        value,
+        "y" | "Y"
+            | "yes"
+            | "Yes"
+            | "YES"
+            | "n"
+            | "N"
+            | "no"
+            | "No"
+            | "NO"
+            | "on"
+            | "On"
+            | "ON"
+            | "off"
+            | "Off"
+            | "OFF"
+    )
+}
+
+/// Name the YAML 1.1 type of a plain scalar YAML 1.2 reads differently, if any
+fn yaml11_kind(value: &str) -> Option<&'static str> {
+    let digits = value
+        .strip_prefix('-')
+        .or_else(|| value.strip_prefix('+'))
+        .unwrap_or(value);
+    if is_yaml11_bool(value) {
+        Some("boolean")
+    } else if digits.len() > 1 && digits.starts_with('0') && is_radix_digits(digits, 8) {
+        Some("octal")
+    } else if digits
+        .strip_prefix("0b")
+        .is_some_and(|b| is_radix_digits(b, 2))
+    {
+        Some("binary integer")
+    } else if resolve_sexagesimal(value).is_some() {
+        Some("base 60 number")
+    } else {
+        None
+    }
+}
+
+/// Resolve the YAML 1.1 boolean spellings that YAML 1.2 reads as strings
+fn resolve_yaml11_bool(value: &str) -> Option<Yaml> {
+    is_yaml11_bool(value).then(|| {
+        Yaml::Boolean(matches!(
+            value.to_ascii_lowercase().as_str(),
+            "y" | "yes" | "on"
+        ))
+    })
+}
+
+/// Resolve the YAML 1.1 integer forms YAML 1.2 does not have: `0b1010` and octal `017`
+fn resolve_yaml11_int(value: &str) -> Option<Yaml> {
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (digits, radix) = match unsigned.strip_prefix("0b") {
+        Some(binary) => (binary, 2),
+        None if unsigned.len() > 1 && unsigned.starts_with('0') => (&unsigned[1..], 8),
+        None => return None,
+    };
+    if !is_radix_digits(digits, radix) {
+        return None;
+    }
+    let magnitude = i64::from_str_radix(digits, radix).ok()?;
+    Some(Yaml::Integer(if negative { -magnitude } else { magnitude }))
+}
+
+/// Resolve a YAML 1.1 base 60 number: `190:20:30` or `190:20:30.15`
+fn resolve_sexagesimal(value: &str) -> Option<Yaml> {
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (whole, fraction) = match unsigned.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let (first, rest) = whole.split_once(':')?;
+    // Integers cannot start with a zero, which would make them octal
+    if !is_radix_digits(first, 10) || (fraction.is_none() && first.starts_with('0')) {
+        return None;
+    }
+    let mut total = first.parse::<i64>().ok()?;
+    for part in rest.split(':') {
+        if part.len() > 2 || !is_radix_digits(part, 10) {
+            return None;
+        }
+        let part = part.parse::<i64>().ok().filter(|part| *part < 60)?;
+        total = total.checked_mul(60)?.checked_add(part)?;
+    }
+    let total = if negative { -total } else { total };
+    match fraction {
+        None => Some(Yaml::Integer(total)),
+        Some(fraction) if fraction.is_empty() || is_radix_digits(fraction, 10) => {
+            let fraction = format!("0.{}", fraction).parse::<f64>().ok()?;
+            let magnitude = total.unsigned_abs() as f64 + fraction;
+            Some(Yaml::Real(format!(
+                "{:?}",
+                if negative { -magnitude } else { magnitude }
+            )))
+        }
+        Some(_) => None,
+    }
+}
+
+/// Resolve a plain scalar with the JSON schema
+fn resolve_json(value: &str) -> Yaml {
+    match value {
+        "null" => Yaml::Null,
+        "true" => Yaml::Boolean(true),
+        "false" => Yaml::Boolean(false),
+        _ => {
+            let digits = value.strip_prefix('-').unwrap_or(value);
+            let int_part_len = digits.bytes().take_while(u8::is_ascii_digit).count();
+            let int_part = &digits[..int_part_len];
+            if int_part.is_empty() || (int_part.len() > 1 && int_part.starts_with('0')) {
+                return Yaml::String(value.to_string());
+            }
+            let rest = &digits[int_part_len..];
+            if rest.is_empty() {
+                return match value.parse::<i64>() {
+                    Ok(i) => Yaml::Integer(i),
+                    Err(_) => Yaml::Real(value.to_string()),
+                };
+            }
+            let rest = match rest.strip_prefix('.') {
+                Some(fraction) => fraction.trim_start_matches(|c: char| c.is_ascii_digit()),
+                None => rest,
+            };
+            if rest.is_empty() || is_exponent(rest) {
+                Yaml::Real(value.to_string())
+            } else {
+                Yaml::String(value.to_string())
+            }
+        }
+    }
+}
+
+/// Resolve a plain scalar with the YAML 1.2 core schema
+fn resolve_core(value: &str) -> Yaml {
+    match value {
+        "" | "~" | "null" | "Null" | "NULL" => return Yaml::Null,
+        "true" | "True" | "TRUE" => return Yaml::Boolean(true),
+        "false" | "False" | "FALSE" => return Yaml::Boolean(false),
+        ".nan" | ".NaN" | ".NAN" => return Yaml::Real(value.to_string()),
+        _ => {}
+    }
+
+    if let Some(hex) = value.strip_prefix("0x") {
+        return match i64::from_str_radix(hex, 16) {
+            Ok(i) if is_radix_digits(hex, 16) => Yaml::Integer(i),
+            _ => Yaml::String(value.to_string()),
+        };
+    }
+    if let Some(octal) = value.strip_prefix("0o") {
+        return match i64::from_str_radix(octal, 8) {
+            Ok(i) if is_radix_digits(octal, 8) => Yaml::Integer(i),
+            _ => Yaml::String(value.to_string()),
+        };
+    }
+
+    let unsigned = value
+        .strip_prefix('-')
+        .or_else(|| value.strip_prefix('+'))
+        .unwrap_or(value);
+    if matches!(unsigned, ".inf" | ".Inf" | ".INF") {
+        return Yaml::Real(value.to_string());
+    }
+    if !unsigned.is_empty() && is_radix_digits(unsigned, 10) {
+        return match value.parse::<i64>() {
+            Ok(i) => Yaml::Integer(i),
+            Err(_) => Yaml::Real(value.to_string()),
+        };
+    }
+    if is_core_float(unsigned) {
+        return Yaml::Real(value.to_string());
+    }
+    Yaml::String(value.to_string())
+}
+
+/// Check `(\.[0-9]+|[0-9]+(\.[0-9]*)?)([eE][-+]?[0-9]+)?`
+fn is_core_float(value: &str) -> bool {
+    let int_part_len = value.bytes().take_while(u8::is_ascii_digit).count();
+    let rest = &value[int_part_len..];
+    let Some(fraction) = rest.strip_prefix('.') else {
+        return int_part_len > 0 && is_exponent(rest);
+    };
+    let fraction_len = fraction.bytes().take_while(u8::is_ascii_digit).count();
+    if int_part_len == 0 && fraction_len == 0 {
+        return false;
+    }
+    let rest = &fraction[fraction_len..];
+    rest.is_empty() || is_exponent(rest)
+}
+
+/// Check `[eE][-+]?[0-9]+`
+fn is_exponent(value: &str) -> bool {
+    let Some(exponent) = value.strip_prefix(['e', 'E']) else {
+        return false;
+    };
+    let digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+    !digits.is_empty() && is_radix_digits(digits, 10)
+}
+
+fn is_radix_digits(value: &str, radix: u32) -> bool {
+    !value.is_empty() && value.chars().all(|c| c.is_digit(radix))
+}
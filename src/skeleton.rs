@@ -0,0 +1,379 @@
+//! Configuration skeletons
+//!
+//! This module writes a starter YAML document from a JSON Schema, to scaffold new
+//! configuration files: required properties with their default or example value, and optional
+//! properties commented out, each under its description.
+
+use wasm_bindgen::prelude::*;
+use yaml_rust2::Yaml;
+
+use crate::dump::{emit, js_value_to_yaml};
+use crate::options::{check_option_keys, get_bool, get_limit, get_string, KeyStyle};
+
+/// Default `maxDepth`: nesting level past which objects are written as `{}`
+const MAX_DEPTH: usize = 16;
+
+/// Longest chain of `$ref`s followed before giving up
+const MAX_REFS: usize = 32;
+
+/// How optional properties are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Optional {
+    /// Commented out, so uncommenting a line enables the property
+    Comment,
+    /// Written like required properties
+    Include,
+    /// Left out
+    Omit,
+}
+
+/// What a schema contributes to the skeleton
+enum Sample<'a> {
+    /// A value written as is: a default, an example or a placeholder
+    Value(Yaml),
+    /// An object, described by the schema and its `allOf` members
+    Object(Vec<&'a Yaml>),
+    /// An array of `count` items of the given schema
+    Items(&'a Yaml, usize),
+}
+
+/// A line of the skeleton
+struct Line {
+    indent: usize,
+    text: String,
+    /// Column of the `#` when the line is commented out
+    comment: Option<usize>,
+}
+
+/// Writes the lines of a skeleton
+struct SkeletonWriter<'a> {
+    root: &'a Yaml,
+    optional: Optional,
+    descriptions: bool,
+    max_depth: usize,
+    lines: Vec<Line>,
+    // Column of the `#` while an optional property is being commented out
+    comment: Option<usize>,
+}
+
+impl<'a> SkeletonWriter<'a> {
+    fn push(&mut self, indent: usize, text: String) {
+        self.lines.push(Line {
+            indent,
+            text,
+            comment: self.comment,
+        });
+    }
+
+    /// Follow local `$ref`s (`#/definitions/name`)
+    fn resolve(&self, mut schema: &'a Yaml) -> &'a Yaml {
+        for _ in 0..MAX_REFS {
+            match schema["$ref"]
+                .as_str()
+                .and_then(|r| resolve_pointer(self.root, r))
+            {
+                Some(target) => schema = target,
+                None => break,
+            }
+        }
+        schema
+    }
+
+    /// Pick the value or structure a schema is written as
+    fn sample(&self, schema: &'a Yaml, depth: usize) -> Sample<'a> {
+        let schema = self.resolve(schema);
+        for key in ["default", "const", "example"] {
+            if !schema[key].is_badvalue() {
+                return Sample::Value(schema[key].clone());
+            }
+        }
+        for key in ["examples", "enum"] {
+            if let Some(first) = schema[key].as_vec().and_then(|values| values.first()) {
+                return Sample::Value(first.clone());
+            }
+        }
+
+        let members = self.members(schema);
+        let kind = type_name(schema).or_else(|| {
+            if members
+                .iter()
+                .any(|member| member["properties"].as_hash().is_some())
+            {
+                Some("object")
+            } else if !schema["items"].is_badvalue() {
+                Some("array")
+            } else {
+                None
+            }
+        });
+        if kind.is_none() {
+            // Without a type of its own, the first alternative describes the value
+            for key in ["anyOf", "oneOf"] {
+                if let Some(first) = schema[key].as_vec().and_then(|alts| alts.first()) {
+                    if depth < self.max_depth {
+                        return self.sample(first, depth + 1);
+                    }
+                }
+            }
+        }
+        Sample::Value(match kind {
+            Some("object") if depth < self.max_depth => return Sample::Object(members),
+            Some("object") => Yaml::Hash(Default::default()),
+            Some("array") => {
+                let count = schema["minItems"].as_i64().unwrap_or(0).max(0) as usize;
+                if count > 0 && schema["items"].as_hash().is_some() {
+                    return Sample::Items(&schema["items"], count);
+                }
+                Yaml::Array(Vec::new())
+            }
+            Some("string") => Yaml::String(String::new()),
+            Some("integer" | "number") => match &schema["minimum"] {
+                minimum @ (Yaml::Integer(_) | Yaml::Real(_)) => minimum.clone(),
+                _ => Yaml::Integer(0),
+            },
+            Some("boolean") => Yaml::Boolean(false),
+            _ => Yaml::Null,
+        })
+    }
+
+    /// The schema and the members of its `allOf`, whose properties are merged
+    fn members(&self, schema: &'a Yaml) -> Vec<&'a Yaml> {
+        let mut members = vec![schema];
+        if let Some(all_of) = schema["allOf"].as_vec() {
+            members.extend(all_of.iter().map(|member| self.resolve(member)));
+        }
+        members
+    }
+
+    /// Write the properties of an object, returning whether any line was written
+    fn write_object(&mut self, members: &[&'a Yaml], indent: usize, depth: usize) -> bool {
+        let required: Vec<&str> = members
+            .iter()
+            .filter_map(|member| member["required"].as_vec())
+            .flatten()
+            .filter_map(Yaml::as_str)
+            .collect();
+        let mut properties: Vec<(&str, &'a Yaml)> = Vec::new();
+        for member in members {
+            for (key, property) in member["properties"].as_hash().into_iter().flatten() {
+                match key.as_str() {
+                    Some(key) if !properties.iter().any(|(seen, _)| *seen == key) => {
+                        properties.push((key, property));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let start = self.lines.len();
+        for (key, property) in properties {
+            let is_required = required.contains(&key);
+            if !is_required && self.optional == Optional::Omit {
+                continue;
+            }
+            let outer = self.comment;
+            // Descriptions inside a commented-out property would be commented twice
+            if self.descriptions && self.comment.is_none() {
+                let property = self.resolve(property);
+                let description = property["description"]
+                    .as_str()
+                    .or_else(|| property["title"].as_str());
+                for line in description.into_iter().flat_map(str::lines) {
+                    self.push(indent, format!("# {}", line).trim_end().to_string());
+                }
+            }
+            if !is_required && self.optional == Optional::Comment && self.comment.is_none() {
+                self.comment = Some(indent);
+            }
+            self.write_entry(key, property, indent, depth);
+            self.comment = outer;
+        }
+        self.lines.len() > start
+    }
+
+    /// Write `key: value`, or `key:` followed by the nested lines
+    fn write_entry(&mut self, key: &str, schema: &'a Yaml, indent: usize, depth: usize) {
+        let head = format!("{}:", inline(&Yaml::String(key.to_string())));
+        match self.sample(schema, depth) {
+            Sample::Value(value) => self.write_value(head, &value, indent),
+            Sample::Object(members) => {
+                self.push(indent, head);
+                if !self.write_object(&members, indent + 2, depth + 1) {
+                    self.lines.last_mut().unwrap().text.push_str(" {}");
+                }
+            }
+            Sample::Items(items, count) => {
+                self.push(indent, head);
+                for _ in 0..count {
+                    self.write_item(items, indent + 2, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// Write a sequence item (`- value`)
+    fn write_item(&mut self, schema: &'a Yaml, indent: usize, depth: usize) {
+        match self.sample(schema, depth) {
+            Sample::Value(value) => self.write_value("-".to_string(), &value, indent),
+            Sample::Object(members) => {
+                let start = self.lines.len();
+                if !self.write_object(&members, indent + 2, depth + 1) {
+                    self.push(indent, "- {}".to_string());
+                    return;
+                }
+                // The first property goes on the `- ` line unless it is commented out deeper
+                let first = &mut self.lines[start];
+                if first.comment.is_none_or(|column| column <= indent) {
+                    first.indent = indent;
+                    first.text.insert_str(0, "- ");
+                } else {
+                    let comment = self.comment;
+                    self.lines.insert(
+                        start,
+                        Line {
+                            indent,
+                            text: "-".to_string(),
+                            comment,
+                        },
+                    );
+                }
+            }
+            Sample::Items(items, count) => {
+                self.push(indent, "-".to_string());
+                for _ in 0..count {
+                    self.write_item(items, indent + 2, depth + 1);
+                }
+            }
+        }
+    }
+
+    /// Write a value after `head` (`key:` or `-`), on the same line if it fits on one
+    fn write_value(&mut self, head: String, value: &Yaml, indent: usize) {
+        let text = inline(value);
+        if !text.contains('\n') {
+            self.push(indent, format!("{} {}", head, text));
+            return;
+        }
+        self.push(indent, head);
+        for line in text.lines() {
+            self.push(indent + 2, line.to_string());
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut output = String::new();
+        for line in &self.lines {
+            match line.comment {
+                Some(column) => {
+                    output.push_str(&" ".repeat(column));
+                    output.push_str("# ");
+                    output.push_str(&" ".repeat(line.indent - column));
+                }
+                None => output.push_str(&" ".repeat(line.indent)),
+            }
+            output.push_str(&line.text);
+            output.push('\n');
+        }
+        output
+    }
+}
+
+/// Emit a value as YAML text without the document marker or final newline
+fn inline(value: &Yaml) -> String {
+    let text = emit(value, "null").unwrap_or_default();
+    let text = text.strip_prefix("---").unwrap_or(&text);
+    text.strip_prefix(['\n', ' '])
+        .unwrap_or(text)
+        .trim_end_matches('\n')
+        .to_string()
+}
+
+/// The first type a schema names, preferring any other type to `null`
+fn type_name(schema: &Yaml) -> Option<&str> {
+    match &schema["type"] {
+        Yaml::String(name) => Some(name),
+        Yaml::Array(names) => {
+            let names: Vec<&str> = names.iter().filter_map(Yaml::as_str).collect();
+            names
+                .iter()
+                .find(|name| **name != "null")
+                .or(names.first())
+                .copied()
+        }
+        _ => None,
+    }
+}
+
+/// Resolve a local JSON pointer reference (`#` or `#/definitions/name`)
+fn resolve_pointer<'a>(root: &'a Yaml, reference: &str) -> Option<&'a Yaml> {
+    let pointer = reference.strip_prefix('#')?;
+    if pointer.is_empty() {
+        return Some(root);
+    }
+    let mut node = root;
+    for token in pointer.strip_prefix('/')?.split('/') {
+        let token = token.replace("~1", "/").replace("~0", "~");
+        node = match node {
+            Yaml::Hash(hash) => hash.get(&Yaml::String(token))?,
+            Yaml::Array(items) => items.get(token.parse::<usize>().ok()?)?,
+            _ => return None,
+        };
+    }
+    Some(node)
+}
+
+/// Generate a starter YAML document from a JSON Schema
+///
+/// Required properties are written with their `default`, `const`, `example`, first `examples`
+/// or first `enum` value, or else a placeholder for their type (`''`, `0` or the `minimum`,
+/// `false`, `[]`, or their own properties for objects). Optional properties are commented out
+/// (`optional: 'comment'`, default), written like required ones (`'include'`) or left out
+/// (`'omit'`). Each property is preceded by its `description` (or `title`) as a comment unless
+/// `descriptions` is false. Local `$ref`s are followed, `allOf` members are merged, the first
+/// `anyOf`/`oneOf` alternative is used for untyped schemas, and arrays get `minItems` items.
+/// Objects nested deeper than `maxDepth` (default 16) are written as `{}`.
+///
+/// @param {Object} schema - The JSON Schema describing the document
+/// @param {Object} options - `optional`, `descriptions` and `maxDepth`
+/// @returns {string} - The YAML text, ending with a newline
+#[wasm_bindgen]
+pub fn skeleton(schema: &JsValue, options: JsValue) -> Result<String, JsValue> {
+    let (mut optional, mut descriptions, mut max_depth) = (Optional::Comment, true, MAX_DEPTH);
+    if !options.is_undefined() && !options.is_null() {
+        check_option_keys(&options, &[&["optional", "descriptions", "maxDepth"]])?;
+        optional = match get_string(&options, "optional")?.as_deref() {
+            None | Some("comment") => Optional::Comment,
+            Some("include") => Optional::Include,
+            Some("omit") => Optional::Omit,
+            Some(policy) => {
+                return Err(JsValue::from_str(&format!("Unknown optional: {}", policy)));
+            }
+        };
+        descriptions = get_bool(&options, "descriptions")?.unwrap_or(true);
+        max_depth = get_limit(&options, "maxDepth")?.unwrap_or(MAX_DEPTH);
+    }
+    let root = js_value_to_yaml(schema, &KeyStyle::Keep)?;
+
+    let mut writer = SkeletonWriter {
+        root: &root,
+        optional,
+        descriptions,
+        max_depth,
+        lines: Vec::new(),
+        comment: None,
+    };
+    match writer.sample(&root, 0) {
+        Sample::Value(value) => return Ok(format!("{}\n", inline(&value))),
+        Sample::Object(members) => {
+            if !writer.write_object(&members, 0, 1) {
+                return Ok("{}\n".to_string());
+            }
+        }
+        Sample::Items(items, count) => {
+            for _ in 0..count {
+                writer.write_item(items, 0, 1);
+            }
+        }
+    }
+    Ok(writer.finish())
+}
@@ -0,0 +1,486 @@
+//! Document splitting
+//!
+//! This module finds the documents of a YAML stream without parsing them. A light scanner
+//! follows each line only far enough to know whether a quoted or block scalar, or a flow
+//! collection, continues past it, so an indented `---` or `...` line inside a scalar is not taken
+//! for a document marker, while one at the start of a line ends the scalar as it does for the
+//! parser, and a stream is counted, split or indexed at about the speed of reading it,
+//! or one of its documents parsed without parsing the others.
+
+use std::mem;
+use std::ops::Range;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use yaml_rust2::scanner::Marker;
+
+use crate::instrument::start_call;
+use crate::loader::{documents, error_at};
+use crate::options::LoadOptions;
+use crate::parse::{node_to_js_value, timed_call};
+
+/// Where a marker line ends a document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Boundary {
+    /// A `---` marker following content, which starts the next document
+    Before,
+    /// A `...` marker, which ends the document before it along with itself
+    After,
+}
+
+/// A block scalar whose lines are being read
+struct Block {
+    // Indentation of the node holding the scalar, -1 at the root
+    parent: isize,
+    // Indentation of the scalar's content, once given or read from its first line with text
+    indent: Option<usize>,
+}
+
+/// Follows the lines of a stream to tell where its documents end
+#[derive(Default)]
+pub(crate) struct Splitter {
+    // Whether the lines since the last boundary hold more than directives, comments and blank
+    // lines
+    pub(crate) has_content: bool,
+    // Whether the root node of the document has started
+    has_root: bool,
+    // The quote of a quoted scalar continuing on the next line
+    quote: Option<u8>,
+    // The block scalar continuing on the next line
+    block: Option<Block>,
+    // Indentation of the node holding a plain scalar that may continue on the next line
+    plain: Option<isize>,
+    // Depth of the flow collections open at the end of the line
+    flow: usize,
+}
+
+impl Splitter {
+    /// Read the next line, with its line break; returns the boundary it marks, if any
+    pub(crate) fn line(&mut self, line: &[u8]) -> Option<Boundary> {
+        // A marker line ends any scalar or flow collection left open before it
+        if is_marker(line, "...") {
+            *self = Splitter::default();
+            return Some(Boundary::After);
+        }
+        if is_marker(line, "---") {
+            let boundary = self.has_content.then_some(Boundary::Before);
+            *self = Splitter {
+                has_content: true,
+                has_root: holds_node(&line[3..]),
+                ..Splitter::default()
+            };
+            self.tokens(line, 3, -1, false);
+            return boundary;
+        }
+        if self.in_block(line) {
+            return None;
+        }
+        let indent = indentation(line);
+        if let Some(quote) = self.quote {
+            let end = close_quote(line, 0, quote)?;
+            self.quote = None;
+            self.tokens(line, end, indent as isize - 1, false);
+            return None;
+        }
+        let plain = self.plain.take();
+        match line.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            // Blank lines may sit inside a plain scalar, and comment lines end it
+            None => self.plain = plain,
+            Some(at) if line[at] == b'#' => {}
+            Some(at) if plain.is_some_and(|parent| indent as isize > parent) => {
+                self.tokens(line, at, plain.unwrap_or(-1), true);
+            }
+            Some(at) if line[at] == b'%' => {}
+            // The root node is held at any indentation
+            Some(at) => {
+                let parent = if self.has_root {
+                    indent as isize - 1
+                } else {
+                    -1
+                };
+                self.has_content = true;
+                self.has_root |= holds_node(&line[at..]);
+                self.tokens(line, at, parent, false);
+            }
+        }
+        None
+    }
+
+    /// Whether a line belongs to the block scalar being read, which ends at the first line
+    /// with text indented less than its content
+    fn in_block(&mut self, line: &[u8]) -> bool {
+        let Some(block) = &mut self.block else {
+            return false;
+        };
+        let indent = indentation(line);
+        if line[indent..].iter().all(u8::is_ascii_whitespace) {
+            return true;
+        }
+        let inside = match block.indent {
+            Some(content) => indent >= content,
+            None if indent as isize > block.parent => {
+                block.indent = Some(indent);
+                true
+            }
+            None => false,
+        };
+        if !inside {
+            self.block = None;
+        }
+        inside
+    }
+
+    /// Read the tokens of a line from `at`, noting a scalar or flow collection that continues
+    /// on the next line; `parent` is the indentation of the node holding the first token, and
+    /// `continued` tells that the line goes on with the plain scalar of the line before
+    fn tokens(&mut self, line: &[u8], mut at: usize, mut parent: isize, continued: bool) {
+        // Column where the last scalar started, the key of a `:` following it
+        let mut key = None;
+        if continued {
+            at = self.plain(line, at, parent);
+        }
+        while let Some(&byte) = line.get(at) {
+            let ends_indicator = line
+                .get(at + 1)
+                .is_none_or(|&next| next.is_ascii_whitespace() || self.is_flow(next));
+            match byte {
+                b' ' | b'\t' | b'\r' | b'\n' => at += 1,
+                b'#' => return,
+                b'"' | b'\'' => {
+                    key = Some(at);
+                    match close_quote(line, at + 1, byte) {
+                        Some(end) => at = end,
+                        None => {
+                            self.quote = Some(byte);
+                            return;
+                        }
+                    }
+                }
+                b'|' | b'>' if self.flow == 0 && block_header(&line[at + 1..]).is_some() => {
+                    let indent = block_header(&line[at + 1..]).flatten();
+                    let indent = indent.map(|indent| parent.max(0) as usize + indent);
+                    self.block = Some(Block { parent, indent });
+                    return;
+                }
+                b'[' | b'{' => {
+                    self.flow += 1;
+                    at += 1;
+                }
+                b']' | b'}' => {
+                    self.flow = self.flow.saturating_sub(1);
+                    at += 1;
+                }
+                b',' if self.flow > 0 => at += 1,
+                // A `:` after a quoted key in a flow collection needs no space after it
+                b':' if self.flow > 0 || ends_indicator => {
+                    parent = key.take().unwrap_or(at) as isize;
+                    at += 1;
+                }
+                b'-' | b'?' if ends_indicator => {
+                    parent = at as isize;
+                    at += 1;
+                }
+                b'&' | b'!' | b'*' => {
+                    at += line[at..]
+                        .iter()
+                        .position(|&byte| byte.is_ascii_whitespace() || self.is_flow(byte))
+                        .unwrap_or(line.len() - at);
+                }
+                _ => {
+                    key = Some(at);
+                    at = self.plain(line, at, parent);
+                }
+            }
+        }
+    }
+
+    /// Read a plain scalar starting at `at`, noting when it may continue on the next line;
+    /// returns where it ends: a `: ` or ` #`, a flow indicator in a flow collection, or the end
+    /// of the line
+    fn plain(&mut self, line: &[u8], mut at: usize, parent: isize) -> usize {
+        at += 1;
+        while let Some(&byte) = line.get(at) {
+            let next = line.get(at + 1);
+            let ends = match byte {
+                b':' => next.is_none_or(|&next| next.is_ascii_whitespace() || self.is_flow(next)),
+                b' ' | b'\t' => next == Some(&b'#'),
+                b'\r' | b'\n' => true,
+                _ => self.is_flow(byte),
+            };
+            if ends {
+                break;
+            }
+            at += 1;
+        }
+        if line[at..].iter().all(u8::is_ascii_whitespace) {
+            // Inside a flow collection, a plain scalar continues on lines of any indentation
+            self.plain = Some(if self.flow > 0 { -1 } else { parent });
+        }
+        at
+    }
+
+    /// Whether a byte ends a token inside a flow collection
+    fn is_flow(&self, byte: u8) -> bool {
+        self.flow > 0 && matches!(byte, b',' | b'[' | b']' | b'{' | b'}')
+    }
+
+    /// The state of the scan as a plain object, for a stream parser checkpoint
+    pub(crate) fn to_js(&self) -> Result<JsValue, JsValue> {
+        let state = Object::new();
+        let set = |key: &str, value: JsValue| Reflect::set(&state, &key.into(), &value);
+        set("hasContent", self.has_content.into())?;
+        set("hasRoot", self.has_root.into())?;
+        set(
+            "quote",
+            self.quote
+                .map_or(JsValue::NULL, |quote| char::from(quote).to_string().into()),
+        )?;
+        let block = match &self.block {
+            Some(block) => {
+                let object = Object::new();
+                Reflect::set(&object, &"parent".into(), &(block.parent as f64).into())?;
+                let indent = block.indent.map_or(JsValue::NULL, |i| (i as f64).into());
+                Reflect::set(&object, &"indent".into(), &indent)?;
+                object.into()
+            }
+            None => JsValue::NULL,
+        };
+        set("block", block)?;
+        set(
+            "plain",
+            self.plain
+                .map_or(JsValue::NULL, |parent| (parent as f64).into()),
+        )?;
+        set("flow", (self.flow as f64).into())?;
+        Ok(state.into())
+    }
+
+    /// Restore the scan from the object `to_js` gave; `None` when it does not hold one
+    pub(crate) fn from_js(state: &JsValue) -> Option<Self> {
+        let quote = match Reflect::get(state, &"quote".into()).ok()? {
+            quote if quote.is_null() => None,
+            quote => match quote.as_string()?.as_str() {
+                "\"" => Some(b'"'),
+                "'" => Some(b'\''),
+                _ => return None,
+            },
+        };
+        let block = Reflect::get(state, &"block".into()).ok()?;
+        let block = if block.is_null() {
+            None
+        } else {
+            Some(Block {
+                parent: integer(&block, "parent")??,
+                indent: integer(&block, "indent")?,
+            })
+        };
+        Some(Splitter {
+            has_content: Reflect::get(state, &"hasContent".into()).ok()?.as_bool()?,
+            has_root: Reflect::get(state, &"hasRoot".into()).ok()?.as_bool()?,
+            quote,
+            block,
+            plain: integer(state, "plain")?,
+            flow: integer(state, "flow")??,
+        })
+    }
+}
+
+/// Read an integer property of a checkpoint: `Some(None)` when it is null, `None` when it is
+/// missing or does not fit `T`
+pub(crate) fn integer<T: TryFrom<i64>>(state: &JsValue, key: &str) -> Option<Option<T>> {
+    if !state.is_object() {
+        return None;
+    }
+    let value = Reflect::get(state, &key.into()).ok()?;
+    if value.is_null() {
+        return Some(None);
+    }
+    let number = value.as_f64()?;
+    if number.fract() != 0.0 || number.abs() > 9_007_199_254_740_991.0 {
+        return None;
+    }
+    T::try_from(number as i64).ok().map(Some)
+}
+
+/// The index past the quote closing a quoted scalar, searched from `at`
+fn close_quote(line: &[u8], mut at: usize, quote: u8) -> Option<usize> {
+    while let Some(&byte) = line.get(at) {
+        match byte {
+            b'\\' if quote == b'"' => at += 2,
+            b'\'' if quote == b'\'' && line.get(at + 1) == Some(&b'\'') => at += 2,
+            _ if byte == quote => return Some(at + 1),
+            _ => at += 1,
+        }
+    }
+    None
+}
+
+/// Read the rest of a block scalar header after its `|` or `>`, giving the indentation
+/// indicator if there is one; `None` when the text is not a header
+fn block_header(rest: &[u8]) -> Option<Option<usize>> {
+    let len = rest
+        .iter()
+        .take(2)
+        .take_while(|byte| matches!(byte, b'+' | b'-' | b'1'..=b'9'))
+        .count();
+    let tail = rest[len..].iter().find(|byte| !byte.is_ascii_whitespace());
+    if !matches!(tail, None | Some(b'#')) {
+        return None;
+    }
+    Some(
+        rest[..len]
+            .iter()
+            .find(|byte| byte.is_ascii_digit())
+            .map(|digit| usize::from(digit - b'0')),
+    )
+}
+
+/// Whether text holds more of a node than its anchor and tag
+fn holds_node(text: &[u8]) -> bool {
+    text.split(u8::is_ascii_whitespace)
+        .filter_map(|token| token.first())
+        .take_while(|&&byte| byte != b'#')
+        .any(|byte| !matches!(byte, b'&' | b'!'))
+}
+
+/// Number of spaces starting a line
+fn indentation(line: &[u8]) -> usize {
+    line.iter().take_while(|&&byte| byte == b' ').count()
+}
+
+/// Whether a line is a `---` or `...` document marker
+pub(crate) fn is_marker(line: &[u8], marker: &str) -> bool {
+    line.strip_prefix(marker.as_bytes())
+        .is_some_and(|rest| matches!(rest.first(), None | Some(b' ' | b'\t' | b'\r' | b'\n')))
+}
+
+/// Byte ranges of the documents of a stream, split before each `---` marker that follows
+/// content and after each `...` marker; parts holding only directives, comments and blank lines
+/// are dropped
+pub(crate) struct Ranges<'a> {
+    input: &'a [u8],
+    splitter: Splitter,
+    // Start of the document being scanned, and of the next line
+    start: usize,
+    offset: usize,
+}
+
+impl<'a> Ranges<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        Ranges {
+            input,
+            splitter: Splitter::default(),
+            start: 0,
+            offset: 0,
+        }
+    }
+}
+
+impl Iterator for Ranges<'_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Range<usize>> {
+        while self.offset < self.input.len() {
+            let end = self.input[self.offset..]
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(self.input.len(), |at| self.offset + at + 1);
+            let has_content = self.splitter.has_content;
+            let boundary = self.splitter.line(&self.input[self.offset..end]);
+            let offset = mem::replace(&mut self.offset, end);
+            match boundary {
+                Some(Boundary::Before) => {
+                    let start = mem::replace(&mut self.start, offset);
+                    return Some(start..offset);
+                }
+                Some(Boundary::After) => {
+                    let start = mem::replace(&mut self.start, end);
+                    if has_content {
+                        return Some(start..end);
+                    }
+                }
+                None => {}
+            }
+        }
+        mem::take(&mut self.splitter.has_content).then_some(self.start..self.input.len())
+    }
+}
+
+/// Find the documents of a YAML stream without parsing them
+///
+/// Returns the `{ start, end }` UTF-8 byte range of each document's source, from its directives
+/// or `---` marker to the next `---` marker or past the `...` marker ending it. Lines are
+/// scanned only far enough to skip markers inside quoted and block scalars, so a stream is
+/// indexed at about the speed of reading it, and any document can then be parsed from its
+/// range on its own; errors inside a document are only found then.
+///
+/// @param {Uint8Array} input - The UTF-8 YAML stream
+/// @returns {Array} - The byte range of each document
+#[wasm_bindgen]
+pub fn index_documents(input: &[u8]) -> Result<Array, JsValue> {
+    let index = Array::new();
+    for range in Ranges::new(input) {
+        let entry = Object::new();
+        Reflect::set(
+            &entry,
+            &"start".into(),
+            &JsValue::from_f64(range.start as f64),
+        )?;
+        Reflect::set(&entry, &"end".into(), &JsValue::from_f64(range.end as f64))?;
+        index.push(&entry);
+    }
+    Ok(index)
+}
+
+/// Count the documents of a YAML stream without parsing them, as `index_documents` finds them
+///
+/// @param {Uint8Array} input - The UTF-8 YAML stream
+/// @returns {number} - The number of documents
+#[wasm_bindgen]
+pub fn count_documents(input: &[u8]) -> u32 {
+    Ranges::new(input).count() as u32
+}
+
+/// Split a YAML stream into the source of each document without parsing them, as
+/// `index_documents` finds them
+///
+/// @param {string} input - The YAML stream
+/// @returns {string[]} - The source of each document
+#[wasm_bindgen]
+pub fn split_documents(input: &str) -> Array {
+    Ranges::new(input.as_bytes())
+        .map(|range| JsValue::from_str(&input[range]))
+        .collect()
+}
+
+/// Parse one document of a YAML stream without parsing the others
+///
+/// The stream is scanned as `index_documents` does up to the document at `index`, counted from
+/// 0, and only that document is parsed, so picking one document out of hundreds costs a single
+/// parse. Errors give lines counted from the start of the stream. When the stream holds no
+/// document at `index`, the value `parse` gives an empty input is returned.
+///
+/// @param {string} input - The YAML stream
+/// @param {number} index - The index of the document
+/// @param {Object} options - Load options
+/// @returns {any} - The document
+#[wasm_bindgen]
+pub fn extract_document(input: &str, index: u32, options: JsValue) -> Result<JsValue, JsValue> {
+    start_call();
+    let options = LoadOptions::from_js(&options)?;
+    timed_call(&options, || {
+        let Some(range) = Ranges::new(input.as_bytes()).nth(index as usize) else {
+            return Ok(options.no_document());
+        };
+        match documents(&input[range.clone()], &options.limits).next() {
+            Some(Ok(doc)) => node_to_js_value(&doc, &options),
+            Some(Err(e)) => {
+                let lines = input[..range.start].matches('\n').count();
+                let mark = e.marker();
+                let mark = Marker::new(mark.index(), mark.line() + lines, mark.col());
+                Err(options.error(&error_at(e.info(), &mark)))
+            }
+            None => Ok(options.no_document()),
+        }
+    })
+}
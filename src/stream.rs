@@ -1,33 +1,610 @@
 //! YAML streaming parser functionality
 //!
-//! This module provides streaming parsing capabilities for large YAML documents.
+//! This module provides streaming parsing capabilities for large YAML documents. A stream
+//! parser receives the input in chunks and keeps only the text of the document being
+//! received, so streams larger than memory can be parsed.
 
-use js_sys::{Function, Object};
+use std::cell::Cell;
+
+use js_sys::{Array, Function, Object, Reflect};
 use wasm_bindgen::prelude::*;
+use yaml_rust2::scanner::{Marker, ScanError};
+
+use crate::dump::{emit_document, js_value_to_yaml};
+use crate::instrument::{timed, Phase};
+use crate::loader::{documents, error_at, Directives, Documents, Node, NodeKind, Part};
+use crate::options::{get_bool, get_number, get_property, KeyStyle, LoadOptions};
+use crate::parse::node_to_js_value;
+use crate::split::{integer, Boundary, Splitter};
+use crate::value::ValueBuilder;
+
+/// Parse the documents of a YAML stream one at a time
+///
+/// `callback` receives a `{ index, value, start, end }` chunk per document as soon as it is
+/// parsed, where `start` and `end` are the UTF-8 byte range of the document's source. With the
+/// `checksum` option, chunks also carry `hash`, a hex FNV-1a 64-bit hash of that source, so
+/// unchanged documents can be skipped without hashing them again. With the `directives` option,
+/// chunks also carry `directives`, the document's `{ version, tags }` from its `%YAML` and
+/// `%TAG` lines, which `dump` accepts to write them back.
+///
+/// With the `sequenceChunk` option, a document holding an untagged sequence at its root is
+/// passed on as it is loaded instead, as `{ index, item, value }` chunks holding up to
+/// `sequenceChunk` of its items from the one at `item` on, so a document made of one long
+/// sequence is not held in memory at once; `maxMemoryBytes` then applies to the items loaded
+/// but not passed on yet.
+///
+/// Returning `false` from `callback` stops parsing there, so a search over a large stream
+/// ends at the first match; the documents passed on until then are counted.
+///
+/// With the `onProgress` option, `{ bytesProcessed, documentsEmitted }` is passed to it each
+/// time the documents passed on reach `progressInterval` more bytes of the stream, 1 MiB by
+/// default, and once more when the stream is parsed to its end.
+///
+/// The whole stream is at hand, so it is parsed before the call returns and the count is
+/// returned as is; the JavaScript wrapper returns a promise for async iterables of chunks,
+/// which it feeds to `create_stream_parser` as they arrive.
+///
+/// @param {string} yaml - The YAML stream to parse
+/// @param {Function} callback - Callback function to receive parsed chunks
+/// @param {Object} options - Load options, `checksum`, `directives`, `sequenceChunk`,
+///   `onProgress` and `progressInterval`
+/// @returns {number} - Number of documents parsed
+#[wasm_bindgen]
+pub fn parse_stream(yaml: &str, callback: &Function, options: JsValue) -> Result<u32, JsValue> {
+    let options = StreamOptions::from_js(&options)?;
+    let load = &options.load;
+
+    let mut docs = documents(yaml, &load.limits);
+    let (count, stopped) = options.pass_documents(&mut docs, yaml, (0, 0), callback, &|e| {
+        load.error(&error_at(e.info(), e.marker()))
+    })?;
+    if !stopped {
+        options.report_end(yaml.len(), count)?;
+    }
+    Ok(count)
+}
+
+/// Options accepted by `parse_stream` and `create_stream_parser`
+struct StreamOptions {
+    load: LoadOptions,
+    checksum: bool,
+    directives: bool,
+    // Number of items of a root sequence passed on at a time
+    sequence_chunk: Option<usize>,
+    progress: Option<Progress>,
+}
+
+/// Reports of the progress through a stream to the `onProgress` option
+struct Progress {
+    callback: Function,
+    // Bytes of the stream between two reports
+    interval: usize,
+    // Bytes processed at the last report
+    reported: Cell<Option<usize>>,
+}
+
+impl Progress {
+    // Bytes between two reports unless `progressInterval` is given
+    const DEFAULT_INTERVAL: usize = 1 << 20;
+
+    fn from_js(options: &JsValue) -> Result<Option<Self>, JsValue> {
+        let Some(callback) = get_property(options, "onProgress")? else {
+            return Ok(None);
+        };
+        if callback.is_null() {
+            return Ok(None);
+        }
+        let callback = callback
+            .dyn_into()
+            .map_err(|_| JsValue::from_str("Option 'onProgress' must be a function"))?;
+        let interval = match get_number(options, "progressInterval")? {
+            None => Self::DEFAULT_INTERVAL,
+            Some(interval) if interval >= 1.0 && interval.fract() == 0.0 => interval as usize,
+            Some(_) => {
+                return Err(JsValue::from_str(
+                    "Option 'progressInterval' must be a positive integer",
+                ))
+            }
+        };
+        Ok(Some(Progress {
+            callback,
+            interval,
+            reported: Cell::new(None),
+        }))
+    }
+
+    /// Pass `{ bytesProcessed, documentsEmitted }` on once `bytes` is `interval` past the last
+    /// report, or at the end of the stream unless it was just reported
+    fn report(&self, bytes: usize, documents: u32, end: bool) -> Result<(), JsValue> {
+        let due = match self.reported.get() {
+            Some(reported) if end => bytes > reported,
+            Some(reported) => bytes >= reported + self.interval,
+            None => end || bytes >= self.interval,
+        };
+        if !due {
+            return Ok(());
+        }
+        self.reported.set(Some(bytes));
+        let info = Object::new();
+        set(&info, "bytesProcessed", JsValue::from_f64(bytes as f64))?;
+        set(&info, "documentsEmitted", JsValue::from(documents))?;
+        self.callback.call1(&JsValue::NULL, &info).map(|_| ())
+    }
+}
+
+impl StreamOptions {
+    fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let (checksum, directives, sequence_chunk) = if options.is_undefined() || options.is_null()
+        {
+            (false, false, None)
+        } else {
+            (
+                get_bool(options, "checksum")?.unwrap_or(false),
+                get_bool(options, "directives")?.unwrap_or(false),
+                get_number(options, "sequenceChunk")?,
+            )
+        };
+        if sequence_chunk.is_some_and(|size| size < 1.0 || size.fract() != 0.0) {
+            return Err(JsValue::from_str(
+                "Option 'sequenceChunk' must be a positive integer",
+            ));
+        }
+        let progress = if options.is_undefined() || options.is_null() {
+            None
+        } else {
+            Progress::from_js(options)?
+        };
+        // `signal` is watched by the JavaScript wrappers of the async entry points
+        let load = LoadOptions::from_js_with(
+            options,
+            &[
+                "checksum",
+                "directives",
+                "sequenceChunk",
+                "signal",
+                "onProgress",
+                "progressInterval",
+            ],
+        )?;
+        load.reject_timing("stream parsers")?;
+        Ok(StreamOptions {
+            load,
+            checksum,
+            directives,
+            sequence_chunk: sequence_chunk.map(|size| size as usize),
+            progress,
+        })
+    }
+
+    /// Report the progress once the stream is parsed to its end, `bytes` long
+    fn report_end(&self, bytes: usize, documents: u32) -> Result<(), JsValue> {
+        match &self.progress {
+            Some(progress) => progress.report(bytes, documents, true),
+            None => Ok(()),
+        }
+    }
+
+    /// Pass the documents of `text` to `callback`, numbered from `index` and placed `offset`
+    /// bytes into the stream, with scan errors reported by `error`; returns how many were
+    /// passed on and whether `callback` stopped parsing by returning `false`
+    fn pass_documents(
+        &self,
+        docs: &mut Documents,
+        text: &str,
+        (index, offset): (u32, usize),
+        callback: &Function,
+        error: &dyn Fn(&ScanError) -> JsValue,
+    ) -> Result<(u32, bool), JsValue> {
+        let mut count = 0;
+        // Items of the document being loaded passed on so far
+        let mut item = 0;
+        loop {
+            let part = match self.sequence_chunk {
+                Some(size) => docs.next_part(size),
+                None => docs.next().map(|doc| doc.map(Part::Document)),
+            };
+            let Some(part) = part else {
+                return Ok((count, false));
+            };
+            let part = part.map_err(|e| error(&e))?;
+            let max = self.load.limits.max_documents;
+            if item == 0 && (index + count) as usize >= max {
+                let info = format!("stream holds more than {} documents (maxDocuments)", max);
+                let mark = match &part {
+                    Part::Items(items) => items[0].mark,
+                    Part::Document(doc) => doc.mark,
+                };
+                return Err(error(&ScanError::new_string(mark, info)));
+            }
+
+            // Bytes of the stream processed once a document is passed on
+            let mut processed = None;
+            let chunk = match part {
+                Part::Items(items) => {
+                    let chunk = self.items_chunk(index + count, item, &items)?;
+                    item += items.len();
+                    Some(chunk)
+                }
+                Part::Document(doc) => {
+                    let chunk = match &doc.kind {
+                        NodeKind::Sequence(items)
+                            if self.sequence_chunk.is_some() && doc.tag.is_none() =>
+                        {
+                            // The last items, unless they were all passed on already
+                            if items.is_empty() && item > 0 {
+                                None
+                            } else {
+                                Some(self.items_chunk(index + count, item, items)?)
+                            }
+                        }
+                        _ => {
+                            let span = docs.span();
+                            let chunk = self.chunk(
+                                index + count,
+                                &doc,
+                                &text[span.clone()],
+                                offset + span.start,
+                            )?;
+                            if self.directives {
+                                set(&chunk, "directives", directives_to_js(&docs.directives())?)?;
+                            }
+                            Some(chunk)
+                        }
+                    };
+                    count += 1;
+                    item = 0;
+                    processed = Some(offset + docs.span().end);
+                    chunk
+                }
+            };
+            if let Some(chunk) = chunk {
+                if callback.call1(&JsValue::NULL, &chunk)?.as_bool() == Some(false) {
+                    // A document whose items were partly passed on counts as well
+                    return Ok((count + u32::from(item > 0), true));
+                }
+            }
+            if let (Some(progress), Some(bytes)) = (&self.progress, processed) {
+                progress.report(bytes, index + count, false)?;
+            }
+        }
+    }
+
+    /// Build the `{ index, item, value }` chunk of items of a root sequence, `item` being the
+    /// index of the first one in the sequence
+    fn items_chunk(&self, index: u32, item: usize, items: &[Node]) -> Result<Object, JsValue> {
+        let values = Array::new();
+        let mut builder = ValueBuilder::new(&self.load);
+        for (at, node) in (item..).zip(items) {
+            let value = timed(Phase::Convert, || {
+                builder.build_at(node, vec![at], &format!("$[{}]", at))
+            });
+            self.load.warn(builder.take_warnings())?;
+            values.push(&value?);
+        }
+        let chunk = Object::new();
+        set(&chunk, "index", JsValue::from(index))?;
+        set(&chunk, "item", JsValue::from_f64(item as f64))?;
+        set(&chunk, "value", values.into())?;
+        Ok(chunk)
+    }
+
+    /// Build the `{ index, value, start, end, hash? }` chunk of a document
+    fn chunk(&self, index: u32, doc: &Node, source: &str, start: usize) -> Result<Object, JsValue> {
+        let chunk = Object::new();
+        set(&chunk, "index", JsValue::from(index))?;
+        set(&chunk, "value", node_to_js_value(doc, &self.load)?)?;
+        set(&chunk, "start", JsValue::from_f64(start as f64))?;
+        set(
+            &chunk,
+            "end",
+            JsValue::from_f64((start + source.len()) as f64),
+        )?;
+        if self.checksum {
+            let hash = format!("{:016x}", fnv1a(source.as_bytes()));
+            set(&chunk, "hash", JsValue::from_str(&hash))?;
+        }
+        Ok(chunk)
+    }
+}
+
+/// A parser receiving a YAML stream in chunks, returned by `create_stream_parser`
+#[wasm_bindgen]
+pub struct StreamParser {
+    callback: Function,
+    options: StreamOptions,
+    // Text received but not parsed yet, starting at a document boundary
+    pending: String,
+    // Length of the start of `pending` whose lines have been looked at
+    scanned: usize,
+    // Follows the scanned lines to find where documents end
+    splitter: Splitter,
+    // UTF-8 bytes and lines of the stream before `pending`
+    offset: usize,
+    lines: usize,
+    count: u32,
+    // Whether the callback stopped parsing by returning `false`
+    stopped: bool,
+}
 
-/// Parse a YAML document in a streaming fashion
+/// Create a parser for a YAML stream received in chunks
 ///
-/// @param {string} yaml - The YAML document to parse
+/// `write(chunk)` passes every document the chunks complete to `callback` as `parse_stream`
+/// does, and `end()` passes the last one and returns the number of documents. A document is
+/// complete once the next `---` or a `...` marker line is received, so memory is bounded by
+/// the largest document, not by the stream: `maxInputSize` applies to each document, and
+/// `maxMemoryBytes` and `maxNodes` to its tree as with `parse_stream`. Once `callback` returns
+/// `false`, the rest of the stream is ignored and `stopped` is true. `onProgress` is called as
+/// with `parse_stream`, counting the bytes of the stream from its first chunk.
+///
+/// @param {Function} callback - Callback function to receive parsed chunks
+/// @param {Object} options - Load options, `checksum`, `directives`, `sequenceChunk`,
+///   `onProgress` and `progressInterval`
+/// @returns {StreamParser} - The parser
+#[wasm_bindgen]
+pub fn create_stream_parser(callback: Function, options: JsValue) -> Result<StreamParser, JsValue> {
+    Ok(StreamParser {
+        callback,
+        options: StreamOptions::from_js(&options)?,
+        pending: String::new(),
+        scanned: 0,
+        splitter: Splitter::default(),
+        offset: 0,
+        lines: 0,
+        count: 0,
+        stopped: false,
+    })
+}
+
+/// Version of the checkpoints `StreamParser::checkpoint` exports
+const CHECKPOINT_VERSION: u32 = 1;
+
+/// Create a stream parser resuming from a checkpoint
+///
+/// The parser continues where the one `checkpoint` was exported from stopped: the next chunk
+/// is the one following the `bytesReceived` first bytes of the stream, and documents keep
+/// their index, offset and lines in the whole stream.
+///
+/// @param {Object} checkpoint - A checkpoint from `StreamParser.checkpoint()`
 /// @param {Function} callback - Callback function to receive parsed chunks
-/// @param {Object} options - Parsing options
-/// @returns {Promise} - Promise that resolves when parsing is complete
+/// @param {Object} options - The options of `create_stream_parser`
+/// @returns {StreamParser} - The parser
 #[wasm_bindgen]
-pub fn parse_stream(
-    _yaml: &str,
-    callback: &Function,
-    _options: &JsValue,
-) -> Result<JsValue, JsValue> {
-    // For now, we'll implement a simple skeleton that just calls the callback once
-    // This will be replaced with actual streaming logic
+pub fn restore_stream_parser(
+    checkpoint: JsValue,
+    callback: Function,
+    options: JsValue,
+) -> Result<StreamParser, JsValue> {
+    let mut parser = create_stream_parser(callback, options)?;
+    parser
+        .restore(&checkpoint)
+        .ok_or_else(|| JsValue::from_str("Invalid stream parser checkpoint"))?;
+    Ok(parser)
+}
+
+#[wasm_bindgen]
+impl StreamParser {
+    /// Receive the next chunk of the stream, parsing the documents it completes
+    ///
+    /// @param {string} chunk - The text following the previous chunk
+    pub fn write(&mut self, chunk: &str) -> Result<(), JsValue> {
+        if self.stopped {
+            return Ok(());
+        }
+        self.pending.push_str(chunk);
+        while let Some(len) = self.pending[self.scanned..].find('\n') {
+            let start = self.scanned;
+            self.scanned += len + 1;
+            let line = &self.pending.as_bytes()[start..self.scanned];
+            match self.splitter.line(line) {
+                Some(Boundary::Before) => self.parse_pending(start)?,
+                Some(Boundary::After) => self.parse_pending(self.scanned)?,
+                None => {}
+            }
+            if self.stopped {
+                return Ok(());
+            }
+        }
+
+        let limit = self.options.load.limits.max_input_size;
+        if self.pending.len() > limit {
+            let info = format!("document is longer than {} bytes (maxInputSize)", limit);
+            let mark = Marker::new(self.offset, self.lines + 1, 0);
+            return Err(self.options.load.error(&error_at(&info, &mark)));
+        }
+        Ok(())
+    }
 
-    // Create a simple object to pass to the callback
-    let chunk = Object::new();
+    /// Parse the rest of the stream
+    ///
+    /// @returns {number} - Number of documents parsed
+    pub fn end(&mut self) -> Result<u32, JsValue> {
+        if !self.stopped {
+            self.parse_pending(self.pending.len())?;
+            // Unless the last document stopped parsing
+            if !self.stopped {
+                self.options.report_end(self.offset, self.count)?;
+            }
+        }
+        Ok(self.count)
+    }
 
-    // Call the callback with the chunk
-    let _ = callback.call1(&JsValue::NULL, &chunk);
+    /// Whether the callback stopped parsing by returning `false`
+    #[wasm_bindgen(getter)]
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
 
-    // Return a resolved promise (in the real implementation, this would be more complex)
-    Ok(JsValue::NULL)
+    /// Export the state of the parser, to resume with `restore_stream_parser`
+    ///
+    /// The checkpoint is a plain object JSON can hold: the text received since the last
+    /// complete document, how far its lines were scanned, and the documents, bytes and lines
+    /// before it. `bytesReceived` counts the UTF-8 bytes of the chunks written so far.
+    ///
+    /// @returns {Object} - The checkpoint
+    pub fn checkpoint(&self) -> Result<JsValue, JsValue> {
+        let checkpoint = Object::new();
+        set(&checkpoint, "version", CHECKPOINT_VERSION.into())?;
+        set(&checkpoint, "pending", JsValue::from_str(&self.pending))?;
+        set(&checkpoint, "scanned", (self.scanned as f64).into())?;
+        set(&checkpoint, "splitter", self.splitter.to_js()?)?;
+        set(&checkpoint, "offset", (self.offset as f64).into())?;
+        set(&checkpoint, "lines", (self.lines as f64).into())?;
+        set(&checkpoint, "count", self.count.into())?;
+        set(&checkpoint, "stopped", self.stopped.into())?;
+        let reported = self
+            .options
+            .progress
+            .as_ref()
+            .and_then(|p| p.reported.get());
+        set(
+            &checkpoint,
+            "progressReported",
+            reported.map_or(JsValue::NULL, |bytes| (bytes as f64).into()),
+        )?;
+        let received = self.offset + self.pending.len();
+        set(&checkpoint, "bytesReceived", (received as f64).into())?;
+        Ok(checkpoint.into())
+    }
 }
 
-// Internal helper functions for streaming will be added here
+impl StreamParser {
+    /// Take the state of a checkpoint; `None` when it does not hold one
+    fn restore(&mut self, checkpoint: &JsValue) -> Option<()> {
+        if integer::<u32>(checkpoint, "version")?? != CHECKPOINT_VERSION {
+            return None;
+        }
+        let pending = Reflect::get(checkpoint, &"pending".into())
+            .ok()?
+            .as_string()?;
+        let scanned: usize = integer(checkpoint, "scanned")??;
+        if !pending.is_char_boundary(scanned) {
+            return None;
+        }
+        let splitter = Splitter::from_js(&Reflect::get(checkpoint, &"splitter".into()).ok()?)?;
+        let reported = integer(checkpoint, "progressReported")?;
+        self.offset = integer(checkpoint, "offset")??;
+        self.lines = integer(checkpoint, "lines")??;
+        self.count = integer(checkpoint, "count")??;
+        self.stopped = Reflect::get(checkpoint, &"stopped".into())
+            .ok()?
+            .as_bool()?;
+        if let Some(progress) = &self.options.progress {
+            progress.reported.set(reported);
+        }
+        self.pending = pending;
+        self.scanned = scanned;
+        self.splitter = splitter;
+        Some(())
+    }
+    /// Parse the documents of the first `len` bytes of the pending text and drop them
+    fn parse_pending(&mut self, len: usize) -> Result<(), JsValue> {
+        let text = &self.pending[..len];
+        let load = &self.options.load;
+        // `maxDocuments` counts the documents of the whole stream
+        let mut limits = load.limits;
+        limits.max_documents = usize::MAX;
+
+        let mut docs = documents(text, &limits);
+        let (count, stopped) = self.options.pass_documents(
+            &mut docs,
+            text,
+            (self.count, self.offset),
+            &self.callback,
+            &|e| self.error(e),
+        )?;
+        self.count += count;
+        if stopped {
+            self.stopped = true;
+            self.pending = String::new();
+            self.scanned = 0;
+            return Ok(());
+        }
+
+        self.offset += len;
+        self.lines += text.matches('\n').count();
+        self.pending.drain(..len);
+        self.scanned -= len.min(self.scanned);
+        Ok(())
+    }
+
+    /// An error in the pending text, with its position counted from the start of the stream
+    fn error(&self, e: &ScanError) -> JsValue {
+        let mark = e.marker();
+        let mark = Marker::new(
+            mark.index() + self.offset,
+            mark.line() + self.lines,
+            mark.col(),
+        );
+        self.options.load.error(&error_at(e.info(), &mark))
+    }
+}
+
+fn set(object: &Object, key: &str, value: JsValue) -> Result<(), JsValue> {
+    Reflect::set(object, &JsValue::from_str(key), &value).map(|_| ())
+}
+
+/// Build `{ version, tags }`, with `version` null when the document has no `%YAML` directive
+fn directives_to_js(directives: &Directives) -> Result<JsValue, JsValue> {
+    let tags = Object::new();
+    for (handle, prefix) in &directives.tags {
+        set(&tags, handle, JsValue::from_str(prefix))?;
+    }
+    let result = Object::new();
+    set(
+        &result,
+        "version",
+        directives
+            .version
+            .as_deref()
+            .map_or(JsValue::NULL, JsValue::from_str),
+    )?;
+    set(&result, "tags", tags.into())?;
+    Ok(result.into())
+}
+
+/// 64-bit FNV-1a hash
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Rewrite the documents of a YAML stream on the fly
+///
+/// Each document is parsed on its own, passed to `transform`, and the returned value is
+/// emitted as YAML to `output` before the next document is parsed, so memory is bounded by
+/// the largest document. Returning `undefined` from `transform` drops the document.
+///
+/// @param {string} yaml - The YAML stream to rewrite
+/// @param {Function} transform - Callback receiving each document and its index
+/// @param {Function} output - Callback receiving the YAML text of each rewritten document
+/// @param {Object} options - Load options used to parse the documents
+/// @returns {number} - Number of documents emitted
+#[wasm_bindgen]
+pub fn transform_stream(
+    yaml: &str,
+    transform: &Function,
+    output: &Function,
+    options: JsValue,
+) -> Result<u32, JsValue> {
+    let options = LoadOptions::from_js(&options)?;
+    options.reject_timing("transformStream")?;
+
+    let mut emitted = 0;
+    for (index, doc) in documents(yaml, &options.limits).enumerate() {
+        let doc = doc.map_err(|e| options.error(&error_at(e.info(), e.marker())))?;
+        let value = node_to_js_value(&doc, &options)?;
+
+        let rewritten = transform.call2(&JsValue::NULL, &value, &JsValue::from(index as u32))?;
+        if rewritten.is_undefined() {
+            continue;
+        }
+
+        let text = emit_document(&js_value_to_yaml(&rewritten, &KeyStyle::Keep)?)
+            .map_err(|e| JsValue::from_str(&e))?;
+        output.call1(&JsValue::NULL, &JsValue::from_str(&text))?;
+        emitted += 1;
+    }
+
+    Ok(emitted)
+}
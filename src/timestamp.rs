@@ -0,0 +1,168 @@
+//! YAML timestamps
+//!
+//! This module parses `!!timestamp` scalars the way js-yaml does, producing milliseconds since
+//! the Unix epoch for `Date` values, and formats them back as ISO 8601 strings for JSON output.
+
+const MS_PER_DAY: i64 = 86_400_000;
+
+/// Parse a YAML timestamp to milliseconds since the epoch
+///
+/// Accepts a date (`2001-12-14`) or a date and time separated by `T` or spaces, with optional
+/// fraction and time zone (`2001-12-14 21:59:43.10 -5`). Times without a zone are UTC. Like
+/// `Date.UTC`, out-of-range fields roll over into the next unit.
+pub(crate) fn parse_timestamp(value: &str) -> Option<f64> {
+    let bytes = value.as_bytes();
+    let mut pos = 0;
+
+    let year = digits(bytes, &mut pos, 4, 4)?;
+    expect(bytes, &mut pos, b'-')?;
+    // A date alone needs two-digit month and day
+    let date_only = bytes.len() == 10;
+    let month = digits(bytes, &mut pos, if date_only { 2 } else { 1 }, 2)?;
+    expect(bytes, &mut pos, b'-')?;
+    let day = digits(bytes, &mut pos, if date_only { 2 } else { 1 }, 2)?;
+    if pos == bytes.len() {
+        return date_only.then(|| to_epoch_ms(year, month, day, 0, 0, 0, 0) as f64);
+    }
+
+    match bytes[pos] {
+        b'T' | b't' => pos += 1,
+        b' ' | b'\t' => skip_blanks(bytes, &mut pos),
+        _ => return None,
+    }
+    let hour = digits(bytes, &mut pos, 1, 2)?;
+    expect(bytes, &mut pos, b':')?;
+    let minute = digits(bytes, &mut pos, 2, 2)?;
+    expect(bytes, &mut pos, b':')?;
+    let second = digits(bytes, &mut pos, 2, 2)?;
+
+    // Only milliseconds are kept: `.1` is 100ms, `.123456` is 123ms
+    let mut millis = 0;
+    if bytes.get(pos) == Some(&b'.') {
+        pos += 1;
+        let start = pos;
+        while bytes.get(pos).is_some_and(u8::is_ascii_digit) {
+            pos += 1;
+        }
+        let fraction = format!("{:0<3}", &value[start..pos.min(start + 3)]);
+        millis = fraction.parse().ok()?;
+    }
+
+    skip_blanks(bytes, &mut pos);
+    let mut offset_minutes = 0;
+    match bytes.get(pos) {
+        None => {}
+        Some(b'Z') => pos += 1,
+        Some(&sign @ (b'+' | b'-')) => {
+            pos += 1;
+            let hours = digits(bytes, &mut pos, 1, 2)?;
+            let minutes = match bytes.get(pos) {
+                Some(b':') => {
+                    pos += 1;
+                    digits(bytes, &mut pos, 2, 2)?
+                }
+                _ => 0,
+            };
+            offset_minutes = hours * 60 + minutes;
+            if sign == b'-' {
+                offset_minutes = -offset_minutes;
+            }
+        }
+        Some(_) => return None,
+    }
+    if pos != bytes.len() {
+        return None;
+    }
+
+    let utc = to_epoch_ms(year, month, day, hour, minute, second, millis);
+    Some((utc - offset_minutes * 60_000) as f64)
+}
+
+/// Format milliseconds since the epoch like `Date.prototype.toISOString`
+pub(crate) fn format_timestamp(ms: f64) -> String {
+    let ms = ms as i64;
+    let days = ms.div_euclid(MS_PER_DAY);
+    let time = ms.rem_euclid(MS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let year = if (0..=9999).contains(&year) {
+        format!("{:04}", year)
+    } else {
+        format!("{:+07}", year)
+    };
+    format!(
+        "{}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        time / 3_600_000,
+        time / 60_000 % 60,
+        time / 1000 % 60,
+        time % 1000
+    )
+}
+
+/// Read between `min` and `max` ASCII digits
+fn digits(bytes: &[u8], pos: &mut usize, min: usize, max: usize) -> Option<i64> {
+    let start = *pos;
+    let mut value = 0;
+    while *pos - start < max && bytes.get(*pos).is_some_and(u8::is_ascii_digit) {
+        value = value * 10 + i64::from(bytes[*pos] - b'0');
+        *pos += 1;
+    }
+    (*pos - start >= min).then_some(value)
+}
+
+fn expect(bytes: &[u8], pos: &mut usize, expected: u8) -> Option<()> {
+    (bytes.get(*pos) == Some(&expected)).then(|| *pos += 1)
+}
+
+fn skip_blanks(bytes: &[u8], pos: &mut usize) {
+    while matches!(bytes.get(*pos), Some(b' ' | b'\t')) {
+        *pos += 1;
+    }
+}
+
+/// Milliseconds since the epoch of a UTC date and time, normalizing out-of-range fields
+fn to_epoch_ms(
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    millis: i64,
+) -> i64 {
+    let year = year + (month - 1).div_euclid(12);
+    let month = (month - 1).rem_euclid(12) + 1;
+    let days = days_from_civil(year, month) + day - 1;
+    days * MS_PER_DAY + ((hour * 60 + minute) * 60 + second) * 1000 + millis
+}
+
+/// Days since the epoch of the first day of a month (proleptic Gregorian calendar)
+fn days_from_civil(year: i64, month: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Year, month and day of a day counted from the epoch
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,35 @@
+//! Timing helpers
+//!
+//! This module provides a millisecond clock usable both in WebAssembly and natively.
+
+/// Current time in milliseconds, for measuring durations
+///
+/// Uses `performance.now()` when the host provides it and falls back to `Date.now()`.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn now_ms() -> f64 {
+    use js_sys::{Function, Reflect};
+    use wasm_bindgen::{JsCast, JsValue};
+
+    let performance = Reflect::get(&js_sys::global(), &JsValue::from_str("performance"))
+        .ok()
+        .filter(JsValue::is_object);
+    performance
+        .and_then(|performance| {
+            let now: Function = Reflect::get(&performance, &JsValue::from_str("now"))
+                .ok()?
+                .dyn_into()
+                .ok()?;
+            now.call0(&performance).ok()?.as_f64()
+        })
+        .unwrap_or_else(js_sys::Date::now)
+}
+
+/// Current time in milliseconds, for measuring durations
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn now_ms() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
@@ -0,0 +1,135 @@
+//! Custom tag types
+//!
+//! This module holds the tag types registered from JavaScript with `register_type`, which turn
+//! scalars, sequences or mappings carrying a local tag (`!Ref`, `!vault`) into user-defined
+//! values, like js-yaml's `Type`.
+
+use std::cell::RefCell;
+
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+use yaml_rust2::parser::Tag;
+
+use crate::loader::NodeKind;
+use crate::options::{get_property, get_string};
+use crate::schema::CORE_TAG_HANDLE;
+
+thread_local! {
+    static TYPES: RefCell<Vec<CustomType>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Node kind a custom type applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TypeKind {
+    Scalar,
+    Sequence,
+    Mapping,
+}
+
+/// A tag type registered from JavaScript
+#[derive(Debug, Clone)]
+pub(crate) struct CustomType {
+    /// Full tag name: `!Ref`, or `tag:yaml.org,2002:python/tuple` for `!!python/tuple`
+    pub tag: String,
+    pub kind: TypeKind,
+    /// Called with the node data (string, array or object) to build the value
+    pub construct: Option<Function>,
+    /// Called with the node data; a falsy result rejects the node
+    pub resolve: Option<Function>,
+}
+
+impl CustomType {
+    /// The tag the way it is written in YAML (`!!python/tuple`)
+    pub fn name(&self) -> String {
+        match self.tag.strip_prefix(CORE_TAG_HANDLE) {
+            Some(suffix) => format!("!!{}", suffix),
+            None => self.tag.clone(),
+        }
+    }
+
+    /// Whether this type applies to a node of the given tag and kind
+    pub fn matches(&self, tag: &Tag, kind: &NodeKind) -> bool {
+        let kind_matches = matches!(
+            (self.kind, kind),
+            (TypeKind::Scalar, NodeKind::Scalar(..))
+                | (TypeKind::Sequence, NodeKind::Sequence(_))
+                | (TypeKind::Mapping, NodeKind::Mapping(_))
+        );
+        kind_matches
+            && self
+                .tag
+                .strip_prefix(tag.handle.as_str())
+                .is_some_and(|suffix| suffix == tag.suffix)
+    }
+}
+
+/// The types registered so far
+pub(crate) fn registered_types() -> Vec<CustomType> {
+    TYPES.with(|types| types.borrow().clone())
+}
+
+/// Register a constructor for a tag
+///
+/// Nodes of the given `kind` (`'scalar'`, `'sequence'` or `'mapping'`) carrying the tag are
+/// converted as usual, the string, array or object is passed to `resolveFn` (if any), which
+/// may reject it by returning a falsy value, and then to `constructFn` (if any), whose result
+/// replaces it. Registering a tag again for the same kind replaces the previous type. Types
+/// apply to `parse`, `parseAll`, `load` and `loadAll`.
+///
+/// @param {string} tag - The tag, such as `!Ref` or `!!python/tuple`
+/// @param {Object} definition - `{ kind, constructFn, resolveFn }`
+#[wasm_bindgen]
+pub fn register_type(tag: &str, definition: &JsValue) -> Result<(), JsValue> {
+    if !tag.starts_with('!') || tag.len() < 2 {
+        return Err(JsValue::from_str(&format!(
+            "Type tag must start with '!': {}",
+            tag
+        )));
+    }
+    if !definition.is_object() {
+        return Err(JsValue::from_str("Type definition must be an object"));
+    }
+    let kind = match get_string(definition, "kind")?.as_deref() {
+        Some("scalar") => TypeKind::Scalar,
+        Some("sequence") => TypeKind::Sequence,
+        Some("mapping") => TypeKind::Mapping,
+        Some(kind) => {
+            return Err(JsValue::from_str(&format!(
+                "Unknown kind '{}' for type {}",
+                kind, tag
+            )))
+        }
+        None => {
+            return Err(JsValue::from_str(&format!(
+                "Type {} needs a kind: 'scalar', 'sequence' or 'mapping'",
+                tag
+            )))
+        }
+    };
+    let custom = CustomType {
+        tag: match tag.strip_prefix("!!") {
+            Some(suffix) => format!("{}{}", CORE_TAG_HANDLE, suffix),
+            None => tag.to_string(),
+        },
+        kind,
+        construct: get_function(definition, "constructFn")?,
+        resolve: get_function(definition, "resolveFn")?,
+    };
+    TYPES.with(|types| {
+        let mut types = types.borrow_mut();
+        types.retain(|existing| existing.tag != custom.tag || existing.kind != custom.kind);
+        types.push(custom);
+    });
+    Ok(())
+}
+
+/// Read an optional function property
+fn get_function(definition: &JsValue, name: &str) -> Result<Option<Function>, JsValue> {
+    match get_property(definition, name)? {
+        Some(value) if !value.is_null() => value
+            .dyn_into()
+            .map(Some)
+            .map_err(|_| JsValue::from_str(&format!("Type '{}' must be a function", name))),
+        _ => Ok(None),
+    }
+}
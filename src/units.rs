@@ -0,0 +1,97 @@
+//! Durations and byte sizes
+//!
+//! This module parses the human-readable quantities found in configuration files, `!duration`
+//! scalars (`250ms`, `1h30m`) and `!bytes` scalars (`512Mi`, `2GB`), to plain numbers.
+
+/// Duration units and their length in milliseconds, `ms` before `m`
+const DURATION_UNITS: [(&str, f64); 9] = [
+    ("ns", 1e-6),
+    ("us", 1e-3),
+    ("µs", 1e-3),
+    ("ms", 1.0),
+    ("s", 1e3),
+    ("m", 60e3),
+    ("h", 3_600e3),
+    ("d", 86_400e3),
+    ("w", 604_800e3),
+];
+
+/// Byte size units and their size in bytes
+const BYTE_UNITS: [(&str, f64); 21] = [
+    ("KiB", 1024.0),
+    ("MiB", 1_048_576.0),
+    ("GiB", 1_073_741_824.0),
+    ("TiB", 1_099_511_627_776.0),
+    ("PiB", 1_125_899_906_842_624.0),
+    ("Ki", 1024.0),
+    ("Mi", 1_048_576.0),
+    ("Gi", 1_073_741_824.0),
+    ("Ti", 1_099_511_627_776.0),
+    ("Pi", 1_125_899_906_842_624.0),
+    ("kB", 1e3),
+    ("KB", 1e3),
+    ("MB", 1e6),
+    ("GB", 1e9),
+    ("TB", 1e12),
+    ("PB", 1e15),
+    ("k", 1e3),
+    ("K", 1e3),
+    ("M", 1e6),
+    ("G", 1e9),
+    ("T", 1e12),
+];
+
+/// Parse a duration to milliseconds
+///
+/// Accepts one or more numbers, each followed by a unit (`ns`, `us`, `µs`, `ms`, `s`, `m`,
+/// `h`, `d` or `w`), with an optional sign: `250ms`, `1.5h`, `-1h30m`.
+pub(crate) fn parse_duration(value: &str) -> Option<f64> {
+    let (negative, mut rest) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    if rest.is_empty() {
+        return None;
+    }
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest)?;
+        let (unit, ms) = DURATION_UNITS
+            .iter()
+            .find(|(unit, _)| after.starts_with(unit))?;
+        total += number * ms;
+        rest = &after[unit.len()..];
+    }
+    Some(if negative { -total } else { total })
+}
+
+/// Parse a byte size to a whole number of bytes
+///
+/// Accepts a number optionally followed by a decimal (`kB`, `MB`, ... `PB`, or `k`, `M`, ...
+/// `T`) or binary (`KiB`, `MiB`, ... `PiB`, or `Ki`, `Mi`, ...) unit, or by `B`, with optional
+/// blanks in between: `512Mi`, `1.5 GB`, `4096`.
+pub(crate) fn parse_byte_size(value: &str) -> Option<f64> {
+    let (number, after) = split_number(value)?;
+    let unit = after.trim_start_matches([' ', '\t']);
+    let multiplier = match unit {
+        "" | "B" => 1.0,
+        unit => BYTE_UNITS
+            .iter()
+            .find(|(name, _)| *name == unit)
+            .map(|&(_, size)| size)?,
+    };
+    let bytes = number * multiplier;
+    (bytes.fract() == 0.0 && bytes.is_finite()).then_some(bytes)
+}
+
+/// Split the leading unsigned decimal number (`12`, `1.5`, `.5`) off a string
+fn split_number(value: &str) -> Option<(f64, &str)> {
+    let end = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let number = &value[..end];
+    if !number.bytes().any(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some((number.parse().ok()?, &value[end..]))
+}
@@ -1,67 +1,996 @@
 //! YAML validation functionality
 //!
-//! This module provides YAML validation functionality for YAML documents.
+//! This module provides JSON Schema validation for YAML documents. The supported keywords are
+//! `type`, `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `minProperties`/`maxProperties`, `items`, `minItems`/`maxItems`, `uniqueItems`,
+//! `minLength`/`maxLength`, `minimum`/`maximum`, `exclusiveMinimum`/`exclusiveMaximum`,
+//! `multipleOf`, `allOf`, `anyOf`, `oneOf`, `not` and local `$ref`s, and `format` for the
+//! formats plugins register; other keywords are ignored.
+
+use std::collections::HashMap;
 
 use js_sys::{Array, Boolean, JsString, Object, Reflect, JSON};
 use serde_json::Value as JsonValue;
 use wasm_bindgen::prelude::*;
-use yaml_rust2::YamlLoader;
+use yaml_rust2::Yaml;
+
+use crate::codes::code_of;
+use crate::dump::{dump_yaml, js_value_to_yaml};
+use crate::handle::DocumentHandle;
+use crate::loader::load_first_resolved;
+use crate::options::{check_option_keys, get_bool, KeyStyle, Limits};
+use crate::plugins::check_format;
+use crate::schema::Diagnostic;
+
+/// A JSON Schema parsed once and reusable across validations
+#[wasm_bindgen]
+pub struct CompiledSchema {
+    schema: JsonValue,
+}
+
+/// Options accepted by `validate` and `validate_batch`
+#[derive(Debug, Clone, Copy, Default)]
+struct ValidateOptions {
+    /// Explain type errors on strings that look like locale-formatted numbers (`1,5`)
+    decimal_comma_hints: bool,
+    /// Resource limits applied while loading each document
+    limits: Limits,
+}
+
+impl ValidateOptions {
+    /// Read validation options from a JavaScript value
+    ///
+    /// `undefined` and `null` yield the default options.
+    fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut result = ValidateOptions::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(result);
+        }
+        check_option_keys(options, &[&["decimalCommaHints", "limits"]])?;
+
+        if let Some(hints) = get_bool(options, "decimalCommaHints")? {
+            result.decimal_comma_hints = hints;
+        }
+        result.limits = Limits::from_js(options)?;
+
+        Ok(result)
+    }
+}
+
+/// A single validation failure
+#[derive(Debug, Clone)]
+pub(crate) struct ValidationError {
+    /// Stable code of the kind of failure (e.g. `REQUIRED`, `TYPE_MISMATCH`)
+    pub code: &'static str,
+    /// Human readable description of the failure
+    pub message: String,
+    /// Location of the failing value (e.g. `$.services[0].port`)
+    pub path: String,
+}
 
 /// Validate a YAML document against a JSON Schema
 ///
+/// With the `decimalCommaHints` option, type errors on strings such as `1,5` or `1.000,5`
+/// where a number is expected explain the likely locale formatting mistake. The `limits`
+/// option bounds the resources spent loading the document, as for `load`.
+///
 /// @param {string} yaml - The YAML document to validate
 /// @param {Object} schema - The JSON Schema to validate against
+/// @param {Object} options - Validation options (`decimalCommaHints`, `limits`)
 /// @returns {Object} - Validation result with success flag and any errors
 #[wasm_bindgen]
-pub fn validate(yaml: &str, schema: &JsValue) -> Result<JsValue, JsValue> {
-    // Parse the YAML document
-    let docs = match YamlLoader::load_from_str(yaml) {
-        Ok(docs) => docs,
-        Err(e) => {
-            return Err(JsValue::from_str(&format!("YAML parsing error: {}", e)));
-        }
-    };
+pub fn validate(yaml: &str, schema: &JsValue, options: &JsValue) -> Result<JsValue, JsValue> {
+    compile_schema(schema)?.validate(yaml, options)
+}
+
+/// Parse a JSON Schema once for repeated validations
+///
+/// `validate` converts the schema from JavaScript on every call, which costs more than
+/// validating a small document; a compiled schema is converted once.
+///
+/// @param {Object} schema - The JSON Schema to compile
+/// @returns {CompiledSchema} - The compiled schema
+#[wasm_bindgen]
+pub fn compile_schema(schema: &JsValue) -> Result<CompiledSchema, JsValue> {
+    Ok(CompiledSchema {
+        schema: schema_from_js(schema)?,
+    })
+}
 
-    if docs.is_empty() {
-        return Err(JsValue::from_str("Empty YAML document"));
+#[wasm_bindgen]
+impl CompiledSchema {
+    /// Validate a YAML document, as `validate` does
+    ///
+    /// @param {string} yaml - The YAML document to validate
+    /// @param {Object} options - Validation options (`decimalCommaHints`, `limits`)
+    /// @returns {Object} - Validation result with success flag and any errors
+    pub fn validate(&self, yaml: &str, options: &JsValue) -> Result<JsValue, JsValue> {
+        let options = ValidateOptions::from_js(options)?;
+        let errors =
+            validate_document(yaml, &self.schema, options).map_err(|e| JsValue::from_str(&e))?;
+        Ok(validation_result(&errors))
     }
 
-    // Convert the YAML to JSON
-    let yaml_value = &docs[0];
-    let _json_value = match yaml_to_json(yaml_value) {
-        Ok(value) => value,
-        Err(e) => {
-            return Err(JsValue::from_str(&format!(
-                "YAML to JSON conversion error: {}",
-                e
-            )));
-        }
-    };
+    /// Validate a document kept by a `DocumentHandle`, as its `validate` does
+    ///
+    /// @param {DocumentHandle} handle - The parsed document
+    /// @param {Object} options - Validation options (`decimalCommaHints`)
+    /// @returns {Object} - Validation result with success flag and any errors
+    #[wasm_bindgen(js_name = validateHandle)]
+    pub fn validate_handle(
+        &self,
+        handle: &DocumentHandle,
+        options: &JsValue,
+    ) -> Result<JsValue, JsValue> {
+        handle.validate_with(&self.schema, options)
+    }
+}
 
+/// Convert a JSON Schema from a JavaScript value
+pub(crate) fn schema_from_js(schema: &JsValue) -> Result<JsonValue, JsValue> {
     // Convert the schema from JsValue to JsonValue
     let schema_str = JSON::stringify(schema)
         .map_err(|_| JsValue::from_str("Failed to stringify schema"))?
         .as_string()
         .ok_or_else(|| JsValue::from_str("Failed to convert schema to string"))?;
 
-    let _schema_value: JsonValue = match serde_json::from_str(&schema_str) {
+    let schema: JsonValue = match serde_json::from_str(&schema_str) {
         Ok(value) => value,
         Err(e) => {
             return Err(JsValue::from_str(&format!("Schema parsing error: {}", e)));
         }
     };
 
-    // TODO: Implement JSON Schema validation
-    // For now, just return a successful result
-    let result = Object::new();
-    let _ = Reflect::set(&result, &JsString::from("valid"), &Boolean::from(true));
-    let _ = Reflect::set(&result, &JsString::from("errors"), &Array::new());
+    Ok(schema)
+}
 
+/// Validate many YAML documents against one compiled schema in a single call
+///
+/// Results are returned in input order. A document that fails to parse yields an invalid
+/// result carrying the parse error instead of aborting the batch.
+///
+/// @param {string[]} docs - The YAML documents to validate
+/// @param {CompiledSchema} schema - The compiled schema to validate against
+/// @param {Object} options - Validation options, as for `validate`
+/// @returns {Object[]} - Validation results in input order
+#[wasm_bindgen]
+pub fn validate_batch(
+    docs: &Array,
+    schema: &CompiledSchema,
+    options: &JsValue,
+) -> Result<Array, JsValue> {
+    let options = ValidateOptions::from_js(options)?;
+    let docs = docs
+        .iter()
+        .map(|doc| {
+            doc.as_string()
+                .ok_or_else(|| JsValue::from_str("validate_batch expects an array of strings"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let result = Array::new();
+    for outcome in validate_documents(&docs, &schema.schema, options) {
+        let errors = outcome.unwrap_or_else(|message| {
+            vec![ValidationError {
+                code: code_of(&message),
+                message,
+                path: String::new(),
+            }]
+        });
+        result.push(&validation_result(&errors));
+    }
+    Ok(result)
+}
+
+/// Schema keywords holding a map of subschemas by name
+const SCHEMA_MAPS: [&str; 5] = [
+    "properties",
+    "patternProperties",
+    "definitions",
+    "$defs",
+    "dependentSchemas",
+];
+
+/// Schema keywords holding a subschema or an array of subschemas
+const SCHEMA_KEYWORDS: [&str; 15] = [
+    "items",
+    "prefixItems",
+    "additionalItems",
+    "additionalProperties",
+    "unevaluatedItems",
+    "unevaluatedProperties",
+    "contains",
+    "propertyNames",
+    "not",
+    "if",
+    "then",
+    "else",
+    "allOf",
+    "anyOf",
+    "oneOf",
+];
+
+/// A `default`, `example` or `examples` item embedded in a schema
+struct SchemaExample<'a> {
+    /// JSON pointer to the subschema holding the example, without the leading `#`
+    pointer: String,
+    keyword: &'static str,
+    /// Position in `examples`
+    index: Option<usize>,
+    value: &'a Yaml,
+}
+
+/// Check every `default`, `example` and `examples` item of a schema against its subschema
+///
+/// Returns `{ valid, examples }`, with one `{ pointer, keyword, index, valid, errors }` entry
+/// per example in schema order, where `pointer` locates the subschema (`#/properties/port`),
+/// `index` is the position in `examples` and `errors` are as for `validate`, with paths
+/// relative to the example. With the `snippets` option, entries also carry `yaml`, the
+/// example written as YAML, to paste into documentation.
+///
+/// @param {Object} schema - The JSON Schema to check
+/// @param {Object} options - `snippets`
+/// @returns {Object} - Whether every example is valid, and the result per example
+#[wasm_bindgen]
+pub fn verify_examples(schema: &JsValue, options: &JsValue) -> Result<JsValue, JsValue> {
+    let snippets = if options.is_undefined() || options.is_null() {
+        false
+    } else {
+        check_option_keys(options, &[&["snippets"]])?;
+        get_bool(options, "snippets")?.unwrap_or(false)
+    };
+    let root = schema_from_js(schema)?;
+    // The schema is walked as YAML to keep the key order of objects
+    let ordered = js_value_to_yaml(schema, &KeyStyle::Keep)?;
+    let mut examples = Vec::new();
+    collect_examples(&ordered, String::new(), &mut examples);
+
+    let entries = Array::new();
+    let mut all_valid = true;
+    for example in examples {
+        let mut errors = Vec::new();
+        let subschema = root
+            .pointer(&example.pointer)
+            .unwrap_or(&JsonValue::Bool(true));
+        match yaml_to_json(example.value) {
+            Ok(value) => validate_value(&value, subschema, &root, "$", &mut errors),
+            Err(message) => push_error(&mut errors, "$", code_of(&message), message),
+        }
+        all_valid &= errors.is_empty();
+
+        let entry = Object::from(validation_result(&errors));
+        let pointer = format!("#{}", example.pointer);
+        Reflect::set(&entry, &JsString::from("pointer"), &JsString::from(pointer))?;
+        Reflect::set(
+            &entry,
+            &JsString::from("keyword"),
+            &JsString::from(example.keyword),
+        )?;
+        if let Some(index) = example.index {
+            Reflect::set(
+                &entry,
+                &JsString::from("index"),
+                &JsValue::from(index as u32),
+            )?;
+        }
+        if snippets {
+            let yaml = dump_yaml(example.value).map_err(|e| JsValue::from_str(&e))?;
+            Reflect::set(&entry, &JsString::from("yaml"), &JsString::from(yaml))?;
+        }
+        entries.push(&entry);
+    }
+
+    let result = Object::new();
+    Reflect::set(&result, &JsString::from("valid"), &Boolean::from(all_valid))?;
+    Reflect::set(&result, &JsString::from("examples"), &entries)?;
     Ok(result.into())
 }
 
+/// Collect the examples of a subschema and of the subschemas it holds
+fn collect_examples<'a>(schema: &'a Yaml, pointer: String, examples: &mut Vec<SchemaExample<'a>>) {
+    if schema.as_hash().is_none() {
+        return;
+    }
+    for keyword in ["default", "example"] {
+        if !schema[keyword].is_badvalue() {
+            examples.push(SchemaExample {
+                pointer: pointer.clone(),
+                keyword,
+                index: None,
+                value: &schema[keyword],
+            });
+        }
+    }
+    for (index, value) in schema["examples"]
+        .as_vec()
+        .into_iter()
+        .flatten()
+        .enumerate()
+    {
+        examples.push(SchemaExample {
+            pointer: pointer.clone(),
+            keyword: "examples",
+            index: Some(index),
+            value,
+        });
+    }
+    for keyword in SCHEMA_MAPS {
+        for (name, subschema) in schema[keyword].as_hash().into_iter().flatten() {
+            if let Some(name) = name.as_str() {
+                let name = name.replace('~', "~0").replace('/', "~1");
+                collect_examples(
+                    subschema,
+                    format!("{}/{}/{}", pointer, keyword, name),
+                    examples,
+                );
+            }
+        }
+    }
+    for keyword in SCHEMA_KEYWORDS {
+        match &schema[keyword] {
+            Yaml::Array(subschemas) => {
+                for (i, subschema) in subschemas.iter().enumerate() {
+                    collect_examples(
+                        subschema,
+                        format!("{}/{}/{}", pointer, keyword, i),
+                        examples,
+                    );
+                }
+            }
+            subschema => collect_examples(subschema, format!("{}/{}", pointer, keyword), examples),
+        }
+    }
+}
+
+/// Validate documents, spreading the work over threads with the `parallel` feature
+#[cfg(all(feature = "parallel", not(target_arch = "wasm32")))]
+fn validate_documents(
+    docs: &[String],
+    schema: &JsonValue,
+    options: ValidateOptions,
+) -> Vec<Result<Vec<ValidationError>, String>> {
+    let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = docs.len().div_ceil(threads).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = docs
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|doc| validate_document(doc, schema, options))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("validation thread panicked"))
+            .collect()
+    })
+}
+
+/// Validate documents one after another
+#[cfg(not(all(feature = "parallel", not(target_arch = "wasm32"))))]
+fn validate_documents(
+    docs: &[String],
+    schema: &JsonValue,
+    options: ValidateOptions,
+) -> Vec<Result<Vec<ValidationError>, String>> {
+    docs.iter()
+        .map(|doc| validate_document(doc, schema, options))
+        .collect()
+}
+
+/// Parse a YAML document and validate its first document against a schema
+fn validate_document(
+    yaml: &str,
+    schema: &JsonValue,
+    options: ValidateOptions,
+) -> Result<Vec<ValidationError>, String> {
+    // Parse the YAML document
+    let (doc, diagnostics) = load_first_resolved(yaml, &options.limits)?
+        .ok_or_else(|| "Empty YAML document".to_string())?;
+    check_document(&doc, &diagnostics, schema, options)
+}
+
+/// Validate a document already parsed and kept by a `DocumentHandle`
+pub(crate) fn validate_resolved(
+    doc: &Yaml,
+    diagnostics: &[Diagnostic],
+    schema: &JsonValue,
+    options: &JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = ValidateOptions::from_js(options)?;
+    let errors =
+        check_document(doc, diagnostics, schema, options).map_err(|e| JsValue::from_str(&e))?;
+    Ok(validation_result(&errors))
+}
+
+/// Validate a resolved document against a schema
+fn check_document(
+    doc: &Yaml,
+    diagnostics: &[Diagnostic],
+    schema: &JsonValue,
+    options: ValidateOptions,
+) -> Result<Vec<ValidationError>, String> {
+    // Convert the YAML to JSON
+    let json_value =
+        yaml_to_json(doc).map_err(|e| format!("YAML to JSON conversion error: {}", e))?;
+
+    // Values that could not be resolved are validated as null and reported first
+    let mut errors: Vec<ValidationError> = diagnostics
+        .iter()
+        .map(|diagnostic| ValidationError {
+            code: code_of(&diagnostic.message),
+            message: diagnostic.message.clone(),
+            path: diagnostic.path.clone(),
+        })
+        .collect();
+    validate_value(&json_value, schema, schema, "$", &mut errors);
+    if options.decimal_comma_hints {
+        add_decimal_comma_hints(&json_value, &mut errors);
+    }
+    Ok(errors)
+}
+
+/// Explain type errors on strings that look like numbers written with locale separators
+fn add_decimal_comma_hints(value: &JsonValue, errors: &mut [ValidationError]) {
+    let mut hints = HashMap::new();
+    collect_decimal_comma_hints(value, "$", &mut hints);
+    if hints.is_empty() {
+        return;
+    }
+    for error in errors {
+        let expects_number = error
+            .message
+            .strip_prefix("expected ")
+            .and_then(|message| message.strip_suffix(", got string"))
+            .is_some_and(|expected| {
+                expected
+                    .split(" or ")
+                    .any(|name| name == "number" || name == "integer")
+            });
+        if let Some(hint) = hints.get(&error.path).filter(|_| expects_number) {
+            error.message.push_str(hint);
+        }
+    }
+}
+
+/// Collect hints for locale-formatted number strings by path, named like validation errors
+fn collect_decimal_comma_hints(value: &JsonValue, path: &str, hints: &mut HashMap<String, String>) {
+    match value {
+        JsonValue::Object(map) => {
+            for (key, item) in map {
+                collect_decimal_comma_hints(item, &format!("{}.{}", path, key), hints);
+            }
+        }
+        JsonValue::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_decimal_comma_hints(item, &format!("{}[{}]", path, i), hints);
+            }
+        }
+        JsonValue::String(text) => {
+            if let Some(hint) = decimal_comma_hint(text) {
+                hints.insert(path.to_string(), hint);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Describe a string that looks like a number written with a decimal comma (`1,5`,
+/// `1.000,5`) or digit grouping (`1,000,000`)
+fn decimal_comma_hint(text: &str) -> Option<String> {
+    let unsigned = text.strip_prefix(['-', '+']).unwrap_or(text);
+    let sign = if text.starts_with('-') { "-" } else { "" };
+    let groups: Vec<&str> = unsigned.split([',', '.']).collect();
+    if !unsigned.contains(',')
+        || groups
+            .iter()
+            .any(|group| group.is_empty() || !group.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+    // Every separator but the last one groups thousands
+    if groups.len() > 2
+        && (groups[0].len() > 3 || groups[1..groups.len() - 1].iter().any(|g| g.len() != 3))
+    {
+        return None;
+    }
+    let commas = unsigned.matches(',').count();
+    let dots = groups.len() - 1 - commas;
+    let last = unsigned.rfind([',', '.'])?;
+    let (whole, fraction) = (&unsigned[..last], &unsigned[last + 1..]);
+    let digits = |part: &str| part.replace([',', '.'], "");
+
+    let (kind, number) = match (&unsigned[last..=last], commas, dots) {
+        // `1,000` reads as one thousand with digit grouping and as one with a decimal comma
+        (",", 1, 0) if fraction.len() == 3 => {
+            return Some(format!(
+                " ('{}' looks like a number written with a decimal comma or digit grouping)",
+                text
+            ))
+        }
+        (",", 1, _) => (
+            "a decimal comma",
+            format!("{}{}.{}", sign, digits(whole), fraction),
+        ),
+        (".", _, 1) => (
+            "digit grouping",
+            format!("{}{}.{}", sign, digits(whole), fraction),
+        ),
+        (",", _, 0) if fraction.len() == 3 => {
+            ("digit grouping", format!("{}{}", sign, digits(unsigned)))
+        }
+        _ => return None,
+    };
+    Some(format!(
+        " ('{}' looks like a number written with {}; use {})",
+        text, kind, number
+    ))
+}
+
+/// Build the `{ valid, errors }` result object
+fn validation_result(errors: &[ValidationError]) -> JsValue {
+    let js_errors = Array::new();
+    for error in errors {
+        let js_error = Object::new();
+        let _ = Reflect::set(
+            &js_error,
+            &JsString::from("code"),
+            &JsString::from(error.code),
+        );
+        let _ = Reflect::set(
+            &js_error,
+            &JsString::from("message"),
+            &JsString::from(error.message.as_str()),
+        );
+        let _ = Reflect::set(
+            &js_error,
+            &JsString::from("path"),
+            &JsString::from(error.path.as_str()),
+        );
+        js_errors.push(&js_error);
+    }
+
+    let result = Object::new();
+    let _ = Reflect::set(
+        &result,
+        &JsString::from("valid"),
+        &Boolean::from(errors.is_empty()),
+    );
+    let _ = Reflect::set(&result, &JsString::from("errors"), &js_errors);
+    result.into()
+}
+
+/// Validate a JSON value against a (sub)schema, collecting every failure
+pub(crate) fn validate_value(
+    value: &JsonValue,
+    schema: &JsonValue,
+    root: &JsonValue,
+    path: &str,
+    errors: &mut Vec<ValidationError>,
+) {
+    let schema = match schema {
+        JsonValue::Bool(true) => return,
+        JsonValue::Bool(false) => {
+            push_error(
+                errors,
+                path,
+                "NOT_ALLOWED",
+                "value is not allowed".to_string(),
+            );
+            return;
+        }
+        JsonValue::Object(schema) => schema,
+        _ => return,
+    };
+
+    if let Some(JsonValue::String(reference)) = schema.get("$ref") {
+        match resolve_ref(root, reference) {
+            Some(target) => validate_value(value, target, root, path, errors),
+            None => push_error(
+                errors,
+                path,
+                "UNRESOLVABLE_REF",
+                format!("unresolvable $ref '{}'", reference),
+            ),
+        }
+    }
+
+    if let Some(expected) = schema.get("type") {
+        let matches = match expected {
+            JsonValue::String(name) => type_matches(value, name),
+            JsonValue::Array(names) => names
+                .iter()
+                .any(|name| name.as_str().is_some_and(|name| type_matches(value, name))),
+            _ => true,
+        };
+        if !matches {
+            push_error(
+                errors,
+                path,
+                "TYPE_MISMATCH",
+                format!("expected {}, got {}", type_list(expected), type_name(value)),
+            );
+        }
+    }
+
+    if let Some(JsonValue::Array(allowed)) = schema.get("enum") {
+        if !allowed
+            .iter()
+            .any(|candidate| json_equals(candidate, value))
+        {
+            push_error(
+                errors,
+                path,
+                "ENUM_MISMATCH",
+                "value is not one of the allowed values".to_string(),
+            );
+        }
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if !json_equals(expected, value) {
+            push_error(
+                errors,
+                path,
+                "CONST_MISMATCH",
+                format!("expected constant {}", expected),
+            );
+        }
+    }
+
+    match value {
+        JsonValue::Object(map) => {
+            if let Some(JsonValue::Array(required)) = schema.get("required") {
+                for name in required.iter().filter_map(JsonValue::as_str) {
+                    if !map.contains_key(name) {
+                        push_error(
+                            errors,
+                            path,
+                            "REQUIRED",
+                            format!("missing required property '{}'", name),
+                        );
+                    }
+                }
+            }
+            check_count(
+                errors,
+                path,
+                schema,
+                "minProperties",
+                "maxProperties",
+                map.len(),
+                "properties",
+            );
+
+            let properties = schema.get("properties").and_then(JsonValue::as_object);
+            for (key, item) in map {
+                let item_path = format!("{}.{}", path, key);
+                match properties.and_then(|properties| properties.get(key)) {
+                    Some(subschema) => validate_value(item, subschema, root, &item_path, errors),
+                    None => match schema.get("additionalProperties") {
+                        Some(JsonValue::Bool(false)) => push_error(
+                            errors,
+                            &item_path,
+                            "ADDITIONAL_PROPERTY",
+                            format!("additional property '{}' is not allowed", key),
+                        ),
+                        Some(subschema) => {
+                            validate_value(item, subschema, root, &item_path, errors)
+                        }
+                        None => {}
+                    },
+                }
+            }
+        }
+        JsonValue::Array(items) => {
+            check_count(
+                errors,
+                path,
+                schema,
+                "minItems",
+                "maxItems",
+                items.len(),
+                "items",
+            );
+
+            match schema.get("items") {
+                Some(JsonValue::Array(tuple)) => {
+                    for (i, (item, subschema)) in items.iter().zip(tuple).enumerate() {
+                        validate_value(item, subschema, root, &format!("{}[{}]", path, i), errors);
+                    }
+                }
+                Some(subschema) => {
+                    for (i, item) in items.iter().enumerate() {
+                        validate_value(item, subschema, root, &format!("{}[{}]", path, i), errors);
+                    }
+                }
+                None => {}
+            }
+
+            if schema.get("uniqueItems") == Some(&JsonValue::Bool(true)) {
+                let duplicated = items
+                    .iter()
+                    .enumerate()
+                    .any(|(i, item)| items[..i].iter().any(|other| json_equals(item, other)));
+                if duplicated {
+                    push_error(
+                        errors,
+                        path,
+                        "UNIQUE_ITEMS",
+                        "array items are not unique".to_string(),
+                    );
+                }
+            }
+        }
+        JsonValue::String(s) => {
+            let length = s.chars().count();
+            check_count(
+                errors,
+                path,
+                schema,
+                "minLength",
+                "maxLength",
+                length,
+                "characters",
+            );
+            if let Some(JsonValue::String(format)) = schema.get("format") {
+                if check_format(format, s) == Some(false) {
+                    push_error(
+                        errors,
+                        path,
+                        "FORMAT",
+                        format!("string is not a valid {}", format),
+                    );
+                }
+            }
+        }
+        JsonValue::Number(n) => {
+            if let Some(n) = n.as_f64() {
+                check_number(errors, path, schema, n);
+            }
+        }
+        _ => {}
+    }
+
+    if let Some(JsonValue::Array(subschemas)) = schema.get("allOf") {
+        for subschema in subschemas {
+            validate_value(value, subschema, root, path, errors);
+        }
+    }
+
+    if let Some(JsonValue::Array(subschemas)) = schema.get("anyOf") {
+        let any_valid = subschemas
+            .iter()
+            .any(|subschema| is_valid(value, subschema, root, path));
+        if !any_valid {
+            push_error(
+                errors,
+                path,
+                "ANY_OF",
+                "value does not match any schema in anyOf".to_string(),
+            );
+        }
+    }
+
+    if let Some(JsonValue::Array(subschemas)) = schema.get("oneOf") {
+        let matched = subschemas
+            .iter()
+            .filter(|subschema| is_valid(value, subschema, root, path))
+            .count();
+        if matched != 1 {
+            push_error(
+                errors,
+                path,
+                "ONE_OF",
+                format!(
+                    "value matches {} schemas in oneOf, expected exactly 1",
+                    matched
+                ),
+            );
+        }
+    }
+
+    if let Some(subschema) = schema.get("not") {
+        if is_valid(value, subschema, root, path) {
+            push_error(
+                errors,
+                path,
+                "NOT",
+                "value must not match the schema in not".to_string(),
+            );
+        }
+    }
+}
+
+fn is_valid(value: &JsonValue, schema: &JsonValue, root: &JsonValue, path: &str) -> bool {
+    let mut errors = Vec::new();
+    validate_value(value, schema, root, path, &mut errors);
+    errors.is_empty()
+}
+
+fn push_error(errors: &mut Vec<ValidationError>, path: &str, code: &'static str, message: String) {
+    errors.push(ValidationError {
+        code,
+        message,
+        path: path.to_string(),
+    });
+}
+
+/// Check a `min*`/`max*` keyword pair against a count
+fn check_count(
+    errors: &mut Vec<ValidationError>,
+    path: &str,
+    schema: &serde_json::Map<String, JsonValue>,
+    min_keyword: &str,
+    max_keyword: &str,
+    count: usize,
+    unit: &str,
+) {
+    if let Some(min) = schema.get(min_keyword).and_then(JsonValue::as_u64) {
+        if (count as u64) < min {
+            push_error(
+                errors,
+                path,
+                keyword_code(min_keyword),
+                format!("expected at least {} {}, got {}", min, unit, count),
+            );
+        }
+    }
+    if let Some(max) = schema.get(max_keyword).and_then(JsonValue::as_u64) {
+        if (count as u64) > max {
+            push_error(
+                errors,
+                path,
+                keyword_code(max_keyword),
+                format!("expected at most {} {}, got {}", max, unit, count),
+            );
+        }
+    }
+}
+
+/// The code of a failed `min*`/`max*` keyword (`minItems` is `MIN_ITEMS`)
+fn keyword_code(keyword: &str) -> &'static str {
+    match keyword {
+        "minProperties" => "MIN_PROPERTIES",
+        "maxProperties" => "MAX_PROPERTIES",
+        "minItems" => "MIN_ITEMS",
+        "maxItems" => "MAX_ITEMS",
+        "minLength" => "MIN_LENGTH",
+        "maxLength" => "MAX_LENGTH",
+        _ => "VALIDATION_ERROR",
+    }
+}
+
+/// Check the numeric range keywords
+fn check_number(
+    errors: &mut Vec<ValidationError>,
+    path: &str,
+    schema: &serde_json::Map<String, JsonValue>,
+    n: f64,
+) {
+    let keyword = |name: &str| schema.get(name).and_then(JsonValue::as_f64);
+    if let Some(minimum) = keyword("minimum") {
+        if n < minimum {
+            push_error(
+                errors,
+                path,
+                "MINIMUM",
+                format!("{} is less than the minimum of {}", n, minimum),
+            );
+        }
+    }
+    if let Some(maximum) = keyword("maximum") {
+        if n > maximum {
+            push_error(
+                errors,
+                path,
+                "MAXIMUM",
+                format!("{} is greater than the maximum of {}", n, maximum),
+            );
+        }
+    }
+    if let Some(minimum) = keyword("exclusiveMinimum") {
+        if n <= minimum {
+            push_error(
+                errors,
+                path,
+                "EXCLUSIVE_MINIMUM",
+                format!("{} must be greater than {}", n, minimum),
+            );
+        }
+    }
+    if let Some(maximum) = keyword("exclusiveMaximum") {
+        if n >= maximum {
+            push_error(
+                errors,
+                path,
+                "EXCLUSIVE_MAXIMUM",
+                format!("{} must be less than {}", n, maximum),
+            );
+        }
+    }
+    if let Some(divisor) = keyword("multipleOf") {
+        if divisor > 0.0
+            && ((n / divisor).round() * divisor - n).abs() > f64::EPSILON * n.abs().max(1.0)
+        {
+            push_error(
+                errors,
+                path,
+                "MULTIPLE_OF",
+                format!("{} is not a multiple of {}", n, divisor),
+            );
+        }
+    }
+}
+
+/// Resolve a local `$ref` (`#` or `#/json/pointer`)
+pub(crate) fn resolve_ref<'a>(root: &'a JsonValue, reference: &str) -> Option<&'a JsonValue> {
+    let pointer = reference.strip_prefix('#')?;
+    root.pointer(pointer)
+}
+
+fn type_matches(value: &JsonValue, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value
+            .as_f64()
+            .is_some_and(|n| value.is_i64() || value.is_u64() || n.fract() == 0.0),
+        _ => false,
+    }
+}
+
+/// Format the `type` keyword for messages (`string or null`)
+fn type_list(expected: &JsonValue) -> String {
+    match expected {
+        JsonValue::Array(names) => names
+            .iter()
+            .filter_map(JsonValue::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        JsonValue::String(name) => name.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn type_name(value: &JsonValue) -> &'static str {
+    match value {
+        JsonValue::Null => "null",
+        JsonValue::Bool(_) => "boolean",
+        JsonValue::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        JsonValue::Number(_) => "number",
+        JsonValue::String(_) => "string",
+        JsonValue::Array(_) => "array",
+        JsonValue::Object(_) => "object",
+    }
+}
+
+/// JSON Schema equality: numbers compare by value, so `1` equals `1.0`
+fn json_equals(a: &JsonValue, b: &JsonValue) -> bool {
+    match (a, b) {
+        (JsonValue::Number(x), JsonValue::Number(y)) => x.as_f64() == y.as_f64(),
+        (JsonValue::Array(x), JsonValue::Array(y)) => {
+            x.len() == y.len() && x.iter().zip(y).all(|(x, y)| json_equals(x, y))
+        }
+        (JsonValue::Object(x), JsonValue::Object(y)) => {
+            x.len() == y.len()
+                && x.iter()
+                    .all(|(key, x)| y.get(key).is_some_and(|y| json_equals(x, y)))
+        }
+        _ => a == b,
+    }
+}
+
 /// Convert YAML to JSON
-fn yaml_to_json(yaml: &yaml_rust2::Yaml) -> Result<JsonValue, String> {
+pub(crate) fn yaml_to_json(yaml: &yaml_rust2::Yaml) -> Result<JsonValue, String> {
     match yaml {
         yaml_rust2::Yaml::Null => Ok(JsonValue::Null),
         yaml_rust2::Yaml::Boolean(b) => Ok(JsonValue::Bool(*b)),
@@ -0,0 +1,711 @@
+//! JavaScript value construction
+//!
+//! This module converts loaded YAML nodes directly into JavaScript values, for output that
+//! JSON cannot represent, such as mappings returned as `Map` (`mapAsMap`, `complexKeys: 'map'`),
+//! `!!set` mappings returned as `Set` (`setAsSet`), `!!binary` scalars decoded to `Uint8Array`
+//! (`decodeBinary`), timestamps resolved to `Date` (`timestamps`), scalars converted by a
+//! `reviver` callback and collection members passed to a `JSON.parse`-style `jsonReviver`. It
+//! also builds truncated previews of large documents.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::mem;
+
+use js_sys::{Array, BigInt, Date, Function, Map, Object, Reflect, Set, Uint8Array};
+use wasm_bindgen::prelude::*;
+use yaml_rust2::scanner::TScalarStyle;
+use yaml_rust2::Yaml;
+
+use crate::json::key_to_string;
+use crate::loader::{error_at, Node, NodeKind};
+use crate::options::{ComplexKeys, FloatMode, LoadOptions, PreviewLimits};
+use crate::resolve::{Pairs, Resolver, MAX_SAFE_INTEGER};
+use crate::schema::CORE_TAG_HANDLE;
+use crate::types::CustomType;
+
+/// Most distinct mapping keys kept as JavaScript strings during a conversion
+const MAX_INTERNED_KEYS: usize = 4096;
+
+/// Longest mapping key, in bytes, kept as a JavaScript string during a conversion
+const MAX_INTERNED_KEY_LEN: usize = 64;
+
+/// Builds JavaScript values from YAML node trees
+pub(crate) struct ValueBuilder<'a> {
+    resolver: Resolver<'a>,
+    // JavaScript strings of the mapping keys seen so far, so repeated keys (`name`, `image`)
+    // are copied across the boundary once
+    keys: HashMap<String, JsValue>,
+    // Path of the current node (e.g. `$.servers[0].port`), tracked only for the reviver
+    path: Option<String>,
+    // Exception thrown by the reviver, rethrown as is
+    thrown: Option<JsValue>,
+    // Size limits and the values cut to them (`parse_preview`)
+    preview: Option<PreviewLimits>,
+    truncated: Vec<Truncation>,
+    // Depth past which collections are left as handles (`parse_lazy`)
+    depth_limit: Option<DepthLimit>,
+}
+
+/// Collections deeper than `max_depth` below the converted node become handles
+struct DepthLimit {
+    max_depth: usize,
+    // Child indexes from the document root to the current node, and to the converted node
+    steps: Vec<usize>,
+    base: usize,
+    // Id of the first handle created
+    first_handle: usize,
+    // Steps of the collections left as handles, by id from `first_handle`
+    handles: Vec<Vec<usize>>,
+}
+
+/// A value cut to the preview limits
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Truncation {
+    /// Location of the value (e.g. `$.items`)
+    pub path: String,
+    /// `string` or `array`
+    pub kind: &'static str,
+    /// Length before truncation, in characters or items
+    pub length: usize,
+}
+
+impl<'a> ValueBuilder<'a> {
+    pub fn new(options: &'a LoadOptions) -> Self {
+        ValueBuilder {
+            resolver: Resolver::new(options),
+            keys: HashMap::new(),
+            path: None,
+            thrown: None,
+            preview: None,
+            truncated: Vec::new(),
+            depth_limit: None,
+        }
+    }
+
+    /// Leave collections more than `max_depth` levels below the converted node as
+    /// `{ $handle, kind }` objects, numbering handles from `first_handle`
+    pub fn with_depth_limit(mut self, max_depth: usize, first_handle: usize) -> Self {
+        self.depth_limit = Some(DepthLimit {
+            max_depth,
+            steps: Vec::new(),
+            base: 0,
+            first_handle,
+            handles: Vec::new(),
+        });
+        self
+    }
+
+    /// Start from the mapping key strings kept from an earlier conversion
+    pub fn with_keys(mut self, keys: HashMap<String, JsValue>) -> Self {
+        self.keys = keys;
+        self
+    }
+
+    /// Take the mapping key strings, to reuse in a later conversion
+    pub fn take_keys(&mut self) -> HashMap<String, JsValue> {
+        mem::take(&mut self.keys)
+    }
+
+    /// Take the steps of the handles created so far, in id order
+    pub fn take_handles(&mut self) -> Vec<Vec<usize>> {
+        match &mut self.depth_limit {
+            Some(limit) => {
+                limit.first_handle += limit.handles.len();
+                mem::take(&mut limit.handles)
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Cut long strings and sequences to the given limits, recording where
+    pub fn with_preview(mut self, limits: PreviewLimits) -> Self {
+        self.preview = Some(limits);
+        self
+    }
+
+    /// Take the values truncated so far
+    pub fn take_truncated(&mut self) -> Vec<Truncation> {
+        mem::take(&mut self.truncated)
+    }
+
+    /// The JavaScript string of a mapping key, created once per distinct short key
+    fn key_string(&mut self, key: &str) -> JsValue {
+        if let Some(name) = self.keys.get(key) {
+            return name.clone();
+        }
+        let name = JsValue::from_str(key);
+        if key.len() <= MAX_INTERNED_KEY_LEN && self.keys.len() < MAX_INTERNED_KEYS {
+            self.keys.insert(key.to_string(), name.clone());
+        }
+        name
+    }
+
+    /// Convert a document to a JavaScript value
+    pub fn build_document(&mut self, node: &Node) -> Result<JsValue, JsValue> {
+        let value = self.build_at(node, Vec::new(), "$")?;
+        match &self.resolver.options.json_reviver {
+            // Like `JSON.parse`, the root is revived last, under the empty key of a holder
+            Some(reviver) => {
+                let holder = Object::new();
+                Reflect::set(&holder, &JsValue::from_str(""), &value)?;
+                let revived = reviver.call2(&holder, &JsValue::from_str(""), &value)?;
+                let options = self.resolver.options;
+                self.cloneable(revived, &value, "jsonReviver")
+                    .map_err(|e| options.error(&e))
+            }
+            None => Ok(value),
+        }
+    }
+
+    /// Convert a node found at `steps` (child indexes from the document root) and `path`
+    pub fn build_at(
+        &mut self,
+        node: &Node,
+        steps: Vec<usize>,
+        path: &str,
+    ) -> Result<JsValue, JsValue> {
+        self.resolver.start_document();
+        if self.resolver.options.reviver.is_some() || self.preview.is_some() {
+            self.path = Some(path.to_string());
+        }
+        if let Some(limit) = &mut self.depth_limit {
+            limit.base = steps.len();
+            limit.steps = steps;
+        }
+        let options = self.resolver.options;
+        self.build(node)
+            .map_err(|e| self.thrown.take().unwrap_or_else(|| options.error(&e)))
+    }
+
+    /// Take the warnings collected so far
+    pub fn take_warnings(&mut self) -> Vec<String> {
+        self.resolver.take_warnings()
+    }
+
+    fn build(&mut self, node: &Node) -> Result<JsValue, String> {
+        self.resolver.count_expanded()?;
+        self.resolver.charge(node)?;
+        self.resolver.descend(node)?;
+        let value = self.build_tagged(node);
+        self.resolver.ascend(node);
+        value
+    }
+
+    /// Convert a node with its registered type, or in a `{ tag, value }` wrapper for a kept
+    /// local tag
+    fn build_tagged(&mut self, node: &Node) -> Result<JsValue, String> {
+        if let Some(custom) = self.resolver.custom_type(node) {
+            return self.construct(custom, node);
+        }
+        if let Some(tag) = self.resolver.wrapped_tag(node) {
+            let wrapper = Object::new();
+            let value = self.build_contents(node)?;
+            for (key, value) in [("tag", JsValue::from_str(&tag)), ("value", value)] {
+                Reflect::set(&wrapper, &JsValue::from_str(key), &value)
+                    .map_err(|_| "Failed to set property".to_string())?;
+            }
+            return Ok(wrapper.into());
+        }
+        self.build_contents(node)
+    }
+
+    /// Convert a node with the schema and options, ignoring registered types
+    fn build_contents(&mut self, node: &Node) -> Result<JsValue, String> {
+        match &node.kind {
+            NodeKind::Scalar(value, style) => {
+                let (js_value, kind) = self.build_scalar(node, value, *style)?;
+                let options = self.resolver.options;
+                match &options.reviver {
+                    Some(reviver) => self.revive(reviver, node, value, js_value, kind),
+                    None => Ok(js_value),
+                }
+            }
+            NodeKind::Sequence(_) | NodeKind::Mapping(_) if self.at_depth_limit() => {
+                self.handle(node)
+            }
+            NodeKind::Sequence(items) => {
+                if let Some(pairs) = self.resolver.pairs(node, items)? {
+                    return self.build_pairs(&pairs);
+                }
+                self.resolver
+                    .warn(self.resolver.collection_warning(node, "seq"), &node.mark);
+                let items = match self.preview {
+                    Some(limits) if items.len() > limits.max_items => {
+                        self.truncate("array", items.len());
+                        &items[..limits.max_items]
+                    }
+                    _ => items,
+                };
+                let array = Array::new_with_length(items.len() as u32);
+                for (i, item) in items.iter().enumerate() {
+                    let len = self.push_path(i, format_args!("[{}]", i));
+                    array.set(i as u32, self.build(item)?);
+                    self.pop_path(len);
+                }
+                self.revive_array(&array)?;
+                Ok(array.into())
+            }
+            NodeKind::Mapping(entries) => {
+                let mapping = self.resolver.mapping(node, entries)?;
+                let options = self.resolver.options;
+                if options.set_as_set && mapping.is_set {
+                    let set = Set::new(&JsValue::UNDEFINED);
+                    for (_, key, _) in mapping.properties() {
+                        set.add(&resolved_to_js(key, options.use_big_int)?);
+                    }
+                    return Ok(set.into());
+                }
+                if options.map_as_map
+                    || (options.complex_keys == ComplexKeys::Map && mapping.has_complex_keys())
+                {
+                    let map = Map::new();
+                    for (n, (_, key, value)) in mapping.properties().enumerate() {
+                        let segment = match self.path {
+                            Some(_) => key_to_string(key)?,
+                            None => String::new(),
+                        };
+                        let len = self.push_path(n, format_args!(".{}", segment));
+                        map.set(
+                            &resolved_to_js(key, options.use_big_int)?,
+                            &self.build(value)?,
+                        );
+                        self.pop_path(len);
+                    }
+                    self.revive_map(&map)?;
+                    return Ok(map.into());
+                }
+                let object = Object::new();
+                for (n, (_, key, value)) in mapping.properties().enumerate() {
+                    let key = match key {
+                        Yaml::String(key) => Cow::Borrowed(key.as_str()),
+                        key => Cow::Owned(key_to_string(key)?),
+                    };
+                    let len = self.push_path(n, format_args!(".{}", key));
+                    let value = self.build(value)?;
+                    self.pop_path(len);
+                    let name = self.key_string(&key);
+                    set_property(&object, &key, &name, &value)
+                        .map_err(|_| "Failed to set property".to_string())?;
+                }
+                self.revive_object(&object)?;
+                Ok(object.into())
+            }
+            NodeKind::Alias(target) => {
+                let outer = self.resolver.enter_alias(node)?;
+                let value = self.build(target);
+                self.resolver.leave_alias(outer);
+                value
+            }
+        }
+    }
+
+    /// Convert a node with a registered type: its string, array or object goes through the
+    /// type's `resolveFn` and `constructFn`
+    fn construct(&mut self, custom: &CustomType, node: &Node) -> Result<JsValue, String> {
+        let data = match &node.kind {
+            NodeKind::Scalar(value, _) => JsValue::from_str(value),
+            _ => self.build_contents(node)?,
+        };
+        if let Some(resolve) = &custom.resolve {
+            let accepted = resolve.call1(&JsValue::NULL, &data).map_err(|e| {
+                self.thrown = Some(e);
+                "resolveFn failed".to_string()
+            })?;
+            if !accepted.is_truthy() {
+                let what = match &node.kind {
+                    NodeKind::Scalar(value, _) => format!("'{}'", value),
+                    NodeKind::Sequence(_) => "a sequence".to_string(),
+                    _ => "a mapping".to_string(),
+                };
+                return Err(error_at(
+                    &format!("cannot resolve {} as {}", what, custom.name()),
+                    &node.mark,
+                ));
+            }
+        }
+        match &custom.construct {
+            Some(construct) => {
+                let value = construct.call1(&JsValue::NULL, &data).map_err(|e| {
+                    self.thrown = Some(e);
+                    "constructFn failed".to_string()
+                })?;
+                self.cloneable(value, &data, "constructFn")
+                    .map_err(|e| error_at(&e, &node.mark))
+            }
+            None => Ok(data),
+        }
+    }
+
+    /// Convert `!!omap` and `!!pairs` entries to an array of `[key, value]` arrays, or an
+    /// `!!omap` to a `Map` with `mapAsMap`
+    fn build_pairs(&mut self, pairs: &Pairs) -> Result<JsValue, String> {
+        let mut built = Vec::with_capacity(pairs.entries.len());
+        for (i, (key, value)) in pairs.entries.iter().enumerate() {
+            let len = self.push_path(2 * i, format_args!("[{}][0]", i));
+            let key = self.build(key)?;
+            self.pop_path(len);
+            let len = self.push_path(2 * i + 1, format_args!("[{}][1]", i));
+            let value = self.build(value)?;
+            self.pop_path(len);
+            built.push((key, value));
+        }
+        if pairs.is_omap && self.resolver.options.map_as_map {
+            let map = Map::new();
+            for (key, value) in &built {
+                map.set(key, value);
+            }
+            self.revive_map(&map)?;
+            return Ok(map.into());
+        }
+        let array = Array::new_with_length(built.len() as u32);
+        for (i, (key, value)) in built.iter().enumerate() {
+            let pair = Array::of2(key, value);
+            self.revive_array(&pair)?;
+            array.set(i as u32, pair.into());
+        }
+        self.revive_array(&array)?;
+        Ok(array.into())
+    }
+
+    /// Convert a scalar node, returning the value and the suffix of its resolved core tag
+    fn build_scalar(
+        &mut self,
+        node: &Node,
+        value: &str,
+        style: TScalarStyle,
+    ) -> Result<(JsValue, &'static str), String> {
+        if let Some(bytes) = self.resolver.binary(node, value)? {
+            return Ok((Uint8Array::from(bytes.as_slice()).into(), "binary"));
+        }
+        if let Some(ms) = self.resolver.timestamp(node, value, style)? {
+            return Ok((Date::new(&JsValue::from_f64(ms)).into(), "timestamp"));
+        }
+        let mut scalar = self.resolver.scalar(node, value, style)?;
+        if let (Some(limits), Yaml::String(s)) = (self.preview, &mut scalar) {
+            if let Some((end, _)) = s.char_indices().nth(limits.max_string_length) {
+                let length = s.chars().count();
+                s.truncate(end);
+                self.truncate("string", length);
+            }
+        }
+        let kind = match scalar {
+            Yaml::Null => "null",
+            Yaml::Boolean(_) => "bool",
+            Yaml::Integer(_) => "int",
+            Yaml::Real(_) => "float",
+            _ => "str",
+        };
+        Ok((self.scalar(&scalar)?, kind))
+    }
+
+    /// Pass a scalar to the reviver as `reviver(value, { path, tag, source })`
+    ///
+    /// The tag is the explicit tag of the node or the core tag it resolved to; returning
+    /// `undefined` keeps the value.
+    fn revive(
+        &mut self,
+        reviver: &Function,
+        node: &Node,
+        source: &str,
+        value: JsValue,
+        kind: &str,
+    ) -> Result<JsValue, String> {
+        let tag = match &node.tag {
+            Some(tag) => format!("{}{}", tag.handle, tag.suffix),
+            None => format!("{}{}", CORE_TAG_HANDLE, kind),
+        };
+        let context = Object::new();
+        for (name, field) in [
+            ("path", self.path.as_deref().unwrap_or("$")),
+            ("tag", &tag),
+            ("source", source),
+        ] {
+            Reflect::set(
+                &context,
+                &JsValue::from_str(name),
+                &JsValue::from_str(field),
+            )
+            .map_err(|_| "Failed to set property".to_string())?;
+        }
+        match reviver.call2(&JsValue::NULL, &value, &context) {
+            Ok(revived) if revived.is_undefined() => Ok(value),
+            Ok(revived) => self
+                .cloneable(revived, &value, "reviver")
+                .map_err(|e| error_at(&e, &node.mark)),
+            Err(e) => {
+                self.thrown = Some(e);
+                Err("reviver failed".to_string())
+            }
+        }
+    }
+
+    /// Call the `jsonReviver` as `reviver.call(holder, key, value)`
+    fn call_json_reviver(
+        &mut self,
+        holder: &JsValue,
+        key: &JsValue,
+        value: &JsValue,
+    ) -> Result<Option<JsValue>, String> {
+        let Some(reviver) = &self.resolver.options.json_reviver else {
+            return Ok(None);
+        };
+        match reviver.call2(holder, key, value) {
+            Ok(revived) => Ok(Some(self.cloneable(revived, value, "jsonReviver")?)),
+            Err(e) => {
+                self.thrown = Some(e);
+                Err("jsonReviver failed".to_string())
+            }
+        }
+    }
+
+    /// Check a value returned by a callback with `cloneable`, unless it is the value given
+    fn cloneable(
+        &self,
+        value: JsValue,
+        given: &JsValue,
+        callback: &str,
+    ) -> Result<JsValue, String> {
+        if !self.resolver.options.cloneable || Object::is(&value, given) {
+            return Ok(value);
+        }
+        match uncloneable(&value) {
+            Some(what) => Err(format!(
+                "{} returned by {} cannot be cloned (cloneable)",
+                what, callback
+            )),
+            None => Ok(value),
+        }
+    }
+
+    /// Revive the items of a built array by index (`"0"`), deleting those revived to
+    /// `undefined` like `JSON.parse` does
+    fn revive_array(&mut self, array: &Array) -> Result<(), String> {
+        for i in 0..array.length() {
+            let key = JsValue::from_str(&i.to_string());
+            match self.call_json_reviver(array, &key, &array.get(i))? {
+                None => return Ok(()),
+                Some(revived) if revived.is_undefined() => {
+                    Reflect::delete_property(array, &JsValue::from(i))
+                        .map_err(|_| "Failed to delete property".to_string())?;
+                }
+                Some(revived) => array.set(i, revived),
+            }
+        }
+        Ok(())
+    }
+
+    /// Revive the properties of a built object, dropping those revived to `undefined`
+    fn revive_object(&mut self, object: &Object) -> Result<(), String> {
+        for key in Object::keys(object).iter() {
+            let value = Reflect::get(object, &key).unwrap_or_default();
+            let result = match self.call_json_reviver(object, &key, &value)? {
+                None => return Ok(()),
+                Some(revived) if revived.is_undefined() => Reflect::delete_property(object, &key),
+                Some(revived) => Reflect::set(object, &key, &revived),
+            };
+            result.map_err(|_| "Failed to set property".to_string())?;
+        }
+        Ok(())
+    }
+
+    /// Revive the entries of a built `Map`, keyed by their typed keys
+    fn revive_map(&mut self, map: &Map) -> Result<(), String> {
+        for entry in Array::from(map).iter() {
+            let entry = Array::from(&entry);
+            let key = entry.get(0);
+            match self.call_json_reviver(map, &key, &entry.get(1))? {
+                None => return Ok(()),
+                Some(revived) if revived.is_undefined() => {
+                    map.delete(&key);
+                }
+                Some(revived) => {
+                    map.set(&key, &revived);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Record that the current value was truncated
+    fn truncate(&mut self, kind: &'static str, length: usize) {
+        self.truncated.push(Truncation {
+            path: self.path.clone().unwrap_or_default(),
+            kind,
+            length,
+        });
+    }
+
+    /// Move to a child of the current node given its index (`!!pairs` key and value count as
+    /// two) and path segment, returning the previous path length
+    fn push_path(&mut self, step: usize, segment: std::fmt::Arguments) -> usize {
+        if let Some(limit) = &mut self.depth_limit {
+            limit.steps.push(step);
+        }
+        let Some(path) = &mut self.path else {
+            return 0;
+        };
+        let len = path.len();
+        let _ = path.write_fmt(segment);
+        len
+    }
+
+    fn pop_path(&mut self, len: usize) {
+        if let Some(limit) = &mut self.depth_limit {
+            limit.steps.pop();
+        }
+        if let Some(path) = &mut self.path {
+            path.truncate(len);
+        }
+    }
+
+    fn at_depth_limit(&self) -> bool {
+        self.depth_limit
+            .as_ref()
+            .is_some_and(|limit| limit.steps.len() - limit.base >= limit.max_depth)
+    }
+
+    /// Leave a collection as a `{ $handle, kind }` object
+    fn handle(&mut self, node: &Node) -> Result<JsValue, String> {
+        let Some(limit) = &mut self.depth_limit else {
+            return Err("No depth limit".to_string());
+        };
+        let id = limit.first_handle + limit.handles.len();
+        limit.handles.push(limit.steps.clone());
+        let kind = match node.kind {
+            NodeKind::Sequence(_) => "sequence",
+            _ => "mapping",
+        };
+        let handle = Object::new();
+        Reflect::set(
+            &handle,
+            &JsValue::from_str("$handle"),
+            &JsValue::from_f64(id as f64),
+        )
+        .map_err(|_| "Failed to set property".to_string())?;
+        Reflect::set(
+            &handle,
+            &JsValue::from_str("kind"),
+            &JsValue::from_str(kind),
+        )
+        .map_err(|_| "Failed to set property".to_string())?;
+        Ok(handle.into())
+    }
+
+    /// Convert a resolved scalar honoring `floatPrecision` and `floatMode`
+    fn scalar(&self, yaml: &Yaml) -> Result<JsValue, String> {
+        let Yaml::Real(s) = yaml else {
+            return resolved_to_js(yaml, self.resolver.options.use_big_int);
+        };
+        let f = yaml
+            .as_f64()
+            .ok_or_else(|| format!("Invalid float: {}", s))?;
+        let f = JsValue::from_f64(self.resolver.float(f)?);
+        if self.resolver.options.float_mode != FloatMode::Wrap {
+            return Ok(f);
+        }
+        let wrapped = Object::new();
+        Reflect::set(&wrapped, &JsValue::from_str("$float"), &f)
+            .map_err(|_| "Failed to set property".to_string())?;
+        Ok(wrapped.into())
+    }
+}
+
+/// Convert a resolved value, keeping the type of mapping keys
+///
+/// With `use_big_int`, integers a `Number` cannot hold exactly become `BigInt`.
+fn resolved_to_js(yaml: &Yaml, use_big_int: bool) -> Result<JsValue, String> {
+    match yaml {
+        Yaml::Null => Ok(JsValue::NULL),
+        Yaml::Boolean(b) => Ok(JsValue::from_bool(*b)),
+        Yaml::Integer(i) if use_big_int && i.unsigned_abs() > MAX_SAFE_INTEGER => {
+            Ok(BigInt::from(*i).into())
+        }
+        Yaml::Integer(i) => Ok(JsValue::from_f64(*i as f64)),
+        Yaml::Real(s) => yaml
+            .as_f64()
+            .map(JsValue::from_f64)
+            .ok_or_else(|| format!("Invalid float: {}", s)),
+        Yaml::String(s) => Ok(JsValue::from_str(s)),
+        Yaml::Array(items) => {
+            let array = Array::new_with_length(items.len() as u32);
+            for (i, item) in items.iter().enumerate() {
+                array.set(i as u32, resolved_to_js(item, use_big_int)?);
+            }
+            Ok(array.into())
+        }
+        Yaml::Hash(hash) => {
+            let map = Map::new();
+            for (key, value) in hash {
+                map.set(
+                    &resolved_to_js(key, use_big_int)?,
+                    &resolved_to_js(value, use_big_int)?,
+                );
+            }
+            Ok(map.into())
+        }
+        _ => Err("Invalid YAML value".to_string()),
+    }
+}
+
+/// Create a property the way `JSON.parse` does, so a `__proto__` key becomes an own property
+/// rather than replacing the prototype
+fn set_property(
+    object: &Object,
+    key: &str,
+    name: &JsValue,
+    value: &JsValue,
+) -> Result<bool, JsValue> {
+    if key != "__proto__" {
+        return Reflect::set(object, name, value);
+    }
+    let descriptor = Object::new();
+    Reflect::set(&descriptor, &"value".into(), value)?;
+    for flag in ["writable", "enumerable", "configurable"] {
+        Reflect::set(&descriptor, &flag.into(), &JsValue::TRUE)?;
+    }
+    Reflect::define_property(object, name, &descriptor)
+}
+
+/// What in a value is not a plain object, array or primitive, if anything
+///
+/// `cloneable` output holds nothing else, so functions, symbols, `BigInt`s and instances of
+/// classes (`Date`, `Map`, `Set`, typed arrays included) are reported.
+fn uncloneable(value: &JsValue) -> Option<String> {
+    let object_prototype = Object::get_prototype_of(&Object::new());
+    let seen = Set::new(&JsValue::UNDEFINED);
+    let mut pending = vec![value.clone()];
+    while let Some(value) = pending.pop() {
+        if value.is_function() {
+            return Some("a function".to_string());
+        }
+        if value.is_symbol() {
+            return Some("a symbol".to_string());
+        }
+        if value.is_bigint() {
+            return Some("a BigInt".to_string());
+        }
+        if !value.is_object() || seen.has(&value) {
+            continue;
+        }
+        seen.add(&value);
+        if Array::is_array(&value) {
+            pending.extend(Array::from(&value).iter());
+            continue;
+        }
+        let prototype = Object::get_prototype_of(&value);
+        if !prototype.is_null() && !Object::is(&prototype, &object_prototype) {
+            let name = Reflect::get(&prototype, &JsValue::from_str("constructor"))
+                .ok()
+                .and_then(|constructor| constructor.dyn_into::<Function>().ok())
+                .map(|constructor| String::from(constructor.name()))
+                .filter(|name| !name.is_empty());
+            return Some(match name {
+                Some(name) => format!("an instance of {}", name),
+                None => "an object with a prototype".to_string(),
+            });
+        }
+        pending.extend(Object::values(value.unchecked_ref()).iter());
+    }
+    None
+}
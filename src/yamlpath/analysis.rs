@@ -0,0 +1,426 @@
+//! YAMLPath static analysis
+//!
+//! This module estimates, without any document, how many values a YAMLPath expression can
+//! select from documents valid against a JSON Schema.
+
+use std::ptr;
+
+use serde_json::{Map, Value as JsonValue};
+
+use super::types::PathExpr;
+use crate::validate::resolve_ref;
+
+/// Schema accepting any value, used where a schema says nothing
+static ANY: JsonValue = JsonValue::Bool(true);
+
+/// Maximum `$ref` indirections followed before giving up on a schema
+const MAX_REF_DEPTH: usize = 32;
+
+/// Result of analyzing a path against a schema
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selectivity {
+    /// Upper bound on the number of matches; `None` when unbounded
+    pub max_matches: Option<u64>,
+    /// First segment that rules out every match, if any
+    pub blocked_at: Option<String>,
+}
+
+impl Selectivity {
+    /// Short description of the bound: `none`, `atMostOne`, `bounded` or `unbounded`
+    pub fn kind(&self) -> &'static str {
+        match self.max_matches {
+            Some(0) => "none",
+            Some(1) => "atMostOne",
+            Some(_) => "bounded",
+            None => "unbounded",
+        }
+    }
+}
+
+/// Possible child schemas of a node and an upper bound on how many children it yields
+struct Step<'s> {
+    schemas: Vec<&'s JsonValue>,
+    count: Option<u64>,
+}
+
+impl<'s> Step<'s> {
+    fn none() -> Self {
+        Step {
+            schemas: vec![],
+            count: Some(0),
+        }
+    }
+
+    fn one(schema: &'s JsonValue) -> Self {
+        Step {
+            schemas: vec![schema],
+            count: Some(1),
+        }
+    }
+
+    /// Combine alternatives (`anyOf`): any of them may apply
+    fn union(steps: Vec<Step<'s>>) -> Self {
+        let mut result = Step::none();
+        for step in steps {
+            result.count = max_count(result.count, step.count);
+            push_unique(&mut result.schemas, step.schemas);
+        }
+        result
+    }
+
+    /// Combine constraints (`allOf`): all of them apply to the same node
+    fn intersection(steps: Vec<Step<'s>>) -> Self {
+        let mut result = Step {
+            schemas: vec![],
+            count: None,
+        };
+        for step in steps {
+            result.count = min_count(result.count, step.count);
+            push_unique(&mut result.schemas, step.schemas);
+        }
+        if result.count == Some(0) {
+            result.schemas.clear();
+        }
+        // A node valid against a specific schema is not described better by `true`
+        if result.schemas.len() > 1 {
+            result.schemas.retain(|schema| !ptr::eq(*schema, &ANY));
+        }
+        result
+    }
+}
+
+/// Analyze flattened path segments against a schema
+pub fn analyze_segments(segments: &[PathExpr], root: &JsonValue) -> Selectivity {
+    let analyzer = Analyzer { root };
+    let mut schemas = vec![root];
+    let mut count = Some(1);
+
+    for segment in segments {
+        let steps: Vec<Step> = schemas
+            .iter()
+            .map(|schema| analyzer.step(schema, segment))
+            .collect();
+        let step = Step::union(steps);
+        count = mul_count(count, step.count);
+        schemas = step.schemas;
+
+        if schemas.is_empty() || count == Some(0) {
+            return Selectivity {
+                max_matches: Some(0),
+                blocked_at: Some(segment.to_string()),
+            };
+        }
+    }
+
+    Selectivity {
+        max_matches: count,
+        blocked_at: None,
+    }
+}
+
+struct Analyzer<'s> {
+    root: &'s JsonValue,
+}
+
+/// A schema after following `$ref`s
+enum Resolved<'s> {
+    /// `false`: no value is valid
+    Nothing,
+    /// `true`, an unresolvable reference, or a non-schema value
+    Any,
+    Object(&'s Map<String, JsonValue>),
+}
+
+impl<'s> Analyzer<'s> {
+    /// Evaluate one segment against the nodes described by `schema`
+    fn step(&self, schema: &'s JsonValue, segment: &PathExpr) -> Step<'s> {
+        match segment {
+            PathExpr::Root => Step::one(schema),
+            PathExpr::RecursiveDescent => self.descendants(schema),
+            PathExpr::Sequence(segments) => {
+                let mut schemas = vec![schema];
+                let mut count = Some(1);
+                for segment in segments {
+                    let step = Step::union(
+                        schemas
+                            .iter()
+                            .map(|schema| self.step(schema, segment))
+                            .collect(),
+                    );
+                    count = mul_count(count, step.count);
+                    schemas = step.schemas;
+                }
+                Step { schemas, count }
+            }
+            _ => self.combined(schema, &|object| self.child_step(object, segment), 0),
+        }
+    }
+
+    /// Apply `step` to a schema, honoring `$ref`, boolean schemas and combinators
+    fn combined(
+        &self,
+        schema: &'s JsonValue,
+        step: &dyn Fn(&'s Map<String, JsonValue>) -> Step<'s>,
+        depth: usize,
+    ) -> Step<'s> {
+        let object = match self.resolve(schema) {
+            Resolved::Nothing => return Step::none(),
+            Resolved::Any => return step(any_object()),
+            Resolved::Object(_) if depth > MAX_REF_DEPTH => return step(any_object()),
+            Resolved::Object(object) => object,
+        };
+
+        let mut constraints = vec![step(object)];
+        if let Some(JsonValue::Array(branches)) = object.get("allOf") {
+            for branch in branches {
+                constraints.push(self.combined(branch, step, depth + 1));
+            }
+        }
+        for keyword in ["anyOf", "oneOf"] {
+            if let Some(JsonValue::Array(branches)) = object.get(keyword) {
+                let alternatives = branches
+                    .iter()
+                    .map(|branch| self.combined(branch, step, depth + 1))
+                    .collect();
+                constraints.push(Step::union(alternatives));
+            }
+        }
+        Step::intersection(constraints)
+    }
+
+    /// Follow `$ref`s to the schema object that applies
+    fn resolve(&self, schema: &'s JsonValue) -> Resolved<'s> {
+        let mut schema = schema;
+        for _ in 0..MAX_REF_DEPTH {
+            match schema {
+                JsonValue::Bool(false) => return Resolved::Nothing,
+                JsonValue::Object(object) => match object.get("$ref") {
+                    Some(JsonValue::String(reference)) => match resolve_ref(self.root, reference) {
+                        Some(target) => schema = target,
+                        None => return Resolved::Any,
+                    },
+                    _ => return Resolved::Object(object),
+                },
+                _ => return Resolved::Any,
+            }
+        }
+        Resolved::Any
+    }
+
+    /// Evaluate a property, index, wildcard or filter segment against one schema object
+    fn child_step(&self, schema: &'s Map<String, JsonValue>, segment: &PathExpr) -> Step<'s> {
+        match segment {
+            PathExpr::Property(name) => {
+                if !allows_type(schema, "object") {
+                    return Step::none();
+                }
+                if let Some(property) = schema
+                    .get("properties")
+                    .and_then(JsonValue::as_object)
+                    .and_then(|properties| properties.get(name))
+                {
+                    return Step::one(property);
+                }
+                if schema.contains_key("patternProperties") {
+                    return Step::one(&ANY);
+                }
+                match schema.get("additionalProperties") {
+                    Some(JsonValue::Bool(false)) => Step::none(),
+                    Some(additional) => Step::one(additional),
+                    None => Step::one(&ANY),
+                }
+            }
+            PathExpr::Index(index) => {
+                if !allows_type(schema, "array") {
+                    return Step::none();
+                }
+                if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64) {
+                    if *index as u64 >= max {
+                        return Step::none();
+                    }
+                }
+                match schema.get("items") {
+                    Some(JsonValue::Array(tuple)) => match tuple.get(*index) {
+                        Some(item) => Step::one(item),
+                        None => match schema.get("additionalItems") {
+                            Some(JsonValue::Bool(false)) => Step::none(),
+                            Some(additional) => Step::one(additional),
+                            None => Step::one(&ANY),
+                        },
+                    },
+                    Some(items) => Step::one(items),
+                    None => Step::one(&ANY),
+                }
+            }
+            PathExpr::Wildcard => {
+                let mut steps = vec![];
+                if allows_type(schema, "object") {
+                    steps.push(object_children(schema));
+                }
+                if allows_type(schema, "array") {
+                    steps.push(array_children(schema));
+                }
+                Step::union(steps)
+            }
+            PathExpr::Filter(_) => {
+                if allows_type(schema, "array") {
+                    array_children(schema)
+                } else {
+                    Step::none()
+                }
+            }
+            _ => Step::none(),
+        }
+    }
+
+    /// The node itself and all of its descendants
+    fn descendants(&self, schema: &'s JsonValue) -> Step<'s> {
+        let mut schemas = vec![];
+        let count = self.collect_descendants(schema, &mut vec![], &mut schemas);
+        Step {
+            schemas,
+            count: count.and_then(|count| count.checked_add(1)),
+        }
+    }
+
+    /// Collect the schemas of a node and its descendants, returning a bound on descendants
+    ///
+    /// A schema reached again below itself is recursive, so its descendants are unbounded.
+    fn collect_descendants(
+        &self,
+        schema: &'s JsonValue,
+        stack: &mut Vec<&'s JsonValue>,
+        schemas: &mut Vec<&'s JsonValue>,
+    ) -> Option<u64> {
+        push_unique(schemas, vec![schema]);
+        let children = self.step(schema, &PathExpr::Wildcard);
+        if children.count == Some(0) {
+            return Some(0);
+        }
+        if stack.iter().any(|seen| ptr::eq(*seen, schema)) {
+            return None;
+        }
+
+        stack.push(schema);
+        let mut per_child = Some(0);
+        for child in &children.schemas {
+            let below = self.collect_descendants(child, stack, schemas);
+            per_child = max_count(per_child, below.and_then(|below| below.checked_add(1)));
+        }
+        stack.pop();
+
+        mul_count(children.count, per_child)
+    }
+}
+
+/// Values of an object: declared properties plus any additional properties
+fn object_children(schema: &Map<String, JsonValue>) -> Step<'_> {
+    let mut step = Step::none();
+    if let Some(properties) = schema.get("properties").and_then(JsonValue::as_object) {
+        step.count = Some(properties.len() as u64);
+        push_unique(&mut step.schemas, properties.values().collect());
+    }
+    if schema.contains_key("patternProperties") {
+        step.count = None;
+        push_unique(&mut step.schemas, vec![&ANY]);
+    }
+    match schema.get("additionalProperties") {
+        Some(JsonValue::Bool(false)) => {}
+        Some(additional) => {
+            step.count = None;
+            push_unique(&mut step.schemas, vec![additional]);
+        }
+        None => {
+            step.count = None;
+            push_unique(&mut step.schemas, vec![&ANY]);
+        }
+    }
+    if let Some(max) = schema.get("maxProperties").and_then(JsonValue::as_u64) {
+        step.count = min_count(step.count, Some(max));
+    }
+    step
+}
+
+/// Items of an array: tuple items plus any additional items
+fn array_children(schema: &Map<String, JsonValue>) -> Step<'_> {
+    let mut step = match schema.get("items") {
+        Some(JsonValue::Array(tuple)) => {
+            let mut step = Step {
+                schemas: vec![],
+                count: Some(tuple.len() as u64),
+            };
+            push_unique(&mut step.schemas, tuple.iter().collect());
+            match schema.get("additionalItems") {
+                Some(JsonValue::Bool(false)) => {}
+                Some(additional) => {
+                    step.count = None;
+                    push_unique(&mut step.schemas, vec![additional]);
+                }
+                None => {
+                    step.count = None;
+                    push_unique(&mut step.schemas, vec![&ANY]);
+                }
+            }
+            step
+        }
+        Some(JsonValue::Bool(false)) => Step::none(),
+        Some(items) => Step {
+            schemas: vec![items],
+            count: None,
+        },
+        None => Step {
+            schemas: vec![&ANY],
+            count: None,
+        },
+    };
+    if let Some(max) = schema.get("maxItems").and_then(JsonValue::as_u64) {
+        step.count = min_count(step.count, Some(max));
+    }
+    if step.count == Some(0) {
+        step.schemas.clear();
+    }
+    step
+}
+
+/// Schema object accepting any value
+fn any_object() -> &'static Map<String, JsonValue> {
+    static EMPTY: std::sync::OnceLock<Map<String, JsonValue>> = std::sync::OnceLock::new();
+    EMPTY.get_or_init(Map::new)
+}
+
+/// Check whether a schema's `type` keyword admits `name`
+fn allows_type(schema: &Map<String, JsonValue>, name: &str) -> bool {
+    match schema.get("type") {
+        Some(JsonValue::String(t)) => t == name,
+        Some(JsonValue::Array(types)) => types.iter().any(|t| t.as_str() == Some(name)),
+        _ => true,
+    }
+}
+
+fn push_unique<'s>(schemas: &mut Vec<&'s JsonValue>, new: Vec<&'s JsonValue>) {
+    for schema in new {
+        if !schemas.iter().any(|seen| ptr::eq(*seen, schema)) {
+            schemas.push(schema);
+        }
+    }
+}
+
+fn max_count(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    Some(a?.max(b?))
+}
+
+fn min_count(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, None) => a,
+        (None, b) => b,
+    }
+}
+
+fn mul_count(a: Option<u64>, b: Option<u64>) -> Option<u64> {
+    match (a, b) {
+        (Some(0), _) | (_, Some(0)) => Some(0),
+        (Some(a), Some(b)) => a.checked_mul(b),
+        _ => None,
+    }
+}
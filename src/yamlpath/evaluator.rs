@@ -4,115 +4,235 @@
 
 use yaml_rust2::Yaml;
 
-use super::types::{FilterExpr, PathExpr};
+use super::types::{FilterExpr, PathExpr, QueryOptions};
+use crate::plugins::call_path_function;
+use crate::timing::now_ms;
+
+/// Cost of evaluating one segment of a path
+#[derive(Debug, Clone)]
+pub struct SegmentProfile {
+    /// The segment as written in the path (e.g. `..image`)
+    pub segment: String,
+    /// Wall-clock time spent evaluating the segment, in milliseconds
+    pub time_ms: f64,
+    /// Number of nodes inspected, including nodes inspected by filters
+    pub nodes_visited: usize,
+    /// Number of values the segment produced
+    pub matches: usize,
+}
 
 /// Evaluate a YAMLPath expression against a YAML document
-pub fn evaluate_path<'a>(yaml: &'a Yaml, path: &PathExpr) -> Vec<&'a Yaml> {
+pub fn evaluate_path<'a>(yaml: &'a Yaml, path: &PathExpr, options: &QueryOptions) -> Vec<&'a Yaml> {
+    Evaluation::new(options).evaluate(yaml, path)
+}
+
+/// Split a path into its segments, flattening nested sequences
+pub fn flatten_path(path: &PathExpr) -> Vec<PathExpr> {
+    let mut segments = vec![];
+    collect_segments(path, &mut segments);
+    segments
+}
+
+fn collect_segments(path: &PathExpr, segments: &mut Vec<PathExpr>) {
     match path {
-        PathExpr::Root => vec![yaml],
-        PathExpr::Property(name) => {
-            if let Yaml::Hash(hash) = yaml {
-                if let Some(value) = hash.get(&Yaml::String(name.clone())) {
-                    vec![value]
+        PathExpr::Sequence(exprs) => {
+            for expr in exprs {
+                collect_segments(expr, segments);
+            }
+        }
+        expr => segments.push(expr.clone()),
+    }
+}
+
+/// Evaluate flattened segments one after another
+pub fn evaluate_segments<'a>(
+    roots: Vec<&'a Yaml>,
+    segments: &[PathExpr],
+    options: &QueryOptions,
+) -> Vec<&'a Yaml> {
+    let mut evaluation = Evaluation::new(options);
+    let mut results = roots;
+    for segment in segments {
+        results = evaluation.evaluate_all(results, segment);
+    }
+    results
+}
+
+/// Evaluate flattened segments one after another, measuring each of them
+pub fn profile_segments<'a>(
+    roots: Vec<&'a Yaml>,
+    segments: &[PathExpr],
+    options: &QueryOptions,
+) -> (Vec<&'a Yaml>, Vec<SegmentProfile>) {
+    let mut results = roots;
+    let mut profiles = Vec::with_capacity(segments.len());
+
+    for segment in segments {
+        let start = now_ms();
+        let mut evaluation = Evaluation::new(options);
+        results = evaluation.evaluate_all(results, segment);
+        profiles.push(SegmentProfile {
+            segment: segment.to_string(),
+            time_ms: now_ms() - start,
+            nodes_visited: evaluation.visited,
+            matches: results.len(),
+        });
+    }
+
+    (results, profiles)
+}
+
+/// State of a single evaluation
+struct Evaluation<'o> {
+    options: &'o QueryOptions,
+    /// Number of nodes inspected so far
+    visited: usize,
+}
+
+impl<'o> Evaluation<'o> {
+    fn new(options: &'o QueryOptions) -> Self {
+        Evaluation {
+            options,
+            visited: 0,
+        }
+    }
+
+    /// Evaluate an expression against every item, concatenating the results
+    fn evaluate_all<'a>(&mut self, items: Vec<&'a Yaml>, path: &PathExpr) -> Vec<&'a Yaml> {
+        let mut results = vec![];
+        for item in items {
+            results.extend(self.evaluate(item, path));
+        }
+        results
+    }
+
+    /// Evaluate a YAMLPath expression
+    fn evaluate<'a>(&mut self, yaml: &'a Yaml, path: &PathExpr) -> Vec<&'a Yaml> {
+        match path {
+            PathExpr::Root => {
+                self.visited += 1;
+                vec![yaml]
+            }
+            PathExpr::Property(name) => {
+                self.visited += 1;
+                if let Yaml::Hash(hash) = yaml {
+                    if let Some(value) = hash.get(&Yaml::String(name.clone())) {
+                        vec![value]
+                    } else {
+                        vec![]
+                    }
                 } else {
                     vec![]
                 }
-            } else {
-                vec![]
             }
-        }
-        PathExpr::Index(index) => {
-            if let Yaml::Array(array) = yaml {
-                if *index < array.len() {
-                    vec![&array[*index]]
+            PathExpr::Index(index) => {
+                self.visited += 1;
+                if let Yaml::Array(array) = yaml {
+                    if *index < array.len() {
+                        vec![&array[*index]]
+                    } else {
+                        vec![]
+                    }
                 } else {
                     vec![]
                 }
-            } else {
-                vec![]
             }
-        }
-        PathExpr::Wildcard => {
-            if let Yaml::Array(array) = yaml {
-                array.iter().collect()
-            } else if let Yaml::Hash(hash) = yaml {
-                hash.values().collect()
-            } else {
-                vec![]
+            PathExpr::Wildcard => {
+                self.visited += 1;
+                if let Yaml::Array(array) = yaml {
+                    self.visited += array.len();
+                    array.iter().collect()
+                } else if let Yaml::Hash(hash) = yaml {
+                    self.visited += hash.len();
+                    hash.values().collect()
+                } else {
+                    vec![]
+                }
             }
-        }
-        PathExpr::RecursiveDescent => {
-            let mut results = vec![];
-            collect_recursive(yaml, &mut results);
-            results
-        }
-        PathExpr::Filter(filter) => {
-            if let Yaml::Array(array) = yaml {
-                array
-                    .iter()
-                    .filter(|item| evaluate_filter(item, filter))
-                    .collect()
-            } else {
-                vec![]
+            PathExpr::RecursiveDescent => {
+                let mut results = vec![];
+                self.collect_recursive(yaml, &mut results);
+                self.visited += results.len();
+                results
             }
-        }
-        PathExpr::Sequence(exprs) => {
-            let mut results = vec![yaml];
-
-            for expr in exprs {
-                let mut new_results = vec![];
+            PathExpr::Filter(filter) => {
+                self.visited += 1;
+                if let Yaml::Array(array) = yaml {
+                    self.visited += array.len();
+                    array
+                        .iter()
+                        .filter(|item| self.evaluate_filter(item, filter))
+                        .collect()
+                } else {
+                    vec![]
+                }
+            }
+            PathExpr::Sequence(exprs) => {
+                let mut results = vec![yaml];
 
-                for item in results {
-                    new_results.extend(evaluate_path(item, expr));
+                for expr in exprs {
+                    results = self.evaluate_all(results, expr);
                 }
 
-                results = new_results;
+                results
             }
-
-            results
         }
     }
-}
 
-/// Recursively collect all values in a YAML document
-fn collect_recursive<'a>(yaml: &'a Yaml, results: &mut Vec<&'a Yaml>) {
-    results.push(yaml);
+    /// Recursively collect all values in a YAML document
+    ///
+    /// With `descendOnlyInto`, only mapping values under the listed keys are descended into.
+    fn collect_recursive<'a>(&self, yaml: &'a Yaml, results: &mut Vec<&'a Yaml>) {
+        results.push(yaml);
 
-    match yaml {
-        Yaml::Array(array) => {
-            for item in array {
-                collect_recursive(item, results);
+        match yaml {
+            Yaml::Array(array) => {
+                for item in array {
+                    self.collect_recursive(item, results);
+                }
             }
-        }
-        Yaml::Hash(hash) => {
-            for (_, value) in hash {
-                collect_recursive(value, results);
+            Yaml::Hash(hash) => {
+                for (key, value) in hash {
+                    if self.options.descends_into(key) {
+                        self.collect_recursive(value, results);
+                    }
+                }
             }
+            _ => {}
         }
-        _ => {}
     }
-}
 
-/// Evaluate a filter expression against a YAML value
-pub fn evaluate_filter(yaml: &Yaml, filter: &FilterExpr) -> bool {
-    match filter {
-        FilterExpr::Equals(path, value) => {
-            let results = evaluate_path(yaml, path);
-            results.iter().any(|result| *result == value)
-        }
-        FilterExpr::NotEquals(path, value) => {
-            let results = evaluate_path(yaml, path);
-            results.iter().all(|result| *result != value)
-        }
-        FilterExpr::GreaterThan(path, value) => {
-            let results = evaluate_path(yaml, path);
-            results.iter().any(|result| *result > value)
-        }
-        FilterExpr::LessThan(path, value) => {
-            let results = evaluate_path(yaml, path);
-            results.iter().any(|result| *result < value)
+    /// Evaluate a filter expression against a YAML value
+    fn evaluate_filter(&mut self, yaml: &Yaml, filter: &FilterExpr) -> bool {
+        match filter {
+            FilterExpr::Equals(path, value) => {
+                let results = self.evaluate(yaml, path);
+                results.contains(&value)
+            }
+            FilterExpr::NotEquals(path, value) => {
+                let results = self.evaluate(yaml, path);
+                results.iter().all(|result| *result != value)
+            }
+            FilterExpr::GreaterThan(path, value) => {
+                let results = self.evaluate(yaml, path);
+                results.iter().any(|result| *result > value)
+            }
+            FilterExpr::LessThan(path, value) => {
+                let results = self.evaluate(yaml, path);
+                results.iter().any(|result| *result < value)
+            }
+            FilterExpr::And(left, right) => {
+                self.evaluate_filter(yaml, left) && self.evaluate_filter(yaml, right)
+            }
+            FilterExpr::Or(left, right) => {
+                self.evaluate_filter(yaml, left) || self.evaluate_filter(yaml, right)
+            }
+            FilterExpr::Call(name, path) => {
+                let results = self.evaluate(yaml, path);
+                results
+                    .iter()
+                    .any(|result| call_path_function(name, result))
+            }
         }
-        FilterExpr::And(left, right) => evaluate_filter(yaml, left) && evaluate_filter(yaml, right),
-        FilterExpr::Or(left, right) => evaluate_filter(yaml, left) || evaluate_filter(yaml, right),
     }
 }
@@ -3,35 +3,50 @@
 //! This module provides functionality for querying YAML documents using a path syntax
 //! similar to JSONPath.
 
+mod analysis;
 mod evaluator;
 mod parser;
+mod session;
 mod types;
 
-use js_sys::Array;
+use js_sys::{Array, Object, Reflect};
 use wasm_bindgen::prelude::*;
-use yaml_rust2::YamlLoader;
+use yaml_rust2::Yaml;
 
+use crate::handle::DocumentHandle;
+use crate::loader::load_first_resolved;
+use crate::options::{check_option_keys, get_property, get_string_array, Limits};
 use crate::parse::yaml_to_js_value;
+use crate::timing::now_ms;
+use crate::validate::schema_from_js;
+
+use self::types::QueryOptions;
+
+pub(crate) use self::types::PathExpr;
+
+pub use self::session::{create_query_session, QuerySession};
+
+/// A YAMLPath expression parsed once and reusable across queries
+#[wasm_bindgen]
+pub struct CompiledPath {
+    segments: Vec<PathExpr>,
+}
 
 /// Query a YAML document using a YAMLPath expression
 ///
 /// @param {string} yaml - The YAML document to query
 /// @param {string} path - The YAMLPath expression
+/// @param {Object} options - Query options
 /// @returns {Array} - Array of matching values
 #[wasm_bindgen]
-pub fn query(yaml: &str, path: &str) -> Result<JsValue, JsValue> {
-    // Parse the YAML document
-    let docs = match YamlLoader::load_from_str(yaml) {
-        Ok(docs) => docs,
-        Err(e) => {
-            return Err(JsValue::from_str(&format!("YAML parsing error: {}", e)));
-        }
-    };
+pub fn query(yaml: &str, path: &str, options: JsValue) -> Result<JsValue, JsValue> {
+    let options = QueryOptions::from_js(&options)?;
 
-    if docs.is_empty() {
+    // Parse the YAML document
+    let Some(doc) = load_first_document(yaml, &options)? else {
         // Return empty array for empty documents
         return Ok(Array::new().into());
-    }
+    };
 
     // Parse the YAMLPath expression
     let path_expr = match parser::parse_path(path) {
@@ -42,7 +57,7 @@ pub fn query(yaml: &str, path: &str) -> Result<JsValue, JsValue> {
     };
 
     // Evaluate the YAMLPath expression against the YAML document
-    let matches = evaluator::evaluate_path(&docs[0], &path_expr);
+    let matches = evaluator::evaluate_path(&doc, &path_expr, &options);
 
     // Convert the matches to a JavaScript array
     let result = Array::new();
@@ -54,4 +69,276 @@ pub fn query(yaml: &str, path: &str) -> Result<JsValue, JsValue> {
     Ok(result.into())
 }
 
-// No re-exports needed for now
+/// Query a document already parsed and kept by a `DocumentHandle`
+pub(crate) fn query_resolved(
+    doc: Option<&Yaml>,
+    path: &str,
+    options: &JsValue,
+) -> Result<JsValue, JsValue> {
+    let options = QueryOptions::from_js(options)?;
+    let path_expr = parser::parse_path(path)
+        .map_err(|e| JsValue::from_str(&format!("YAMLPath parsing error: {}", e)))?;
+    let matches = match doc {
+        Some(doc) => evaluator::evaluate_path(doc, &path_expr, &options),
+        None => Vec::new(),
+    };
+    Ok(matches_to_js_array(&matches)?.into())
+}
+
+/// Parse a YAMLPath expression into its segments, the root first
+pub(crate) fn path_segments(path: &str) -> Result<Vec<PathExpr>, JsValue> {
+    let path_expr = parser::parse_path(path)
+        .map_err(|e| JsValue::from_str(&format!("YAMLPath parsing error: {}", e)))?;
+    Ok(evaluator::flatten_path(&path_expr))
+}
+
+/// Parse a YAMLPath expression once for repeated queries
+///
+/// @param {string} path - The YAMLPath expression
+/// @returns {CompiledPath} - The compiled path
+#[wasm_bindgen]
+pub fn compile_path(path: &str) -> Result<CompiledPath, JsValue> {
+    let path_expr = parser::parse_path(path)
+        .map_err(|e| JsValue::from_str(&format!("YAMLPath parsing error: {}", e)))?;
+    Ok(CompiledPath {
+        segments: evaluator::flatten_path(&path_expr),
+    })
+}
+
+#[wasm_bindgen]
+impl CompiledPath {
+    /// The path in normalized form (e.g. `$.services[*].name`)
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        let _ = types::write_segments(&mut path, &self.segments);
+        path
+    }
+
+    /// Query a YAML document
+    ///
+    /// An empty document yields no matches.
+    ///
+    /// @param {string} yaml - The YAML document to query
+    /// @param {Object} options - Query options
+    /// @returns {Array} - Array of matching values
+    pub fn query(&self, yaml: &str, options: JsValue) -> Result<JsValue, JsValue> {
+        let options = QueryOptions::from_js(&options)?;
+        let doc = load_first_document(yaml, &options)?;
+        let matches = evaluator::evaluate_segments(doc.iter().collect(), &self.segments, &options);
+        Ok(matches_to_js_array(&matches)?.into())
+    }
+
+    /// Query a document kept by a `DocumentHandle`, parsing neither the path nor the document
+    ///
+    /// @param {DocumentHandle} handle - The parsed document
+    /// @param {Object} options - Query options
+    /// @returns {Array} - Array of matching values
+    #[wasm_bindgen(js_name = queryHandle)]
+    pub fn query_handle(
+        &self,
+        handle: &DocumentHandle,
+        options: JsValue,
+    ) -> Result<JsValue, JsValue> {
+        let options = QueryOptions::from_js(&options)?;
+        let doc = handle.resolved()?.map(|(doc, _)| doc);
+        let matches =
+            evaluator::evaluate_segments(doc.into_iter().collect(), &self.segments, &options);
+        Ok(matches_to_js_array(&matches)?.into())
+    }
+
+    /// Query a YAML document, measuring the cost of every segment
+    ///
+    /// Returns `{ results, loadMs, segments }` where each segment entry is
+    /// `{ segment, timeMs, nodesVisited, matches }`.
+    ///
+    /// @param {string} yaml - The YAML document to query
+    /// @param {Object} options - Query options
+    /// @returns {Object} - Matching values and per-segment timings
+    pub fn profile(&self, yaml: &str, options: JsValue) -> Result<JsValue, JsValue> {
+        let options = QueryOptions::from_js(&options)?;
+        let start = now_ms();
+        let doc = load_first_document(yaml, &options)?;
+        let load_ms = now_ms() - start;
+
+        let (matches, profiles) =
+            evaluator::profile_segments(doc.iter().collect(), &self.segments, &options);
+
+        let segments = Array::new();
+        for profile in profiles {
+            let entry = Object::new();
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("segment"),
+                &JsValue::from_str(&profile.segment),
+            )?;
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("timeMs"),
+                &JsValue::from_f64(profile.time_ms),
+            )?;
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("nodesVisited"),
+                &JsValue::from_f64(profile.nodes_visited as f64),
+            )?;
+            Reflect::set(
+                &entry,
+                &JsValue::from_str("matches"),
+                &JsValue::from_f64(profile.matches as f64),
+            )?;
+            segments.push(&entry);
+        }
+
+        let results = matches_to_js_array(&matches)?;
+        let result = Object::new();
+        Reflect::set(&result, &JsValue::from_str("results"), &results)?;
+        Reflect::set(
+            &result,
+            &JsValue::from_str("loadMs"),
+            &JsValue::from_f64(load_ms),
+        )?;
+        Reflect::set(&result, &JsValue::from_str("segments"), &segments)?;
+        Ok(result.into())
+    }
+}
+
+/// Query many YAML documents with one compiled path in a single call
+///
+/// Returns one array of matching values per document, in input order. A document that fails
+/// to parse aborts the call with an error naming its index.
+///
+/// @param {string[]} docs - The YAML documents to query
+/// @param {CompiledPath} path - The compiled YAMLPath expression
+/// @param {Object} options - Query options
+/// @returns {Array[]} - Matching values per document
+#[wasm_bindgen]
+pub fn query_many(docs: &Array, path: &CompiledPath, options: JsValue) -> Result<Array, JsValue> {
+    let options = QueryOptions::from_js(&options)?;
+
+    let result = Array::new();
+    for (index, yaml) in docs.iter().enumerate() {
+        let yaml = yaml
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("query_many expects an array of strings"))?;
+        let doc = load_first_document(&yaml, &options).map_err(|e| {
+            JsValue::from_str(&format!(
+                "document {}: {}",
+                index,
+                e.as_string().unwrap_or_default()
+            ))
+        })?;
+        let matches = evaluator::evaluate_segments(doc.iter().collect(), &path.segments, &options);
+        let matches = matches_to_js_array(&matches)?;
+        result.push(&matches);
+    }
+    Ok(result)
+}
+
+/// Estimate how many values a path can select from documents valid against a JSON Schema
+///
+/// Returns `{ canMatch, maxMatches, selectivity, blockedAt }`: `maxMatches` is an upper bound
+/// (`null` when unbounded), `selectivity` is `'none'`, `'atMostOne'`, `'bounded'` or
+/// `'unbounded'`, and `blockedAt` names the first segment that rules out every match.
+///
+/// @param {string} path - The YAMLPath expression
+/// @param {Object} schema - The JSON Schema documents are valid against
+/// @returns {Object} - The analysis result
+#[wasm_bindgen]
+pub fn analyze_path(path: &str, schema: &JsValue) -> Result<JsValue, JsValue> {
+    let compiled = compile_path(path)?;
+    let schema = schema_from_js(schema)?;
+    let selectivity = analysis::analyze_segments(&compiled.segments, &schema);
+
+    let result = Object::new();
+    Reflect::set(
+        &result,
+        &JsValue::from_str("canMatch"),
+        &JsValue::from_bool(selectivity.max_matches != Some(0)),
+    )?;
+    Reflect::set(
+        &result,
+        &JsValue::from_str("maxMatches"),
+        &selectivity
+            .max_matches
+            .map_or(JsValue::NULL, |max| JsValue::from_f64(max as f64)),
+    )?;
+    Reflect::set(
+        &result,
+        &JsValue::from_str("selectivity"),
+        &JsValue::from_str(selectivity.kind()),
+    )?;
+    Reflect::set(
+        &result,
+        &JsValue::from_str("blockedAt"),
+        &selectivity
+            .blocked_at
+            .as_deref()
+            .map_or(JsValue::NULL, JsValue::from_str),
+    )?;
+    Ok(result.into())
+}
+
+impl QueryOptions {
+    /// Read query options from a JavaScript value
+    ///
+    /// `undefined` and `null` yield the default options.
+    fn from_js(options: &JsValue) -> Result<Self, JsValue> {
+        let mut result = QueryOptions::default();
+        if options.is_undefined() || options.is_null() {
+            return Ok(result);
+        }
+        check_option_keys(options, &[&["descendOnlyInto", "onWarning", "limits"]])?;
+
+        result.descend_only_into = get_string_array(options, "descendOnlyInto")?;
+        result.limits = Limits::from_js(options)?;
+
+        if let Some(callback) = get_property(options, "onWarning")? {
+            if !callback.is_null() {
+                result.on_warning = Some(
+                    callback
+                        .dyn_into()
+                        .map_err(|_| JsValue::from_str("Option 'onWarning' must be a function"))?,
+                );
+            }
+        }
+
+        Ok(result)
+    }
+}
+
+/// Parse a YAMLPath expression for use outside this module
+pub(crate) fn parse_path(path: &str) -> Result<PathExpr, String> {
+    parser::parse_path(path).map_err(|e| format!("YAMLPath parsing error: {}", e))
+}
+
+/// Find the values a parsed YAMLPath expression matches in a document
+pub(crate) fn select<'a>(doc: &'a Yaml, path: &PathExpr) -> Vec<&'a Yaml> {
+    evaluator::evaluate_path(doc, path, &QueryOptions::default())
+}
+
+/// Load the first document of a YAML string, if any
+///
+/// Values that cannot be resolved are read as null and reported to `onWarning`.
+fn load_first_document(yaml: &str, options: &QueryOptions) -> Result<Option<Yaml>, JsValue> {
+    let Some((doc, diagnostics)) =
+        load_first_resolved(yaml, &options.limits).map_err(|e| JsValue::from_str(&e))?
+    else {
+        return Ok(None);
+    };
+    if let Some(callback) = &options.on_warning {
+        for diagnostic in diagnostics {
+            callback.call1(&JsValue::NULL, &JsValue::from_str(&diagnostic.message))?;
+        }
+    }
+    Ok(Some(doc))
+}
+
+/// Convert matched values to a JavaScript array
+fn matches_to_js_array(matches: &[&Yaml]) -> Result<Array, JsValue> {
+    let result = Array::new();
+    for value in matches {
+        result.push(&yaml_to_js_value(value)?);
+    }
+    Ok(result)
+}
@@ -8,6 +8,7 @@ use std::str::Chars;
 use yaml_rust2::Yaml;
 
 use super::types::{FilterExpr, Operator, PathExpr};
+use crate::plugins::has_path_function;
 
 /// Parse a YAMLPath expression
 pub fn parse_path(path: &str) -> Result<PathExpr, String> {
@@ -192,6 +193,24 @@ fn parse_filter_term(chars: &mut Peekable<Chars>) -> Result<FilterExpr, String>
     // Skip any whitespace
     skip_whitespace(chars);
 
+    // A function registered by a plugin, applied to a path expression
+    if chars.peek().is_some_and(|c| c.is_alphabetic()) {
+        let name = parse_identifier(chars)?;
+        if !has_path_function(&name) {
+            return Err(format!("Unknown YAMLPath function '{}'", name));
+        }
+        expect_char(chars, '(')?;
+        skip_whitespace(chars);
+        expect_char(chars, '@')?;
+        let path = match chars.peek() {
+            Some('.') | Some('[') => parse_path_segment(chars)?,
+            _ => PathExpr::Sequence(Vec::new()),
+        };
+        skip_whitespace(chars);
+        expect_char(chars, ')')?;
+        return Ok(FilterExpr::Call(name, Box::new(path)));
+    }
+
     // Parse the left side of the filter (path expression)
     expect_char(chars, '@')?;
     let left = parse_path_segment(chars)?;
@@ -0,0 +1,169 @@
+//! YAMLPath query sessions
+//!
+//! This module keeps a parsed document between queries for interactive front-ends (REPLs,
+//! notebooks), with a current location that relative paths start from and a cache of the
+//! paths compiled so far.
+
+use std::collections::HashMap;
+
+use js_sys::Array;
+use wasm_bindgen::prelude::*;
+use yaml_rust2::Yaml;
+
+use super::types::{self, PathExpr, QueryOptions};
+use super::{evaluator, load_first_document, matches_to_js_array, parser};
+
+/// A document retained for repeated queries from a current location
+#[wasm_bindgen]
+pub struct QuerySession {
+    doc: Option<Yaml>,
+    options: QueryOptions,
+    // Segments from the root to the current location, without the root
+    cwd: Vec<PathExpr>,
+    // Compiled paths by text: whether the path is absolute, and its segments without the root
+    cache: HashMap<String, (bool, Vec<PathExpr>)>,
+    // Absolute form of every path queried or moved to
+    history: Vec<String>,
+}
+
+/// Parse the first document of a YAML string for an interactive query session
+///
+/// Paths starting with `$` are absolute; paths starting with `.` or `[` are relative to the
+/// current location, which `cd` moves and `up` moves back. Values that cannot be resolved
+/// are read as null and reported to `onWarning` once, when the session is created.
+///
+/// @param {string} yaml - The YAML document to query
+/// @param {Object} options - Query options
+/// @returns {QuerySession} - The session, located at the root
+#[wasm_bindgen]
+pub fn create_query_session(yaml: &str, options: JsValue) -> Result<QuerySession, JsValue> {
+    let options = QueryOptions::from_js(&options)?;
+    let doc = load_first_document(yaml, &options)?;
+    Ok(QuerySession {
+        doc,
+        options,
+        cwd: Vec::new(),
+        cache: HashMap::new(),
+        history: Vec::new(),
+    })
+}
+
+#[wasm_bindgen]
+impl QuerySession {
+    /// Query the document, relative to the current location unless the path starts with `$`
+    ///
+    /// @param {string} path - The YAMLPath expression
+    /// @returns {Array} - Array of matching values
+    pub fn query(&mut self, path: &str) -> Result<JsValue, JsValue> {
+        let segments = self.resolve(path)?;
+        self.history.push(segments_to_path(&segments));
+        let matches = self.evaluate(&segments);
+        Ok(matches_to_js_array(&matches)?.into())
+    }
+
+    /// Move the current location to a path, which must match at least one value
+    ///
+    /// A location matching several values (`$.items[*]`) makes relative queries start from
+    /// each of them.
+    ///
+    /// @param {string} path - The YAMLPath expression
+    /// @returns {string} - The new location
+    pub fn cd(&mut self, path: &str) -> Result<String, JsValue> {
+        let segments = self.resolve(path)?;
+        let location = segments_to_path(&segments);
+        if self.evaluate(&segments).is_empty() {
+            return Err(JsValue::from_str(&format!(
+                "Path matches nothing: {}",
+                location
+            )));
+        }
+        self.history.push(location.clone());
+        self.cwd = segments;
+        Ok(location)
+    }
+
+    /// Move the current location to its parent; the root stays the root
+    ///
+    /// @returns {string} - The new location
+    pub fn up(&mut self) -> String {
+        self.cwd.pop();
+        // `..name` is a single step
+        if matches!(self.cwd.last(), Some(PathExpr::RecursiveDescent)) {
+            self.cwd.pop();
+        }
+        self.cwd()
+    }
+
+    /// The current location in normalized form (e.g. `$.spec.containers`)
+    #[wasm_bindgen(getter)]
+    pub fn cwd(&self) -> String {
+        segments_to_path(&self.cwd)
+    }
+
+    /// Absolute form of every path queried or moved to, oldest first
+    #[wasm_bindgen(getter)]
+    pub fn history(&self) -> Array {
+        self.history
+            .iter()
+            .map(|path| JsValue::from_str(path))
+            .collect()
+    }
+
+    /// Number of distinct paths compiled so far
+    #[wasm_bindgen(getter, js_name = cacheSize)]
+    pub fn cache_size(&self) -> usize {
+        self.cache.len()
+    }
+}
+
+impl QuerySession {
+    /// Compile a path, or take it from the cache, and make it absolute
+    fn resolve(&mut self, path: &str) -> Result<Vec<PathExpr>, JsValue> {
+        if !self.cache.contains_key(path) {
+            let compiled = compile_session_path(path)
+                .map_err(|e| JsValue::from_str(&format!("YAMLPath parsing error: {}", e)))?;
+            self.cache.insert(path.to_string(), compiled);
+        }
+        let (absolute, segments) = &self.cache[path];
+        Ok(if *absolute {
+            segments.clone()
+        } else {
+            self.cwd.iter().chain(segments).cloned().collect()
+        })
+    }
+
+    fn evaluate(&self, segments: &[PathExpr]) -> Vec<&Yaml> {
+        evaluator::evaluate_segments(self.doc.iter().collect(), segments, &self.options)
+    }
+}
+
+/// Parse an absolute (`$...`) or relative (`.name`, `[0]`) path into segments after the root
+fn compile_session_path(path: &str) -> Result<(bool, Vec<PathExpr>), String> {
+    let (absolute, rest) = match path.strip_prefix('$') {
+        Some(rest) => (true, rest),
+        None => (false, path),
+    };
+    if rest.is_empty() {
+        return if absolute {
+            Ok((true, Vec::new()))
+        } else {
+            Err("Path must start with '$', '.' or '['".to_string())
+        };
+    }
+    if !rest.starts_with(['.', '[']) {
+        return Err("Path must start with '$', '.' or '['".to_string());
+    }
+    let expr = parser::parse_path(&format!("${}", rest))?;
+    let segments = evaluator::flatten_path(&expr)
+        .into_iter()
+        .filter(|segment| !matches!(segment, PathExpr::Root))
+        .collect();
+    Ok((absolute, segments))
+}
+
+/// Write segments after the root as an absolute path
+fn segments_to_path(segments: &[PathExpr]) -> String {
+    let mut path = String::from("$");
+    let _ = types::write_segments(&mut path, segments);
+    path
+}
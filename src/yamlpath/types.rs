@@ -2,8 +2,14 @@
 //!
 //! This module contains the type definitions for YAMLPath expressions and filters.
 
+use std::collections::HashSet;
+use std::fmt;
+
+use js_sys::Function;
 use yaml_rust2::Yaml;
 
+use crate::options::Limits;
+
 /// YAMLPath expression types
 #[derive(Debug, Clone)]
 pub enum PathExpr {
@@ -23,6 +29,28 @@ pub enum PathExpr {
     Sequence(Vec<PathExpr>),
 }
 
+/// Options accepted by `query`
+#[derive(Debug, Clone, Default)]
+pub struct QueryOptions {
+    /// Keys recursive descent is restricted to; `None` descends everywhere
+    pub descend_only_into: Option<HashSet<String>>,
+    /// Callback receiving values that could not be resolved and were read as null
+    pub on_warning: Option<Function>,
+    /// Resource limits applied while loading the document
+    pub(crate) limits: Limits,
+}
+
+impl QueryOptions {
+    /// Check whether recursive descent enters the value under `key`
+    pub fn descends_into(&self, key: &Yaml) -> bool {
+        match (&self.descend_only_into, key) {
+            (None, _) => true,
+            (Some(keys), Yaml::String(key)) => keys.contains(key),
+            (Some(_), _) => false,
+        }
+    }
+}
+
 /// Filter expression types
 #[derive(Debug, Clone)]
 pub enum FilterExpr {
@@ -38,6 +66,9 @@ pub enum FilterExpr {
     And(Box<FilterExpr>, Box<FilterExpr>),
     /// Logical OR of two filter expressions
     Or(Box<FilterExpr>, Box<FilterExpr>),
+    /// Function registered by a plugin, true when it holds for any value of the path
+    /// (e.g., `isPort(@.port)`)
+    Call(String, Box<PathExpr>),
 }
 
 /// Operator types
@@ -71,3 +102,52 @@ impl Operator {
         }
     }
 }
+
+impl fmt::Display for PathExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathExpr::Root => write!(f, "$"),
+            PathExpr::Property(name) => write!(f, ".{}", name),
+            PathExpr::Index(index) => write!(f, "[{}]", index),
+            PathExpr::Wildcard => write!(f, "[*]"),
+            PathExpr::RecursiveDescent => write!(f, ".."),
+            PathExpr::Filter(filter) => write!(f, "[?({})]", filter),
+            PathExpr::Sequence(exprs) => write_segments(f, exprs),
+        }
+    }
+}
+
+/// Write consecutive segments, joining `..` and a property as `..name`
+pub fn write_segments(f: &mut impl fmt::Write, segments: &[PathExpr]) -> fmt::Result {
+    let mut after_descent = false;
+    for segment in segments {
+        match segment {
+            PathExpr::Property(name) if after_descent => write!(f, "{}", name)?,
+            segment => write!(f, "{}", segment)?,
+        }
+        after_descent = matches!(segment, PathExpr::RecursiveDescent);
+    }
+    Ok(())
+}
+
+impl fmt::Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (path, op, value) = match self {
+            FilterExpr::Equals(path, value) => (path, "==", value),
+            FilterExpr::NotEquals(path, value) => (path, "!=", value),
+            FilterExpr::GreaterThan(path, value) => (path, ">", value),
+            FilterExpr::LessThan(path, value) => (path, "<", value),
+            FilterExpr::And(left, right) => return write!(f, "{} && {}", left, right),
+            FilterExpr::Or(left, right) => return write!(f, "{} || {}", left, right),
+            FilterExpr::Call(name, path) => return write!(f, "{}(@{})", name, path),
+        };
+        write!(f, "@{}{}", path, op)?;
+        match value {
+            Yaml::String(s) => write!(f, "\"{}\"", s),
+            Yaml::Integer(i) => write!(f, "{}", i),
+            Yaml::Real(r) => write!(f, "{}", r),
+            Yaml::Boolean(b) => write!(f, "{}", b),
+            _ => write!(f, "null"),
+        }
+    }
+}
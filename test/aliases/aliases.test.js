@@ -0,0 +1,59 @@
+/**
+ * Alias Analysis Tests
+ *
+ * This test file tests reporting how much each anchor contributes to the expanded size of a
+ * document.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Alias Analysis Tests', () => {
+  test('reports alias counts and expansion sizes per anchor', () => {
+    const report = fastYaml.analyzeAliases('a: &a [x, y]\nb: &b [*a, *a]\nc: [*b, *b]\n');
+
+    expect(report).toEqual({
+      nodes: 29,
+      anchors: [
+        { anchor: 'a', document: 0, line: 1, column: 4, aliases: 2, nodes: 3, expandedNodes: 18 },
+        { anchor: 'b', document: 0, line: 2, column: 4, aliases: 2, nodes: 7, expandedNodes: 14 },
+      ],
+    });
+  });
+
+  test('reports unused anchors and anchors of later documents', () => {
+    const report = fastYaml.analyzeAliases('a: &unused 1\n---\n- &item x\n- *item\n');
+
+    const summary = report.anchors.map(({ anchor, document, aliases }) => [
+      anchor,
+      document,
+      aliases,
+    ]);
+
+    expect(report.nodes).toBe(6);
+    expect(summary).toEqual([
+      ['unused', 0, 0],
+      ['item', 1, 1],
+    ]);
+  });
+
+  test('measures alias bombs without expanding them', () => {
+    const levels = 'abcdefghij'.split('');
+    const bomb = levels
+      .map((name, i) => {
+        const items = i === 0 ? Array(10).fill('lol') : Array(10).fill(`*${levels[i - 1]}`);
+        return `${name}: &${name} [${items.join(', ')}]`;
+      })
+      .join('\n');
+
+    const { anchors } = fastYaml.analyzeAliases(bomb);
+
+    // Each level repeats the previous one ten times: 10 + 100 + ... + 10^9 copies of 'a'
+    expect(anchors[0]).toMatchObject({ anchor: 'a', aliases: 10, nodes: 11 });
+    expect(anchors[0].expandedNodes).toBe(11 * 1111111110);
+    expect(() => fastYaml.parse(bomb)).toThrow(/maxAlias/);
+  });
+
+  test('throws on invalid documents', () => {
+    expect(() => fastYaml.analyzeAliases('a: *missing')).toThrow(fastYaml.YAMLException);
+  });
+});
@@ -0,0 +1,58 @@
+/**
+ * Async Parsing Tests
+ *
+ * This test file tests parsing in time slices with a timeout and an abort signal.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Async Parsing Tests', () => {
+  const large = Array.from({ length: 50000 }, (_, i) => `key${i}: [a, b, {c: ${i}}]`).join('\n');
+
+  test('parses like parse', async () => {
+    await expect(fastYaml.parseAsync('a: 1\nb: [x, y]\n')).resolves.toEqual({
+      a: 1,
+      b: ['x', 'y'],
+    });
+    await expect(fastYaml.parseAsync('')).resolves.toBeNull();
+    await expect(fastYaml.parseAsync('a: 1', { schema: 'failsafe' })).resolves.toEqual({ a: '1' });
+    const parsed = await fastYaml.parseAsync(large);
+    expect(parsed.key49999).toEqual(['a', 'b', { c: 49999 }]);
+  });
+
+  test('yields to the event loop between slices', async () => {
+    let ticks = 0;
+    const timer = setInterval(() => ticks++, 0);
+    await fastYaml.parseAsync(large + '\n' + large.replace(/key/g, 'more'));
+    clearInterval(timer);
+    expect(ticks).toBeGreaterThan(0);
+  });
+
+  test('rejects syntax errors with YAMLException', async () => {
+    await expect(fastYaml.parseAsync('a: [1, 2')).rejects.toBeInstanceOf(fastYaml.YAMLException);
+  });
+
+  test('rejects once timeoutMs passes', async () => {
+    await expect(fastYaml.parseAsync(large, { timeoutMs: 0 })).rejects.toThrow(
+      /parsing timed out after 0 ms \(timeoutMs\)/
+    );
+    await expect(fastYaml.parseAsync('a: 1', { timeoutMs: -1 })).rejects.toThrow(
+      /must be a non-negative number/
+    );
+  });
+
+  test('rejects with the reason of an aborted signal', async () => {
+    const reason = new Error('cancelled');
+    await expect(
+      fastYaml.parseAsync('a: 1', { signal: AbortSignal.abort(reason) })
+    ).rejects.toBe(reason);
+
+    const controller = new AbortController();
+    setTimeout(() => controller.abort(reason), 0);
+    await expect(
+      fastYaml.parseAsync(large + '\n' + large.replace(/key/g, 'more'), {
+        signal: controller.signal,
+      })
+    ).rejects.toBe(reason);
+  });
+});
@@ -0,0 +1,77 @@
+/**
+ * Batch Parse Tests
+ *
+ * This test file tests parseBatch, which parses many YAML strings in a single call, and
+ * createParser, which reuses its options and buffers across calls.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Batch Parse Tests', () => {
+  test('parses every input in order', () => {
+    const inputs = ['title: Home\n', 'tags: [a, b]\n', '', '42'];
+    const results = fastYaml.parseBatch(inputs);
+    expect(results).toEqual([
+      { value: { title: 'Home' }, error: null },
+      { value: { tags: ['a', 'b'] }, error: null },
+      { value: null, error: null },
+      { value: 42, error: null },
+    ]);
+    expect(results.map((r) => r.value)).toEqual(inputs.map((input) => fastYaml.parse(input)));
+  });
+
+  test('reports a failing input without failing the others', () => {
+    const results = fastYaml.parseBatch(['a: 1\n', 'title: [About\n', 'b: 2\n']);
+    expect(results[0].value).toEqual({ a: 1 });
+    expect(results[1].value).toBeUndefined();
+    expect(results[1].error).toBeInstanceOf(fastYaml.YAMLException);
+    expect(results[1].error.code).toBe('UNCLOSED_FLOW_COLLECTION');
+    expect(results[1].error.snippet).toContain('title: [About');
+    expect(results[2].value).toEqual({ b: 2 });
+  });
+
+  test('applies options to every input', () => {
+    const results = fastYaml.parseBatch(['a: 1', 'a: 2'], { filename: 'page.md' });
+    expect(results.map((r) => r.value)).toEqual([{ a: 1 }, { a: 2 }]);
+    const [failed] = fastYaml.parseBatch(['a: [1'], { filename: 'page.md' });
+    expect(failed.error.message).toMatch(/^page\.md: /);
+  });
+
+  test('rejects inputs that are not strings', () => {
+    expect(() => fastYaml.parseBatch(['a: 1', 2])).toThrow(/array of strings/);
+  });
+});
+
+describe('Reusable Parser Tests', () => {
+  test('parses many inputs with the options it was created with', () => {
+    const parser = fastYaml.createParser({ keyStyle: 'camel' });
+    for (let i = 0; i < 100; i++) {
+      expect(parser.parse(`item_id: ${i}\nitem_name: n${i}\n`)).toEqual({
+        itemId: i,
+        itemName: `n${i}`,
+      });
+    }
+    expect(parser.parseAll('a_b: 1\n---\nc_d: 2\n')).toEqual([{ aB: 1 }, { cD: 2 }]);
+    parser.free();
+  });
+
+  test('gives the same results with json conversion', () => {
+    const parser = fastYaml.createParser({ conversion: 'json' });
+    const inputs = ['a: 1\n', 'b: [1, 2]\n', 'c: .inf\n', 'a: 1\n'];
+    for (const input of inputs) {
+      expect(parser.parse(input)).toEqual(fastYaml.parse(input));
+    }
+    parser.free();
+  });
+
+  test('throws YAMLExceptions and keeps working after an error', () => {
+    const parser = fastYaml.createParser();
+    expect(() => parser.parse('a: [1')).toThrow(fastYaml.YAMLException);
+    expect(parser.parse('a: 1')).toEqual({ a: 1 });
+    parser.free();
+  });
+
+  test('rejects invalid options when created', () => {
+    expect(() => fastYaml.createParser({ conversion: 'serde' })).toThrow(/Unknown conversion/);
+  });
+});
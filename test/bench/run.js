@@ -5,6 +5,9 @@
  *
  * This script runs benchmarks comparing fast-yaml with js-yaml.
  * It uses hyperfine for accurate timing measurements.
+ * fast-yaml is timed with both of its conversion modes: json (the default) and direct. They are
+ * also compared in process, without node startup, on small, large and deeply nested documents
+ * and on many mappings sharing their keys.
  */
 
 const fs = require('fs');
@@ -16,6 +19,13 @@ const WARMUP_RUNS = 3;
 const BENCHMARK_RUNS = 10;
 const RESULTS_FILE = path.join(__dirname, 'results.json');
 const YAML_SIZES = ['10KB', '100KB', '1MB'];
+const CONVERSION_FIXTURES = {
+  small: 'test-10KB.yaml',
+  large: 'test-1MB.yaml',
+  deep: 'test-deep.yaml',
+  repeated: 'test-repeated.yaml',
+};
+const CONVERSION_BYTES = 20 * 1024 * 1024; // Input parsed per fixture and conversion
 
 // Ensure the benchmark data directory exists
 const dataDir = path.join(__dirname, 'data');
@@ -56,6 +66,83 @@ function generateTestFiles() {
       console.log(`Generated ${size} test file: ${filePath}`);
     }
   }
+
+  // 40 mappings nested 60 levels deep, each level holding a scalar and a flow sequence
+  const deepPath = path.join(dataDir, 'test-deep.yaml');
+  if (!fs.existsSync(deepPath)) {
+    console.log('Generating deep test file...');
+
+    let yaml = '';
+    for (let i = 0; i < 40; i++) {
+      yaml += `doc${i}:\n`;
+      let indent = '  ';
+      for (let depth = 0; depth < 60; depth++) {
+        yaml += `${indent}level${depth}:\n`;
+        yaml += `${indent}  value: ${depth}\n`;
+        yaml += `${indent}  list: [a, b, ${depth}]\n`;
+        yaml += `${indent}  next:\n`;
+        indent += '    ';
+      }
+      yaml += `${indent}end: true\n`;
+    }
+
+    fs.writeFileSync(deepPath, yaml);
+    console.log(`Generated deep test file: ${deepPath}`);
+  }
+
+  // 20000 small mappings sharing the same keys, as in a list of records or manifests
+  const repeatedPath = path.join(dataDir, 'test-repeated.yaml');
+  if (!fs.existsSync(repeatedPath)) {
+    console.log('Generating repeated keys test file...');
+
+    let yaml = '';
+    for (let i = 0; i < 20000; i++) {
+      yaml += `- {kind: Service, name: svc${i}, namespace: default, image: app, port: ${i}, `;
+      yaml += `labels: {app: app${i % 10}, tier: web}}\n`;
+    }
+
+    fs.writeFileSync(repeatedPath, yaml);
+    console.log(`Generated repeated keys test file: ${repeatedPath}`);
+  }
+}
+
+// Time both conversion modes in this process, where node startup does not hide them
+function compareConversions() {
+  console.log('Comparing conversion modes...');
+
+  const fastYaml = require('../../js/index.cjs');
+  const conversions = {};
+
+  for (const [name, file] of Object.entries(CONVERSION_FIXTURES)) {
+    const yaml = fs.readFileSync(path.join(dataDir, file), 'utf8');
+    const runs = Math.max(5, Math.round(CONVERSION_BYTES / yaml.length));
+    conversions[name] = { bytes: Buffer.byteLength(yaml) };
+
+    for (const conversion of ['json', 'direct']) {
+      for (let i = 0; i < WARMUP_RUNS; i++) {
+        fastYaml.parse(yaml, { conversion });
+      }
+      const times = [];
+      for (let i = 0; i < runs; i++) {
+        const start = process.hrtime.bigint();
+        fastYaml.parse(yaml, { conversion });
+        times.push(Number(process.hrtime.bigint() - start) / 1e6);
+      }
+      times.sort((a, b) => a - b);
+      conversions[name][conversion] = times[Math.floor(times.length / 2)];
+    }
+  }
+
+  console.log('\n| Fixture  | Size (KB) | json (ms) | direct (ms) | direct / json |');
+  console.log('|----------|-----------|-----------|-------------|---------------|');
+  for (const [name, { bytes, json, direct }] of Object.entries(conversions)) {
+    const kb = (bytes / 1024).toFixed(0);
+    const ratio = (direct / json).toFixed(2);
+    console.log(`| ${name.padEnd(8)} | ${kb.padEnd(9)} | ${json.toFixed(2).padEnd(9)} | ${direct.toFixed(2).padEnd(11)} | ${ratio.padEnd(13)} |`);
+  }
+  console.log();
+
+  return conversions;
 }
 
 // Create benchmark scripts
@@ -74,8 +161,9 @@ const fs = require('fs');
 const fastYaml = require('../../../js/index.cjs');
 
 const file = process.argv[2];
+const conversion = process.argv[3] || 'json';
 const yaml = fs.readFileSync(file, 'utf8');
-fastYaml.parse(yaml);
+fastYaml.parse(yaml, { conversion });
   `);
 
   // Create js-yaml benchmark script
@@ -94,17 +182,19 @@ jsYaml.load(yaml);
 }
 
 // Run benchmarks
-function runBenchmarks() {
+function runBenchmarks(conversions) {
   console.log('Running benchmarks...');
 
   const results = {
     date: new Date().toISOString(),
+    conversions,
     benchmarks: {}
   };
 
   for (const size of YAML_SIZES) {
     const yamlFile = path.join(dataDir, `test-${size}.yaml`);
     const fastYamlCmd = `node ${path.join(__dirname, 'scripts', 'bench-fast-yaml.js')} ${yamlFile}`;
+    const fastYamlDirectCmd = `${fastYamlCmd} direct`;
     const jsYamlCmd = `node ${path.join(__dirname, 'scripts', 'bench-js-yaml.js')} ${yamlFile}`;
 
     console.log(`\nBenchmarking ${size} YAML file...`);
@@ -117,6 +207,7 @@ function runBenchmarks() {
         `--runs ${BENCHMARK_RUNS}`,
         `--export-json ${path.join(dataDir, `bench-${size}.json`)}`,
         `--command-name "fast-yaml" "${fastYamlCmd}"`,
+        `--command-name "fast-yaml-direct" "${fastYamlDirectCmd}"`,
         `--command-name "js-yaml" "${jsYamlCmd}"`
       ].join(' ');
 
@@ -129,9 +220,10 @@ function runBenchmarks() {
 
       // Extract relevant metrics
       const fastYamlResult = benchResult.results.find(r => r.command === 'fast-yaml');
+      const fastYamlDirectResult = benchResult.results.find(r => r.command === 'fast-yaml-direct');
       const jsYamlResult = benchResult.results.find(r => r.command === 'js-yaml');
 
-      if (fastYamlResult && jsYamlResult) {
+      if (fastYamlResult && fastYamlDirectResult && jsYamlResult) {
         const speedup = jsYamlResult.mean / fastYamlResult.mean;
 
         results.benchmarks[size] = {
@@ -141,6 +233,12 @@ function runBenchmarks() {
             max: fastYamlResult.max,
             'stddev': fastYamlResult.stddev
           },
+          'fast-yaml-direct': {
+            mean: fastYamlDirectResult.mean,
+            min: fastYamlDirectResult.min,
+            max: fastYamlDirectResult.max,
+            'stddev': fastYamlDirectResult.stddev
+          },
           'js-yaml': {
             mean: jsYamlResult.mean,
             min: jsYamlResult.min,
@@ -152,6 +250,7 @@ function runBenchmarks() {
 
         console.log(`\nResults for ${size}:`);
         console.log(`fast-yaml: ${(fastYamlResult.mean * 1000).toFixed(2)}ms`);
+        console.log(`fast-yaml (conversion: direct): ${(fastYamlDirectResult.mean * 1000).toFixed(2)}ms`);
         console.log(`js-yaml: ${(jsYamlResult.mean * 1000).toFixed(2)}ms`);
         console.log(`Speedup: ${speedup.toFixed(2)}x`);
       }
@@ -167,16 +266,17 @@ function runBenchmarks() {
   // Generate summary
   console.log('\nBenchmark Summary:');
   console.log('=================');
-  console.log('| Size  | fast-yaml (ms) | js-yaml (ms) | Speedup |');
-  console.log('|-------|----------------|--------------|---------|');
+  console.log('| Size  | fast-yaml (ms) | fast-yaml direct (ms) | js-yaml (ms) | Speedup |');
+  console.log('|-------|----------------|-----------------------|--------------|---------|');
 
   for (const size of YAML_SIZES) {
     if (results.benchmarks[size]) {
       const fastYamlMs = (results.benchmarks[size]['fast-yaml'].mean * 1000).toFixed(2);
+      const fastYamlDirectMs = (results.benchmarks[size]['fast-yaml-direct'].mean * 1000).toFixed(2);
       const jsYamlMs = (results.benchmarks[size]['js-yaml'].mean * 1000).toFixed(2);
       const speedup = results.benchmarks[size].speedup.toFixed(2);
 
-      console.log(`| ${size.padEnd(5)} | ${fastYamlMs.padEnd(14)} | ${jsYamlMs.padEnd(12)} | ${speedup.padEnd(7)} |`);
+      console.log(`| ${size.padEnd(5)} | ${fastYamlMs.padEnd(14)} | ${fastYamlDirectMs.padEnd(21)} | ${jsYamlMs.padEnd(12)} | ${speedup.padEnd(7)} |`);
     }
   }
 }
@@ -186,6 +286,10 @@ function main() {
   console.log('fast-yaml benchmark runner');
   console.log('=========================\n');
 
+  // The conversion modes are compared in process, so without hyperfine or js-yaml
+  generateTestFiles();
+  const conversions = compareConversions();
+
   try {
     // Check if hyperfine is installed
     execSync('hyperfine --version', { stdio: 'ignore' });
@@ -205,9 +309,8 @@ function main() {
     execSync('npm install js-yaml', { stdio: 'inherit' });
   }
 
-  generateTestFiles();
   createBenchmarkScripts();
-  runBenchmarks();
+  runBenchmarks(conversions);
 }
 
 // Run the main function
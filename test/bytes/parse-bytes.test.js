@@ -0,0 +1,103 @@
+/**
+ * Parse Bytes Tests
+ *
+ * This test file tests parsing Uint8Array input with encoding detection, and parsing input
+ * written into a buffer in WebAssembly memory with allocInput and parseInPlace.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+function utf16(text, bigEndian, bom) {
+  const units = (bom ? '\uFEFF' : '') + text;
+  const bytes = new Uint8Array(units.length * 2);
+  for (let i = 0; i < units.length; i++) {
+    const code = units.charCodeAt(i);
+    bytes[i * 2 + (bigEndian ? 1 : 0)] = code & 0xff;
+    bytes[i * 2 + (bigEndian ? 0 : 1)] = code >> 8;
+  }
+  return bytes;
+}
+
+describe('Parse Bytes Tests', () => {
+  const yaml = 'name: café\nemoji: 🐱\nitems: [1, 2]\n';
+  const expected = { name: 'café', emoji: '🐱', items: [1, 2] };
+
+  test('parses UTF-8 with and without a byte order mark', () => {
+    expect(fastYaml.parseBytes(new TextEncoder().encode(yaml))).toEqual(expected);
+    expect(fastYaml.parseBytes(Buffer.from('\uFEFF' + yaml))).toEqual(expected);
+  });
+
+  test('parses UTF-16 with a byte order mark', () => {
+    expect(fastYaml.parseBytes(utf16(yaml, false, true))).toEqual(expected);
+    expect(fastYaml.parseBytes(utf16(yaml, true, true))).toEqual(expected);
+  });
+
+  test('detects UTF-16 without a byte order mark', () => {
+    expect(fastYaml.parseBytes(utf16(yaml, false, false))).toEqual(expected);
+    expect(fastYaml.parseBytes(utf16(yaml, true, false))).toEqual(expected);
+  });
+
+  test('passes options through', () => {
+    expect(fastYaml.parseBytes(Buffer.from('a: yes'), { schema: fastYaml.JSON_SCHEMA })).toEqual({
+      a: 'yes',
+    });
+    expect(fastYaml.parseBytes(new Uint8Array(0))).toBeNull();
+  });
+
+  test('rejects invalid byte sequences', () => {
+    expect(() => fastYaml.parseBytes(Uint8Array.of(0x61, 0x3a, 0x20, 0xff))).toThrow(
+      /Invalid input encoding: invalid UTF-8 at byte 3/
+    );
+    expect(() => fastYaml.parseBytes(Uint8Array.of(0xff, 0xfe, 0x61))).toThrow(
+      /odd number of bytes/
+    );
+    expect(() => fastYaml.parseBytes(Uint8Array.of(0xff, 0xfe, 0x00, 0xd8))).toThrow(
+      /unpaired UTF-16 surrogate/
+    );
+    expect(() => fastYaml.parseBytes(Uint8Array.of(0xff, 0xfe, 0x00, 0x00))).toThrow(
+      fastYaml.YAMLException
+    );
+  });
+});
+
+describe('Parse In Place Tests', () => {
+  function fill(bytes) {
+    const buffer = fastYaml.allocInput(bytes.length);
+    buffer.view().set(bytes);
+    return buffer;
+  }
+
+  test('parses the bytes written into the buffer', () => {
+    const yaml = 'name: fast-yaml\nitems: [1, 2]\n';
+    const buffer = fill(Buffer.from(yaml));
+    expect(buffer.length).toBe(Buffer.byteLength(yaml));
+    expect(fastYaml.parseInPlace(buffer)).toEqual(fastYaml.parse(yaml));
+    expect(fastYaml.parseInPlace(buffer, { schema: fastYaml.FAILSAFE_SCHEMA })).toEqual({
+      name: 'fast-yaml',
+      items: ['1', '2'],
+    });
+    buffer.free();
+  });
+
+  test('detects the encoding as parseBytes does', () => {
+    const bytes = utf16('a: b', false, true);
+    const buffer = fill(bytes);
+    expect(fastYaml.parseInPlace(buffer)).toEqual(fastYaml.parseBytes(bytes));
+    buffer.free();
+  });
+
+  test('an empty buffer holds no document', () => {
+    const buffer = fastYaml.allocInput(0);
+    expect(fastYaml.parseInPlace(buffer)).toBeNull();
+    buffer.free();
+  });
+
+  test('rejects invalid input', () => {
+    const invalid = fill(Uint8Array.of(0x61, 0x3a, 0x20, 0xff));
+    expect(() => fastYaml.parseInPlace(invalid)).toThrow(/Invalid input encoding/);
+    invalid.free();
+    const unclosed = fill(Buffer.from('a: [1, 2'));
+    expect(() => fastYaml.parseInPlace(unclosed)).toThrow(fastYaml.YAMLException);
+    unclosed.free();
+  });
+});
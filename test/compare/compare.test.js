@@ -0,0 +1,52 @@
+/**
+ * Reference Comparison Tests
+ *
+ * This test file tests comparing parse results against reference JSON dumps.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Reference Comparison Tests', () => {
+  test('reports equal documents', () => {
+    const result = fastYaml.compareWithReference(
+      'b: 1.0\na: [x, y]',
+      JSON.stringify({ a: ['x', 'y'], b: 1 })
+    );
+    expect(result).toEqual({ equal: true, differences: [] });
+  });
+
+  test('reports type and value differences with paths', () => {
+    const result = fastYaml.compareWithReference(
+      'server:\n  port: 8080\n  host: a',
+      JSON.stringify({ server: { port: '8080', host: 'b' } })
+    );
+    expect(result.equal).toBe(false);
+    expect(result.differences).toEqual([
+      { path: '$.server.host', kind: 'value', expected: 'b', actual: 'a' },
+      { path: '$.server.port', kind: 'type', expected: '8080', actual: 8080 },
+    ]);
+  });
+
+  test('reports missing and extra values', () => {
+    const result = fastYaml.compareWithReference(
+      'items: [1, 2, 3]\nextra: true',
+      JSON.stringify({ items: [1, 2], name: 'x' })
+    );
+    expect(result.differences).toEqual([
+      { path: '$.items[2]', kind: 'extra', expected: undefined, actual: 3 },
+      { path: '$.name', kind: 'missing', expected: 'x', actual: undefined },
+      { path: '$.extra', kind: 'extra', expected: undefined, actual: true },
+    ]);
+  });
+
+  test('passes load options through', () => {
+    const result = fastYaml.compareWithReference('port: 8080', '{"port":"8080"}', {
+      schema: fastYaml.FAILSAFE_SCHEMA,
+    });
+    expect(result.equal).toBe(true);
+  });
+
+  test('throws on an invalid reference', () => {
+    expect(() => fastYaml.compareWithReference('a: 1', '{')).toThrow(/Reference parsing error/);
+  });
+});
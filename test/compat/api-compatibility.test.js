@@ -99,6 +99,32 @@ baz: qux
     expect(fastYamlResults).toEqual(expectedResults);
   });
 
+  test('loadAll with an iterator', () => {
+    const yaml = '---\nfoo: bar\n---\nbaz: 1\n--- [\n';
+    const documents = [];
+    const iterator = (doc) => documents.push(doc);
+
+    expect(() => fastYaml.loadAll(yaml, iterator, { schema: 'failsafe' })).toThrow(
+      fastYaml.YAMLException
+    );
+    // Documents before the syntax error were already delivered
+    expect(documents).toEqual([{ foo: 'bar' }, { baz: '1' }]);
+
+    const results = [];
+    expect(fastYaml.loadAll('a: 1\n---\nb: 2\n', (doc) => results.push(doc))).toBeUndefined();
+    expect(results).toEqual([{ a: 1 }, { b: 2 }]);
+    expect(fastYaml.loadAll('a: 1', { schema: 'failsafe' })).toEqual([{ a: '1' }]);
+
+    // Without an iterator, the options in the third argument still apply
+    expect(fastYaml.loadAll('a: 1', null, { schema: 'failsafe' })).toEqual([{ a: '1' }]);
+    expect(fastYaml.loadAll('a: 1', undefined, { schema: 'failsafe' })).toEqual([{ a: '1' }]);
+
+    // Returning false stops parsing, before the invalid document
+    const first = [];
+    fastYaml.loadAll(yaml, (doc) => first.push(doc) && false);
+    expect(first).toEqual([{ foo: 'bar' }]);
+  });
+
   // Test error handling
   test('Error handling for invalid YAML', () => {
     // Use a more clearly invalid YAML that will definitely cause errors
@@ -147,6 +173,53 @@ baz: [invalid
     expect(fastYamlError.toString()).toMatch(/YAMLException:.+at line \d+/);
   });
 
+  test('Errors show a snippet with a caret under the column', () => {
+    let error = null;
+    try {
+      fastYaml.load('name: web\n  port: 80\nreplicas: 2\n');
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error.snippet).toBe(
+      ' 1 | name: web\n 2 |   port: 80\n-----------^\n 3 | replicas: 2\n 4 | \n'
+    );
+    expect(error.message).toBe(`${error.reason}\n\n${error.snippet}`);
+    expect(error.toString()).toContain(error.snippet);
+  });
+
+  test('Tabs used for indentation are reported at the tab', () => {
+    let error = null;
+    try {
+      fastYaml.load('server:\n  host: web\n\tport: 80\n');
+    } catch (e) {
+      error = e;
+    }
+
+    expect(error.code).toBe('TAB_INDENT');
+    expect(error.reason).toMatch(/tab character used for indentation; indent with spaces/);
+    expect([error.line, error.column]).toEqual([3, 1]);
+  });
+
+  test('Errors carry a stable code', () => {
+    const codeOf = (fn) => {
+      try {
+        fn();
+      } catch (error) {
+        return error.code;
+      }
+      return null;
+    };
+
+    expect(codeOf(() => fastYaml.load('a: 1\na: 2'))).toBe('DUPLICATE_KEY');
+    expect(codeOf(() => fastYaml.load('a:\n\tb: 1'))).toBe('TAB_INDENT');
+    expect(codeOf(() => fastYaml.load('a: "unterminated'))).toBe('UNEXPECTED_EOF');
+    expect(codeOf(() => fastYaml.load('a: *missing'))).toBe('UNKNOWN_ANCHOR');
+    const strict = { strictOptions: true, scheme: 'json' };
+    expect(codeOf(() => fastYaml.load('a: 1', strict))).toBe('UNKNOWN_OPTION');
+    expect(codeOf(() => fastYaml.query('a: 1', '$[?('))).toBe('INVALID_PATH');
+  });
+
   // Test complex data types
   test('Complex data types', () => {
     const yaml = `string: Hello World
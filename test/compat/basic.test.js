@@ -95,6 +95,31 @@ foo:
       expect(result).toEqual(expected);
       // expect(result).toEqual(jsYamlResult);
     });
+
+    test('maps .inf, -.inf and .nan to Infinity, -Infinity and NaN', () => {
+      const yaml = `
+up: .inf
+down: -.Inf
+missing: .NaN
+values: [1.5, +.inf]
+`;
+
+      const result = fastYaml.parse(yaml);
+
+      expect(result).toEqual({
+        up: Infinity,
+        down: -Infinity,
+        missing: NaN,
+        values: [1.5, Infinity]
+      });
+      expect(fastYaml.parseAll('---\n.inf\n---\n.nan\n')).toEqual([Infinity, NaN]);
+    });
+
+    test('writes non-finite floats as null in JSON strings', () => {
+      const json = fastYaml.parseToJsonString('a: .inf\nb: .nan\nc: 1\n');
+
+      expect(JSON.parse(json)).toEqual({ a: null, b: null, c: 1 });
+    });
   });
 
   describe('Multiple Document Parsing', () => {
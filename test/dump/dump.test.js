@@ -0,0 +1,121 @@
+/**
+ * Dump Tests
+ *
+ * This test file tests serializing JavaScript values as YAML.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Dump Tests', () => {
+  const value = { name: 'web', replicas: null, ports: [80, null] };
+
+  test('dumps without a document marker', () => {
+    expect(fastYaml.dump(value)).toBe('name: web\nreplicas: null\nports:\n  - 80\n  - null\n');
+  });
+
+  test('round-trips through parse', () => {
+    expect(fastYaml.parse(fastYaml.dump(value))).toEqual(value);
+  });
+
+  test.each([
+    ['canonical', 'replicas: ~\n'],
+    ['lowercase', 'replicas: null\n'],
+    ['uppercase', 'replicas: NULL\n'],
+    ['camelcase', 'replicas: Null\n'],
+    ['empty', 'replicas:\n'],
+  ])('writes nulls with the %s style', (style, expected) => {
+    expect(fastYaml.dump({ replicas: null }, { styles: { '!!null': style } })).toBe(expected);
+  });
+
+  test('keeps Map key types', () => {
+    const map = new Map([
+      [1, 'one'],
+      [true, 'ok'],
+    ]);
+    expect(fastYaml.dump(map)).toBe('1: one\ntrue: ok\n');
+    expect(fastYaml.parse(fastYaml.dump(map), { mapAsMap: true })).toEqual(map);
+  });
+
+  test('rejects unknown styles', () => {
+    expect(() => fastYaml.dump(null, { styles: { '!!null': 'blank' } })).toThrow(/Unknown !!null style/);
+  });
+
+  describe('keyStyle', () => {
+    test('renames keys to snake_case', () => {
+      const value = { maxRetries: 3, logLevel: { outputFile: 'a.log' } };
+      expect(fastYaml.dump(value, { keyStyle: 'snake' })).toBe(
+        'max_retries: 3\nlog_level:\n  output_file: a.log\n'
+      );
+    });
+
+    test('round-trips with the camel load style', () => {
+      const yaml = 'max_retries: 3\nlog_level: debug\n';
+      const config = fastYaml.parse(yaml, { keyStyle: 'camel' });
+      expect(config).toEqual({ maxRetries: 3, logLevel: 'debug' });
+      expect(fastYaml.dump(config, { keyStyle: 'snake' })).toBe(yaml);
+    });
+
+    test('renames keys with a callback', () => {
+      expect(fastYaml.dump({ a: 1 }, { keyStyle: (key) => `x-${key}` })).toBe('x-a: 1\n');
+    });
+  });
+
+  describe('directives', () => {
+    test('writes directives before a document marker', () => {
+      const directives = { version: '1.2', tags: { '!e!': 'tag:example.com,2000:' } };
+      expect(fastYaml.dump({ a: 1 }, { directives })).toBe(
+        '%YAML 1.2\n%TAG !e! tag:example.com,2000:\n---\na: 1\n'
+      );
+      expect(fastYaml.estimateDumpSize({ a: 1 }, { directives })).toBe(
+        fastYaml.dump({ a: 1 }, { directives }).length
+      );
+    });
+
+    test('round-trips the directives reported by parseStream', () => {
+      const yaml = '%TAG !e! tag:example.com,2000:\n---\na: 1\n';
+      fastYaml.parseStream(
+        yaml,
+        ({ value, directives }) => {
+          expect(fastYaml.dump(value, { directives })).toBe(yaml);
+        },
+        { directives: true }
+      );
+    });
+
+    test('rejects invalid directives', () => {
+      expect(() => fastYaml.dump(1, { directives: { version: '2.0' } })).toThrow(
+        /Unknown YAML version: 2.0/
+      );
+      expect(() => fastYaml.dump(1, { directives: { tags: { e: 'tag:x' } } })).toThrow(
+        /Invalid tag handle: e/
+      );
+      expect(() => fastYaml.dump(1, { directives: { tags: { '!e!': 'a b' } } })).toThrow(
+        /Invalid tag prefix for !e!/
+      );
+    });
+  });
+
+  describe('estimateDumpSize', () => {
+    test.each([
+      ['a mapping', { name: 'web', replicas: null, ports: [80, 443], env: {} }],
+      ['nested collections', [{ a: [1, [2, 3]] }, { b: { c: { d: 'e' } } }]],
+      ['quoted and non-ASCII strings', { text: 'ünïcödé', quoted: 'a: b', empty: '', tab: 'a\tb' }],
+      ['a scalar', 'plain'],
+      ['an empty array', []],
+    ])('matches the byte length of dump for %s', (_, value) => {
+      expect(fastYaml.estimateDumpSize(value)).toBe(Buffer.byteLength(fastYaml.dump(value)));
+    });
+
+    test('applies dump options', () => {
+      const value = { someKey: null };
+      const options = { keyStyle: 'snake', styles: { '!!null': 'empty' } };
+      expect(fastYaml.estimateDumpSize(value, options)).toBe(
+        Buffer.byteLength(fastYaml.dump(value, options))
+      );
+    });
+
+    test('rejects values dump rejects', () => {
+      expect(() => fastYaml.estimateDumpSize({ f: () => 1 })).toThrow(/Unacceptable kind of value/);
+    });
+  });
+});
@@ -0,0 +1,87 @@
+/**
+ * Graph Export Tests
+ *
+ * This test file tests exporting the node structure of YAML documents as Graphviz DOT graphs
+ * and node and edge lists.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Graph Export Tests', () => {
+  const yaml = 'base: &base {host: db}\nweb:\n  <<: *base\n  ports: [80]\n';
+
+  test('writes DOT with keys and indexes on edges', () => {
+    expect(fastYaml.toDot(yaml)).toBe(
+      [
+        'digraph yaml {',
+        '  node [shape=box];',
+        '  n0 [label="document 0", shape=ellipse];',
+        '  n1 [label="{}"];',
+        '  n2 [label="&base {}"];',
+        '  n3 [label="db"];',
+        '  n4 [label="{}"];',
+        '  n5 [label="*base", shape=diamond];',
+        '  n6 [label="[]"];',
+        '  n7 [label="80"];',
+        '  n0 -> n1;',
+        '  n1 -> n2 [label="base"];',
+        '  n2 -> n3 [label="host"];',
+        '  n1 -> n4 [label="web"];',
+        '  n4 -> n5 [label="<<"];',
+        '  n4 -> n6 [label="ports"];',
+        '  n6 -> n7 [label="0"];',
+        '}',
+        '',
+      ].join('\n')
+    );
+  });
+
+  test('links aliases to their anchored node on request', () => {
+    const dot = fastYaml.toDot(yaml, { aliases: true });
+    expect(dot).toContain('  n5 -> n2 [style=dashed, constraint=false];\n');
+  });
+
+  test('returns node and edge lists', () => {
+    const graph = fastYaml.toDot('a: &x [x]\nb: *x\n', { format: 'json', aliases: true });
+    expect(graph.nodes.map(({ id, kind, label }) => ({ id, kind, label }))).toEqual([
+      { id: 0, kind: 'document', label: 'document 0' },
+      { id: 1, kind: 'mapping', label: '{}' },
+      { id: 2, kind: 'sequence', label: '&x []' },
+      { id: 3, kind: 'scalar', label: 'x' },
+      { id: 4, kind: 'alias', label: '*x' },
+    ]);
+    expect(graph.nodes[3]).toMatchObject({ line: 1, column: 8 });
+    expect(graph.nodes[4]).toMatchObject({ line: 2, column: 4 });
+    expect(graph.edges).toEqual([
+      { from: 0, to: 1, kind: 'child' },
+      { from: 1, to: 2, kind: 'child', label: 'a' },
+      { from: 2, to: 3, kind: 'child', label: '0' },
+      { from: 1, to: 4, kind: 'child', label: 'b' },
+      { from: 4, to: 2, kind: 'alias' },
+    ]);
+  });
+
+  test('links collection keys to their value', () => {
+    const { edges } = fastYaml.toDot('? [a]\n: v\n', { format: 'json' });
+    expect(edges).toContainEqual({ from: 1, to: 2, kind: 'child', label: 'key' });
+    expect(edges).toContainEqual({ from: 2, to: 4, kind: 'child', label: 'value' });
+  });
+
+  test('includes every document, tags and escaped labels', () => {
+    const dot = fastYaml.toDot('--- !Ref \'say "hi"\'\n--- x\n');
+    expect(dot).toContain('n0 [label="document 0", shape=ellipse];');
+    expect(dot).toContain('n1 [label="!Ref say \\"hi\\""];');
+    expect(dot).toContain('n2 [label="document 1", shape=ellipse];');
+    expect(dot).toContain('n2 -> n3;');
+  });
+
+  test('cuts long scalars', () => {
+    const { nodes } = fastYaml.toDot('- abcdefgh', { format: 'json', maxLabelLength: 4 });
+    expect(nodes[2].label).toBe('abcd...');
+  });
+
+  test('rejects unknown formats and invalid YAML', () => {
+    expect(() => fastYaml.toDot('a: 1', { format: 'svg' })).toThrow(/Unknown format: svg/);
+    expect(() => fastYaml.toDot('a: [1')).toThrow(/YAML parsing error|line/);
+  });
+});
@@ -0,0 +1,215 @@
+/**
+ * Document Handle Tests
+ *
+ * This test file tests parseToHandle, which keeps a parsed document in WebAssembly memory for
+ * repeated conversion, queries and validation.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Document Handle Tests', () => {
+  const yaml = 'name: web\nports:\n  - 80\n  - 443\nowner_name: ops\n';
+
+  test('converts, queries and validates without parsing again', () => {
+    const doc = fastYaml.parseToHandle(yaml, { keyStyle: 'camel' });
+    expect(doc.toJS()).toEqual({ name: 'web', ports: [80, 443], ownerName: 'ops' });
+    expect(doc.toJS()).toEqual(doc.toJS());
+    expect(doc.query('$.ports[*]')).toEqual([80, 443]);
+    expect(doc.query('$.name')).toEqual(['web']);
+
+    const schema = { type: 'object', required: ['name', 'image'] };
+    const result = doc.validate(schema);
+    expect(result.valid).toBe(false);
+    expect(result.errors[0].code).toBe('REQUIRED');
+    expect(doc.validate({ type: 'object' }).valid).toBe(true);
+    doc.free();
+  });
+
+  test('handles input without a document', () => {
+    const doc = fastYaml.parseToHandle('# nothing\n');
+    expect(doc.toJS()).toBeNull();
+    expect(doc.query('$.a')).toEqual([]);
+    expect(() => doc.validate({ type: 'object' })).toThrow(/Empty YAML document/);
+    doc.free();
+  });
+
+  test('reports values that cannot be resolved once', () => {
+    const warnings = [];
+    const doc = fastYaml.parseToHandle('port: !!int abc\n', { onWarning: (w) => warnings.push(w) });
+    expect(doc.query('$.port')).toEqual([null]);
+    expect(doc.query('$.port')).toEqual([null]);
+    expect(warnings).toHaveLength(1);
+    expect(doc.validate({ type: 'object' }).valid).toBe(false);
+    doc.free();
+  });
+
+  test('resolves queried values with the YAML version and sexagesimal rules', () => {
+    const options = { version: '1.1', sexagesimal: true };
+    const doc = fastYaml.parseToHandle('enabled: on\nmode: 0755\nduration: 1:30\n', options);
+    expect(doc.query('$.*')).toEqual(Object.values(doc.toJS()));
+    expect(doc.query('$.*')).toEqual([true, 493, 90]);
+    doc.free();
+  });
+
+  test('queries and validates the values toJS gives with the schema option', () => {
+    const doc = fastYaml.parseToHandle('port: 80\nenabled: true\n', { schema: 'failsafe' });
+    expect(doc.toJS()).toEqual({ port: '80', enabled: 'true' });
+    expect(doc.query('$.*')).toEqual(['80', 'true']);
+    const schema = { type: 'object', properties: { port: { type: 'string' } } };
+    expect(doc.validate(schema).valid).toBe(true);
+    doc.free();
+  });
+
+  test('queries merged mappings as toJS gives them', () => {
+    const doc = fastYaml.parseToHandle('base: &b {b: 1}\nc: {<<: *b}\n');
+    expect(doc.query('$.c')).toEqual([doc.toJS().c]);
+    expect(doc.query('$.c.b')).toEqual([1]);
+    doc.free();
+  });
+
+  test('follows the duplicateKey option when querying', () => {
+    const yaml = 'a: 1\nb: 2\na: 3\n';
+    expect(() => fastYaml.parseToHandle(yaml).query('$.a')).toThrow(/duplicated key 'a'/);
+
+    const warnings = [];
+    const doc = fastYaml.parseToHandle(yaml, {
+      duplicateKey: 'warn',
+      onWarning: (w) => warnings.push(w.message),
+    });
+    expect(doc.query('$.*')).toEqual([3, 2]);
+    expect(doc.query('$.*')).toEqual(Object.values(doc.toJS()));
+    expect(warnings[0]).toMatch(/duplicated key 'a' in mapping overrides an earlier value/);
+    doc.free();
+  });
+
+  test('bounds alias expansion when querying and validating', () => {
+    const levels = 'abcdefghi'.split('');
+    const bomb = levels
+      .map((name, i) => {
+        const items = i === 0 ? Array(9).fill('lol') : Array(9).fill(`*${levels[i - 1]}`);
+        return `${name}: &${name} [${items.join(', ')}]`;
+      })
+      .join('\n');
+    const doc = fastYaml.parseToHandle(bomb);
+    expect(() => doc.query('$.a')).toThrow(/maxAliasCount/);
+    expect(() => doc.validate({ type: 'object' })).toThrow(/maxAliasCount/);
+    doc.free();
+  });
+
+  test('throws a YAMLException for invalid input', () => {
+    expect(() => fastYaml.parseToHandle('a: [1, 2')).toThrow(fastYaml.YAMLException);
+  });
+});
+
+describe('Document Handle get Tests', () => {
+  const yaml =
+    'defaults: &defaults {retries: 3}\n' +
+    'service:\n  <<: *defaults\n  max_conn: 10\n  ports: [80, 443]\n';
+
+  test('converts only the value at a path', () => {
+    const doc = fastYaml.parseToHandle(yaml, { keyStyle: 'camel' });
+    expect(doc.get('$')).toEqual(doc.toJS());
+    expect(doc.get('$.service')).toEqual(doc.toJS().service);
+    expect(doc.get('$.service.maxConn')).toBe(10);
+    expect(doc.get('$.service.retries')).toBe(3);
+    expect(doc.get('$.service.ports[1]')).toBe(443);
+    doc.free();
+  });
+
+  test('returns undefined for a path with no value', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(doc.get('$.missing')).toBeUndefined();
+    expect(doc.get('$.service.ports[5]')).toBeUndefined();
+    expect(doc.get('$.service.ports.name')).toBeUndefined();
+    doc.free();
+  });
+
+  test('rejects paths selecting several values', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(() => doc.get('$.service.ports[*]')).toThrow(/use query\(\)/);
+    expect(() => doc.get('$..ports')).toThrow(/use query\(\)/);
+    doc.free();
+  });
+});
+
+describe('Document Handle update Tests', () => {
+  const yaml = 'name: app\nspec:\n  replicas: 3\n  ports: [80]\nlabels:\n  - web\n';
+  const edit = (source, search, inserted) => {
+    const offset = source.indexOf(search);
+    return { offset, removed: search.length, inserted };
+  };
+
+  test('parses again only the edited entries', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(doc.update([edit(yaml, '3', '5')])).toBe(true);
+    expect(doc.get('$.spec.replicas')).toBe(5);
+    expect(doc.toJS()).toEqual(fastYaml.load(yaml.replace('3', '5')));
+    doc.free();
+  });
+
+  test('inserts and removes entries', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    const inserted = yaml.replace('labels:', 'image: nginx\nlabels:');
+    expect(doc.update([{ offset: yaml.indexOf('labels:'), inserted: 'image: nginx\n' }]))
+      .toBe(true);
+    expect(doc.toJS()).toEqual(fastYaml.load(inserted));
+    expect(doc.update([edit(inserted, 'name: app\n', '')])).toBe(true);
+    expect(doc.toJS()).toEqual(fastYaml.load(inserted.replace('name: app\n', '')));
+    doc.free();
+  });
+
+  test('applies edits in order', () => {
+    const doc = fastYaml.parseToHandle('- a\n- b\n');
+    doc.update([
+      { offset: 2, removed: 1, inserted: 'x' },
+      { offset: 8, inserted: '- c\n' },
+    ]);
+    expect(doc.toJS()).toEqual(['x', 'b', 'c']);
+    doc.free();
+  });
+
+  test('counts offsets in UTF-16 code units', () => {
+    const source = 'a: 😀\nb: 1\n';
+    const doc = fastYaml.parseToHandle(source);
+    doc.update([{ offset: source.indexOf('1'), removed: 1, inserted: '2' }]);
+    expect(doc.toJS()).toEqual({ a: '😀', b: 2 });
+    expect(() => doc.update([{ offset: 4, removed: 1 }])).toThrow(/splits a character/);
+    doc.free();
+  });
+
+  test('parses the whole source when edits cannot be confined to entries', () => {
+    const doc = fastYaml.parseToHandle('a: &x 1\nb: *x\n');
+    expect(doc.update([{ offset: 6, removed: 1, inserted: '2' }])).toBe(false);
+    expect(doc.toJS()).toEqual({ a: 2, b: 2 });
+    doc.free();
+
+    const scalar = fastYaml.parseToHandle('a: 1\n');
+    expect(scalar.update([{ offset: 0, removed: 5, inserted: 'text' }])).toBe(false);
+    expect(scalar.toJS()).toBe('text');
+    scalar.free();
+  });
+
+  test('keeps the last document that parsed when an edit does not parse', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(() => doc.update([{ offset: yaml.indexOf('80'), inserted: '[' }])).toThrow();
+    expect(doc.toJS()).toEqual(fastYaml.load(yaml));
+    expect(doc.update([{ offset: yaml.indexOf('80'), removed: 1 }])).toBe(false);
+    expect(doc.toJS()).toEqual(fastYaml.load(yaml));
+    doc.free();
+  });
+
+  test('refreshes query results after an update', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(doc.query('$.labels[*]')).toEqual(['web']);
+    doc.update([edit(yaml, 'web', 'api')]);
+    expect(doc.query('$.labels[*]')).toEqual(['api']);
+    doc.free();
+  });
+
+  test('rejects invalid edits', () => {
+    const doc = fastYaml.parseToHandle(yaml);
+    expect(() => doc.update([{ offset: -1 }])).toThrow(/non-negative integer/);
+    expect(() => doc.update([{ offset: yaml.length + 1 }])).toThrow(/outside the text/);
+    doc.free();
+  });
+});
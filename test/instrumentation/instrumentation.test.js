@@ -0,0 +1,144 @@
+/**
+ * Instrumentation Tests
+ *
+ * This test file tests the counters reported by builds with the instrumentation feature, and
+ * the timing of calls made with the withTiming option.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+// Regular builds report nothing; the counters are checked against `npm run build:instrumented`
+const instrumented = fastYaml.instrumentation() !== null;
+const describeInstrumented = instrumented ? describe : describe.skip;
+
+describe('Instrumentation Tests', () => {
+  test('returns null or counters', () => {
+    const counters = fastYaml.instrumentation();
+    if (!instrumented) {
+      expect(counters).toBeNull();
+    } else {
+      expect(Object.keys(counters).sort()).toEqual([
+        'bytesAllocated',
+        'peakBytes',
+        'peakTreeBytes',
+        'phases',
+      ]);
+    }
+  });
+
+  describeInstrumented('instrumented builds', () => {
+    const yaml = Array.from({ length: 500 }, (_, i) => `key${i}: value ${i}`).join('\n');
+
+    test('counts the last parse call', () => {
+      fastYaml.parse(yaml);
+      const counters = fastYaml.instrumentation();
+      expect(counters.bytesAllocated).toBeGreaterThan(0);
+      expect(counters.peakBytes).toBeGreaterThan(0);
+      expect(counters.peakTreeBytes).toBeGreaterThan(0);
+      expect(counters.phases.load).toBeGreaterThanOrEqual(0);
+      expect(counters.phases.emit).toBe(0);
+    });
+
+    test('resets the counters on each call', () => {
+      fastYaml.parse(yaml);
+      const large = fastYaml.instrumentation();
+      fastYaml.parse('a: 1');
+      const small = fastYaml.instrumentation();
+      expect(small.bytesAllocated).toBeLessThan(large.bytesAllocated);
+      expect(small.peakTreeBytes).toBeLessThan(large.peakTreeBytes);
+    });
+
+    test('counts dump calls', () => {
+      fastYaml.dump({ a: [1, 2, 3] });
+      const counters = fastYaml.instrumentation();
+      expect(counters.peakTreeBytes).toBe(0);
+      expect(counters.phases.load).toBe(0);
+      expect(counters.bytesAllocated).toBeGreaterThan(0);
+    });
+  });
+
+  describe('withTiming', () => {
+    const yaml = Array.from({ length: 500 }, (_, i) => `key${i}: [value, ${i}]`).join('\n');
+
+    test('returns the value with the time spent in each part', () => {
+      const { value, timing } = fastYaml.parse(yaml, { withTiming: true });
+      expect(value).toEqual(fastYaml.parse(yaml));
+      expect(Object.keys(timing).sort()).toEqual(['build', 'convert', 'scan', 'total']);
+      for (const ms of Object.values(timing)) {
+        expect(ms).toBeGreaterThanOrEqual(0);
+      }
+      expect(timing.scan + timing.build + timing.convert).toBeLessThanOrEqual(timing.total + 1);
+    });
+
+    test('times parseAll and parseBytes', () => {
+      const stream = 'a: 1\n---\nb: 2\n';
+      expect(fastYaml.parseAll(stream, { withTiming: true }).value).toEqual([{ a: 1 }, { b: 2 }]);
+      const bytes = Buffer.from(yaml);
+      expect(fastYaml.parseBytes(bytes, { withTiming: true }).value).toEqual(fastYaml.parse(yaml));
+    });
+
+    test('times the calls of a created parser', () => {
+      const parser = fastYaml.createParser({ withTiming: true });
+      const { value, timing } = parser.parse(yaml);
+      expect(value).toEqual(fastYaml.parse(yaml));
+      expect(Object.keys(timing).sort()).toEqual(['build', 'convert', 'scan', 'total']);
+      expect(parser.parseAll('a: 1\n---\nb: 2\n').value).toEqual([{ a: 1 }, { b: 2 }]);
+      parser.free();
+    });
+
+    test('times the other calls returning values', () => {
+      const options = { withTiming: true };
+      const keys = ['build', 'convert', 'scan', 'total'];
+      const batch = fastYaml.parseBatch(['a: 1', 'a: ['], options);
+      expect(Object.keys(batch.timing).sort()).toEqual(keys);
+      expect(batch.value[0]).toEqual({ value: { a: 1 }, error: null });
+      expect(batch.value[1].error).toBeInstanceOf(fastYaml.YAMLException);
+      const lenient = fastYaml.parseLenient('a: 1\n---\nb: [\n', options).value;
+      expect(lenient.documents[0]).toEqual({ a: 1 });
+      expect(lenient.errors[0]).toBeInstanceOf(fastYaml.YAMLException);
+      const meta = fastYaml.parseWithMeta('a: 1', options);
+      expect(Object.keys(meta.timing).sort()).toEqual(keys);
+      expect(meta.value).toMatchObject({ value: { a: 1 }, documentCount: 1, warnings: [] });
+      expect(fastYaml.parseToJsonString('a: 1', options).value).toBe('{"a":1}');
+      expect(fastYaml.parsePreview('a: 1', options).value).toEqual({
+        value: { a: 1 },
+        truncated: [],
+      });
+      expect(fastYaml.extractDocument('a: 1\n---\nb: 2\n', 1, options).value).toEqual({ b: 2 });
+    });
+
+    test('times loadAll with an iterator', () => {
+      const docs = [];
+      const result = fastYaml.loadAll('a: 1\n---\nb: 2\n', (doc) => docs.push(doc), {
+        withTiming: true,
+      });
+      expect(docs).toEqual([{ a: 1 }, { b: 2 }]);
+      expect(result.value).toBeUndefined();
+      expect(Object.keys(result.timing).sort()).toEqual(['build', 'convert', 'scan', 'total']);
+    });
+
+    test('is rejected by calls that cannot return timings', () => {
+      const options = { withTiming: true };
+      expect(() => fastYaml.parseLazy('a: 1', options)).toThrow(
+        "Option 'withTiming' is not supported by parseLazy"
+      );
+      expect(() => fastYaml.parseToHandle('a: 1', options)).toThrow(/parseToHandle/);
+      expect(() => fastYaml.parseStream('a: 1', () => {}, options)).toThrow(/stream parsers/);
+      expect(() => fastYaml.createStreamParser(() => {}, options)).toThrow(/stream parsers/);
+      expect(() => fastYaml.pipeline('a: 1', [], options)).toThrow(/pipeline/);
+      expect(() => fastYaml.compareWithReference('a: 1', '{}', options)).toThrow(
+        /compareWithReference/
+      );
+    });
+
+    test('leaves calls without the option unchanged', () => {
+      expect(fastYaml.parse('a: 1', { withTiming: false })).toEqual({ a: 1 });
+      expect(fastYaml.parse('a: 1')).toEqual({ a: 1 });
+    });
+
+    test('throws syntax errors as usual', () => {
+      expect(() => fastYaml.parse('a: [1,', { withTiming: true })).toThrow(fastYaml.YAMLException);
+      expect(fastYaml.parse('a: 1')).toEqual({ a: 1 });
+    });
+  });
+});
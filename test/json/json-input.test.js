@@ -0,0 +1,67 @@
+/**
+ * JSON Input Tests
+ *
+ * This test file tests input that is plain JSON, which is read with JSON.parse instead of the
+ * YAML loader, and checks the results match what the YAML loader produces.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+// Options that keep the YAML loader without changing these documents
+const YAML_LOADER = { maxNodes: 1e9 };
+
+describe('JSON Input Tests', () => {
+  const documents = [
+    '{"name": "app", "ports": [80, 443], "debug": false, "parent": null}',
+    '[1, -2.5, 1e3, 1E-2, -0.0, 12345678901234567890, "\\u00e9\\n\\/"]',
+    '{\n  "nested": {\n    "list": [\n      {"a": 1},\n      []\n    ]\n  }\n}\n',
+    '{"__proto__": {"polluted": true}, "constructor": 1}',
+  ];
+
+  test.each(documents)('reads %j like the YAML loader', (json) => {
+    expect(fastYaml.parse(json)).toEqual(fastYaml.parse(json, YAML_LOADER));
+    expect(fastYaml.parse(json)).toEqual(JSON.parse(json));
+    expect(fastYaml.parseAll(json)).toEqual([JSON.parse(json)]);
+  });
+
+  test('keeps YAML semantics for JSON that reads differently', () => {
+    expect(() => fastYaml.parse('{"a": 1, "a": 2}')).toThrow(/duplicated key/);
+    expect(fastYaml.parse('{"<<": {"a": 1}, "b": 2}')).toEqual({ a: 1, b: 2 });
+    expect(Object.is(fastYaml.parse('[-0]')[0], 0)).toBe(true);
+  });
+
+  test('reports duplicate keys as the YAML loader does', () => {
+    const json = '[{"b": {"c": 1, "d": 2, "c": 3}}]';
+    expect(() => fastYaml.parse(json)).toThrow(/duplicated key/);
+    expect(() => fastYaml.parseAll(json)).toThrow(/duplicated key/);
+
+    const warnings = [];
+    const onWarning = (warning) => warnings.push(warning.message);
+    expect(fastYaml.parse(json, { json: true, onWarning })).toEqual([{ b: { c: 3, d: 2 } }]);
+    expect(warnings).toEqual([]);
+    expect(fastYaml.parse(json, { duplicateKey: 'warn', onWarning })).toEqual(JSON.parse(json));
+    expect(warnings).toHaveLength(1);
+    expect(warnings[0]).toMatch(/duplicated key/);
+
+    expect(fastYaml.parse(json, { duplicateKey: 'last' })).toEqual(JSON.parse(json));
+    expect(fastYaml.parse(json, { duplicateKey: 'first' })).toEqual([{ b: { c: 1, d: 2 } }]);
+  });
+
+  test('applies options that change values or keys', () => {
+    const json = '{"max_size": 1.23456, "count": 1}';
+    expect(fastYaml.parse(json, { keyStyle: 'camel' })).toEqual({ maxSize: 1.23456, count: 1 });
+    expect(fastYaml.parse(json, { floatPrecision: 2 })).toEqual({ max_size: 1.23, count: 1 });
+    expect(fastYaml.parse(json, { schema: 'failsafe' })).toEqual({
+      max_size: '1.23456',
+      count: '1',
+    });
+    expect(fastYaml.parse('[9007199254740993]', { useBigInt64: true })).toEqual([
+      9007199254740993n,
+    ]);
+  });
+
+  test('enforces limits on JSON input', () => {
+    expect(() => fastYaml.parse('[[[[1]]]]', { maxNestingDepth: 2 })).toThrow(/maxNestingDepth/);
+    expect(() => fastYaml.parse('[1, 2, 3]', { maxNodes: 3 })).toThrow(/maxNodes/);
+  });
+});
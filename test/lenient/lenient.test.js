@@ -0,0 +1,53 @@
+/**
+ * Lenient Parsing Tests
+ *
+ * This test file tests recovering from syntax errors with partial results.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Lenient Parsing Tests', () => {
+  test('returns documents without errors for valid input', () => {
+    expect(fastYaml.parseLenient('a: 1\n---\nb: 2\n')).toEqual({
+      value: { a: 1 },
+      documents: [{ a: 1 }, { b: 2 }],
+      errors: [],
+    });
+    expect(fastYaml.parseLenient('')).toEqual({ value: null, documents: [], errors: [] });
+  });
+
+  test('skips a badly indented line', () => {
+    const { value, errors } = fastYaml.parseLenient('name: web\n  port: 80\nreplicas: 2\n');
+    expect(value).toEqual({ name: 'web', replicas: 2 });
+    expect(errors).toHaveLength(1);
+    expect(errors[0]).toBeInstanceOf(fastYaml.YAMLException);
+    expect(errors[0].line).toBe(2);
+  });
+
+  test('keeps the other documents of a stream', () => {
+    const { documents, errors } = fastYaml.parseLenient('a: 1\n---\nb: [1, 2\n---\nc: 3\n...\n');
+    expect(documents[0]).toEqual({ a: 1 });
+    expect(documents[documents.length - 1]).toEqual({ c: 3 });
+    expect(errors.length).toBeGreaterThan(0);
+    expect(errors[0].line).toBe(4);
+  });
+
+  test('splits streams as splitDocuments does', () => {
+    const stream = 'a: |\n  ---\n  text\n  ...\nb: 1\n--- |\n  %x\n---\nc: >\n  --- folded\n';
+    const { documents, errors } = fastYaml.parseLenient(stream);
+    expect(errors).toEqual([]);
+    expect(documents).toEqual(fastYaml.splitDocuments(stream).map((doc) => fastYaml.parse(doc)));
+    expect(documents).toEqual([
+      { a: '---\ntext\n...\n', b: 1 },
+      '%x\n',
+      { c: '--- folded\n' },
+    ]);
+  });
+
+  test('applies load options', () => {
+    expect(fastYaml.parseLenient('a: 1\n', { schema: 'failsafe' }).value).toEqual({ a: '1' });
+    expect(() => fastYaml.parseLenient('a: 1\n---\nb: 2\n', { maxDocuments: 1 })).toThrow(
+      /maxDocuments/
+    );
+  });
+});
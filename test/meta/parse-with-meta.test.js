@@ -0,0 +1,40 @@
+/**
+ * Parse Metadata Tests
+ *
+ * This test file tests parseWithMeta, which returns statistics along with the document.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Parse Metadata Tests', () => {
+  test('returns the first document with metadata about the stream', () => {
+    const meta = fastYaml.parseWithMeta('%YAML 1.1\n---\na: 1\n---\nb: 2\n');
+    expect(meta.value).toEqual({ a: 1 });
+    expect(meta.documentCount).toBe(2);
+    expect(meta.yamlVersion).toBe('1.1');
+    expect(meta.warnings).toEqual([]);
+    expect(meta.durationMs).toBeGreaterThanOrEqual(0);
+  });
+
+  test('describes input without a document', () => {
+    const meta = fastYaml.parseWithMeta('# nothing here\n');
+    expect(meta.value).toBeNull();
+    expect(meta.documentCount).toBe(0);
+    expect(meta.yamlVersion).toBeNull();
+  });
+
+  test('collects warnings and still passes them to onWarning', () => {
+    const received = [];
+    const meta = fastYaml.parseWithMeta('a: yes\nb: !custom x\n', {
+      onWarning: (w) => received.push(w),
+    });
+    expect(meta.warnings).toHaveLength(2);
+    expect(meta.warnings[0]).toBeInstanceOf(fastYaml.YAMLException);
+    expect(meta.warnings.map((w) => w.code)).toEqual(['YAML11_SCALAR', 'UNKNOWN_TAG']);
+    expect(received).toEqual(meta.warnings);
+  });
+
+  test('throws on syntax errors like parse', () => {
+    expect(() => fastYaml.parseWithMeta('a: [1\n')).toThrow(fastYaml.YAMLException);
+  });
+});
@@ -0,0 +1,1421 @@
+/**
+ * Load Options Tests
+ *
+ * This test file tests the options accepted by parse, parseAll, load and loadAll.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Load Options Tests', () => {
+  describe('schema', () => {
+    const yaml = `
+plain: yes
+bool: True
+nothing: ~
+int: 0x1F
+float: 1e3
+quoted: "true"
+`;
+
+    test('DEFAULT_SCHEMA resolves core schema scalars', () => {
+      expect(fastYaml.parse(yaml)).toEqual({
+        plain: 'yes',
+        bool: true,
+        nothing: null,
+        int: 31,
+        float: 1000,
+        quoted: 'true',
+      });
+    });
+
+    test('FAILSAFE_SCHEMA returns every scalar as a string', () => {
+      const result = fastYaml.parse(yaml, { schema: fastYaml.FAILSAFE_SCHEMA });
+      expect(result).toEqual({
+        plain: 'yes',
+        bool: 'True',
+        nothing: '~',
+        int: '0x1F',
+        float: '1e3',
+        quoted: 'true',
+      });
+    });
+
+    test('JSON_SCHEMA only resolves JSON literals', () => {
+      const result = fastYaml.parse(yaml, { schema: fastYaml.JSON_SCHEMA });
+      expect(result).toEqual({
+        plain: 'yes',
+        bool: 'True',
+        nothing: '~',
+        int: '0x1F',
+        float: 1000,
+        quoted: 'true',
+      });
+    });
+
+    test('explicit tags take precedence over the schema', () => {
+      const result = fastYaml.parse('a: !!int "42"\nb: !!str 42', {
+        schema: fastYaml.FAILSAFE_SCHEMA,
+      });
+      expect(result).toEqual({ a: 42, b: '42' });
+    });
+
+    test('throws for unknown schema names', () => {
+      expect(() => fastYaml.parse('a: 1', { schema: 'strict' })).toThrow(/Unknown schema/);
+    });
+  });
+
+  describe('sourceMap', () => {
+    test('parseToJsonString returns plain JSON by default', () => {
+      expect(fastYaml.parseToJsonString('a: [1, 2]')).toBe('{"a":[1,2]}');
+    });
+
+    test('maps JSON offsets back to YAML positions', () => {
+      const { json, sourceMap } = fastYaml.parseToJsonString('a: 1\nb: x\n', { sourceMap: true });
+      expect(json).toBe('{"a":1,"b":"x"}');
+
+      const triples = [];
+      for (let i = 0; i < sourceMap.length; i += 3) {
+        triples.push([sourceMap[i], sourceMap[i + 1], sourceMap[i + 2]]);
+      }
+      expect(triples).toContainEqual([json.indexOf('"b"'), 2, 1]);
+      expect(triples).toContainEqual([json.indexOf('"x"'), 2, 4]);
+    });
+  });
+
+  describe('allErrors', () => {
+    const yaml = 'a: 1\n b: 2\nc: 3\n  d: 4\n---\nx: [\n';
+
+    test('throws only the first error by default', () => {
+      expect.assertions(2);
+      try {
+        fastYaml.parse(yaml);
+      } catch (e) {
+        expect(e.line).toBe(2);
+        expect(e.errors).toBeNull();
+      }
+    });
+
+    test('reports every syntax error at once', () => {
+      expect.assertions(3);
+      try {
+        fastYaml.parseAll(yaml, { allErrors: true });
+      } catch (e) {
+        expect(e).toBeInstanceOf(fastYaml.YAMLException);
+        expect(e.line).toBe(2);
+        expect(e.errors.map((error) => error.line)).toEqual([2, 4, 7]);
+      }
+    });
+
+    test('reports every error to loadAll with an iterator', () => {
+      expect.assertions(2);
+      try {
+        fastYaml.loadAll(yaml, () => {}, { allErrors: true });
+      } catch (e) {
+        expect(e).toBeInstanceOf(fastYaml.YAMLException);
+        expect(e.errors.map((error) => error.line)).toEqual([2, 4, 7]);
+      }
+    });
+  });
+
+  describe('noDocument', () => {
+    test('returns null for input without a document by default', () => {
+      expect(fastYaml.parse('')).toBeNull();
+      expect(fastYaml.parse('# only a comment\n')).toBeNull();
+    });
+
+    test('undefined tells no document apart from a null document', () => {
+      const options = { noDocument: 'undefined' };
+      expect(fastYaml.parse('', options)).toBeUndefined();
+      expect(fastYaml.parse('# only a comment\n', options)).toBeUndefined();
+      expect(fastYaml.parse('---\n', options)).toBeNull();
+      expect(fastYaml.parse('null', options)).toBeNull();
+      expect(fastYaml.parseAll('', options)).toEqual([]);
+    });
+
+    test('rejects unknown values', () => {
+      expect(() => fastYaml.parse('', { noDocument: 'none' })).toThrow(/Unknown noDocument/);
+    });
+  });
+
+  describe('conversion', () => {
+    const yaml = 'name: app\nports: [80, 443]\nratio: 0.5\nbig: 12345678901234567890\n' +
+      'inf: .inf\nnested: {list: [~, true, "x"]}\n? [a, b]\n: complex\n';
+
+    test('direct and json conversion give the same result', () => {
+      const direct = fastYaml.parse(yaml, { conversion: 'direct' });
+      expect(fastYaml.parse(yaml)).toEqual(direct);
+      expect(fastYaml.parse(yaml, { conversion: 'json' })).toEqual(direct);
+      expect(direct.inf).toBe(Infinity);
+      expect(direct['["a","b"]']).toBe('complex');
+    });
+
+    test('keys repeated across many mappings are all set', () => {
+      const items = Array.from({ length: 5000 }, (_, i) => `- {name: n${i}, image: i${i}}`);
+      items.push(`- {${'k'.repeat(100)}: long}`);
+      const value = fastYaml.parse(`${items.join('\n')}\n`, { conversion: 'direct' });
+      expect(value).toHaveLength(5001);
+      expect(value[4999]).toEqual({ name: 'n4999', image: 'i4999' });
+      expect(value[5000]).toEqual({ ['k'.repeat(100)]: 'long' });
+    });
+
+    test('a __proto__ key is an own property, as with JSON.parse', () => {
+      for (const conversion of ['direct', 'json']) {
+        const value = fastYaml.parse('__proto__: {polluted: true}\n', { conversion });
+        expect(Object.getPrototypeOf(value)).toBe(Object.prototype);
+        expect(Object.keys(value)).toEqual(['__proto__']);
+        expect(value.polluted).toBeUndefined();
+      }
+    });
+
+    test('rejects unknown values', () => {
+      expect(() => fastYaml.parse('a: 1', { conversion: 'serde' })).toThrow(/Unknown conversion/);
+    });
+  });
+
+  describe('floats', () => {
+    test('floatPrecision rounds to significant digits', () => {
+      expect(fastYaml.parse('pi: 3.14159', { floatPrecision: 3 })).toEqual({ pi: 3.14 });
+    });
+
+    test('floatMode decimal keeps the decimal point in JSON output', () => {
+      expect(fastYaml.parseToJsonString('[1.0, 1]', { floatMode: 'decimal' })).toBe('[1.0,1]');
+    });
+
+    test('floatMode wrap distinguishes floats from integers', () => {
+      expect(fastYaml.parse('[1.0, 1]', { floatMode: 'wrap' })).toEqual([{ $float: 1 }, 1]);
+    });
+  });
+
+  describe('json', () => {
+    const yaml = 'a: 1\nb: 2\na: 3\n';
+
+    test('duplicate keys throw by default', () => {
+      expect(() => fastYaml.parse(yaml)).toThrow(/duplicated key/);
+    });
+
+    test('later duplicate keys override earlier values', () => {
+      const warnings = [];
+      const result = fastYaml.parse(yaml, { json: true, onWarning: (w) => warnings.push(w) });
+      expect(result).toEqual({ a: 3, b: 2 });
+      expect(Object.keys(result)).toEqual(['a', 'b']);
+      expect(warnings).toEqual([]);
+    });
+  });
+
+  describe('filename', () => {
+    test('prefixes error messages with the file name', () => {
+      expect(() => fastYaml.parse('a: 1\na: 2\n', { filename: 'config.yaml' })).toThrow(
+        /^config\.yaml: .*duplicated key.*line 2/
+      );
+    });
+  });
+
+  describe('onWarning', () => {
+    const collect = (yaml, options = {}) => {
+      const warnings = [];
+      fastYaml.parse(yaml, { ...options, onWarning: (w) => warnings.push(w) });
+      return warnings;
+    };
+
+    test('receives YAMLException instances with positions', () => {
+      const [warning] = collect('a: 1\na: 2\n', { duplicateKey: 'warn' });
+      expect(warning).toBeInstanceOf(fastYaml.YAMLException);
+      expect(warning.message).toMatch(/duplicated key/);
+      expect(warning.line).toBe(2);
+    });
+
+    test('reports unknown tags', () => {
+      expect(collect('a: !custom x')[0].message).toMatch(/unknown tag !custom/);
+    });
+
+    test('reports YAML 1.1 booleans and octals', () => {
+      const messages = collect('a: yes\nb: 0755').map((w) => w.message);
+      expect(messages).toEqual([expect.stringMatching(/'yes'/), expect.stringMatching(/0o755/)]);
+    });
+
+    test('is not called for clean documents', () => {
+      expect(collect('a: true\nb: 0o755')).toEqual([]);
+    });
+
+    test('includes the filename', () => {
+      expect(collect('a: !x y', { filename: 'f.yaml' })[0].message).toMatch(/^f\.yaml: /);
+    });
+  });
+
+  describe('alias limits', () => {
+    const levels = 'abcdefghi'.split('');
+    const bomb = levels
+      .map((name, i) => {
+        const items = i === 0 ? Array(9).fill('lol') : Array(9).fill(`*${levels[i - 1]}`);
+        return `${name}: &${name} [${items.join(', ')}]`;
+      })
+      .join('\n');
+
+    test('billion laughs fails fast', () => {
+      expect(() => fastYaml.parse(bomb)).toThrow(/maxAliasCount/);
+    });
+
+    test('maxAliasExpansion caps the expanded size', () => {
+      expect(() => fastYaml.parse(bomb, { maxAliasCount: Infinity })).toThrow(
+        /maxAliasExpansion/
+      );
+    });
+
+    test('documents within the limits load', () => {
+      const yaml = 'base: &base {a: 1}\nx: *base\ny: *base\n';
+      expect(fastYaml.parse(yaml, { maxAliasCount: 2 })).toEqual({
+        base: { a: 1 },
+        x: { a: 1 },
+        y: { a: 1 },
+      });
+      expect(() => fastYaml.parse(yaml, { maxAliasCount: 1 })).toThrow(/line 3/);
+    });
+
+    test('rejects invalid limits', () => {
+      expect(() => fastYaml.parse('a: 1', { maxAliasCount: -1 })).toThrow(/non-negative/);
+    });
+  });
+
+  describe('maxMemoryBytes', () => {
+    const yaml = `items:\n${Array.from({ length: 1000 }, (_, i) => `  - item ${i}`).join('\n')}\n`;
+
+    test('loads documents within the limit', () => {
+      expect(fastYaml.parse(yaml, { maxMemoryBytes: 1e6 }).items).toHaveLength(1000);
+      expect(fastYaml.parse(yaml, { maxMemoryBytes: Infinity }).items).toHaveLength(1000);
+    });
+
+    test('fails with a YAMLException at the node past the limit', () => {
+      let error;
+      try {
+        fastYaml.parse(yaml, { maxMemoryBytes: 4096 });
+      } catch (e) {
+        error = e;
+      }
+      expect(error).toBeInstanceOf(fastYaml.YAMLException);
+      expect(error.message).toMatch(/more than 4096 bytes of memory \(maxMemoryBytes\)/);
+      expect(error.line).toBeGreaterThan(1);
+    });
+
+    test('counts alias expansions while converting', () => {
+      const aliased = `base: &base [${Array(200).fill('value').join(', ')}]\n${Array.from(
+        { length: 50 },
+        (_, i) => `k${i}: *base`
+      ).join('\n')}\n`;
+      expect(() => fastYaml.parse(aliased, { maxMemoryBytes: 100000 })).toThrow(
+        /conversion needs more than 100000 bytes/
+      );
+      expect(() => fastYaml.parseToJsonString(aliased, { maxMemoryBytes: 100000 })).toThrow(
+        /maxMemoryBytes/
+      );
+      expect(Object.keys(fastYaml.parse(aliased, { maxMemoryBytes: 1e7 }))).toHaveLength(51);
+    });
+
+    test('rejects invalid limits', () => {
+      expect(() => fastYaml.parse('a: 1', { maxMemoryBytes: 1.5 })).toThrow(/non-negative/);
+    });
+  });
+
+  describe('unknown options', () => {
+    const strict = { strictOptions: true };
+
+    test('are ignored unless strictOptions is set', () => {
+      expect(fastYaml.parse('a: 1', { scheme: 'json', colour: true })).toEqual({ a: 1 });
+      expect(fastYaml.parse('a', { limits: { maxNode: 3 } })).toBe('a');
+      expect(fastYaml.dump({ a: 1 }, { indnt: 4 })).toBe('a: 1\n');
+      expect(fastYaml.skeleton({}, { depth: 1 })).toEqual(fastYaml.skeleton({}));
+    });
+
+    test('lists every unknown key', () => {
+      let error;
+      try {
+        const options = { ...strict, scheme: 'json', mapAsmap: true, filename: 'f.yaml' };
+        fastYaml.parse('a: 1', options);
+      } catch (e) {
+        error = e;
+      }
+      expect(error).toBeInstanceOf(fastYaml.YAMLException);
+      expect(error.message).toBe(
+        "Unknown options: scheme (did you mean 'schema'?), mapAsmap (did you mean 'mapAsMap'?)"
+      );
+      expect(error.unknownKeys).toEqual(['scheme', 'mapAsmap']);
+    });
+
+    test('names the closest option for typos only', () => {
+      let error;
+      try {
+        fastYaml.parse('a: 1', { ...strict, shema: 'json', colour: true });
+      } catch (e) {
+        error = e;
+      }
+      expect(error.message).toBe("Unknown options: shema (did you mean 'schema'?), colour");
+      expect(error.unknownKeys).toEqual(['shema', 'colour']);
+      expect(error.suggestions).toEqual({ shema: 'schema' });
+      expect(() => fastYaml.dump({ a: 1 }, { ...strict, sortkeys: true })).toThrow(
+        /did you mean 'sortKeys'/
+      );
+      expect(() => fastYaml.parse('a', { ...strict, limits: { maxNode: 3 } })).toThrow(
+        /did you mean 'maxNodes'/
+      );
+    });
+
+    test('accepts the options each function reads', () => {
+      expect(fastYaml.parse('a: 1', { ...strict, listener: () => {}, json: true })).toEqual({
+        a: 1,
+      });
+      expect(
+        fastYaml.parsePreview('a: 1', { ...strict, maxItems: 1, schema: 'json' }).value
+      ).toEqual({ a: 1 });
+      expect(fastYaml.parseLazy('a: [1]', { ...strict, maxDepth: 2 }).root()).toEqual({ a: [1] });
+      expect(() => fastYaml.parse('a: 1', { ...strict, maxItems: 1 })).toThrow(
+        /Unknown options: maxItems/
+      );
+    });
+
+    test('accepts js-yaml dump options', () => {
+      const options = { ...strict, indent: 4, sortKeys: true, lineWidth: -1 };
+      expect(fastYaml.dump({ a: 1 }, options)).toBe('a: 1\n');
+      expect(() => fastYaml.dump({ a: 1 }, { ...strict, indnt: 4 })).toThrow(
+        /Unknown options: indnt/
+      );
+    });
+
+    test('checks the options of other functions', () => {
+      expect(() => fastYaml.query('a: 1', '$.a', { ...strict, descendOnly: [] })).toThrow(
+        /descendOnly/
+      );
+      expect(() => fastYaml.validate('a: 1', {}, { ...strict, hints: true })).toThrow(/hints/);
+      expect(() => fastYaml.skeleton({}, { ...strict, depth: 1 })).toThrow(
+        /Unknown options: depth/
+      );
+      expect(() => fastYaml.toDot('a: 1', { ...strict, alias: true })).toThrow(
+        /Unknown options: alias/
+      );
+    });
+  });
+
+  describe('maxDocuments', () => {
+    const stream = 'a: 1\n---\nb: 2\n---\nc: 3\n';
+
+    test('loads streams within the limit', () => {
+      expect(fastYaml.loadAll(stream, { maxDocuments: 3 })).toHaveLength(3);
+      expect(fastYaml.parseAll('', { maxDocuments: 0 })).toEqual([]);
+    });
+
+    test('fails at the first document past the limit', () => {
+      let error;
+      try {
+        fastYaml.loadAll(stream, { maxDocuments: 2 });
+      } catch (e) {
+        error = e;
+      }
+      expect(error).toBeInstanceOf(fastYaml.YAMLException);
+      expect(error.message).toMatch(/more than 2 documents \(maxDocuments\)/);
+      expect(error.line).toBe(4);
+    });
+
+    test('streams the documents before the limit', () => {
+      const seen = [];
+      expect(() =>
+        fastYaml.parseStream(stream, (chunk) => seen.push(chunk.value), { maxDocuments: 2 })
+      ).toThrow(/maxDocuments/);
+      expect(seen).toEqual([{ a: 1 }, { b: 2 }]);
+    });
+  });
+
+  describe('limits', () => {
+    test('bounds the nodes of each document', () => {
+      expect(fastYaml.parse('[1, 2]', { maxNodes: 3 })).toEqual([1, 2]);
+      expect(() => fastYaml.parse('[1, 2, 3]', { maxNodes: 3 })).toThrow(
+        /more than 3 nodes \(maxNodes\)/
+      );
+      expect(fastYaml.loadAll('[1, 2]\n---\n[3, 4]\n', { maxNodes: 3 })).toHaveLength(2);
+    });
+
+    test('bounds the length of scalars in characters', () => {
+      expect(fastYaml.parse('a: ééé', { maxScalarLength: 3 })).toEqual({ a: 'ééé' });
+      let error;
+      try {
+        fastYaml.parse('a: 1\nb: éééé\n', { maxScalarLength: 3 });
+      } catch (e) {
+        error = e;
+      }
+      expect(error).toBeInstanceOf(fastYaml.YAMLException);
+      expect(error.message).toMatch(/longer than 3 characters \(maxScalarLength\)/);
+      expect(error.line).toBe(2);
+    });
+
+    test('bounds the size of the input before parsing it', () => {
+      expect(fastYaml.parse('a: b', { maxInputSize: 4 })).toEqual({ a: 'b' });
+      expect(() => fastYaml.parse('a: b', { maxInputSize: 3 })).toThrow(
+        /longer than 3 bytes \(maxInputSize\)/
+      );
+      expect(() => fastYaml.parse('[', { maxInputSize: 0 })).toThrow(/maxInputSize/);
+    });
+
+    test('accepts every limit in one object', () => {
+      const limits = { maxNodes: 3, maxNestingDepth: 1 };
+      expect(() => fastYaml.parse('[1, 2, 3]', { limits })).toThrow(/maxNodes/);
+      expect(() => fastYaml.parse('[[1]]', { limits })).toThrow(/maxNestingDepth/);
+      expect(() => fastYaml.parseAll('a\n---\nb', { limits: { maxDocuments: 1 } })).toThrow(
+        /maxDocuments/
+      );
+    });
+
+    test('prefers limits given as options of their own', () => {
+      const options = { limits: { maxNodes: 3 }, maxNodes: 4 };
+      expect(fastYaml.parse('[1, 2, 3]', options)).toEqual([1, 2, 3]);
+    });
+
+    test('rejects unknown limits and non-objects', () => {
+      expect(() => fastYaml.parse('a', { strictOptions: true, limits: { maxNode: 3 } })).toThrow(
+        /Unknown options: maxNode/
+      );
+      expect(() => fastYaml.parse('a', { limits: 3 })).toThrow(/'limits' must be an object/);
+    });
+  });
+
+  describe('maxNestingDepth', () => {
+    const nested = (depth) => `${'- '.repeat(depth)}x`;
+
+    test('loads documents up to 512 levels by default', () => {
+      expect(() => fastYaml.parse(nested(512))).not.toThrow();
+      expect(() => fastYaml.parse(nested(513))).toThrow(
+        /nesting exceeds 512 levels \(maxNestingDepth\)/
+      );
+    });
+
+    test('fails on hostile documents instead of overflowing the stack', () => {
+      expect(() => fastYaml.parse(nested(100000))).toThrow(fastYaml.YAMLException);
+      expect(() => fastYaml.parseToJsonString(nested(100000))).toThrow(/maxNestingDepth/);
+    });
+
+    test('counts nesting through aliases', () => {
+      const yaml = 'a: &a [[1]]\nb: [[*a]]\n';
+      expect(fastYaml.parse(yaml, { maxNestingDepth: 5 }).b).toEqual([[[[1]]]]);
+      expect(() => fastYaml.parse(yaml, { maxNestingDepth: 4 })).toThrow(/line 1/);
+      expect(() => fastYaml.parse(yaml, { maxNestingDepth: 4, mapAsMap: true })).toThrow(
+        /maxNestingDepth/
+      );
+    });
+
+    test('can be raised', () => {
+      expect(fastYaml.parse('[[[]]]', { maxNestingDepth: 3 })).toEqual([[[]]]);
+      expect(() => fastYaml.parse('[[[]]]', { maxNestingDepth: 2 })).toThrow(/line 1, column 3/);
+      expect(() => fastYaml.parse(nested(600), { maxNestingDepth: 1000 })).not.toThrow();
+    });
+  });
+
+  describe('merge keys', () => {
+    test('merges an aliased mapping, explicit keys winning', () => {
+      const yaml = 'base: &b {a: 1, b: 2}\nx:\n  <<: *b\n  b: 3\n';
+      expect(fastYaml.parse(yaml).x).toEqual({ a: 1, b: 3 });
+    });
+
+    test('earlier sources in a sequence take precedence', () => {
+      const yaml = '- &A {x: 1, y: 1}\n- &B {y: 2, z: 2}\n- <<: [*A, *B]\n';
+      expect(fastYaml.parse(yaml)[2]).toEqual({ x: 1, y: 1, z: 2 });
+    });
+
+    test('rejects non-mapping sources', () => {
+      expect(() => fastYaml.parse('a: {<<: 1}')).toThrow(/cannot merge mappings/);
+    });
+
+    test('quoted keys and other schemas keep << as a key', () => {
+      expect(fastYaml.parse("a: {'<<': 1}")).toEqual({ a: { '<<': 1 } });
+      expect(fastYaml.parse('a: {<<: {b: 1}}', { schema: 'core' })).toEqual({
+        a: { '<<': { b: 1 } },
+      });
+    });
+  });
+
+  describe('duplicateKey', () => {
+    const yaml = 'a: 1\nb: 2\na: 3\n';
+
+    test('error reports both positions', () => {
+      expect(() => fastYaml.parse(yaml, { duplicateKey: 'error' })).toThrow(
+        /duplicated key 'a' in mapping at line 3, column 1 \(first defined at line 1, column 1\)/
+      );
+    });
+
+    test('first and last keep the respective value', () => {
+      expect(fastYaml.parse(yaml, { duplicateKey: 'first' })).toEqual({ a: 1, b: 2 });
+      expect(fastYaml.parse(yaml, { duplicateKey: 'last' })).toEqual({ a: 3, b: 2 });
+    });
+
+    test('warn keeps the last value and reports the duplicate', () => {
+      const warnings = [];
+      const result = fastYaml.parse(yaml, {
+        duplicateKey: 'warn',
+        onWarning: (w) => warnings.push(w),
+      });
+      expect(result).toEqual({ a: 3, b: 2 });
+      expect(warnings).toHaveLength(1);
+      expect(warnings[0].line).toBe(3);
+    });
+
+    test('takes precedence over json', () => {
+      expect(() => fastYaml.parse(yaml, { json: true, duplicateKey: 'error' })).toThrow();
+    });
+
+    test('rejects unknown policies', () => {
+      expect(() => fastYaml.parse(yaml, { duplicateKey: 'merge' })).toThrow(/Unknown duplicateKey/);
+    });
+  });
+
+  describe('mapAsMap', () => {
+    test('returns mappings as Map with typed keys', () => {
+      const result = fastYaml.parse('1: int\ntrue: bool\n~: null\nname: str\nnested: { a: [x] }', {
+        mapAsMap: true,
+      });
+      expect(result).toBeInstanceOf(Map);
+      expect([...result.keys()]).toEqual([1, true, null, 'name', 'nested']);
+      expect(result.get(1)).toBe('int');
+      expect(result.get('nested').get('a')).toEqual(['x']);
+    });
+
+    test('applies the other load options', () => {
+      const yaml = 'a: 1\na: 2.5\n';
+      expect(() => fastYaml.parse(yaml, { mapAsMap: true })).toThrow(/duplicated key 'a'/);
+      const result = fastYaml.parse(yaml, { mapAsMap: true, duplicateKey: 'last', floatMode: 'wrap' });
+      expect(result.get('a')).toEqual({ $float: 2.5 });
+    });
+
+    test('keeps plain objects by default', () => {
+      expect(fastYaml.parse('a: 1', { mapAsMap: false })).toEqual({ a: 1 });
+    });
+  });
+
+  describe('complexKeys', () => {
+    const yaml = '? [a, b]\n: seq\n? { k: 1 }\n: map\n1: int\n';
+
+    test('names collection keys by their JSON encoding by default', () => {
+      expect(fastYaml.parse(yaml)).toEqual({ '["a","b"]': 'seq', '{"k":1}': 'map', 1: 'int' });
+      expect(fastYaml.parseToJsonString(yaml)).toBe('{"[\\"a\\",\\"b\\"]":"seq","{\\"k\\":1}":"map","1":"int"}');
+    });
+
+    test('map returns mappings with collection keys as Map', () => {
+      const result = fastYaml.parse(
+        'outer:\n  ? [a, b]\n  : seq\n  ? { k: 1 }\n  : map\n  1: int\nplain: { a: 1 }\n',
+        { complexKeys: 'map' }
+      );
+      expect(result.outer).toBeInstanceOf(Map);
+      const [seqKey, mapKey, intKey] = [...result.outer.keys()];
+      expect(seqKey).toEqual(['a', 'b']);
+      expect(mapKey).toBeInstanceOf(Map);
+      expect(mapKey.get('k')).toBe(1);
+      expect(intKey).toBe(1);
+      expect(result.plain).toEqual({ a: 1 });
+    });
+
+    test('error rejects collection keys with their position', () => {
+      expect(() => fastYaml.parse(yaml, { complexKeys: 'error' })).toThrow(
+        /collection mapping keys are not allowed \(complexKeys\) at line 1, column 3/
+      );
+      expect(fastYaml.parse('1: a', { complexKeys: 'error' })).toEqual({ 1: 'a' });
+    });
+
+    test('rejects unknown modes', () => {
+      expect(() => fastYaml.parse(yaml, { complexKeys: 'string' })).toThrow(/Unknown complexKeys/);
+    });
+  });
+
+  describe('emptyValue', () => {
+    const yaml = 'a:\nb: ~\nc: ""\nd:\n  - \n  - x\n';
+
+    test('resolves empty values as null by default', () => {
+      expect(fastYaml.parse(yaml)).toEqual({ a: null, b: null, c: '', d: [null, 'x'] });
+    });
+
+    test('string resolves empty values as empty strings', () => {
+      expect(fastYaml.parse(yaml, { emptyValue: 'string' })).toEqual({
+        a: '',
+        b: null,
+        c: '',
+        d: ['', 'x'],
+      });
+    });
+
+    test('omit drops mapping entries with empty values', () => {
+      expect(fastYaml.parse(yaml, { emptyValue: 'omit' })).toEqual({
+        b: null,
+        c: '',
+        d: [null, 'x'],
+      });
+    });
+
+    test('rejects unknown policies', () => {
+      expect(() => fastYaml.parse(yaml, { emptyValue: 'undefined' })).toThrow(/Unknown emptyValue/);
+    });
+  });
+
+  describe('decodeBinary', () => {
+    const yaml = 'icon: !!binary |\n  aGVs\n  bG8=\n';
+
+    test('returns the base64 text by default', () => {
+      expect(fastYaml.parse(yaml)).toEqual({ icon: 'aGVs\nbG8=\n' });
+    });
+
+    test('decodes binary scalars to Uint8Array', () => {
+      const { icon } = fastYaml.parse(yaml, { decodeBinary: true });
+      expect(icon).toBeInstanceOf(Uint8Array);
+      expect(Buffer.from(icon).toString()).toBe('hello');
+    });
+
+    test('rejects invalid base64 with its position', () => {
+      expect(() => fastYaml.parse('a: !!binary "not base64!"', { decodeBinary: true })).toThrow(
+        /cannot resolve 'not base64!' as !!binary at line 1, column 13/
+      );
+    });
+  });
+
+  describe('timestamps', () => {
+    const yaml = 'date: 2001-12-14\ntime: 2001-12-14t21:59:43.10-05:00\nquoted: "2001-12-14"\n';
+
+    test('returns strings by default', () => {
+      expect(fastYaml.parse(yaml).date).toBe('2001-12-14');
+    });
+
+    test('resolves plain timestamps to Date', () => {
+      const result = fastYaml.parse(yaml, { timestamps: true });
+      expect(result.date).toBeInstanceOf(Date);
+      expect(result.date.toISOString()).toBe('2001-12-14T00:00:00.000Z');
+      expect(result.time.toISOString()).toBe('2001-12-15T02:59:43.100Z');
+      expect(result.quoted).toBe('2001-12-14');
+    });
+
+    test('resolves !!timestamp scalars in any schema', () => {
+      const result = fastYaml.parse('a: !!timestamp 2001-12-14 21:59:43.10 -5\nb: 2001-12-14', {
+        timestamps: true,
+        schema: 'core',
+      });
+      expect(result.a.toISOString()).toBe('2001-12-15T02:59:43.100Z');
+      expect(result.b).toBe('2001-12-14');
+    });
+
+    test('writes ISO 8601 strings in parseToJsonString', () => {
+      expect(fastYaml.parseToJsonString('a: 2001-12-14', { timestamps: true })).toBe(
+        '{"a":"2001-12-14T00:00:00.000Z"}'
+      );
+    });
+
+    test('rejects invalid timestamps with their position', () => {
+      expect(() => fastYaml.parse('a: !!timestamp x', { timestamps: true })).toThrow(
+        /cannot resolve 'x' as !!timestamp at line 1, column 16/
+      );
+    });
+  });
+
+  describe('reviver', () => {
+    test('converts scalars by tag and source text', () => {
+      const reviver = (value, { tag, source }) =>
+        tag === 'tag:yaml.org,2002:str' && /^\d+s$/.test(source)
+          ? parseInt(source, 10) * 1000
+          : undefined;
+      expect(fastYaml.parse('timeout: 30s\nretries: 3\nname: web', { reviver })).toEqual({
+        timeout: 30000,
+        retries: 3,
+        name: 'web',
+      });
+    });
+
+    test('receives the path, tag and source of each value', () => {
+      const calls = [];
+      fastYaml.parse('a: [1, "x"]\nb: {c: !dur 5m, d: ~}\n', {
+        reviver: (value, context) => {
+          calls.push([value, context]);
+        },
+      });
+      expect(calls).toEqual([
+        [1, { path: '$.a[0]', tag: 'tag:yaml.org,2002:int', source: '1' }],
+        ['x', { path: '$.a[1]', tag: 'tag:yaml.org,2002:str', source: 'x' }],
+        ['5m', { path: '$.b.c', tag: '!dur', source: '5m' }],
+        [null, { path: '$.b.d', tag: 'tag:yaml.org,2002:null', source: '~' }],
+      ]);
+    });
+
+    test('selects values by path', () => {
+      const reviver = (value, { path }) => (path === '$.version' ? String(value) : undefined);
+      expect(fastYaml.parse('version: 1.10\ncount: 2', { reviver })).toEqual({
+        version: '1.1',
+        count: 2,
+      });
+    });
+
+    test('propagates errors thrown by the reviver', () => {
+      expect(() =>
+        fastYaml.parse('a: 1', {
+          reviver: () => {
+            throw new Error('bad value');
+          },
+        })
+      ).toThrow(/bad value/);
+    });
+  });
+
+  describe('setAsSet', () => {
+    const yaml = 'tags: !!set\n  ? web\n  ? api\n  ? 1\n';
+
+    test('returns objects with null values by default', () => {
+      expect(fastYaml.parse(yaml)).toEqual({ tags: { web: null, api: null, 1: null } });
+    });
+
+    test('does not report !!set as an unknown tag', () => {
+      const warnings = [];
+      fastYaml.parse(yaml, { onWarning: (w) => warnings.push(w) });
+      expect(warnings).toEqual([]);
+    });
+
+    test('returns sets of typed keys', () => {
+      const { tags } = fastYaml.parse(yaml, { setAsSet: true });
+      expect(tags).toBeInstanceOf(Set);
+      expect([...tags]).toEqual(['web', 'api', 1]);
+    });
+
+    test('rejects sets with non-null values', () => {
+      expect(() => fastYaml.parse('s: !!set {a: 1}')).toThrow(
+        /cannot resolve a mapping with non-null values as !!set at line 1, column 14/
+      );
+    });
+  });
+
+  describe('keyStyle', () => {
+    test('renames keys to camelCase', () => {
+      expect(
+        fastYaml.parse('max_retries: 3\nlog-level: {output_file: a.log}\n_private: 1', {
+          keyStyle: 'camel',
+        })
+      ).toEqual({ maxRetries: 3, logLevel: { outputFile: 'a.log' }, _private: 1 });
+    });
+
+    test('renames keys to snake_case', () => {
+      expect(fastYaml.parse('maxRetries: 3\nHTTPServer: a', { keyStyle: 'snake' })).toEqual({
+        max_retries: 3,
+        http_server: 'a',
+      });
+    });
+
+    test('renames keys with a callback', () => {
+      expect(fastYaml.parse('a: 1\nb: 2', { keyStyle: (key) => key.toUpperCase() })).toEqual({
+        A: 1,
+        B: 2,
+      });
+    });
+
+    test('keeps non-string keys', () => {
+      expect(fastYaml.parse('1: a\nfoo_bar: b', { keyStyle: 'camel', mapAsMap: true })).toEqual(
+        new Map([
+          [1, 'a'],
+          ['fooBar', 'b'],
+        ])
+      );
+    });
+
+    test('reports keys that collide after renaming', () => {
+      expect(() => fastYaml.parse('max_count: 1\nmaxCount: 2', { keyStyle: 'camel' })).toThrow(
+        /duplicated key 'maxCount' in mapping at line 2, column 1/
+      );
+    });
+
+    test('rejects unknown styles', () => {
+      expect(() => fastYaml.parse('a: 1', { keyStyle: 'pascal' })).toThrow(/Unknown keyStyle/);
+    });
+  });
+
+  describe('ordered mappings', () => {
+    test('reads !!omap as an array of pairs', () => {
+      expect(fastYaml.parse('steps: !!omap\n  - build: make\n  - test: make check\n')).toEqual({
+        steps: [
+          ['build', 'make'],
+          ['test', 'make check'],
+        ],
+      });
+    });
+
+    test('reads !!omap as a Map with mapAsMap', () => {
+      const steps = fastYaml.parse('!!omap [b: 1, a: 2]', { mapAsMap: true });
+      expect([...steps]).toEqual([
+        ['b', 1],
+        ['a', 2],
+      ]);
+    });
+
+    test('keeps duplicate keys in !!pairs', () => {
+      expect(fastYaml.parse('!!pairs [a: 1, a: 2, 1: x]')).toEqual([
+        ['a', 1],
+        ['a', 2],
+        [1, 'x'],
+      ]);
+    });
+
+    test('rejects duplicate keys in !!omap', () => {
+      expect(() => fastYaml.parse('o: !!omap [a: 1, a: 2]')).toThrow(
+        /duplicated key 'a' in !!omap at line 1, column 18/
+      );
+    });
+
+    test('rejects items that are not single-pair mappings', () => {
+      expect(() => fastYaml.parse('o: !!pairs [{a: 1, b: 2}]')).toThrow(
+        /not a single-pair mapping as !!pairs at line 1, column 13/
+      );
+    });
+  });
+
+  describe('key filters', () => {
+    const yaml =
+      'openapi: 3.1.0\nx-internal: true\npaths:\n  /users:\n    x-owner: team\n    get: {}\n';
+
+    test('drops keys matching excludeKeys at every level', () => {
+      expect(fastYaml.parse(yaml, { excludeKeys: ['x-*'] })).toEqual({
+        openapi: '3.1.0',
+        paths: { '/users': { get: {} } },
+      });
+    });
+
+    test('keeps only keys matching includeKeys', () => {
+      expect(fastYaml.parse('a: 1\nb: {a: 2, c: 3}\nab: 4', { includeKeys: ['a*', 'b'] })).toEqual({
+        a: 1,
+        b: { a: 2 },
+        ab: 4,
+      });
+    });
+
+    test('ignores duplicates among dropped keys', () => {
+      expect(fastYaml.parse('a: 1\nx-a: 2\nx-a: 3', { excludeKeys: ['x-*'] })).toEqual({ a: 1 });
+    });
+
+    test('matches keys before keyStyle renames them', () => {
+      expect(
+        fastYaml.parse('x-foo: 1\nfoo_bar: 2', { excludeKeys: ['x-*'], keyStyle: 'camel' })
+      ).toEqual({ fooBar: 2 });
+    });
+
+    test('rejects patterns that are not strings', () => {
+      expect(() => fastYaml.parse('a: 1', { excludeKeys: 'x-*' })).toThrow(
+        /Option 'excludeKeys' must be an array of strings/
+      );
+    });
+  });
+
+  describe('useBigInt64', () => {
+    const yaml = 'id: 9223372036854775807\nneg: -9007199254740993\nsmall: 9007199254740991\n';
+
+    test('rounds large integers by default', () => {
+      expect(fastYaml.parse(yaml).id).toBe(9223372036854775807);
+    });
+
+    test('returns integers beyond the safe range as BigInt', () => {
+      expect(fastYaml.parse(yaml, { useBigInt64: true })).toEqual({
+        id: 9223372036854775807n,
+        neg: -9007199254740993n,
+        small: 9007199254740991,
+      });
+    });
+
+    test('applies to Map keys', () => {
+      const map = fastYaml.parse('9007199254740993: a', { useBigInt64: true, mapAsMap: true });
+      expect(map.get(9007199254740993n)).toBe('a');
+    });
+  });
+
+  describe('largeIntAsString', () => {
+    const yaml = 'id: 1234567890123456789\nhash: 123456789012345678901234\ncount: 42\n';
+
+    test('returns large integers as strings', () => {
+      expect(fastYaml.parse(yaml, { largeIntAsString: true })).toEqual({
+        id: '1234567890123456789',
+        hash: '123456789012345678901234',
+        count: 42,
+      });
+    });
+
+    test('applies to parseToJsonString', () => {
+      expect(fastYaml.parseToJsonString('id: -9007199254740993', { largeIntAsString: true })).toBe(
+        '{"id":"-9007199254740993"}'
+      );
+    });
+
+    test('reports each converted integer', () => {
+      const warnings = [];
+      fastYaml.parse(yaml, { largeIntAsString: true, onWarning: (w) => warnings.push(w.message) });
+      expect(warnings).toEqual([
+        expect.stringMatching(/integer 1234567890123456789 is beyond the safe Number range/),
+        expect.stringMatching(/integer 123456789012345678901234 is beyond the safe Number range/),
+      ]);
+    });
+
+    test('takes precedence over useBigInt64', () => {
+      const options = { largeIntAsString: true, useBigInt64: true };
+      expect(fastYaml.parse('id: 1234567890123456789', options)).toEqual({
+        id: '1234567890123456789',
+      });
+    });
+  });
+
+  describe('version', () => {
+    const yaml = 'on:\n  push: yes\ndebug: Off\nshort: y\nquoted: "no"\n';
+
+    test('reads YAML 1.1 booleans as strings by default', () => {
+      expect(fastYaml.parse(yaml)).toEqual({
+        on: { push: 'yes' },
+        debug: 'Off',
+        short: 'y',
+        quoted: 'no',
+      });
+    });
+
+    test('reads YAML 1.1 booleans with version 1.1', () => {
+      expect(fastYaml.parse(yaml, { version: '1.1' })).toEqual({
+        true: { push: true },
+        debug: false,
+        short: true,
+        quoted: 'no',
+      });
+      expect(fastYaml.parse('a: !!bool on', { version: '1.1' })).toEqual({ a: true });
+    });
+
+    test('reads YAML 1.1 integer forms with version 1.1', () => {
+      const yaml = 'mode: 0755\nflags: 0b1010\nhex: 0x1F\noctal: 0o17\n';
+
+      expect(fastYaml.parse(yaml)).toEqual({ mode: 755, flags: '0b1010', hex: 31, octal: 15 });
+      expect(fastYaml.parse(yaml, { version: '1.1' })).toEqual({
+        mode: 493,
+        flags: 10,
+        hex: 31,
+        octal: 15,
+      });
+      expect(fastYaml.parse('a: !!int -017', { version: '1.1' })).toEqual({ a: -15 });
+    });
+
+    test('warns about legacy octals with either version', () => {
+      const messages = (options) => {
+        const warnings = [];
+        fastYaml.parse('mode: 0755\nflags: 0b11', {
+          ...options,
+          onWarning: (w) => warnings.push(w.message),
+        });
+        return warnings;
+      };
+
+      expect(messages({})).toEqual([
+        expect.stringMatching(/'0755' is a YAML 1.1 octal, read as a decimal/),
+        expect.stringMatching(/'0b11' is a YAML 1.1 binary integer/),
+      ]);
+      expect(messages({ version: '1.1' })).toEqual([
+        expect.stringMatching(/'0755' is a legacy YAML 1.1 octal, read as 493/),
+      ]);
+    });
+
+    test('keeps the JSON and failsafe schemas strict', () => {
+      expect(fastYaml.parse('a: yes', { version: '1.1', schema: 'json' })).toEqual({ a: 'yes' });
+    });
+
+    test('does not warn about booleans read with version 1.1', () => {
+      const warnings = [];
+      fastYaml.parse('a: yes', { version: '1.1', onWarning: (w) => warnings.push(w) });
+      expect(warnings).toEqual([]);
+    });
+
+    test('rejects unknown versions', () => {
+      expect(() => fastYaml.parse('a: 1', { version: '2.0' })).toThrow(/Unknown version: 2.0/);
+    });
+  });
+
+  describe('sexagesimal', () => {
+    test('keeps base 60 numbers as strings by default', () => {
+      expect(fastYaml.parse('ports: 22:22\nlength: 1:30:00', { version: '1.1' })).toEqual({
+        ports: '22:22',
+        length: '1:30:00',
+      });
+    });
+
+    test('resolves base 60 integers and floats', () => {
+      const yaml = 'length: 1:30:00\nlap: -1:30.5\nangle: 190:20:30\n';
+      expect(fastYaml.parse(yaml, { sexagesimal: true })).toEqual({
+        length: 5400,
+        lap: -90.5,
+        angle: 685230,
+      });
+    });
+
+    test('leaves malformed values as strings', () => {
+      const yaml = 'a: 1:60\nb: 01:30\nc: 12:30:45Z\n';
+      expect(fastYaml.parse(yaml, { sexagesimal: true })).toEqual({
+        a: '1:60',
+        b: '01:30',
+        c: '12:30:45Z',
+      });
+    });
+
+    test('applies to !!int and !!float scalars', () => {
+      expect(fastYaml.parse('a: !!int 1:30\nb: !!float 1:30', { sexagesimal: true })).toEqual({
+        a: 90,
+        b: 90,
+      });
+      expect(() => fastYaml.parse('a: !!int 1:30')).toThrow(/cannot resolve '1:30' as !!int/);
+    });
+
+    test('reports base 60 numbers to onWarning without failing', () => {
+      const collect = (options) => {
+        const warnings = [];
+        fastYaml.parse('length: 1:30:00\nport: 22:22x\n', {
+          ...options,
+          onWarning: (w) => warnings.push(w),
+        });
+        return warnings;
+      };
+      const [warning] = collect({});
+      expect(warning.message).toMatch(/'1:30:00' is a YAML 1.1 base 60 number, read as a string/);
+      expect(warning.code).toBe('YAML11_SCALAR');
+      expect(warning.line).toBe(1);
+      expect(collect({ sexagesimal: true }).map((w) => w.message)).toEqual([
+        expect.stringMatching(/'1:30:00' .* read as 5400 \(YAML 1.2 reads a string\)/),
+      ]);
+    });
+
+    test('are read as strings with ambiguousAsString', () => {
+      expect(
+        fastYaml.parse('length: 1:30:00', { sexagesimal: true, ambiguousAsString: true })
+      ).toEqual({ length: '1:30:00' });
+    });
+  });
+
+  describe('stringifyScalars', () => {
+    const yaml = 'country: NO\nport: 8080\nmode: 0755\nratio: 1.0\nnone: ~\nempty:\nflag: true\n';
+
+    test('reads every plain scalar as a string', () => {
+      expect(fastYaml.parse(yaml, { stringifyScalars: true })).toEqual({
+        country: 'NO',
+        port: '8080',
+        mode: '0755',
+        ratio: '1.0',
+        none: '~',
+        empty: '',
+        flag: 'true',
+      });
+    });
+
+    test('applies whatever the schema and version', () => {
+      const options = { stringifyScalars: true, version: '1.1', schema: 'json' };
+      expect(fastYaml.parse('a: yes\nb: null', options)).toEqual({ a: 'yes', b: 'null' });
+    });
+
+    test('keeps explicit tags and merge keys', () => {
+      const tagged = 'base: &base\n  port: !!int 80\nweb:\n  <<: *base\n  debug: !!bool true\n';
+      expect(fastYaml.parse(tagged, { stringifyScalars: true })).toEqual({
+        base: { port: 80 },
+        web: { port: 80, debug: true },
+      });
+    });
+
+    test('stringifies keys and resolves no timestamps', () => {
+      const map = fastYaml.parse('1: 2001-12-14', {
+        stringifyScalars: true,
+        mapAsMap: true,
+        timestamps: true,
+      });
+      expect([...map.entries()]).toEqual([['1', '2001-12-14']]);
+    });
+
+    test('reports no YAML 1.1 warnings', () => {
+      const warnings = [];
+      fastYaml.parse('a: yes\nb: 017', {
+        stringifyScalars: true,
+        onWarning: (w) => warnings.push(w),
+      });
+      expect(warnings).toEqual([]);
+    });
+  });
+
+  describe('ambiguousAsString', () => {
+    const yaml = 'country: no\nswitch: on\nmode: 022\nmask: 0b101\nport: 22\nflag: true\n';
+
+    test('reads YAML 1.1 booleans, octals and binaries as strings', () => {
+      const expected = {
+        country: 'no',
+        switch: 'on',
+        mode: '022',
+        mask: '0b101',
+        port: 22,
+        flag: true,
+      };
+      expect(fastYaml.parse(yaml, { ambiguousAsString: true })).toEqual(expected);
+      expect(fastYaml.parse(yaml, { ambiguousAsString: true, version: '1.1' })).toEqual(expected);
+    });
+
+    test('reports each occurrence with its position', () => {
+      const warnings = [];
+      fastYaml.parse(yaml, {
+        ambiguousAsString: true,
+        onWarning: (w) => warnings.push(w.message),
+      });
+      expect(warnings).toEqual([
+        expect.stringMatching(/'no' is a YAML 1.1 boolean, read as a string .*line 1, column 10/),
+        expect.stringMatching(/'on' is a YAML 1.1 boolean, read as a string .*line 2, column 9/),
+        expect.stringMatching(/'022' is a YAML 1.1 octal, read as a string .*line 3, column 7/),
+        expect.stringMatching(/'0b101' is a YAML 1.1 binary integer, read as a string .*line 4/),
+      ]);
+    });
+
+    test('leaves quoted and tagged scalars alone', () => {
+      const warnings = [];
+      const options = { ambiguousAsString: true, onWarning: (w) => warnings.push(w) };
+      expect(fastYaml.parse("a: 'no'\nb: !!int 022\nc: !!str on", options)).toEqual({
+        a: 'no',
+        b: 22,
+        c: 'on',
+      });
+      expect(warnings).toEqual([]);
+    });
+  });
+
+  describe('units', () => {
+    const yaml = [
+      'timeout: !duration 250ms',
+      'window: !duration 1h30m',
+      'retry: !duration 1.5s',
+      'memory: !bytes 512Mi',
+      'disk: !bytes 2GB',
+      'buffer: !bytes 4096',
+    ].join('\n');
+
+    test('leaves tagged quantities as strings by default', () => {
+      expect(fastYaml.parse(yaml).timeout).toBe('250ms');
+    });
+
+    test('resolves durations to milliseconds and sizes to bytes', () => {
+      expect(fastYaml.parse(yaml, { units: true })).toEqual({
+        timeout: 250,
+        window: 5400000,
+        retry: 1500,
+        memory: 536870912,
+        disk: 2000000000,
+        buffer: 4096,
+      });
+      expect(JSON.parse(fastYaml.parseToJsonString(yaml, { units: true })).memory).toBe(536870912);
+    });
+
+    test('accepts fractions, signs and blanks before byte units', () => {
+      const values = fastYaml.parse('a: !duration -100us\nb: !bytes 1.5 KiB\nc: !bytes 0.5Ki', {
+        units: true,
+      });
+      expect(values).toEqual({ a: -0.1, b: 1536, c: 512 });
+    });
+
+    test('rejects values that do not parse', () => {
+      for (const text of ['!duration 90', '!duration 5x', '!bytes 1.5B', '!bytes 1Kib']) {
+        expect(() => fastYaml.parse(`a: ${text}`, { units: true })).toThrow(
+          /cannot resolve '.+' as !(duration|bytes) at line 1, column \d+/
+        );
+      }
+    });
+
+    test('passes the tag and source text to the reviver', () => {
+      const seen = [];
+      fastYaml.parse('a: !duration 2h', {
+        units: true,
+        reviver: (value, { tag, source }) => {
+          seen.push([value, tag, source]);
+        },
+      });
+      expect(seen).toEqual([[7200000, '!duration', '2h']]);
+    });
+  });
+
+  describe('wrapUnknownTags', () => {
+    const yaml = [
+      'bucket: !Ref Logs',
+      'url: !Sub [a, !Ref b]',
+      'props: &p !Props {size: 2}',
+      'copy: *p',
+      'count: !!int 3',
+    ].join('\n');
+
+    test('keeps local tags in wrapper objects', () => {
+      expect(fastYaml.parse(yaml, { wrapUnknownTags: true })).toEqual({
+        bucket: { tag: '!Ref', value: 'Logs' },
+        url: { tag: '!Sub', value: ['a', { tag: '!Ref', value: 'b' }] },
+        props: { tag: '!Props', value: { size: 2 } },
+        copy: { tag: '!Props', value: { size: 2 } },
+        count: 3,
+      });
+    });
+
+    test('writes the same wrappers to JSON and to JavaScript values', () => {
+      const options = { wrapUnknownTags: true };
+      const json = JSON.parse(fastYaml.parseToJsonString(yaml, options));
+      expect(fastYaml.parse(yaml, { ...options, mapAsMap: true }).get('url')).toEqual({
+        tag: '!Sub',
+        value: ['a', { tag: '!Ref', value: 'b' }],
+      });
+      expect(json.url).toEqual(fastYaml.parse(yaml, options).url);
+    });
+
+    test('reads tagged scalars as strings without warnings', () => {
+      const warnings = [];
+      const value = fastYaml.parse('a: !Port 80\nb: !Port [81]', {
+        wrapUnknownTags: true,
+        onWarning: (w) => warnings.push(w.message),
+      });
+      expect(value).toEqual({
+        a: { tag: '!Port', value: '80' },
+        b: { tag: '!Port', value: [81] },
+      });
+      expect(warnings).toEqual([]);
+    });
+
+    test('leaves units to the units option', () => {
+      const options = { wrapUnknownTags: true, units: true };
+      expect(fastYaml.parse('t: !duration 2s\nu: !duration [2s]', options)).toEqual({
+        t: 2000,
+        u: { tag: '!duration', value: ['2s'] },
+      });
+    });
+
+    test('drops tags by default', () => {
+      expect(fastYaml.parse('a: !Ref x')).toEqual({ a: 'x' });
+    });
+  });
+
+  describe('jsonReviver', () => {
+    test('is called bottom-up with keys and holders like JSON.parse', () => {
+      const calls = [];
+      fastYaml.parse('a: [1, 2]\nb: {c: x}', {
+        jsonReviver(key, value) {
+          calls.push([key, JSON.stringify(this)]);
+          return value;
+        },
+      });
+      const root = '{"a":[1,2],"b":{"c":"x"}}';
+      expect(calls).toEqual([
+        ['0', '[1,2]'],
+        ['1', '[1,2]'],
+        ['c', '{"c":"x"}'],
+        ['a', root],
+        ['b', root],
+        ['', `{"":${root}}`],
+      ]);
+    });
+
+    test('replaces members and removes those revived to undefined', () => {
+      const yaml = 'name: web\npassword: secret\nports: [80, 443]';
+      const value = fastYaml.parse(yaml, (key, value) => {
+        if (key === 'password') return undefined;
+        return typeof value === 'number' ? value + 1 : value;
+      });
+      expect(value).toEqual({ name: 'web', ports: [81, 444] });
+    });
+
+    test('revives the root under the empty key', () => {
+      const wrap = (key, value) => (key === '' ? [value] : value);
+      expect(fastYaml.load('a: 1', wrap)).toEqual([{ a: 1 }]);
+      expect(fastYaml.parseAll('--- 1\n--- 2', (key, value) => value * 10)).toEqual([10, 20]);
+    });
+
+    test('passes typed keys of Map values', () => {
+      const keys = [];
+      fastYaml.parse('1: a\ntrue: b', {
+        mapAsMap: true,
+        jsonReviver: (key, value) => {
+          keys.push(key);
+          return value;
+        },
+      });
+      expect(keys).toEqual([1, true, '']);
+    });
+
+    test('rethrows exceptions from the reviver', () => {
+      expect(() =>
+        fastYaml.parse('a: 1', () => {
+          throw new RangeError('nope');
+        })
+      ).toThrow(RangeError);
+    });
+  });
+
+  describe('cloneable', () => {
+    const yaml = 'when: 2001-12-14\ntags: !!set {a, b}\nitems: [1, 2]\n';
+
+    const typed = `${yaml}big: 9007199254740993\ndata: !!binary aGk=\n? [a, b]\n: pair\n`;
+
+    // Whether a value holds nothing but plain objects, arrays and primitives
+    function isPlain(value) {
+      if (typeof value === 'bigint' || typeof value === 'function') {
+        return false;
+      }
+      if (value === null || typeof value !== 'object') {
+        return true;
+      }
+      const prototype = Object.getPrototypeOf(value);
+      if (![Object.prototype, Array.prototype, null].includes(prototype)) {
+        return false;
+      }
+      return Object.values(value).every(isPlain);
+    }
+
+    test.each([
+      [{ useBigInt64: true }, 'useBigInt64'],
+      [{ mapAsMap: true }, 'mapAsMap'],
+      [{ setAsSet: true }, 'setAsSet'],
+      [{ complexKeys: 'map' }, "complexKeys: 'map'"],
+      [{ timestamps: true }, 'timestamps'],
+      [{ decodeBinary: true }, 'decodeBinary'],
+    ])('rejects %o', (option, name) => {
+      expect(isPlain(fastYaml.parse(typed, option))).toBe(false);
+      expect(() => fastYaml.parse(typed, { cloneable: true, ...option })).toThrow(
+        `Option 'cloneable' cannot be combined with '${name}'`
+      );
+    });
+
+    test('builds plain values', () => {
+      const value = fastYaml.parse(typed, {
+        cloneable: true,
+        useBigInt64: false,
+        mapAsMap: false,
+        complexKeys: 'json',
+      });
+      expect(isPlain(value)).toBe(true);
+      expect(value).toEqual(fastYaml.parse(typed));
+      expect(structuredClone(value)).toEqual(value);
+    });
+
+    test('keeps plain values from callbacks', () => {
+      const options = {
+        cloneable: true,
+        reviver: (value) => (typeof value === 'number' ? String(value) : value),
+        jsonReviver: (key, value) => (key === 'items' ? { list: value } : value),
+      };
+      const value = fastYaml.parse(yaml, options);
+      expect(value.items).toEqual({ list: ['1', '2'] });
+      expect(structuredClone(value)).toEqual(value);
+    });
+
+    test('rejects values from callbacks that are not plain', () => {
+      const reviver = (value) => (value === 1 ? Symbol('one') : value);
+      expect(fastYaml.parse(yaml, { reviver })).toHaveProperty('items');
+      expect(() => fastYaml.parse(yaml, { cloneable: true, reviver })).toThrow(
+        /a symbol returned by reviver cannot be cloned \(cloneable\) at line 3/
+      );
+
+      const jsonReviver = (key, value) => (key === 'items' ? new URL('https://x.test') : value);
+      expect(() => fastYaml.parse(yaml, { cloneable: true, jsonReviver })).toThrow(
+        /an instance of URL returned by jsonReviver/
+      );
+      const date = (value) => (value === 1 ? new Date(0) : value);
+      expect(() => fastYaml.parse(yaml, { cloneable: true, reviver: date })).toThrow(
+        /an instance of Date returned by reviver/
+      );
+      const big = (value) => (value === 1 ? 1n : value);
+      expect(() => fastYaml.parse(yaml, { cloneable: true, reviver: big })).toThrow(/a BigInt/);
+      const nested = (key, value) => (key === '' ? { value, run: [() => 1] } : value);
+      expect(() => fastYaml.parse(yaml, { cloneable: true, jsonReviver: nested })).toThrow(
+        fastYaml.YAMLException
+      );
+    });
+
+    test('accepts objects without a prototype', () => {
+      const jsonReviver = (key, value) =>
+        key === '' ? Object.assign(Object.create(null), value) : value;
+      expect(fastYaml.parse('a: 1', { cloneable: true, jsonReviver })).toEqual({ a: 1 });
+    });
+  });
+});
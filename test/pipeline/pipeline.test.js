@@ -0,0 +1,170 @@
+/**
+ * Pipeline Tests
+ *
+ * This test file tests running built-in steps over YAML documents in one call.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Pipeline Tests', () => {
+  const config = `
+base: &base
+  host: db
+  port: 5432
+service:
+  <<: *base
+  port: '8080'
+  password: hunter2
+  url: http://\${HOST:-localhost}:\${PORT}
+`;
+
+  const schema = {
+    type: 'object',
+    properties: {
+      service: {
+        type: 'object',
+        required: ['host', 'port'],
+        properties: { port: { type: 'integer' }, host: { type: 'string' } },
+      },
+    },
+  };
+
+  test('runs every step in one call', () => {
+    const output = fastYaml.pipeline(config, [
+      'resolveAliases',
+      'mergeKeys',
+      { op: 'env', env: { PORT: '9000' } },
+      { op: 'coerce', schema },
+      { op: 'validate', schema },
+      { op: 'redact', paths: ['$..password'] },
+      'sortKeys',
+      { op: 'emit', format: 'json' },
+    ]);
+    expect(JSON.parse(output)).toEqual({
+      base: { host: 'db', port: 5432 },
+      service: {
+        host: 'db',
+        password: '[REDACTED]',
+        port: 8080,
+        url: 'http://localhost:9000',
+      },
+    });
+    expect(Object.keys(JSON.parse(output).service)).toEqual(['host', 'password', 'port', 'url']);
+  });
+
+  test('emits YAML by default', () => {
+    expect(fastYaml.pipeline('b: 1\na: [2]\n', ['sortKeys'])).toBe('a:\n  - 2\nb: 1\n');
+    expect(fastYaml.pipeline('a: 1\n---\nb: 2\n', [])).toBe('---\na: 1\n---\nb: 2\n');
+    expect(fastYaml.pipeline('a: 1\n---\nb: 2\n', [{ op: 'emit', format: 'json' }])).toBe(
+      '{"a":1}\n{"b":2}'
+    );
+  });
+
+  test('expands aliases only with resolveAliases', () => {
+    expect(() => fastYaml.pipeline('a: &x 1\nb: *x\n', [])).toThrow(/resolveAliases/);
+    expect(fastYaml.pipeline('a: &x 1\nb: *x\n', ['resolveAliases'])).toBe('a: 1\nb: 1\n');
+  });
+
+  test('merges keys only with mergeKeys', () => {
+    const yaml = 'a: {<<: [{x: 1}, {x: 2, y: 3}], y: 0}\n';
+    expect(fastYaml.pipeline(yaml, ['mergeKeys', { op: 'emit', format: 'json' }])).toBe(
+      '{"a":{"x":1,"y":0}}'
+    );
+    expect(fastYaml.pipeline('a: {<<: {x: 1}}\n', [{ op: 'emit', format: 'json' }])).toBe(
+      '{"a":{"<<":{"x":1}}}'
+    );
+  });
+
+  test('substitutes environment variables', () => {
+    const env = { NAME: 'app', EMPTY: '' };
+    const run = (yaml) => fastYaml.pipeline(yaml, [{ op: 'env', env }]);
+    expect(run('a: ${NAME}-${EMPTY:-none}\n')).toBe('a: app-none\n');
+    expect(run('a: $${NAME}\n')).toBe('a: "${NAME}"\n');
+    expect(() => run('a: [x, "${MISSING}"]\n')).toThrow(
+      /environment variable 'MISSING' is not set at \$\.a\[1\]/
+    );
+  });
+
+  test('reads process.env for env steps without variables', () => {
+    process.env.FAST_YAML_PIPELINE_TEST = 'from-env';
+    expect(fastYaml.pipeline('a: ${FAST_YAML_PIPELINE_TEST}\n', ['env'])).toBe('a: from-env\n');
+    delete process.env.FAST_YAML_PIPELINE_TEST;
+  });
+
+  test('reports every validation error', () => {
+    let error;
+    try {
+      fastYaml.pipeline('service: {port: abc}\n', [{ op: 'validate', schema }]);
+    } catch (e) {
+      error = e;
+    }
+    expect(error).toBeInstanceOf(fastYaml.YAMLException);
+    expect(error.message).toBe(
+      "Validation failed: $.service: missing required property 'host'; " +
+        '$.service.port: expected integer, got string'
+    );
+  });
+
+  test('redacts with a custom replacement', () => {
+    const steps = [{ op: 'redact', paths: ['$.users[*].token'], replacement: null }];
+    expect(fastYaml.pipeline('users: [{name: a, token: t1}, {name: b, token: t2}]\n', steps)).toBe(
+      'users:\n  - name: a\n    token: null\n  - name: b\n    token: null\n'
+    );
+  });
+
+  test('edits values that paths match', () => {
+    const yaml = 'services:\n  web: {img: nginx, debug: true, replicas: 1}\n  db: {img: pg}\n';
+    const steps = [
+      { op: 'rename', match: '$.services[*].img', to: 'image' },
+      { op: 'delete', match: ['$..debug'] },
+      { op: 'set', match: '$.services.web.replicas', value: 3 },
+      { op: 'emit', format: 'json' },
+    ];
+    expect(JSON.parse(fastYaml.pipeline(yaml, steps))).toEqual({
+      services: { web: { image: 'nginx', replicas: 3 }, db: { image: 'pg' } },
+    });
+    expect(fastYaml.pipeline('a: [1, 2, 3]\n', [{ op: 'delete', match: '$.a[1]' }])).toBe(
+      'a:\n  - 1\n  - 3\n'
+    );
+    expect(() =>
+      fastYaml.pipeline('a: {x: 1, y: 2}\n', [{ op: 'rename', match: '$.a.x', to: 'y' }])
+    ).toThrow(/cannot rename to a key that is already set at \$\.a\.y/);
+  });
+
+  test('reads steps written as YAML', () => {
+    const rules = `
+- resolveAliases
+- op: rename
+  match: $.b
+  to: c
+- op: emit
+  format: json
+`;
+    expect(fastYaml.pipeline('a: &x 1\nb: *x\n', rules)).toBe('{"a":1,"c":1}');
+    process.env.FAST_YAML_PIPELINE_TEST = 'from-env';
+    expect(fastYaml.pipeline('a: ${FAST_YAML_PIPELINE_TEST}\n', '[env]')).toBe('a: from-env\n');
+    delete process.env.FAST_YAML_PIPELINE_TEST;
+    expect(() => fastYaml.pipeline('a: 1', 'op: sortKeys')).toThrow(
+      /must be an array or a YAML sequence/
+    );
+    expect(() => fastYaml.pipeline('a: 1', '[set]')).toThrow(/needs a 'value'/);
+  });
+
+  test('rejects invalid steps', () => {
+    expect(() => fastYaml.pipeline('a: 1', 'sortKeys')).toThrow(/must be an array/);
+    expect(() => fastYaml.pipeline('a: 1', ['shuffle'])).toThrow(/Unknown pipeline step 'shuffle'/);
+    expect(() => fastYaml.pipeline('a: 1', ['emit', 'sortKeys'])).toThrow(/must come last/);
+    expect(() => fastYaml.pipeline('a: 1', ['validate'])).toThrow(/needs a 'schema'/);
+    expect(() => fastYaml.pipeline('a: 1', [{ op: 'redact', paths: ['$['] }])).toThrow(
+      /YAMLPath parsing error/
+    );
+    expect(() => fastYaml.pipeline('a: 1', [{ op: 'sortKeys', deep: true }])).toThrow(
+      /Unknown options: deep/
+    );
+  });
+
+  test('applies load options', () => {
+    expect(fastYaml.pipeline('a: 1\n', [], { schema: 'failsafe' })).toBe('a: "1"\n');
+    expect(() => fastYaml.pipeline('[1, 2, 3]', [], { maxNodes: 2 })).toThrow(/maxNodes/);
+  });
+});
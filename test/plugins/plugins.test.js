@@ -0,0 +1,70 @@
+/**
+ * Plugin Tests
+ *
+ * This test file tests registering formats, YAMLPath functions, lint rules and tag types
+ * through plugins.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Plugin Tests', () => {
+  beforeAll(() => {
+    fastYaml.registerPlugin({
+      name: 'test-plugin',
+      types: { '!upper': { kind: 'scalar', constructFn: (text) => text.toUpperCase() } },
+      formats: { port: (value) => /^\d+$/.test(value) && Number(value) < 65536 },
+      functions: { isEven: (value) => typeof value === 'number' && value % 2 === 0 },
+      lintRules: {
+        'no-latest-tag': ({ path, source }) =>
+          path.endsWith('.image') && source.endsWith(':latest') ? 'image uses :latest' : undefined,
+      },
+    });
+  });
+
+  test('registers tag types', () => {
+    expect(fastYaml.parse('a: !upper web')).toEqual({ a: 'WEB' });
+  });
+
+  test('checks registered formats in validate', () => {
+    const schema = { type: 'object', properties: { port: { type: 'string', format: 'port' } } };
+    expect(fastYaml.validate("port: '8080'", schema).valid).toBe(true);
+    const result = fastYaml.validate("port: '99999'", schema);
+    expect(result.valid).toBe(false);
+    expect(result.errors[0].message).toBe('string is not a valid port');
+    // Formats nobody registered are ignored
+    const unknown = { properties: { a: { format: 'unknown' } } };
+    expect(fastYaml.validate('a: x', unknown).valid).toBe(true);
+  });
+
+  test('calls registered functions in YAMLPath filters', () => {
+    const yaml = 'items: [{n: 1, v: a}, {n: 2, v: b}, {n: 4, v: c}]';
+    expect(fastYaml.query(yaml, '$.items[?(isEven(@.n))].v')).toEqual(['b', 'c']);
+    expect(fastYaml.query('[1, 2, 3, 4]', '$[?(isEven(@))]')).toEqual([2, 4]);
+    expect(() => fastYaml.query(yaml, '$.items[?(isOdd(@.n))]')).toThrow(
+      /Unknown YAMLPath function 'isOdd'/
+    );
+  });
+
+  test('reports lint findings as warnings', () => {
+    const warnings = [];
+    const value = fastYaml.parse('containers:\n  - image: nginx:latest\n  - image: redis:7\n', {
+      onWarning: (warning) => warnings.push(warning),
+    });
+    expect(value.containers).toHaveLength(2);
+    expect(warnings.map((warning) => warning.message)).toEqual([
+      'image uses :latest (no-latest-tag) at line 2, column 12',
+    ]);
+    expect(warnings[0]).toBeInstanceOf(fastYaml.YAMLException);
+  });
+
+  test('rejects invalid plugins', () => {
+    expect(() => fastYaml.registerPlugin('x')).toThrow(/Plugin must be an object/);
+    expect(() => fastYaml.registerPlugin({ format: {} })).toThrow(
+      /Unknown options: format \(did you mean 'formats'\?\)/
+    );
+    expect(() => fastYaml.registerPlugin({ formats: { a: 1 } })).toThrow(/must be a function/);
+    expect(() => fastYaml.registerPlugin({ functions: { 'is-even': () => true } })).toThrow(
+      /must be an identifier/
+    );
+  });
+});
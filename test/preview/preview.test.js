@@ -0,0 +1,112 @@
+/**
+ * Preview Tests
+ *
+ * This test file tests parsing documents for previews: with long strings and sequences cut, and
+ * converted level by level.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Preview Tests', () => {
+  test('cuts long strings and sequences', () => {
+    expect(
+      fastYaml.parsePreview('name: web\nlogs: [a, b, c, d]\n', { maxItems: 2, maxStringLength: 2 })
+    ).toEqual({
+      value: { name: 'we', logs: ['a', 'b'] },
+      truncated: [
+        { path: '$.name', kind: 'string', length: 3 },
+        { path: '$.logs', kind: 'array', length: 4 },
+      ],
+    });
+  });
+
+  test('keeps values within the limits', () => {
+    expect(fastYaml.parsePreview('a: [1, 2]\nb: text')).toEqual({
+      value: { a: [1, 2], b: 'text' },
+      truncated: [],
+    });
+  });
+
+  test('reports nested paths', () => {
+    const yaml = 'items:\n  - tags: [x, y, z]\n  - tags: [x]\n';
+    expect(fastYaml.parsePreview(yaml, { maxItems: 2 }).truncated).toEqual([
+      { path: '$.items[0].tags', kind: 'array', length: 3 },
+    ]);
+  });
+
+  test('counts characters rather than bytes', () => {
+    const { value } = fastYaml.parsePreview('a: héllo wörld', { maxStringLength: 5 });
+    expect(value.a).toBe('héllo');
+  });
+
+  test('does not convert items past the limit', () => {
+    // The invalid item is never resolved, so it does not throw
+    const yaml = 'a: [1, !!int x]';
+    expect(() => fastYaml.parse(yaml)).toThrow();
+    expect(fastYaml.parsePreview(yaml, { maxItems: 1 }).value).toEqual({ a: [1] });
+  });
+
+  test('applies load options', () => {
+    const { value } = fastYaml.parsePreview('max_count: 1', { keyStyle: 'camel' });
+    expect(value).toEqual({ maxCount: 1 });
+  });
+
+  test('returns null for empty input', () => {
+    expect(fastYaml.parsePreview('')).toEqual({ value: null, truncated: [] });
+  });
+
+  test('rejects invalid limits', () => {
+    expect(() => fastYaml.parsePreview('a: 1', { maxItems: -1 })).toThrow(
+      /Option 'maxItems' must be a non-negative integer or Infinity/
+    );
+  });
+
+  describe('parseLazy', () => {
+    const yaml = 'name: web\nspec:\n  replicas: 3\n  ports: [80, 443]\nbase: &b {x: 1}\nref: *b\n';
+
+    test('converts the top level and returns handles for collections', () => {
+      const doc = fastYaml.parseLazy(yaml);
+      expect(doc.root()).toEqual({
+        name: 'web',
+        spec: { $handle: 0, kind: 'mapping' },
+        base: { $handle: 1, kind: 'mapping' },
+        ref: { $handle: 2, kind: 'mapping' },
+      });
+      expect(doc.handleCount).toBe(3);
+      doc.free();
+    });
+
+    test('expands handles level by level', () => {
+      const doc = fastYaml.parseLazy(yaml);
+      const spec = doc.expand(doc.root().spec);
+      expect(spec).toEqual({ replicas: 3, ports: { $handle: 3, kind: 'sequence' } });
+      expect(doc.expand(spec.ports)).toEqual([80, 443]);
+      expect(doc.expand(2)).toEqual({ x: 1 });
+      doc.free();
+    });
+
+    test('converts maxDepth levels per call', () => {
+      const doc = fastYaml.parseLazy(yaml, { maxDepth: 2 });
+      expect(doc.root().spec).toEqual({ replicas: 3, ports: { $handle: 0, kind: 'sequence' } });
+      doc.free();
+    });
+
+    test('applies load options to expanded values', () => {
+      const doc = fastYaml.parseLazy('a: {max_count: 1}', { keyStyle: 'camel' });
+      expect(doc.expand(doc.root().a)).toEqual({ maxCount: 1 });
+      doc.free();
+    });
+
+    test('rejects unknown handles', () => {
+      const doc = fastYaml.parseLazy(yaml);
+      expect(() => doc.expand(42)).toThrow(/Unknown handle/);
+      doc.free();
+    });
+
+    test('returns null for empty input', () => {
+      const doc = fastYaml.parseLazy('');
+      expect(doc.root()).toBeNull();
+      doc.free();
+    });
+  });
+});
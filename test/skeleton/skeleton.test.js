@@ -0,0 +1,122 @@
+/**
+ * Skeleton Tests
+ *
+ * This test file tests generating starter YAML documents from JSON Schemas.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Skeleton Tests', () => {
+  const schema = {
+    type: 'object',
+    required: ['name', 'port', 'server', 'replicas'],
+    properties: {
+      name: { type: 'string', description: 'Service name' },
+      port: { type: 'integer', default: 8080 },
+      debug: { type: 'boolean', description: 'Verbose logging\nfor development' },
+      server: { $ref: '#/definitions/server' },
+      replicas: {
+        type: 'array',
+        minItems: 1,
+        items: {
+          type: 'object',
+          required: ['host'],
+          properties: { host: { type: 'string' }, weight: { type: 'number', minimum: 1 } },
+        },
+      },
+      mode: { enum: ['fast', 'safe'] },
+      limits: {
+        type: 'object',
+        required: ['cpu'],
+        properties: { cpu: { type: 'string', examples: ['500m'] } },
+      },
+    },
+    definitions: {
+      server: {
+        type: 'object',
+        required: ['host'],
+        properties: { host: { type: 'string', default: 'localhost' }, tls: { type: 'object' } },
+      },
+    },
+  };
+
+  test('writes required properties and comments out optional ones', () => {
+    expect(fastYaml.skeleton(schema)).toBe(
+      [
+        '# Service name',
+        'name: ""',
+        'port: 8080',
+        '# Verbose logging',
+        '# for development',
+        '# debug: false',
+        'server:',
+        '  host: localhost',
+        '  # tls: {}',
+        'replicas:',
+        '  - host: ""',
+        '    # weight: 1',
+        '# mode: fast',
+        '# limits:',
+        '#   cpu: 500m',
+        '',
+      ].join('\n')
+    );
+  });
+
+  test('parses back to the required values', () => {
+    expect(fastYaml.parse(fastYaml.skeleton(schema))).toEqual({
+      name: '',
+      port: 8080,
+      server: { host: 'localhost' },
+      replicas: [{ host: '' }],
+    });
+  });
+
+  test('includes or omits optional properties', () => {
+    const options = { optional: 'include', descriptions: false };
+    expect(fastYaml.parse(fastYaml.skeleton(schema, options))).toMatchObject({
+      debug: false,
+      mode: 'fast',
+      limits: { cpu: '500m' },
+      server: { host: 'localhost', tls: {} },
+    });
+    expect(fastYaml.skeleton(schema, { optional: 'omit', descriptions: false })).toBe(
+      'name: ""\nport: 8080\nserver:\n  host: localhost\nreplicas:\n  - host: ""\n'
+    );
+  });
+
+  test('merges allOf members and writes defaults of any shape', () => {
+    const merged = {
+      allOf: [
+        { required: ['a'], properties: { a: { const: 1 } } },
+        { required: ['b'], properties: { b: { default: { x: [1, 2] } } } },
+      ],
+    };
+    expect(fastYaml.parse(fastYaml.skeleton(merged))).toEqual({ a: 1, b: { x: [1, 2] } });
+  });
+
+  test('stops at maxDepth on recursive schemas', () => {
+    const tree = {
+      $ref: '#/definitions/node',
+      definitions: {
+        node: {
+          type: 'object',
+          required: ['child'],
+          properties: { child: { $ref: '#/definitions/node' } },
+        },
+      },
+    };
+    expect(fastYaml.skeleton(tree, { maxDepth: 2 })).toBe('child:\n  child: {}\n');
+  });
+
+  test('writes non-object roots', () => {
+    expect(fastYaml.skeleton({ type: 'array', minItems: 2, items: { type: 'integer' } })).toBe(
+      '- 0\n- 0\n'
+    );
+    expect(fastYaml.skeleton({ type: 'object' })).toBe('{}\n');
+  });
+
+  test('rejects unknown optional policies', () => {
+    expect(() => fastYaml.skeleton(schema, { optional: 'hide' })).toThrow(/Unknown optional: hide/);
+  });
+});
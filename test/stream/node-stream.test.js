@@ -0,0 +1,61 @@
+/**
+ * Node.js Stream Adapter Tests
+ *
+ * This test file tests the Transform stream of fast-yaml/stream.
+ */
+
+const fs = require('node:fs');
+const os = require('node:os');
+const path = require('node:path');
+const { Readable } = require('node:stream');
+const { pipeline } = require('node:stream/promises');
+const fastYaml = require('../../js/index.cjs');
+const { createParseStream } = require('../../js/stream.cjs');
+
+describe('Node.js Stream Adapter Tests', () => {
+  async function collect(stream) {
+    const chunks = [];
+    for await (const chunk of stream) {
+      chunks.push(chunk);
+    }
+    return chunks;
+  }
+
+  test('parses a file read stream into document chunks', async () => {
+    const yaml = 'name: é\n---\n~\n---\nlist: [1, 2]\n';
+    const file = path.join(fs.mkdtempSync(path.join(os.tmpdir(), 'fast-yaml-')), 'docs.yaml');
+    fs.writeFileSync(file, yaml);
+    const stream = fs.createReadStream(file, { highWaterMark: 3 }).pipe(createParseStream());
+    const chunks = await collect(stream);
+    expect(chunks.map(({ value }) => value)).toEqual(fastYaml.parseAll(yaml));
+    expect(chunks.map(({ index }) => index)).toEqual([0, 1, 2]);
+    expect(chunks[1]).toMatchObject({ start: 9, end: 15 });
+  });
+
+  test('works in a pipeline with stream options', async () => {
+    const items = [];
+    await pipeline(
+      Readable.from(['- 1\n- 2\n', '- 3\n']),
+      createParseStream({ sequenceChunk: 2 }),
+      async (chunks) => {
+        for await (const { value } of chunks) {
+          items.push(...value);
+        }
+      }
+    );
+    expect(items).toEqual([1, 2, 3]);
+  });
+
+  test('destroys the stream on invalid documents', async () => {
+    const stream = Readable.from(['a: 1\n---\n', 'b: [1,\n']).pipe(createParseStream());
+    await expect(collect(stream)).rejects.toThrow(fastYaml.YAMLException);
+  });
+
+  test('destroys the stream when its signal aborts', async () => {
+    const controller = new AbortController();
+    const stream = createParseStream({ signal: controller.signal });
+    stream.write('a: 1\n---\n');
+    setTimeout(() => controller.abort(), 10);
+    await expect(collect(stream)).rejects.toMatchObject({ name: 'AbortError' });
+  });
+});
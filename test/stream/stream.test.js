@@ -0,0 +1,887 @@
+/**
+ * Streaming Tests
+ *
+ * This test file tests the streaming functionality of fast-yaml.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Streaming Tests', () => {
+  describe('transformStream', () => {
+    const yaml = `
+---
+name: a
+---
+name: b
+---
+name: c
+`;
+
+    test('emits each transformed document as YAML', () => {
+      const chunks = [];
+      const count = fastYaml.transformStream(
+        yaml,
+        (doc, index) => ({ ...doc, index }),
+        (text) => chunks.push(text)
+      );
+
+      expect(count).toBe(3);
+      expect(fastYaml.parseAll(chunks.join(''))).toEqual([
+        { name: 'a', index: 0 },
+        { name: 'b', index: 1 },
+        { name: 'c', index: 2 },
+      ]);
+    });
+
+    test('drops documents when the transform returns undefined', () => {
+      const chunks = [];
+      const count = fastYaml.transformStream(
+        yaml,
+        (doc) => (doc.name === 'b' ? undefined : doc),
+        (text) => chunks.push(text)
+      );
+
+      expect(count).toBe(2);
+      expect(fastYaml.parseAll(chunks.join(''))).toEqual([{ name: 'a' }, { name: 'c' }]);
+    });
+  });
+
+  describe('parseStream', () => {
+    const yaml = 'name: a\n---\nname: b\n---\nname: a\n';
+
+    test('passes each document with its byte range', () => {
+      const chunks = [];
+      const count = fastYaml.parseStream(yaml, (chunk) => chunks.push(chunk));
+
+      expect(count).toBe(3);
+      expect(chunks.map(({ index, value }) => [index, value])).toEqual([
+        [0, { name: 'a' }],
+        [1, { name: 'b' }],
+        [2, { name: 'a' }],
+      ]);
+      expect(chunks.map(({ start, end }) => yaml.slice(start, end))).toEqual([
+        'name: a\n',
+        '---\nname: b\n',
+        '---\nname: a\n',
+      ]);
+      expect(chunks[0].hash).toBeUndefined();
+    });
+
+    test('reports byte offsets for non-ASCII text', () => {
+      const text = 'name: é\n---\nname: b\n';
+      const chunks = [];
+      fastYaml.parseStream(text, (chunk) => chunks.push(chunk));
+
+      const bytes = Buffer.from(text);
+      expect(chunks.map(({ start, end }) => bytes.subarray(start, end).toString())).toEqual([
+        'name: é\n',
+        '---\nname: b\n',
+      ]);
+    });
+
+    test('hashes the source of each document with checksum', () => {
+      const chunks = [];
+      fastYaml.parseStream('a: 1\n---\nb: 2\n---\nb: 2\n', (chunk) => chunks.push(chunk), {
+        checksum: true,
+      });
+
+      const hashes = chunks.map((chunk) => chunk.hash);
+      expect(hashes[0]).toMatch(/^[0-9a-f]{16}$/);
+      expect(hashes[1]).toBe(hashes[2]);
+      expect(hashes[0]).not.toBe(hashes[1]);
+    });
+
+    test('reports the directives of each document', () => {
+      const chunks = [];
+      const yaml = [
+        '%YAML 1.1',
+        '%TAG !e! tag:example.com,2000:',
+        '%TAG !f! tag:f.org:',
+        '--- !e!thing {a: 1}',
+        '...',
+        '--- b',
+      ].join('\n');
+      fastYaml.parseStream(yaml, (chunk) => chunks.push(chunk), { directives: true });
+
+      expect(chunks.map((chunk) => chunk.directives)).toEqual([
+        { version: '1.1', tags: { '!e!': 'tag:example.com,2000:', '!f!': 'tag:f.org:' } },
+        { version: null, tags: {} },
+      ]);
+      expect(chunks[0].value).toEqual({ a: 1 });
+    });
+
+    test('omits directives by default', () => {
+      const chunks = [];
+      fastYaml.parseStream('%YAML 1.2\n--- a', (chunk) => chunks.push(chunk));
+
+      expect(chunks[0]).not.toHaveProperty('directives');
+    });
+
+    test('applies load options', () => {
+      const values = [];
+      fastYaml.parseStream('port: 80', ({ value }) => values.push(value), { schema: 'failsafe' });
+
+      expect(values).toEqual([{ port: '80' }]);
+    });
+  });
+
+  describe('parseStream with chunks', () => {
+    const yaml = [
+      '%TAG !e! tag:example.com,2000:',
+      '--- !e!thing',
+      'name: é',
+      '...',
+      '# next',
+      'name: b',
+      '---',
+      'list:',
+      '  - |',
+      '    text',
+      '--- c',
+      '',
+    ].join('\n');
+
+    function split(text, size) {
+      const bytes = Buffer.from(text);
+      const chunks = [];
+      for (let i = 0; i < bytes.length; i += size) {
+        chunks.push(bytes.subarray(i, i + size));
+      }
+      return chunks;
+    }
+
+    function collect(source, options) {
+      const chunks = [];
+      const count = fastYaml.parseStream(source, (chunk) => chunks.push(chunk), options);
+      return { count, chunks };
+    }
+
+    test('passes the documents of every chunk size', () => {
+      const expected = fastYaml.parseAll(yaml);
+      for (const size of [1, 2, 3, 7, 64]) {
+        const { count, chunks } = collect(split(yaml, size), { checksum: true });
+        expect(count).toBe(expected.length);
+        expect(chunks.map((chunk) => chunk.value)).toEqual(expected);
+        expect(chunks.map((chunk) => chunk.index)).toEqual([0, 1, 2, 3]);
+        expect(chunks).toEqual(collect([yaml], { checksum: true }).chunks);
+      }
+    });
+
+    test('reports byte ranges from the start of the stream', () => {
+      const bytes = Buffer.from(yaml);
+      const { chunks } = collect(split(yaml, 5));
+      expect(chunks.map(({ start, end }) => bytes.subarray(start, end).toString())).toEqual([
+        '--- !e!thing\nname: é\n',
+        '# next\nname: b\n',
+        '---\nlist:\n  - |\n    text\n',
+        '--- c\n',
+      ]);
+    });
+
+    test('reports the directives of each document', () => {
+      const { chunks } = collect(split(yaml, 4), { directives: true });
+      expect(chunks[0].directives).toEqual({
+        version: null,
+        tags: { '!e!': 'tag:example.com,2000:' },
+      });
+      expect(chunks[1].directives).toEqual({ version: null, tags: {} });
+    });
+
+    test('accepts async iterables', async () => {
+      async function* generate() {
+        yield* split(yaml, 3);
+      }
+      const values = [];
+      const count = await fastYaml.parseStream(generate(), ({ value }) => values.push(value));
+      expect(count).toBe(4);
+      expect(values).toEqual(fastYaml.parseAll(yaml));
+    });
+
+    test('bounds each document with maxInputSize', () => {
+      const stream = ['a: 1\n---\n', 'b: 2\n---\n', 'c: 3\n'];
+      expect(collect(stream, { maxInputSize: 12 }).count).toBe(3);
+
+      const values = [];
+      const oversized = ['a: 1\n---\n', 'b: 22222222222\n'];
+      expect(() =>
+        fastYaml.parseStream(oversized, ({ value }) => values.push(value), { maxInputSize: 12 })
+      ).toThrow(/maxInputSize/);
+      expect(values).toEqual([{ a: 1 }]);
+    });
+
+    test('counts maxDocuments over the whole stream', () => {
+      const values = [];
+      const stream = ['a\n---\n', 'b\n---\n', 'c\n'];
+      expect(() =>
+        fastYaml.parseStream(stream, ({ value }) => values.push(value), { maxDocuments: 2 })
+      ).toThrow(/maxDocuments/);
+      expect(values).toEqual(['a', 'b']);
+    });
+
+    test('reports errors at their line in the stream', () => {
+      expect(() => collect(['a: 1\n---\n', 'b: 2\n---\n', 'c: [1,\n'])).toThrow(/line 6/);
+    });
+
+    test('stops when the callback returns false', () => {
+      const yaml = 'kind: Service\n---\nkind: Deployment\n---\nkind: Job\n--- [\n';
+      const found = [];
+      const find = ({ value }) => {
+        found.push(value.kind);
+        return value.kind !== 'Deployment';
+      };
+      expect(fastYaml.parseStream(yaml, find)).toBe(2);
+      expect(found).toEqual(['Service', 'Deployment']);
+
+      const read = [];
+      function* chunks() {
+        for (const chunk of yaml.split(/(?<=---\n)/)) {
+          read.push(chunk);
+          yield chunk;
+        }
+      }
+      found.length = 0;
+      expect(fastYaml.parseStream(chunks(), find)).toBe(2);
+      expect(found).toEqual(['Service', 'Deployment']);
+      expect(read).toHaveLength(2);
+    });
+  });
+
+  describe('createStreamParser', () => {
+    test('passes each document once the chunks written complete it', () => {
+      const values = [];
+      const parser = fastYaml.createStreamParser(({ value }) => values.push(value));
+      parser.write('a: 1\n--');
+      expect(values).toEqual([]);
+      parser.write('-\nb: 2\n');
+      expect(values).toEqual([{ a: 1 }]);
+      parser.write(Buffer.from('---\nc: 3\n'));
+      expect(values).toEqual([{ a: 1 }, { b: 2 }]);
+      expect(parser.end()).toBe(3);
+      expect(values).toEqual([{ a: 1 }, { b: 2 }, { c: 3 }]);
+    });
+
+    test('decodes characters split between byte chunks', () => {
+      const bytes = Buffer.from('name: é✓\n---\nname: 🎉\n');
+      const values = [];
+      const parser = fastYaml.createStreamParser(({ value }) => values.push(value));
+      for (const byte of bytes) {
+        parser.write(Uint8Array.of(byte));
+      }
+      parser.end();
+      expect(values).toEqual([{ name: 'é✓' }, { name: '🎉' }]);
+    });
+
+    test('reports byte ranges and accepts stream options', () => {
+      const chunks = [];
+      const parser = fastYaml.createStreamParser((chunk) => chunks.push(chunk), {
+        checksum: true,
+      });
+      parser.write('a: 1\n---\nb: 2\n');
+      parser.end();
+      expect(chunks.map(({ start, end }) => [start, end])).toEqual([
+        [0, 5],
+        [5, 14],
+      ]);
+      expect(chunks[0].hash).toMatch(/^[0-9a-f]{16}$/);
+    });
+
+    test('ignores the rest of the stream once the callback returns false', () => {
+      const values = [];
+      const parser = fastYaml.createStreamParser(({ value }) => {
+        values.push(value);
+        return value !== 'b';
+      });
+      parser.write('a\n---\nb\n');
+      expect(parser.stopped).toBe(false);
+      parser.write('---\nc\n');
+      expect(parser.stopped).toBe(true);
+      parser.write('--- [\n');
+      expect(parser.end()).toBe(2);
+      expect(values).toEqual(['a', 'b']);
+    });
+
+    test('ends root block scalars at a marker line', () => {
+      const values = [];
+      const parser = fastYaml.createStreamParser(({ value }) => values.push(value));
+      parser.write('--- |\nfoo\n--- |\n');
+      expect(values).toEqual(['foo\n']);
+      parser.write('bar\n');
+      expect(parser.end()).toBe(2);
+      expect(values).toEqual(['foo\n', 'bar\n']);
+    });
+
+    test('throws YAMLException for errors in a document', () => {
+      const parser = fastYaml.createStreamParser(() => {});
+      parser.write('a: 1\n---\nb: [1,\n');
+      expect(() => parser.end()).toThrow(fastYaml.YAMLException);
+      expect(() => parser.free()).not.toThrow();
+    });
+
+    test('reports errors after the first document at their line in the stream', () => {
+      const parser = fastYaml.createStreamParser(() => {});
+      parser.write('a: 1\n---\n');
+      parser.write('b: 2\n---\nc: [1,\n');
+      expect.assertions(2);
+      try {
+        parser.end();
+      } catch (e) {
+        expect(e.line).toBe(6);
+        expect(e.message).toMatch(/at line 6, column 1/);
+      }
+    });
+  });
+
+  describe('restoreStreamParser', () => {
+    const restore = (parser, callback) =>
+      fastYaml.restoreStreamParser(JSON.parse(JSON.stringify(parser.checkpoint())), callback);
+
+    test('resumes the stream from a checkpoint', () => {
+      const yaml = 'a: 1\n---\nb: "x\n  --- y"\n---\nc: |\n  z\n...\n---\nd: 4\n';
+      const expected = [];
+      fastYaml.parseStream(yaml, (chunk) => expected.push(chunk));
+
+      for (let split = 0; split <= yaml.length; split += 1) {
+        const chunks = [];
+        const first = fastYaml.createStreamParser((chunk) => chunks.push(chunk));
+        first.write(yaml.slice(0, split));
+        const checkpoint = first.checkpoint();
+        expect(checkpoint.bytesReceived).toBe(split);
+        const second = restore(first, (chunk) => chunks.push(chunk));
+        first.free();
+        second.write(yaml.slice(split));
+        expect(second.end()).toBe(4);
+        expect(chunks).toEqual(expected);
+      }
+    });
+
+    test('reports errors at their line in the whole stream', () => {
+      const first = fastYaml.createStreamParser(() => {});
+      first.write('a: 1\n---\nb: 2\n');
+      const second = restore(first, () => {});
+      second.write('---\nc: [1,\n');
+      expect(() => second.end()).toThrow(/at line 6, column 1/);
+    });
+
+    test('keeps a character split between byte chunks', () => {
+      const bytes = Buffer.from('a: é\n---\nb: 🎉\n');
+      const values = [];
+      const first = fastYaml.createStreamParser(({ value }) => values.push(value));
+      first.write(bytes.subarray(0, 15));
+      expect(first.checkpoint()).toMatchObject({ bytesReceived: 15, partial: [0xf0, 0x9f] });
+      const second = restore(first, ({ value }) => values.push(value));
+      second.write(bytes.subarray(15));
+      second.end();
+      expect(values).toEqual([{ a: 'é' }, { b: '🎉' }]);
+    });
+
+    test('stays stopped once the callback returned false', () => {
+      const first = fastYaml.createStreamParser(() => false);
+      first.write('a\n---\nb\n');
+      const second = restore(first, () => {});
+      expect(second.stopped).toBe(true);
+      second.write('---\nc\n');
+      expect(second.end()).toBe(1);
+    });
+
+    test('rejects invalid checkpoints', () => {
+      const checkpoint = fastYaml.createStreamParser(() => {}).checkpoint();
+      const invalid = [null, {}, { ...checkpoint, version: 2 }, { ...checkpoint, scanned: 1 }];
+      for (const value of invalid) {
+        expect(() => fastYaml.restoreStreamParser(value, () => {})).toThrow(
+          'Invalid stream parser checkpoint'
+        );
+      }
+    });
+  });
+
+  describe('parseFromStream', () => {
+    function readable(chunks) {
+      return new ReadableStream({
+        pull(controller) {
+          if (chunks.length === 0) {
+            controller.close();
+          } else {
+            controller.enqueue(chunks.shift());
+          }
+        },
+      });
+    }
+
+    test('parses the documents of a byte stream', async () => {
+      const bytes = Buffer.from('name: é\n---\nname: b\n');
+      const chunks = Array.from(bytes, (byte) => Uint8Array.of(byte));
+      const values = [];
+      const count = await fastYaml.parseFromStream(readable(chunks), ({ value }) =>
+        values.push(value)
+      );
+      expect(count).toBe(2);
+      expect(values).toEqual([{ name: 'é' }, { name: 'b' }]);
+    });
+
+    test('reads text streams and a response body', async () => {
+      const values = [];
+      await fastYaml.parseFromStream(readable(['a: 1\n', '---\nb: 2\n']), ({ value }) =>
+        values.push(value)
+      );
+      expect(values).toEqual([{ a: 1 }, { b: 2 }]);
+
+      const response = new Response('- 1\n- 2\n- 3\n');
+      const chunks = [];
+      await fastYaml.parseFromStream(response.body, (chunk) => chunks.push(chunk), {
+        sequenceChunk: 2,
+      });
+      expect(chunks.map(({ value }) => value)).toEqual([[1, 2], [3]]);
+    });
+
+    test('cancels the stream when parsing fails', async () => {
+      let cancelled = null;
+      const stream = new ReadableStream({
+        start(controller) {
+          controller.enqueue('a: 1\n---\n');
+          controller.enqueue('b: [1,\n---\n');
+        },
+        cancel(reason) {
+          cancelled = reason;
+        },
+      });
+      await expect(fastYaml.parseFromStream(stream, () => {})).rejects.toThrow(
+        fastYaml.YAMLException
+      );
+      expect(cancelled).toBeInstanceOf(fastYaml.YAMLException);
+    });
+
+    test('cancels the stream when onDocument returns false', async () => {
+      let cancelled = false;
+      const stream = new ReadableStream({
+        start(controller) {
+          controller.enqueue('id: 1\n---\nid: 2\n---\n');
+          controller.enqueue('id: 3\n---\n');
+        },
+        cancel() {
+          cancelled = true;
+        },
+      });
+      const count = await fastYaml.parseFromStream(stream, ({ value }) => value.id !== 2);
+      expect(count).toBe(2);
+      expect(cancelled).toBe(true);
+    });
+  });
+
+  describe('documentStream', () => {
+    async function read(stream) {
+      const values = [];
+      const reader = stream.getReader();
+      for (;;) {
+        const { done, value } = await reader.read();
+        if (done) {
+          return values;
+        }
+        values.push(value);
+      }
+    }
+
+    test('turns text into the values of its documents', async () => {
+      const response = new Response('a: 1\n---\n- b\n--- ~\n');
+      const values = await read(response.body.pipeThrough(fastYaml.documentStream()));
+      expect(values).toEqual([{ a: 1 }, ['b'], null]);
+    });
+
+    test('passes the items of root sequences one at a time with sequenceChunk', async () => {
+      const stream = fastYaml.documentStream({ sequenceChunk: 2 });
+      const writer = stream.writable.getWriter();
+      writer.write('- 1\n- 2\n');
+      writer.write(Buffer.from('- 3\n---\nk: v\n'));
+      writer.close();
+      expect(await read(stream.readable)).toEqual([1, 2, 3, { k: 'v' }]);
+    });
+
+    test('errors the stream on invalid documents', async () => {
+      const response = new Response('a: 1\n---\nb: [1,\n');
+      await expect(read(response.body.pipeThrough(fastYaml.documentStream()))).rejects.toThrow(
+        fastYaml.YAMLException
+      );
+    });
+  });
+
+  describe('parseDocuments', () => {
+    async function collect(iterable) {
+      const values = [];
+      for await (const value of iterable) {
+        values.push(value);
+      }
+      return values;
+    }
+
+    test('iterates over the documents of a string', async () => {
+      const values = await collect(fastYaml.parseDocuments('a: 1\n---\n- b\n--- ~\n'));
+      expect(values).toEqual([{ a: 1 }, ['b'], null]);
+    });
+
+    test('iterates over the documents of large strings and bytes', async () => {
+      const yaml = Array.from({ length: 20000 }, (_, i) => `id: ${i}\n`).join('---\n');
+      expect(await collect(fastYaml.parseDocuments(yaml))).toHaveLength(20000);
+      const values = await collect(fastYaml.parseDocuments(Buffer.from(yaml)));
+      expect(values[19999]).toEqual({ id: 19999 });
+    });
+
+    test('reads chunks from iterables and ReadableStreams', async () => {
+      async function* chunks() {
+        yield 'a: 1\n--';
+        yield Buffer.from('-\nb: 2\n');
+      }
+      expect(await collect(fastYaml.parseDocuments(chunks()))).toEqual([{ a: 1 }, { b: 2 }]);
+      expect(await collect(fastYaml.parseDocuments(['x\n', '---\ny\n']))).toEqual(['x', 'y']);
+      const response = new Response('- 1\n- 2\n');
+      expect(await collect(fastYaml.parseDocuments(response.body))).toEqual([[1, 2]]);
+    });
+
+    test('reads input only as documents are consumed', async () => {
+      let pulled = 0;
+      let cancelled = false;
+      const source = {
+        pull(controller) {
+          pulled += 1;
+          controller.enqueue(`n: ${pulled}\n---\n`);
+        },
+        cancel() {
+          cancelled = true;
+        },
+      };
+      const stream = new ReadableStream(source, { highWaterMark: 0 });
+      for await (const doc of fastYaml.parseDocuments(stream)) {
+        if (doc.n === 3) {
+          break;
+        }
+      }
+      expect(pulled).toBeLessThanOrEqual(4);
+      expect(cancelled).toBe(true);
+    });
+
+    test('iterates over root sequence items with sequenceChunk', async () => {
+      const values = await collect(
+        fastYaml.parseDocuments('- 1\n- 2\n- 3\n---\nk: v\n', { sequenceChunk: 2 })
+      );
+      expect(values).toEqual([1, 2, 3, { k: 'v' }]);
+    });
+
+    test('throws on invalid documents after yielding the valid ones', async () => {
+      const values = [];
+      await expect(async () => {
+        for await (const value of fastYaml.parseDocuments('a: 1\n---\nb: [1,\n')) {
+          values.push(value);
+        }
+      }).rejects.toThrow(fastYaml.YAMLException);
+      expect(values).toEqual([{ a: 1 }]);
+    });
+  });
+
+  describe('signal option', () => {
+    // A stream holding one document, then waiting for more forever
+    function stalled(onCancel) {
+      const source = {
+        start(controller) {
+          controller.enqueue('a: 1\n---\n');
+        },
+        pull() {},
+        cancel: onCancel,
+      };
+      return new ReadableStream(source, { highWaterMark: 0 });
+    }
+
+    async function* stalledChunks() {
+      yield 'a: 1\n---\n';
+      await new Promise(() => {});
+    }
+
+    function abortLater() {
+      const controller = new AbortController();
+      setTimeout(() => controller.abort(), 10);
+      return controller.signal;
+    }
+
+    test('aborts parseStream over an async iterable', async () => {
+      const values = [];
+      const parsing = fastYaml.parseStream(stalledChunks(), ({ value }) => values.push(value), {
+        signal: abortLater(),
+      });
+      await expect(parsing).rejects.toMatchObject({ name: 'AbortError' });
+      expect(values).toEqual([{ a: 1 }]);
+    });
+
+    test('aborts parseFromStream and cancels its stream', async () => {
+      let cancelled = null;
+      const parsing = fastYaml.parseFromStream(
+        stalled((reason) => {
+          cancelled = reason;
+        }),
+        () => {},
+        { signal: abortLater() }
+      );
+      await expect(parsing).rejects.toMatchObject({ name: 'AbortError' });
+      expect(cancelled?.name).toBe('AbortError');
+    });
+
+    test('aborts parseDocuments', async () => {
+      let cancelled = false;
+      const values = [];
+      const iterate = async () => {
+        const stream = stalled(() => {
+          cancelled = true;
+        });
+        for await (const value of fastYaml.parseDocuments(stream, { signal: abortLater() })) {
+          values.push(value);
+        }
+      };
+      await expect(iterate()).rejects.toMatchObject({ name: 'AbortError' });
+      expect(values).toEqual([{ a: 1 }]);
+      expect(cancelled).toBe(true);
+    });
+
+    test('errors documentStream', async () => {
+      const controller = new AbortController();
+      const stream = fastYaml.documentStream({ signal: controller.signal });
+      stream.writable.getWriter().write('a: 1\n---\n');
+      const reader = stream.readable.getReader();
+      expect((await reader.read()).value).toEqual({ a: 1 });
+      controller.abort();
+      await expect(reader.read()).rejects.toMatchObject({ name: 'AbortError' });
+    });
+
+    test('rejects at once when already aborted', async () => {
+      const signal = AbortSignal.abort();
+      const parsing = fastYaml.parseStream(stalledChunks(), () => {}, { signal });
+      await expect(parsing).rejects.toMatchObject({ name: 'AbortError' });
+      const iterating = fastYaml.parseDocuments('a: 1\n', { signal }).next();
+      await expect(iterating).rejects.toMatchObject({ name: 'AbortError' });
+    });
+  });
+
+  describe('onProgress option', () => {
+    const yaml = 'a: 1\n---\nb: 2\n---\nc: 3\n';
+
+    test('reports every progressInterval bytes', () => {
+      const reports = [];
+      fastYaml.parseStream(yaml, () => {}, {
+        onProgress: (progress) => reports.push(progress),
+        progressInterval: 1,
+      });
+      expect(reports).toEqual([
+        { bytesProcessed: 5, documentsEmitted: 1 },
+        { bytesProcessed: 14, documentsEmitted: 2 },
+        { bytesProcessed: 23, documentsEmitted: 3 },
+      ]);
+    });
+
+    test('reports the end of the stream', () => {
+      const reports = [];
+      const onProgress = (progress) => reports.push(progress);
+      fastYaml.parseStream(yaml, () => {}, { onProgress });
+      expect(reports).toEqual([{ bytesProcessed: 23, documentsEmitted: 3 }]);
+
+      reports.length = 0;
+      fastYaml.parseStream(yaml, () => {}, { onProgress, progressInterval: 10 });
+      expect(reports).toEqual([
+        { bytesProcessed: 14, documentsEmitted: 2 },
+        { bytesProcessed: 23, documentsEmitted: 3 },
+      ]);
+    });
+
+    test('counts the bytes of chunked streams from their start', async () => {
+      const reports = [];
+      const parser = fastYaml.createStreamParser(() => {}, {
+        onProgress: (progress) => reports.push(progress),
+        progressInterval: 1,
+      });
+      parser.write('a: 1\n---\nb: 2\n');
+      parser.write(Buffer.from('---\nc: é\n'));
+      parser.end();
+      expect(reports.map(({ bytesProcessed }) => bytesProcessed)).toEqual([5, 14, 24]);
+
+      reports.length = 0;
+      const response = new Response(yaml);
+      await fastYaml.parseFromStream(response.body, () => {}, {
+        onProgress: (progress) => reports.push(progress),
+      });
+      expect(reports).toEqual([{ bytesProcessed: 23, documentsEmitted: 3 }]);
+    });
+
+    test('rejects invalid progress options', () => {
+      expect(() => fastYaml.parseStream(yaml, () => {}, { onProgress: 1 })).toThrow(/onProgress/);
+      expect(() =>
+        fastYaml.parseStream(yaml, () => {}, { onProgress: () => {}, progressInterval: 0 })
+      ).toThrow(/progressInterval/);
+    });
+  });
+
+  describe('parseStream with sequenceChunk', () => {
+    const yaml = '- 1\n- [2]\n- c: 3\n- 4\n- 5\n---\nkey: v\n--- !!omap\n- a: 1\n';
+
+    function collect(source, options) {
+      const chunks = [];
+      const count = fastYaml.parseStream(source, (chunk) => chunks.push(chunk), options);
+      return { count, chunks };
+    }
+
+    test('passes the items of a root sequence a chunk at a time', () => {
+      const { count, chunks } = collect(yaml, { sequenceChunk: 2 });
+      expect(count).toBe(3);
+      expect(chunks.slice(0, 3)).toEqual([
+        { index: 0, item: 0, value: [1, [2]] },
+        { index: 0, item: 2, value: [{ c: 3 }, 4] },
+        { index: 0, item: 4, value: [5] },
+      ]);
+      const [, mapping, omap] = fastYaml.parseAll(yaml);
+      expect(chunks[3]).toMatchObject({ index: 1, value: mapping });
+      expect(chunks[4]).toMatchObject({ index: 2, value: omap });
+      expect(chunks[4].item).toBeUndefined();
+    });
+
+    test('passes every root sequence as items', () => {
+      expect(collect('- 1\n- 2\n--- []\n', { sequenceChunk: 2 }).chunks).toEqual([
+        { index: 0, item: 0, value: [1, 2] },
+        { index: 1, item: 0, value: [] },
+      ]);
+      expect(collect('- &a x\n- *a\n', { sequenceChunk: 1 }).chunks).toEqual([
+        { index: 0, item: 0, value: ['x'] },
+        { index: 0, item: 1, value: ['x'] },
+      ]);
+    });
+
+    test('passes the same items from chunked input', () => {
+      const { chunks } = collect(yaml, { sequenceChunk: 3 });
+      const pieces = Array.from(yaml, (char) => char);
+      expect(collect(pieces, { sequenceChunk: 3 }).chunks).toEqual(chunks);
+    });
+
+    test('rejects sizes that are not positive integers', () => {
+      expect(() => collect(yaml, { sequenceChunk: 0 })).toThrow(/sequenceChunk/);
+      expect(() => collect(yaml, { sequenceChunk: 1.5 })).toThrow(/sequenceChunk/);
+    });
+  });
+
+  describe('indexDocuments', () => {
+    const yaml = 'a: 1\n--- b\n...\n# note\n---\n- x\n...\n%YAML 1.2\n---\nname: é\n';
+
+    test('returns the byte range of each document', () => {
+      const bytes = Buffer.from(yaml);
+      const index = fastYaml.indexDocuments(bytes);
+      expect(index).toHaveLength(4);
+      expect(index[0]).toEqual({ start: 0, end: 5 });
+      const docs = index.map(({ start, end }) => fastYaml.parseBytes(bytes.subarray(start, end)));
+      expect(docs).toEqual(fastYaml.parseAll(yaml));
+    });
+
+    test('accepts strings, counting UTF-8 bytes', () => {
+      const index = fastYaml.indexDocuments(yaml);
+      expect(index).toEqual(fastYaml.indexDocuments(Buffer.from(yaml)));
+      expect(index[3].end).toBe(Buffer.byteLength(yaml));
+    });
+
+    test('keeps directives and comments with the document they precede', () => {
+      const index = fastYaml.indexDocuments(yaml);
+      expect(yaml.slice(index[2].start, index[2].end)).toBe('# note\n---\n- x\n...\n');
+      expect(yaml.slice(index[3].start)).toBe('%YAML 1.2\n---\nname: é\n');
+    });
+
+    test('finds no documents in comments and markers alone', () => {
+      expect(fastYaml.indexDocuments('')).toEqual([]);
+      expect(fastYaml.indexDocuments('# comment\n...\n')).toEqual([]);
+      expect(fastYaml.indexDocuments('---\n')).toEqual([{ start: 0, end: 4 }]);
+    });
+
+    test('ends root block scalars at a marker line', () => {
+      expect(fastYaml.indexDocuments('--- |\nfoo\n--- |\nbar\n')).toEqual([
+        { start: 0, end: 10 },
+        { start: 10, end: 20 },
+      ]);
+    });
+
+    test('does not parse the documents', () => {
+      expect(fastYaml.indexDocuments('a: [1,\n---\nb: 2\n')).toEqual([
+        { start: 0, end: 7 },
+        { start: 7, end: 16 },
+      ]);
+    });
+  });
+
+  describe('countDocuments and splitDocuments', () => {
+    const yaml = 'a: 1\n---\nb: |\n  text\n...\n# note\n--- c\n';
+
+    test('count and split the documents of a stream', () => {
+      expect(fastYaml.countDocuments(yaml)).toBe(3);
+      expect(fastYaml.splitDocuments(yaml)).toEqual([
+        'a: 1\n',
+        '---\nb: |\n  text\n...\n',
+        '# note\n--- c\n',
+      ]);
+      const docs = fastYaml.splitDocuments(yaml).map((source) => fastYaml.parse(source));
+      expect(docs).toEqual(fastYaml.parseAll(yaml));
+    });
+
+    test('split bytes into views of the input', () => {
+      const bytes = Buffer.from(yaml);
+      const parts = fastYaml.splitDocuments(bytes);
+      expect(parts.map((part) => part.toString())).toEqual(fastYaml.splitDocuments(yaml));
+      expect(parts[1].buffer).toBe(bytes.buffer);
+      expect(fastYaml.countDocuments(bytes)).toBe(3);
+    });
+
+    test('do not take marker lines inside scalars for markers', () => {
+      const scalars = [
+        '--- |\n  first\n  ---\n  still first\n',
+        '--- "quoted\n  ---\n  still quoted"\n',
+        'plain\n  ---\n',
+        'key: "it\'s\n  --- quoted"\n',
+      ];
+      for (const source of scalars) {
+        expect(fastYaml.countDocuments(source)).toBe(fastYaml.parseAll(source).length);
+      }
+      const stream = scalars.join('---\n');
+      expect(fastYaml.countDocuments(stream)).toBe(fastYaml.parseAll(stream).length);
+      expect(fastYaml.indexDocuments(stream)).toHaveLength(fastYaml.parseAll(stream).length);
+    });
+
+    test('end root block scalars at a marker line', () => {
+      const blocks = '--- |\nfoo\n--- |\nbar\n';
+      expect(fastYaml.countDocuments(blocks)).toBe(2);
+      expect(fastYaml.splitDocuments(blocks)).toEqual(['--- |\nfoo\n', '--- |\nbar\n']);
+      expect(fastYaml.parseAll(blocks)).toEqual(['foo\n', 'bar\n']);
+    });
+
+    test('find no documents in comments and directives alone', () => {
+      expect(fastYaml.countDocuments('')).toBe(0);
+      expect(fastYaml.countDocuments('# comment\n%YAML 1.2\n')).toBe(0);
+      expect(fastYaml.splitDocuments('# comment\n')).toEqual([]);
+    });
+  });
+
+  describe('extractDocument', () => {
+    test('parse the document at an index', () => {
+      const yaml = 'a: 1\n---\nb: |\n  ---\n...\n--- [c]\n';
+      expect(fastYaml.extractDocument(yaml, 0)).toEqual({ a: 1 });
+      expect(fastYaml.extractDocument(yaml, 1)).toEqual({ b: '---\n' });
+      expect(fastYaml.extractDocument(yaml, 2)).toEqual(['c']);
+    });
+
+    test('end root block scalars at a marker line', () => {
+      const yaml = '--- |\nfoo\n--- |\nbar\n';
+      expect(fastYaml.extractDocument(yaml, 0)).toBe('foo\n');
+      expect(fastYaml.extractDocument(yaml, 1)).toBe('bar\n');
+    });
+
+    test('do not parse the other documents', () => {
+      const yaml = 'a: [1\n---\nb: 2\n---\nc: {\n';
+      expect(fastYaml.extractDocument(yaml, 1)).toEqual({ b: 2 });
+    });
+
+    test('count error lines from the start of the stream', () => {
+      const yaml = 'a: 1\n---\nb: 2\n---\nc: [1,\n';
+      expect(() => fastYaml.extractDocument(yaml, 2)).toThrow(/line 6/);
+    });
+
+    test('return what parse gives an empty input past the last document', () => {
+      expect(fastYaml.extractDocument('a: 1\n', 1)).toBe(fastYaml.parse(''));
+      expect(fastYaml.extractDocument('', 0)).toBe(fastYaml.parse(''));
+    });
+  });
+});
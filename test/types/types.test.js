@@ -0,0 +1,121 @@
+/**
+ * Custom Type Tests
+ *
+ * This test file tests constructing user-defined values from tagged nodes with types registered
+ * by registerType.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Custom Type Tests', () => {
+  fastYaml.registerType('!Ref', { kind: 'scalar', constructFn: (name) => ({ Ref: name }) });
+  fastYaml.registerType('!GetAtt', {
+    kind: 'scalar',
+    resolveFn: (text) => text.includes('.'),
+    constructFn: (text) => ({ 'Fn::GetAtt': text.split('.') }),
+  });
+  fastYaml.registerType('!Sub', { kind: 'sequence', constructFn: (args) => ({ 'Fn::Sub': args }) });
+  fastYaml.registerType('!Tags', {
+    kind: 'mapping',
+    constructFn: (tags) => Object.entries(tags).map(([Key, Value]) => ({ Key, Value })),
+  });
+
+  test('constructs scalars, sequences and mappings', () => {
+    const yaml = [
+      'bucket: !Ref Logs',
+      'arn: !GetAtt Logs.Arn',
+      "url: !Sub ['${Host}/x', {Host: !Ref Host}]",
+      'tags: !Tags {team: web, env: prod}',
+    ].join('\n');
+    expect(fastYaml.parse(yaml)).toEqual({
+      bucket: { Ref: 'Logs' },
+      arn: { 'Fn::GetAtt': ['Logs', 'Arn'] },
+      url: { 'Fn::Sub': ['${Host}/x', { Host: { Ref: 'Host' } }] },
+      tags: [
+        { Key: 'team', Value: 'web' },
+        { Key: 'env', Value: 'prod' },
+      ],
+    });
+  });
+
+  test('passes the scalar text without resolving it', () => {
+    expect(fastYaml.load('a: !Ref 123\nb: !Ref ""')).toEqual({ a: { Ref: '123' }, b: { Ref: '' } });
+  });
+
+  test('applies to every document and to aliases', () => {
+    expect(fastYaml.parseAll('--- !Ref a\n--- [&x !Ref b, *x]\n')).toEqual([
+      { Ref: 'a' },
+      [{ Ref: 'b' }, { Ref: 'b' }],
+    ]);
+  });
+
+  test('rejects nodes refused by resolveFn with their position', () => {
+    expect(() => fastYaml.parse('a: 1\nb: !GetAtt Logs')).toThrow(
+      /cannot resolve 'Logs' as !GetAtt at line 2, column 12/
+    );
+  });
+
+  test('rethrows exceptions from constructFn', () => {
+    fastYaml.registerType('!fail', {
+      kind: 'scalar',
+      constructFn: () => {
+        throw new TypeError('boom');
+      },
+    });
+    expect(() => fastYaml.parse('a: !fail x')).toThrow('boom');
+  });
+
+  test('keeps the data when there is no constructFn', () => {
+    fastYaml.registerType('!!python/tuple', { kind: 'sequence' });
+    expect(fastYaml.parse('a: !!python/tuple [1, 2]')).toEqual({ a: [1, 2] });
+  });
+
+  test('leaves nodes of another kind alone and reports them', () => {
+    const warnings = [];
+    const value = fastYaml.parse('a: !Ref [x]\nb: !Ref x', {
+      onWarning: (w) => warnings.push(w.message),
+    });
+    expect(value).toEqual({ a: ['x'], b: { Ref: 'x' } });
+    expect(warnings).toEqual([expect.stringMatching(/unknown tag !Ref, ignored/)]);
+  });
+
+  test('replaces an earlier definition of the same tag and kind', () => {
+    fastYaml.registerType('!env', { kind: 'scalar', constructFn: () => 'first' });
+    fastYaml.registerType('!env', { kind: 'scalar', constructFn: (name) => `$${name}` });
+    expect(fastYaml.parse('home: !env HOME')).toEqual({ home: '$HOME' });
+  });
+
+  test('validates definitions', () => {
+    expect(() => fastYaml.registerType('Ref', { kind: 'scalar' })).toThrow(/must start with '!'/);
+    expect(() => fastYaml.registerType('!x', {})).toThrow(/needs a kind/);
+    expect(() => fastYaml.registerType('!x', { kind: 'list' })).toThrow(/Unknown kind 'list'/);
+    expect(() => fastYaml.registerType('!x', { kind: 'scalar', constructFn: 1 })).toThrow(
+      /'constructFn' must be a function/
+    );
+  });
+
+  test('takes precedence over wrapUnknownTags', () => {
+    expect(fastYaml.parse('a: !Ref x\nb: !Other y', { wrapUnknownTags: true })).toEqual({
+      a: { Ref: 'x' },
+      b: { tag: '!Other', value: 'y' },
+    });
+  });
+
+  test('rejects constructed class instances with cloneable', () => {
+    class Point {
+      constructor([x, y]) {
+        this.x = x;
+        this.y = y;
+      }
+    }
+    fastYaml.registerType('!point', { kind: 'sequence', constructFn: (xy) => new Point(xy) });
+    fastYaml.registerType('!callback', { kind: 'scalar', constructFn: () => () => 1 });
+
+    expect(fastYaml.parse('a: !point [1, 2]')).toBeInstanceOf(Object);
+    expect(() => fastYaml.parse('a: !point [1, 2]', { cloneable: true })).toThrow(
+      /an instance of Point returned by constructFn cannot be cloned \(cloneable\) at line 1/
+    );
+    expect(() => fastYaml.parse('a: !callback x', { cloneable: true })).toThrow(/a function/);
+    expect(fastYaml.parse('a: !Ref x', { cloneable: true })).toEqual({ a: { Ref: 'x' } });
+  });
+});
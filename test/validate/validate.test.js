@@ -0,0 +1,290 @@
+/**
+ * Validation Tests
+ *
+ * This test file tests JSON Schema validation of YAML documents.
+ */
+
+const fastYaml = require('../../js/index.cjs');
+
+describe('Validation Tests', () => {
+  const schema = {
+    type: 'object',
+    required: ['name', 'port'],
+    properties: {
+      name: { type: 'string', minLength: 1 },
+      port: { type: 'integer', minimum: 1, maximum: 65535 },
+      tags: { type: 'array', items: { type: 'string' }, uniqueItems: true },
+    },
+    additionalProperties: false,
+  };
+
+  describe('validate', () => {
+    test('accepts a matching document', () => {
+      const result = fastYaml.validate('name: web\nport: 80\ntags: [a, b]', schema);
+      expect(result).toEqual({ valid: true, errors: [] });
+    });
+
+    test('reports every failure with its path', () => {
+      const result = fastYaml.validate('port: 70000\ntags: [a, a]\nextra: 1', schema);
+      expect(result.valid).toBe(false);
+      expect(result.errors.map((e) => e.path)).toEqual(['$', '$.extra', '$.port', '$.tags']);
+    });
+  });
+
+  describe('validateBatch', () => {
+    const compiled = fastYaml.compileSchema(schema);
+
+    test('returns results in input order', () => {
+      const results = fastYaml.validateBatch(
+        ['name: a\nport: 1', 'name: b\nport: 0', 'name: c\nport: 443'],
+        compiled
+      );
+      expect(results.map((r) => r.valid)).toEqual([true, false, true]);
+      expect(results[1].errors).toEqual([
+        { code: 'MINIMUM', message: '0 is less than the minimum of 1', path: '$.port' },
+      ]);
+    });
+
+    test('reports parse errors without aborting the batch', () => {
+      const results = fastYaml.validateBatch(['[', 'name: a\nport: 1'], compiled);
+      expect(results[0].valid).toBe(false);
+      expect(results[0].errors[0].message).toMatch(/YAML parsing error/);
+      expect(results[1].valid).toBe(true);
+    });
+
+    test('reuses one compiled schema across calls', () => {
+      expect(fastYaml.validateBatch([], compiled)).toEqual([]);
+      expect(fastYaml.validateBatch(['name: a\nport: 1'], compiled)[0].valid).toBe(true);
+    });
+
+    test('rejects non-string documents', () => {
+      expect(() => fastYaml.validateBatch([1], compiled)).toThrow();
+    });
+  });
+
+  describe('compiled schemas', () => {
+    const compiled = fastYaml.compileSchema(schema);
+
+    test('validate accepts a compiled schema', () => {
+      for (const yaml of ['name: web\nport: 80', 'port: 70000\nextra: 1', 'name: a\nport: 0']) {
+        expect(fastYaml.validate(yaml, compiled)).toEqual(fastYaml.validate(yaml, schema));
+      }
+      expect(compiled.validate('name: web\nport: 80')).toEqual({ valid: true, errors: [] });
+    });
+
+    test('passes options through', () => {
+      expect(() =>
+        fastYaml.validate('name: web\nport: 80', compiled, { limits: { maxNodes: 2 } })
+      ).toThrow(fastYaml.YAMLException);
+      expect(() => fastYaml.validate('', compiled)).toThrow(/Empty YAML document/);
+    });
+
+    test('validateHandle validates a parsed document', () => {
+      const doc = fastYaml.parseToHandle('name: web\nport: 0');
+      expect(compiled.validateHandle(doc)).toEqual(doc.validate(schema));
+      expect(compiled.validateHandle(doc).valid).toBe(false);
+      doc.free();
+    });
+  });
+
+  describe('unresolvable values', () => {
+    test('are reported with their path and validated as null', () => {
+      const result = fastYaml.validate('name: web\nport: !!int abc', schema);
+      expect(result.valid).toBe(false);
+      expect(result.errors).toEqual([
+        {
+          code: 'INVALID_TAGGED_VALUE',
+          message: "cannot resolve 'abc' as !!int, read as null at line 2, column 13",
+          path: '$.port',
+        },
+        { code: 'TYPE_MISMATCH', message: 'expected integer, got null', path: '$.port' },
+      ]);
+    });
+  });
+
+  describe('merge keys', () => {
+    test('validate the merged mapping', () => {
+      const compose =
+        'x-base: &base\n  image: app\nservices:\n  web:\n    <<: *base\n    port: 80\n';
+      const service = {
+        type: 'object',
+        required: ['image', 'port'],
+        properties: { image: { type: 'string' }, port: { type: 'integer' } },
+      };
+      const schema = {
+        type: 'object',
+        properties: { services: { type: 'object', additionalProperties: service } },
+      };
+      expect(fastYaml.validate(compose, schema)).toEqual({ valid: true, errors: [] });
+    });
+  });
+
+  describe('duplicate keys', () => {
+    test('are rejected as parse rejects them', () => {
+      expect(() => fastYaml.validate('name: a\nport: 1\nname: b\n', schema)).toThrow(
+        /duplicated key 'name'/
+      );
+    });
+  });
+
+  describe('limits', () => {
+    const limits = { maxNodes: 5 };
+
+    test('bounds the documents validated', () => {
+      expect(fastYaml.validate('name: a\nport: 1', schema, { limits }).valid).toBe(true);
+      expect(() => fastYaml.validate('name: a\nport: 1\nx: y', schema, { limits })).toThrow(
+        /maxNodes/
+      );
+    });
+
+    test('reports documents past a limit in batches', () => {
+      const compiled = fastYaml.compileSchema(schema);
+      const results = fastYaml.validateBatch(
+        ['name: a\nport: 1\nx: y', 'name: a\nport: 1'],
+        compiled,
+        { limits }
+      );
+      expect(results[0].errors[0].message).toMatch(/maxNodes/);
+      expect(results[1].valid).toBe(true);
+    });
+
+    test('bounds alias expansion', () => {
+      const levels = 'abcdefghi'.split('');
+      const bomb = levels
+        .map((name, i) => {
+          const items = i === 0 ? Array(9).fill('lol') : Array(9).fill(`*${levels[i - 1]}`);
+          return `${name}: &${name} [${items.join(', ')}]`;
+        })
+        .join('\n');
+      expect(() => fastYaml.validate(bomb, { type: 'object' })).toThrow(/maxAliasCount/);
+
+      const yaml = 'base: &base [1, 2]\nx: *base\ny: *base\n';
+      const object = { type: 'object' };
+      expect(fastYaml.validate(yaml, object, { limits: { maxAliasCount: 2 } }).valid).toBe(true);
+      expect(() => fastYaml.validate(yaml, object, { limits: { maxAliasCount: 1 } })).toThrow(
+        /maxAliasCount/
+      );
+    });
+  });
+
+  describe('decimalCommaHints', () => {
+    const numbers = {
+      type: 'object',
+      properties: {
+        price: { type: 'number' },
+        count: { type: ['integer', 'null'] },
+        label: { type: 'string' },
+        sizes: { type: 'array', items: { type: 'number' } },
+      },
+    };
+    const hints = { decimalCommaHints: true };
+
+    test('is off by default', () => {
+      expect(fastYaml.validate('price: 1,5', numbers).errors).toEqual([
+        { code: 'TYPE_MISMATCH', message: 'expected number, got string', path: '$.price' },
+      ]);
+    });
+
+    test('explains decimal commas', () => {
+      expect(fastYaml.validate('price: 1,5', numbers, hints).errors).toEqual([
+        {
+          code: 'TYPE_MISMATCH',
+          message:
+            "expected number, got string ('1,5' looks like a number written with a decimal comma; use 1.5)",
+          path: '$.price',
+        },
+      ]);
+      expect(fastYaml.validate('price: -1.000,25', numbers, hints).errors[0].message).toMatch(
+        /decimal comma; use -1000\.25\)$/
+      );
+    });
+
+    test('explains digit grouping', () => {
+      expect(fastYaml.validate('count: 1,000,000', numbers, hints).errors[0].message).toBe(
+        "expected integer or null, got string ('1,000,000' looks like a number written with digit grouping; use 1000000)"
+      );
+      expect(fastYaml.validate('price: 1,000.5', numbers, hints).errors[0].message).toMatch(
+        /digit grouping; use 1000\.5\)$/
+      );
+    });
+
+    test('flags a comma before three digits as ambiguous', () => {
+      expect(fastYaml.validate('price: 1,500', numbers, hints).errors[0].message).toBe(
+        "expected number, got string ('1,500' looks like a number written with a decimal comma or digit grouping)"
+      );
+    });
+
+    test('applies to sequence items and batches', () => {
+      const result = fastYaml.validate('sizes:\n  - 1\n  - 2,5', numbers, hints);
+      expect(result.errors[0].path).toBe('$.sizes[1]');
+      expect(result.errors[0].message).toMatch(/use 2\.5\)$/);
+
+      const [batch] = fastYaml.validateBatch(
+        ['price: 3,75'],
+        fastYaml.compileSchema(numbers),
+        hints
+      );
+      expect(batch.errors[0].message).toMatch(/use 3\.75\)$/);
+    });
+
+    test('leaves other strings and string positions alone', () => {
+      expect(fastYaml.validate('label: 1,5', numbers, hints).valid).toBe(true);
+      expect(fastYaml.validate('price: a,b', numbers, hints).errors[0].message).toBe(
+        'expected number, got string'
+      );
+      for (const text of ['1,,5', '1,5,2', '1.00,5']) {
+        expect(fastYaml.validate(`price: ${text}`, numbers, hints).errors[0].message).toBe(
+          'expected number, got string'
+        );
+      }
+    });
+  });
+
+  describe('verifyExamples', () => {
+    const documented = {
+      type: 'object',
+      default: { port: 80 },
+      properties: {
+        port: { type: 'integer', minimum: 1, default: 0, examples: [8080, 'http'] },
+        tags: { type: 'array', items: { type: 'string', example: 'web' } },
+      },
+      definitions: { name: { type: 'string', examples: ['x'] } },
+    };
+
+    test('checks every default and example against its subschema', () => {
+      const { valid, examples } = fastYaml.verifyExamples(documented);
+      expect(valid).toBe(false);
+      const summary = examples.map(({ pointer, keyword, index, valid }) => [
+        pointer,
+        keyword,
+        index,
+        valid,
+      ]);
+      expect(summary).toEqual([
+        ['#', 'default', undefined, true],
+        ['#/properties/port', 'default', undefined, false],
+        ['#/properties/port', 'examples', 0, true],
+        ['#/properties/port', 'examples', 1, false],
+        ['#/properties/tags/items', 'example', undefined, true],
+        ['#/definitions/name', 'examples', 0, true],
+      ]);
+      expect(examples[1].errors).toEqual([
+        { code: 'MINIMUM', message: '0 is less than the minimum of 1', path: '$' },
+      ]);
+      expect(examples[3].errors).toEqual([
+        { code: 'TYPE_MISMATCH', message: 'expected integer, got string', path: '$' },
+      ]);
+    });
+
+    test('writes examples as YAML snippets on request', () => {
+      const { examples } = fastYaml.verifyExamples(documented, { snippets: true });
+      expect(examples[0].yaml).toBe('port: 80\n');
+      expect(examples[2].yaml).toBe('8080\n');
+      expect(fastYaml.verifyExamples(documented).examples[0]).not.toHaveProperty('yaml');
+    });
+
+    test('is valid for schemas without examples', () => {
+      expect(fastYaml.verifyExamples({ type: 'string' })).toEqual({ valid: true, examples: [] });
+    });
+  });
+});
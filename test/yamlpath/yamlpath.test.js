@@ -93,4 +93,293 @@ environments:
       }).toThrow();
     });
   });
+
+  describe('Compiled Paths', () => {
+    test('query matches the uncompiled query', () => {
+      const path = fastYaml.compilePath('.services[*].name');
+      expect(path.query(sampleYaml)).toEqual(fastYaml.query(sampleYaml, '.services[*].name'));
+      expect(path.path).toBe('$.services[*].name');
+    });
+
+    test('profile reports every segment', () => {
+      const { results, loadMs, segments } = fastYaml.compilePath('$..timeout').profile(sampleYaml);
+      expect(results).toEqual([30, 60]);
+      expect(loadMs).toBeGreaterThanOrEqual(0);
+      expect(segments.map((s) => s.segment)).toEqual(['$', '..', '.timeout']);
+      expect(segments[1].nodesVisited).toBe(segments[1].matches);
+      expect(segments[2].matches).toBe(2);
+      segments.forEach((s) => expect(s.timeMs).toBeGreaterThanOrEqual(0));
+    });
+
+    test('filter segments count the nodes their conditions inspect', () => {
+      const { segments } = fastYaml
+        .compilePath('.services[?(@.enabled==true)]')
+        .profile(sampleYaml);
+      const filter = segments[segments.length - 1];
+      expect(filter.segment).toBe('[?(@.enabled==true)]');
+      expect(filter.matches).toBe(1);
+      expect(filter.nodesVisited).toBeGreaterThan(2);
+    });
+
+    test('query accepts a compiled path', () => {
+      const path = fastYaml.compilePath('$..timeout');
+      expect(fastYaml.query(sampleYaml, path)).toEqual([30, 60]);
+      expect(fastYaml.query('', path)).toEqual([]);
+    });
+
+    test('queryHandle queries a parsed document', () => {
+      const doc = fastYaml.parseToHandle(sampleYaml);
+      const path = fastYaml.compilePath('.services[*].name');
+      expect(path.queryHandle(doc)).toEqual(fastYaml.query(sampleYaml, '.services[*].name'));
+      expect(path.queryHandle(doc)).toEqual(doc.query('.services[*].name'));
+      expect(fastYaml.compilePath('$.missing').queryHandle(doc)).toEqual([]);
+      doc.free();
+
+      const empty = fastYaml.parseToHandle('');
+      expect(path.queryHandle(empty)).toEqual([]);
+      empty.free();
+    });
+
+    test('throws for invalid path syntax', () => {
+      expect(() => fastYaml.compilePath('.services[invalid]')).toThrow();
+    });
+  });
+
+  describe('descendOnlyInto', () => {
+    const manifest = `
+spec:
+  template:
+    spec:
+      containers:
+        - name: app
+          image: app:1.0
+        - name: sidecar
+          image: proxy:2.0
+metadata:
+  annotations:
+    image: not-an-image
+`;
+
+    test('restricts recursive descent to the listed keys', () => {
+      const result = fastYaml.query(manifest, '$..image', {
+        descendOnlyInto: ['spec', 'template', 'containers'],
+      });
+      expect(result).toEqual(['app:1.0', 'proxy:2.0']);
+    });
+
+    test('descends everywhere by default', () => {
+      expect(fastYaml.query(manifest, '$..image')).toHaveLength(3);
+    });
+
+    test('visits fewer nodes', () => {
+      const path = fastYaml.compilePath('$..image');
+      const all = path.profile(manifest).segments[1].nodesVisited;
+      const pruned = path.profile(manifest, { descendOnlyInto: ['spec'] }).segments[1].nodesVisited;
+      expect(pruned).toBeLessThan(all);
+    });
+
+    test('rejects non-array values', () => {
+      expect(() => fastYaml.query(manifest, '$..image', { descendOnlyInto: 'spec' })).toThrow();
+    });
+  });
+
+  describe('merge keys', () => {
+    test('are expanded as parse expands them', () => {
+      const yaml = 'base: &b {b: 1, c: 2}\nc: {<<: *b, c: 3}\n';
+      expect(fastYaml.query(yaml, '$.c')).toEqual([fastYaml.parse(yaml).c]);
+      expect(fastYaml.query(yaml, '$.c.b')).toEqual([1]);
+      expect(fastYaml.query(yaml, '$.c.c')).toEqual([3]);
+      const sources = 'a: &a {x: 1}\nb: &b {x: 2, y: 2}\nc: {<<: [*a, *b]}\n';
+      expect(fastYaml.query(sources, '$.c')).toEqual([{ x: 1, y: 2 }]);
+    });
+  });
+
+  describe('duplicate keys', () => {
+    test('are rejected as parse rejects them', () => {
+      const yaml = 'a: 1\nb: 2\na: 3\n';
+      expect(() => fastYaml.parse(yaml)).toThrow(/duplicated key 'a'/);
+      expect(() => fastYaml.query(yaml, '$.a')).toThrow(/duplicated key 'a'/);
+    });
+  });
+
+  describe('limits', () => {
+    test('bounds the document queried', () => {
+      const limits = { maxScalarLength: 5 };
+      expect(fastYaml.query('name: short', '$.name', { limits })).toEqual(['short']);
+      expect(() => fastYaml.query('name: too long', '$.name', { limits })).toThrow(
+        /maxScalarLength/
+      );
+    });
+
+    test('bounds alias expansion', () => {
+      const levels = 'abcdefghi'.split('');
+      const bomb = levels
+        .map((name, i) => {
+          const items = i === 0 ? Array(9).fill('lol') : Array(9).fill(`*${levels[i - 1]}`);
+          return `${name}: &${name} [${items.join(', ')}]`;
+        })
+        .join('\n');
+      expect(() => fastYaml.query(bomb, '$.a')).toThrow(/maxAliasCount/);
+      expect(() => fastYaml.query(bomb, '$.a', { limits: { maxAliasCount: Infinity } })).toThrow(
+        /maxAliasExpansion/
+      );
+
+      const yaml = 'base: &base [1, 2]\nx: *base\ny: *base\n';
+      expect(fastYaml.query(yaml, '$.y[1]', { limits: { maxAliasCount: 2 } })).toEqual([2]);
+      expect(() => fastYaml.query(yaml, '$.y', { limits: { maxAliasCount: 1 } })).toThrow(
+        /maxAliasCount/
+      );
+    });
+
+    test('bounds the nesting and memory of expanded aliases', () => {
+      const yaml = 'a: &a [[1]]\nb: [*a]\n';
+      expect(fastYaml.query(yaml, '$.a[0][0]', { limits: { maxNestingDepth: 4 } })).toEqual([1]);
+      expect(() => fastYaml.query(yaml, '$.a', { limits: { maxNestingDepth: 3 } })).toThrow(
+        /maxNestingDepth/
+      );
+
+      const aliased = `base: &base [${Array(200).fill('value').join(', ')}]\n${Array.from(
+        { length: 50 },
+        (_, i) => `k${i}: *base`
+      ).join('\n')}\n`;
+      expect(() => fastYaml.query(aliased, '$.k0', { limits: { maxMemoryBytes: 100000 } })).toThrow(
+        /conversion needs more than 100000 bytes/
+      );
+    });
+  });
+
+  describe('analyzePath', () => {
+    const schema = {
+      type: 'object',
+      additionalProperties: false,
+      properties: {
+        version: { type: 'number' },
+        services: {
+          type: 'array',
+          items: { $ref: '#/definitions/service' },
+        },
+        ports: { type: 'array', items: { type: 'integer' }, maxItems: 3 },
+      },
+      definitions: {
+        service: {
+          type: 'object',
+          additionalProperties: false,
+          properties: { name: { type: 'string' }, port: { type: 'integer' } },
+        },
+      },
+    };
+
+    test('reports single matches', () => {
+      expect(fastYaml.analyzePath('.services[0].name', schema)).toEqual({
+        canMatch: true,
+        maxMatches: 1,
+        selectivity: 'atMostOne',
+        blockedAt: null,
+      });
+    });
+
+    test('reports bounded and unbounded matches', () => {
+      expect(fastYaml.analyzePath('.ports[*]', schema).maxMatches).toBe(3);
+      expect(fastYaml.analyzePath('.services[*].name', schema).selectivity).toBe('unbounded');
+    });
+
+    test('reports the segment that cannot match', () => {
+      const result = fastYaml.analyzePath('.services[*].host', schema);
+      expect(result.canMatch).toBe(false);
+      expect(result.blockedAt).toBe('.host');
+      expect(fastYaml.analyzePath('.version.major', schema).blockedAt).toBe('.major');
+    });
+
+    test('throws for invalid path syntax', () => {
+      expect(() => fastYaml.analyzePath('.services[invalid]', schema)).toThrow();
+    });
+  });
+
+  describe('queryMany', () => {
+    const docs = ['name: a\nport: 1', 'port: 2', 'items: [{name: b}, {name: c}]'];
+
+    test('returns matches per document in input order', () => {
+      expect(fastYaml.queryMany(docs, '$..name')).toEqual([['a'], [], ['b', 'c']]);
+    });
+
+    test('accepts a compiled path and query options', () => {
+      const path = fastYaml.compilePath('$..name');
+      expect(fastYaml.queryMany(docs, path, { descendOnlyInto: [] })).toEqual([[], [], []]);
+      expect(fastYaml.queryMany(docs, path)).toEqual([['a'], [], ['b', 'c']]);
+    });
+
+    test('names the document that fails to parse', () => {
+      expect(() => fastYaml.queryMany(['a: 1', '['], '.a')).toThrow(/document 1/);
+    });
+  });
+
+  describe('unresolvable values', () => {
+    const yaml = 'port: !!int abc\nhost: web\n';
+
+    test('reads them as null and reports their position', () => {
+      const warnings = [];
+      const result = fastYaml.query(yaml, '$.port', { onWarning: (w) => warnings.push(w) });
+      expect(result).toEqual([null]);
+      expect(warnings).toHaveLength(1);
+      expect(warnings[0]).toBeInstanceOf(fastYaml.YAMLException);
+      expect(warnings[0].message).toMatch(/cannot resolve 'abc' as !!int, read as null/);
+      expect(warnings[0].line).toBe(1);
+    });
+
+    test('do not prevent matching other values', () => {
+      expect(fastYaml.query(yaml, '$.host')).toEqual(['web']);
+    });
+  });
+
+  describe('Query sessions', () => {
+    const yaml = [
+      'metadata: {name: web}',
+      'spec:',
+      '  containers:',
+      '    - {name: app, image: app:1.0}',
+      '    - {name: proxy, image: proxy:2.0}',
+    ].join('\n');
+
+    test('queries relative to the current location', () => {
+      const session = fastYaml.createQuerySession(yaml);
+      expect(session.cwd).toBe('$');
+      expect(session.cd('$.spec.containers')).toBe('$.spec.containers');
+      expect(session.query('[*].image')).toEqual(['app:1.0', 'proxy:2.0']);
+      expect(session.query('[0].name')).toEqual(['app']);
+      expect(session.query('$.metadata.name')).toEqual(['web']);
+      expect(session.up()).toBe('$.spec');
+      expect(session.up()).toBe('$');
+      expect(session.up()).toBe('$');
+      session.free();
+    });
+
+    test('starts from every value of a location matching several', () => {
+      const session = fastYaml.createQuerySession(yaml);
+      session.cd('$.spec.containers[*]');
+      expect(session.query('.name')).toEqual(['app', 'proxy']);
+      session.free();
+    });
+
+    test('records history and caches compiled paths', () => {
+      const session = fastYaml.createQuerySession(yaml);
+      session.cd('.spec');
+      session.query('.containers[0].name');
+      session.query('.containers[0].name');
+      expect(session.history).toEqual([
+        '$.spec',
+        '$.spec.containers[0].name',
+        '$.spec.containers[0].name',
+      ]);
+      expect(session.cacheSize).toBe(2);
+      session.free();
+    });
+
+    test('rejects locations matching nothing and invalid paths', () => {
+      const session = fastYaml.createQuerySession(yaml);
+      expect(() => session.cd('.missing')).toThrow(/Path matches nothing: \$\.missing/);
+      expect(session.cwd).toBe('$');
+      expect(() => session.query('name')).toThrow(/YAMLPath parsing error/);
+      session.free();
+    });
+  });
 });
@@ -54,6 +54,7 @@ pub struct YamlEmitter<'a> {
     compact: bool,
     level: isize,
     multiline_strings: bool,
+    null_repr: &'a str,
 }
 
 /// A convenience alias for emitter functions that may fail without returning a value.
@@ -131,6 +132,7 @@ impl<'a> YamlEmitter<'a> {
             compact: true,
             level: -1,
             multiline_strings: false,
+            null_repr: "~",
         }
     }
 
@@ -186,6 +188,20 @@ impl<'a> YamlEmitter<'a> {
         self.multiline_strings
     }
 
+    /// Set the text null values are written as (`~` by default).
+    ///
+    /// With an empty representation, null mapping values and sequence items are left blank
+    /// (`key:`); null mapping keys are still written as `~`.
+    pub fn null_repr(&mut self, null_repr: &'a str) {
+        self.null_repr = null_repr;
+    }
+
+    /// Get the text null values are written as.
+    #[must_use]
+    pub fn get_null_repr(&self) -> &str {
+        self.null_repr
+    }
+
     /// Dump Yaml to an output stream.
     /// # Errors
     /// Returns `EmitError` when an error occurs.
@@ -242,7 +258,7 @@ impl<'a> YamlEmitter<'a> {
                 Ok(())
             }
             Yaml::Null | Yaml::BadValue => {
-                write!(self.writer, "~")?;
+                self.writer.write_str(self.null_repr)?;
                 Ok(())
             }
             // XXX(chenyh) Alias
@@ -307,7 +323,11 @@ impl<'a> YamlEmitter<'a> {
                     write!(self.writer, ":")?;
                     self.emit_val(true, v)?;
                 } else {
-                    self.emit_node(k)?;
+                    if matches!(*k, Yaml::Null) && self.null_repr.is_empty() {
+                        self.writer.write_str("~")?;
+                    } else {
+                        self.emit_node(k)?;
+                    }
                     write!(self.writer, ":")?;
                     self.emit_val(false, v)?;
                 }
@@ -345,6 +365,7 @@ impl<'a> YamlEmitter<'a> {
                 }
                 self.emit_hash(h)
             }
+            Yaml::Null | Yaml::BadValue if self.null_repr.is_empty() => Ok(()),
             _ => {
                 write!(self.writer, " ")?;
                 self.emit_node(val)
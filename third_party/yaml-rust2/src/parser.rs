@@ -403,8 +403,21 @@ impl<T: Iterator<Item = char>> Parser<T> {
         }
         recv.on_event(first_ev, mark);
 
-        let (ev, mark) = self.next_token()?;
-        self.load_node(ev, mark, recv)?;
+        // Nodes are read in a loop rather than recursively, so deeply nested documents cannot
+        // overflow the stack.
+        let mut depth = 0usize;
+        loop {
+            let (ev, mark) = self.next_token()?;
+            match ev {
+                Event::SequenceStart(..) | Event::MappingStart(..) => depth += 1,
+                Event::SequenceEnd | Event::MappingEnd => depth -= 1,
+                _ => {}
+            }
+            recv.on_event(ev, mark);
+            if depth == 0 {
+                break;
+            }
+        }
 
         // DOCUMENT-END is expected.
         let (ev, mark) = self.next_token()?;
@@ -414,65 +427,6 @@ impl<T: Iterator<Item = char>> Parser<T> {
         Ok(())
     }
 
-    fn load_node<R: MarkedEventReceiver>(
-        &mut self,
-        first_ev: Event,
-        mark: Marker,
-        recv: &mut R,
-    ) -> Result<(), ScanError> {
-        match first_ev {
-            Event::Alias(..) | Event::Scalar(..) => {
-                recv.on_event(first_ev, mark);
-                Ok(())
-            }
-            Event::SequenceStart(..) => {
-                recv.on_event(first_ev, mark);
-                self.load_sequence(recv)
-            }
-            Event::MappingStart(..) => {
-                recv.on_event(first_ev, mark);
-                self.load_mapping(recv)
-            }
-            _ => {
-                println!("UNREACHABLE EVENT: {first_ev:?}");
-                unreachable!();
-            }
-        }
-    }
-
-    fn load_mapping<R: MarkedEventReceiver>(&mut self, recv: &mut R) -> Result<(), ScanError> {
-        let (mut key_ev, mut key_mark) = self.next_token()?;
-        while key_ev != Event::MappingEnd {
-            // key
-            self.load_node(key_ev, key_mark, recv)?;
-
-            // value
-            let (ev, mark) = self.next_token()?;
-            self.load_node(ev, mark, recv)?;
-
-            // next event
-            let (ev, mark) = self.next_token()?;
-            key_ev = ev;
-            key_mark = mark;
-        }
-        recv.on_event(key_ev, key_mark);
-        Ok(())
-    }
-
-    fn load_sequence<R: MarkedEventReceiver>(&mut self, recv: &mut R) -> Result<(), ScanError> {
-        let (mut ev, mut mark) = self.next_token()?;
-        while ev != Event::SequenceEnd {
-            self.load_node(ev, mark, recv)?;
-
-            // next event
-            let (next_ev, next_mark) = self.next_token()?;
-            ev = next_ev;
-            mark = next_mark;
-        }
-        recv.on_event(ev, mark);
-        Ok(())
-    }
-
     fn state_machine(&mut self) -> ParseResult {
         // let next_tok = self.peek_token().cloned()?;
         // println!("cur_state {:?}, next tok: {:?}", self.state, next_tok);
@@ -561,8 +515,8 @@ impl<T: Iterator<Item = char>> Parser<T> {
 
     fn parser_process_directives(&mut self) -> Result<(), ScanError> {
         let mut version_directive_received = false;
+        let mut tags = HashMap::new();
         loop {
-            let mut tags = HashMap::new();
             match self.peek_token()? {
                 Token(mark, TokenType::VersionDirective(_, _)) => {
                     // XXX parsing with warning according to spec
@@ -583,9 +537,12 @@ impl<T: Iterator<Item = char>> Parser<T> {
                 }
                 _ => break,
             }
-            self.tags = tags;
             self.skip();
         }
+        // Directives apply together: a later `%YAML` or `%TAG` must not drop earlier handles
+        if !tags.is_empty() || version_directive_received {
+            self.tags = tags;
+        }
         Ok(())
     }
 
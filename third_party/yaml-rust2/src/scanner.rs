@@ -53,7 +53,9 @@ pub struct Marker {
 }
 
 impl Marker {
-    fn new(index: usize, line: usize, col: usize) -> Marker {
+    /// Create a new [`Marker`] at the given position.
+    #[must_use]
+    pub fn new(index: usize, line: usize, col: usize) -> Marker {
         Marker { index, line, col }
     }
 
@@ -593,17 +595,6 @@ impl<T: Iterator<Item = char>> Scanner<T> {
         s.push('\n');
     }
 
-    /// Check whether the next characters correspond to an end of document.
-    ///
-    /// [`Self::lookahead`] must have been called before calling this function.
-    fn next_is_document_end(&self) -> bool {
-        assert!(self.buffer.len() >= 4);
-        self.buffer[0] == '.'
-            && self.buffer[1] == '.'
-            && self.buffer[2] == '.'
-            && is_blank_or_breakz(self.buffer[3])
-    }
-
     /// Check whether the next characters correspond to a document indicator.
     ///
     /// [`Self::lookahead`] must have been called before calling this function.
@@ -1678,9 +1669,10 @@ impl<T: Iterator<Item = char>> Scanner<T> {
         let mut line_buffer = String::with_capacity(100);
         let start_mark = self.mark;
         while self.mark.col == indent && !is_z(self.ch()) {
+            // A document marker ends a block scalar at the root, as with any other node.
             if indent == 0 {
                 self.lookahead(4);
-                if self.next_is_document_end() {
+                if self.next_is_document_indicator() {
                     break;
                 }
             }